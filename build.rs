@@ -0,0 +1,60 @@
+use std::env;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// # Summary
+///
+/// Find the abbreviated hash of the commit that is being built. The GIT_COMMIT environment variable takes precedence,
+/// so that builds without a git checkout, such as Docker builds, can still report their commit.
+///
+/// # Returns
+///
+/// * `String` - The commit hash, or `unknown` if it cannot be determined.
+fn git_commit() -> String {
+    if let Ok(c) = env::var("GIT_COMMIT") {
+        if !c.trim().is_empty() {
+            return c.trim().to_string();
+        }
+    }
+
+    Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| String::from("unknown"))
+}
+
+/// # Summary
+///
+/// Find the build timestamp in seconds since the Unix epoch. SOURCE_DATE_EPOCH takes precedence, for reproducible
+/// builds.
+///
+/// # Returns
+///
+/// * `u64` - The build timestamp.
+fn build_timestamp() -> u64 {
+    if let Ok(t) = env::var("SOURCE_DATE_EPOCH") {
+        if let Ok(t) = t.trim().parse::<u64>() {
+            return t;
+        }
+    }
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn main() {
+    println!("cargo:rustc-env=GIT_COMMIT={}", git_commit());
+    println!("cargo:rustc-env=BUILD_TIMESTAMP={}", build_timestamp());
+
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+}
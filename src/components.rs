@@ -1,2 +1,4 @@
 pub mod env_reader;
+pub mod localizer;
+pub mod log_initializer;
 pub mod open_api;
@@ -1,14 +1,53 @@
+use crate::components::log_initializer::LogFormat;
 use crate::configuration::config::Config;
-use crate::configuration::db_config::DbConfig;
+use crate::configuration::cookie_config::CookieConfig;
+use crate::configuration::db_config::{AuditFailurePolicy, DbConfig};
 use crate::configuration::default_user_config::DefaultUserConfig;
-use crate::configuration::jwt_config::JwtConfig;
-use crate::configuration::server_config::ServerConfig;
-use log::info;
-use std::env;
+use crate::configuration::email_config::EmailConfig;
+use crate::configuration::jwt_config::{JwtConfig, RsaKey, TokenFormat, TokenMode};
+use crate::configuration::login_defense_config::{LoginDefense, LoginDefenseConfig};
+use crate::configuration::name_config::{NameConfig, NameNormalization};
+use crate::configuration::password_config::{PasswordConfig, PasswordHashAlgorithm};
+use crate::configuration::role_template::RoleTemplate;
+use crate::configuration::server_config::{
+    ImportConflictPolicy, RegistrationMode, ServerConfig, DEV_MODE_ACKNOWLEDGEMENT,
+};
+use crate::services::audit::audit_service::DEFAULT_SYSTEM_ACTOR_ID;
+use crate::services::email::disposable_email_policy::DisposableEmailPolicy;
+use actix_web::cookie::SameSite;
+use jsonwebtoken::Algorithm;
+use log::{info, warn};
+use std::net::IpAddr;
+use std::{env, fs};
 
 pub struct EnvReader {}
 
 impl EnvReader {
+    /// # Summary
+    ///
+    /// Reads the log format from the environment variables. This is read separately from the rest of
+    /// the configuration, because the logger has to be initialized before the configuration is read.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let log_format = EnvReader::read_log_format();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// The LogFormat to use.
+    pub fn read_log_format() -> LogFormat {
+        match env::var("LOG_FORMAT") {
+            Ok(d) => match d.trim().to_lowercase().as_str() {
+                "text" => LogFormat::Text,
+                "json" => LogFormat::Json,
+                _ => panic!("LOG_FORMAT must be either text or json"),
+            },
+            Err(_) => LogFormat::Text,
+        }
+    }
+
     /// # Summary
     ///
     /// Reads the configuration from the environment variables.
@@ -41,12 +80,29 @@ impl EnvReader {
             Err(_) => 8080,
         };
 
-        let max_limit = match env::var("MAX_FETCH_LIMIT") {
+        // MAX_FETCH_LIMIT is the former name of MAX_PAGE_SIZE and is still read if MAX_PAGE_SIZE is not set
+        let max_page_size = match env::var("MAX_PAGE_SIZE") {
             Ok(d) => {
-                let res: i64 = d.trim().parse().expect("MAX_FETCH_LIMIT must be a number");
+                let res: i64 = d.trim().parse().expect("MAX_PAGE_SIZE must be a number");
+                if res < 1 {
+                    panic!("MAX_PAGE_SIZE must be greater than zero");
+                }
                 res
             }
-            Err(_) => 100,
+            Err(_) => match env::var("MAX_FETCH_LIMIT") {
+                Ok(d) => {
+                    warn!("MAX_FETCH_LIMIT is deprecated, use MAX_PAGE_SIZE instead");
+                    let res: i64 = d.trim().parse().expect("MAX_FETCH_LIMIT must be a number");
+                    // The former example configuration used 0, which meant no limit and is no longer supported
+                    if res < 1 {
+                        warn!("MAX_FETCH_LIMIT is not greater than zero, using the default of 100");
+                        100
+                    } else {
+                        res
+                    }
+                }
+                Err(_) => 100,
+            },
         };
 
         let workers = match env::var("SERVER_WORKERS") {
@@ -60,6 +116,198 @@ impl EnvReader {
             Err(_) => 0,
         };
 
+        let route_prefix = match env::var("SERVER_ROUTE_PREFIX") {
+            Ok(d) => {
+                let res = d.trim().trim_matches('/');
+                if res.is_empty() {
+                    String::new()
+                } else {
+                    format!("/{}", res)
+                }
+            }
+            Err(_) => String::from("/api/v1"),
+        };
+
+        let prefix_health = match env::var("SERVER_PREFIX_HEALTH") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("SERVER_PREFIX_HEALTH must be a boolean");
+                res
+            }
+            Err(_) => false,
+        };
+
+        let idempotency_ttl = match env::var("SERVER_IDEMPOTENCY_TTL") {
+            Ok(d) => {
+                let res: u64 = d
+                    .trim()
+                    .parse()
+                    .expect("SERVER_IDEMPOTENCY_TTL must be a number");
+                res
+            }
+            Err(_) => 86400,
+        };
+
+        let allow_self_registration = match env::var("ALLOW_SELF_REGISTRATION") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("ALLOW_SELF_REGISTRATION must be a boolean");
+                res
+            }
+            Err(_) => true,
+        };
+
+        let registration_mode = match env::var("REGISTRATION_MODE") {
+            Ok(d) => match d.trim().to_lowercase().as_str() {
+                "open" => RegistrationMode::Open,
+                "invite" => RegistrationMode::Invite,
+                _ => panic!("REGISTRATION_MODE must be either open or invite"),
+            },
+            Err(_) => RegistrationMode::Open,
+        };
+
+        let invite_ttl = match env::var("INVITE_TTL") {
+            Ok(d) => {
+                let res: u64 = d.trim().parse().expect("INVITE_TTL must be a number");
+                if res == 0 {
+                    panic!("INVITE_TTL must be greater than zero");
+                }
+                res
+            }
+            Err(_) => 604800,
+        };
+
+        let import_conflict_policy = match env::var("USER_IMPORT_CONFLICT_POLICY") {
+            Ok(d) => match d.trim().to_lowercase().as_str() {
+                "skip" => ImportConflictPolicy::Skip,
+                "overwrite" => ImportConflictPolicy::Overwrite,
+                _ => panic!("USER_IMPORT_CONFLICT_POLICY must be either skip or overwrite"),
+            },
+            Err(_) => ImportConflictPolicy::Skip,
+        };
+
+        let tenancy = match env::var("TENANCY_ENABLED") {
+            Ok(d) => {
+                let res: bool = d.trim().parse().expect("TENANCY_ENABLED must be a boolean");
+                res
+            }
+            Err(_) => false,
+        };
+
+        let default_roles: Vec<String> = match env::var("DEFAULT_ROLES") {
+            Ok(d) => d
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect(),
+            Err(_) => vec![String::from("DEFAULT")],
+        };
+
+        let require_default_role = match env::var("REQUIRE_DEFAULT_ROLE") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("REQUIRE_DEFAULT_ROLE must be a boolean");
+                res
+            }
+            Err(_) => true,
+        };
+
+        let disclose_disabled_accounts = match env::var("DISCLOSE_DISABLED_ACCOUNTS") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("DISCLOSE_DISABLED_ACCOUNTS must be a boolean");
+                res
+            }
+            Err(_) => false,
+        };
+
+        let trusted_proxies: Vec<IpAddr> = match env::var("TRUSTED_PROXIES") {
+            Ok(d) => d
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    s.parse()
+                        .expect("TRUSTED_PROXIES must be a comma-separated list of IP addresses")
+                })
+                .collect(),
+            Err(_) => vec![],
+        };
+
+        let cors_allowed_origins: Vec<String> = match env::var("CORS_ALLOWED_ORIGINS") {
+            Ok(d) => d
+                .split(',')
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    let is_origin = (s.starts_with("https://") || s.starts_with("http://"))
+                        && !s.ends_with('/')
+                        && !s.contains('*');
+                    if !is_origin {
+                        panic!("CORS_ALLOWED_ORIGINS must be a comma-separated list of origins, such as https://example.com");
+                    }
+                    String::from(s)
+                })
+                .collect(),
+            Err(_) => vec![],
+        };
+
+        let rate_limit = match env::var("RATE_LIMIT") {
+            Ok(d) => {
+                let res: u32 = d.trim().parse().expect("RATE_LIMIT must be a number");
+                res
+            }
+            Err(_) => 0,
+        };
+
+        let rate_limit_window = match env::var("RATE_LIMIT_WINDOW") {
+            Ok(d) => {
+                let res: u64 = d
+                    .trim()
+                    .parse()
+                    .expect("RATE_LIMIT_WINDOW must be a number");
+                if res == 0 {
+                    panic!("RATE_LIMIT_WINDOW must be greater than 0");
+                }
+                res
+            }
+            Err(_) => 60,
+        };
+
+        let enforce_json_content_type = match env::var("ENFORCE_JSON_CONTENT_TYPE") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("ENFORCE_JSON_CONTENT_TYPE must be a boolean");
+                res
+            }
+            Err(_) => true,
+        };
+
+        // Dev mode exposes token claims and permissions, so it has to be acknowledged explicitly instead of using a
+        // boolean that could be flipped by accident
+        let dev_mode = match env::var("DEV_MODE") {
+            Ok(d) => match d.trim() {
+                "" | "false" => false,
+                DEV_MODE_ACKNOWLEDGEMENT => true,
+                _ => panic!(
+                    "DEV_MODE must be either false or {} and must never be enabled in production",
+                    DEV_MODE_ACKNOWLEDGEMENT
+                ),
+            },
+            Err(_) => false,
+        };
+
         let conn_string = match env::var("DB_CONNECTION_STRING") {
             Ok(d) => d,
             Err(_) => panic!("No connection string specified"),
@@ -90,19 +338,335 @@ impl EnvReader {
             Err(_) => String::from("audits"),
         };
 
-        let jwt_secret = match env::var("JWT_SECRET") {
+        let session_collection = match env::var("DB_SESSION_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("sessions"),
+        };
+
+        let token_collection = match env::var("DB_TOKEN_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("tokens"),
+        };
+
+        let migration_collection = match env::var("DB_MIGRATION_COLLECTION") {
+            Ok(d) => d,
+            Err(_) => String::from("migrations"),
+        };
+
+        let invite_collection = match env::var("DB_INVITE_COLLECTION") {
             Ok(d) => d,
-            Err(_) => panic!("No JWT secret specified"),
+            Err(_) => String::from("invites"),
         };
 
+        let token_mode = match env::var("TOKEN_MODE") {
+            Ok(d) => match d.trim().to_lowercase().as_str() {
+                "stateless" => TokenMode::Stateless,
+                "opaque" => TokenMode::Opaque,
+                _ => panic!("TOKEN_MODE must be either stateless or opaque"),
+            },
+            Err(_) => TokenMode::Stateless,
+        };
+
+        let token_format = match env::var("TOKEN_FORMAT") {
+            Ok(d) => match d.trim().to_lowercase().as_str() {
+                "jwt" => TokenFormat::Jwt,
+                "paseto" => TokenFormat::Paseto,
+                _ => panic!("TOKEN_FORMAT must be either jwt or paseto"),
+            },
+            Err(_) => TokenFormat::Jwt,
+        };
+
+        let jwt_algorithm = match env::var("JWT_ALGORITHM") {
+            Ok(d) => match d.trim().to_uppercase().as_str() {
+                "HS256" => Algorithm::HS256,
+                "RS256" => Algorithm::RS256,
+                _ => panic!("JWT_ALGORITHM must be either HS256 or RS256"),
+            },
+            Err(_) => Algorithm::HS256,
+        };
+
+        let mut jwt_secret = String::new();
+        let mut previous_jwt_secrets: Vec<String> = vec![];
+        let mut rsa_keys: Vec<RsaKey> = vec![];
+        let mut paseto_private_key: Option<String> = None;
+
+        // Opaque tokens are random values that are looked up instead of verified, so no signing keys are needed
+        if token_mode == TokenMode::Stateless {
+            if token_format == TokenFormat::Paseto {
+                paseto_private_key = match env::var("PASETO_PRIVATE_KEY") {
+                    Ok(d) => Some(
+                        fs::read_to_string(d.trim()).expect("Failed to read PASETO_PRIVATE_KEY"),
+                    ),
+                    Err(_) => panic!("No PASETO private key specified"),
+                };
+            } else if jwt_algorithm == Algorithm::HS256 {
+                jwt_secret = match env::var("JWT_SECRET") {
+                    Ok(d) => d,
+                    Err(_) => panic!("No JWT secret specified"),
+                };
+
+                if jwt_secret.trim().is_empty() {
+                    panic!("JWT_SECRET must not be empty");
+                }
+
+                previous_jwt_secrets = match env::var("JWT_PREVIOUS_SECRETS") {
+                    Ok(d) => d
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect(),
+                    Err(_) => vec![],
+                };
+            } else {
+                let kid = match env::var("JWT_RSA_KID") {
+                    Ok(d) => d.trim().to_string(),
+                    Err(_) => panic!("No JWT RSA key ID specified"),
+                };
+
+                if kid.is_empty() {
+                    panic!("JWT_RSA_KID must not be empty");
+                }
+
+                let private_key = match env::var("JWT_RSA_PRIVATE_KEY") {
+                    Ok(d) => {
+                        fs::read_to_string(d.trim()).expect("Failed to read JWT_RSA_PRIVATE_KEY")
+                    }
+                    Err(_) => panic!("No JWT RSA private key specified"),
+                };
+
+                let public_key = match env::var("JWT_RSA_PUBLIC_KEY") {
+                    Ok(d) => {
+                        fs::read_to_string(d.trim()).expect("Failed to read JWT_RSA_PUBLIC_KEY")
+                    }
+                    Err(_) => panic!("No JWT RSA public key specified"),
+                };
+
+                rsa_keys.push(RsaKey::new(kid, Some(private_key), public_key));
+
+                if let Ok(d) = env::var("JWT_RSA_PREVIOUS_PUBLIC_KEYS") {
+                    for entry in d.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+                        let (kid, path) = entry.split_once(':').expect(
+                            "JWT_RSA_PREVIOUS_PUBLIC_KEYS entries must be formatted as kid:path",
+                        );
+
+                        if kid.trim().is_empty() {
+                            panic!(
+                                "JWT_RSA_PREVIOUS_PUBLIC_KEYS entries must have a non-empty kid"
+                            );
+                        }
+
+                        let public_key = fs::read_to_string(path.trim())
+                            .expect("Failed to read JWT_RSA_PREVIOUS_PUBLIC_KEYS");
+
+                        rsa_keys.push(RsaKey::new(kid.trim().to_string(), None, public_key));
+                    }
+                }
+            }
+        }
+
         let jwt_expiration = match env::var("JWT_EXPIRATION") {
             Ok(d) => {
                 let res: usize = d.trim().parse().expect("JWT_EXPIRATION must be a number");
+                if res == 0 {
+                    panic!("JWT_EXPIRATION must be greater than zero");
+                }
                 res
             }
             Err(_) => 3600,
         };
 
+        let password_hash_algo = match env::var("PASSWORD_HASH_ALGO") {
+            Ok(d) => match d.trim().to_lowercase().as_str() {
+                "argon2" => PasswordHashAlgorithm::Argon2,
+                "bcrypt" => PasswordHashAlgorithm::Bcrypt,
+                _ => panic!("PASSWORD_HASH_ALGO must be either argon2 or bcrypt"),
+            },
+            Err(_) => PasswordHashAlgorithm::Argon2,
+        };
+
+        let bcrypt_cost = match env::var("PASSWORD_BCRYPT_COST") {
+            Ok(d) => {
+                let res: u32 = d
+                    .trim()
+                    .parse()
+                    .expect("PASSWORD_BCRYPT_COST must be a number");
+                if !(4..=31).contains(&res) {
+                    panic!("PASSWORD_BCRYPT_COST must be between 4 and 31");
+                }
+                res
+            }
+            Err(_) => 12,
+        };
+
+        let argon2_memory_cost = match env::var("ARGON2_MEMORY_COST") {
+            Ok(d) => {
+                let res: u32 = d
+                    .trim()
+                    .parse()
+                    .expect("ARGON2_MEMORY_COST must be a number");
+                res
+            }
+            Err(_) => 19456,
+        };
+
+        let argon2_time_cost = match env::var("ARGON2_TIME_COST") {
+            Ok(d) => {
+                let res: u32 = d.trim().parse().expect("ARGON2_TIME_COST must be a number");
+                if res == 0 {
+                    panic!("ARGON2_TIME_COST must be greater than zero");
+                }
+                res
+            }
+            Err(_) => 2,
+        };
+
+        let argon2_parallelism = match env::var("ARGON2_PARALLELISM") {
+            Ok(d) => {
+                let res: u32 = d
+                    .trim()
+                    .parse()
+                    .expect("ARGON2_PARALLELISM must be a number");
+                if res == 0 {
+                    panic!("ARGON2_PARALLELISM must be greater than zero");
+                }
+                res
+            }
+            Err(_) => 1,
+        };
+
+        let argon2_target_hash_time = match env::var("ARGON2_TARGET_HASH_TIME") {
+            Ok(d) => {
+                let res: u64 = d
+                    .trim()
+                    .parse()
+                    .expect("ARGON2_TARGET_HASH_TIME must be a number");
+                res
+            }
+            Err(_) => 0,
+        };
+
+        let argon2_autotune = match env::var("ARGON2_AUTOTUNE") {
+            Ok(d) => {
+                let res: bool = d.trim().parse().expect("ARGON2_AUTOTUNE must be a boolean");
+                res
+            }
+            Err(_) => false,
+        };
+
+        let password_max_length = match env::var("PASSWORD_MAX_LENGTH") {
+            Ok(d) => {
+                let res: usize = d
+                    .trim()
+                    .parse()
+                    .expect("PASSWORD_MAX_LENGTH must be a number");
+                if res == 0 {
+                    panic!("PASSWORD_MAX_LENGTH must be greater than zero");
+                }
+                res
+            }
+            Err(_) => 256,
+        };
+
+        let password_history_size = match env::var("PASSWORD_HISTORY_SIZE") {
+            Ok(d) => {
+                let res: usize = d
+                    .trim()
+                    .parse()
+                    .expect("PASSWORD_HISTORY_SIZE must be a number");
+                res
+            }
+            Err(_) => 0,
+        };
+
+        let password_max_age_days = match env::var("PASSWORD_MAX_AGE_DAYS") {
+            Ok(d) => {
+                let res: u64 = d
+                    .trim()
+                    .parse()
+                    .expect("PASSWORD_MAX_AGE_DAYS must be a number");
+                res
+            }
+            Err(_) => 0,
+        };
+
+        let password_pepper = match env::var("PASSWORD_PEPPER") {
+            Ok(d) => Some(d).filter(|d| !d.is_empty()),
+            Err(_) => None,
+        };
+
+        let jwt_issuer = match env::var("JWT_ISSUER") {
+            Ok(d) => Some(d.trim().to_string()).filter(|d| !d.is_empty()),
+            Err(_) => None,
+        };
+
+        let jwt_audience = match env::var("JWT_AUDIENCE") {
+            Ok(d) => Some(d.trim().to_string()).filter(|d| !d.is_empty()),
+            Err(_) => None,
+        };
+
+        let jwt_roles_claim = match env::var("JWT_ROLES_CLAIM") {
+            Ok(d) => {
+                let res: bool = d.trim().parse().expect("JWT_ROLES_CLAIM must be a boolean");
+                res
+            }
+            Err(_) => false,
+        };
+
+        let jwt_permissions_claim = match env::var("JWT_PERMISSIONS_CLAIM") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("JWT_PERMISSIONS_CLAIM must be a boolean");
+                res
+            }
+            Err(_) => false,
+        };
+
+        let jwt_max_claims_size = match env::var("JWT_MAX_CLAIMS_SIZE") {
+            Ok(d) => {
+                let res: usize = d
+                    .trim()
+                    .parse()
+                    .expect("JWT_MAX_CLAIMS_SIZE must be a number");
+                res
+            }
+            Err(_) => 4096,
+        };
+
+        let jwt_max_sessions = match env::var("JWT_MAX_SESSIONS") {
+            Ok(d) => {
+                let res: usize = d.trim().parse().expect("JWT_MAX_SESSIONS must be a number");
+                res
+            }
+            Err(_) => 0,
+        };
+
+        let jwt_leeway = match env::var("JWT_LEEWAY_SECONDS") {
+            Ok(d) => {
+                let res: u64 = d
+                    .trim()
+                    .parse()
+                    .expect("JWT_LEEWAY_SECONDS must be a number");
+                res
+            }
+            Err(_) => 60,
+        };
+
+        let jwt_refresh_expiration = match env::var("JWT_REFRESH_EXPIRATION") {
+            Ok(d) => {
+                let res: usize = d
+                    .trim()
+                    .parse()
+                    .expect("JWT_REFRESH_EXPIRATION must be a number");
+                if res != 0 && res <= jwt_expiration {
+                    panic!("JWT_REFRESH_EXPIRATION must be greater than JWT_EXPIRATION");
+                }
+                res
+            }
+            Err(_) => 0,
+        };
+
         let generate_default_user = match env::var("GENERATE_DEFAULT_USER") {
             Ok(d) => {
                 let res: bool = d
@@ -166,6 +730,45 @@ impl EnvReader {
             Err(_) => 0,
         };
 
+        let audit_system_actor_id = match env::var("DB_AUDIT_SYSTEM_ACTOR_ID") {
+            Ok(d) => String::from(d.trim()),
+            Err(_) => String::from(DEFAULT_SYSTEM_ACTOR_ID),
+        };
+
+        let audit_failure_policy = match env::var("AUDIT_FAILURE") {
+            Ok(d) => match d.trim().to_lowercase().as_str() {
+                "fail" => AuditFailurePolicy::Fail,
+                "warn" => AuditFailurePolicy::Warn,
+                _ => panic!("AUDIT_FAILURE must be either fail or warn"),
+            },
+            Err(_) => AuditFailurePolicy::Fail,
+        };
+
+        let audit_batch_size = match env::var("DB_AUDIT_BATCH_SIZE") {
+            Ok(d) => {
+                let res: usize = d
+                    .trim()
+                    .parse()
+                    .expect("DB_AUDIT_BATCH_SIZE must be a number");
+                res
+            }
+            Err(_) => 0,
+        };
+
+        let audit_flush_interval = match env::var("DB_AUDIT_FLUSH_INTERVAL") {
+            Ok(d) => {
+                let res: u64 = d
+                    .trim()
+                    .parse()
+                    .expect("DB_AUDIT_FLUSH_INTERVAL must be a number");
+                if res == 0 {
+                    panic!("DB_AUDIT_FLUSH_INTERVAL must be greater than 0");
+                }
+                res
+            }
+            Err(_) => 1000,
+        };
+
         let create_indexes = match env::var("DB_CREATE_INDEXES") {
             Ok(d) => {
                 let res: bool = d
@@ -177,6 +780,235 @@ impl EnvReader {
             Err(_) => true,
         };
 
+        let allowed_email_domains: Vec<String> = match env::var("ALLOWED_EMAIL_DOMAINS") {
+            Ok(d) => d
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            Err(_) => vec![],
+        };
+
+        let blocked_email_domains: Vec<String> = match env::var("BLOCKED_EMAIL_DOMAINS") {
+            Ok(d) => d
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            Err(_) => vec![],
+        };
+
+        let block_disposable_emails = match env::var("BLOCK_DISPOSABLE_EMAILS") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("BLOCK_DISPOSABLE_EMAILS must be a boolean");
+                res
+            }
+            Err(_) => false,
+        };
+
+        let disposable_email_domains: Option<Vec<String>> =
+            match env::var("DISPOSABLE_EMAIL_DOMAINS_FILE") {
+                Ok(d) if !d.trim().is_empty() => {
+                    let contents = fs::read_to_string(d.trim())
+                        .expect("Failed to read DISPOSABLE_EMAIL_DOMAINS_FILE");
+                    Some(DisposableEmailPolicy::parse_domains(&contents))
+                }
+                _ => None,
+            };
+
+        let email_change_verification = match env::var("EMAIL_CHANGE_VERIFICATION") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("EMAIL_CHANGE_VERIFICATION must be a boolean");
+                res
+            }
+            Err(_) => false,
+        };
+
+        let name_normalization = match env::var("NAME_NORMALIZATION") {
+            Ok(d) => match d.trim().to_lowercase().as_str() {
+                "none" => NameNormalization::None,
+                "uppercase" => NameNormalization::Uppercase,
+                "slug" => NameNormalization::Slug,
+                _ => panic!("NAME_NORMALIZATION must be either none, uppercase or slug"),
+            },
+            Err(_) => NameNormalization::None,
+        };
+
+        let name_max_length = match env::var("NAME_MAX_LENGTH") {
+            Ok(d) => {
+                let res: usize = d.trim().parse().expect("NAME_MAX_LENGTH must be a number");
+                if res == 0 {
+                    panic!("NAME_MAX_LENGTH must be greater than zero");
+                }
+                res
+            }
+            Err(_) => 64,
+        };
+
+        let login_defense = match env::var("LOGIN_DEFENSE") {
+            Ok(d) => match d.trim().to_lowercase().as_str() {
+                "none" => LoginDefense::None,
+                "lockout" => LoginDefense::Lockout,
+                "throttle" => LoginDefense::Throttle,
+                _ => panic!("LOGIN_DEFENSE must be either none, lockout or throttle"),
+            },
+            Err(_) => LoginDefense::None,
+        };
+
+        let login_max_attempts = match env::var("LOGIN_MAX_ATTEMPTS") {
+            Ok(d) => {
+                let res: u32 = d
+                    .trim()
+                    .parse()
+                    .expect("LOGIN_MAX_ATTEMPTS must be a number");
+                if res == 0 {
+                    panic!("LOGIN_MAX_ATTEMPTS must be greater than zero");
+                }
+                res
+            }
+            Err(_) => 5,
+        };
+
+        let login_lockout_duration = match env::var("LOGIN_LOCKOUT_DURATION") {
+            Ok(d) => {
+                let res: u64 = d
+                    .trim()
+                    .parse()
+                    .expect("LOGIN_LOCKOUT_DURATION must be a number");
+                res
+            }
+            Err(_) => 900,
+        };
+
+        let login_throttle_delay = match env::var("LOGIN_THROTTLE_DELAY") {
+            Ok(d) => {
+                let res: u64 = d
+                    .trim()
+                    .parse()
+                    .expect("LOGIN_THROTTLE_DELAY must be a number");
+                res
+            }
+            Err(_) => 500,
+        };
+
+        let login_throttle_max_delay = match env::var("LOGIN_THROTTLE_MAX_DELAY") {
+            Ok(d) => {
+                let res: u64 = d
+                    .trim()
+                    .parse()
+                    .expect("LOGIN_THROTTLE_MAX_DELAY must be a number");
+                res
+            }
+            Err(_) => 10000,
+        };
+
+        let login_disclose_lockout = match env::var("LOGIN_DISCLOSE_LOCKOUT") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("LOGIN_DISCLOSE_LOCKOUT must be a boolean");
+                res
+            }
+            Err(_) => false,
+        };
+
+        let role_templates: Vec<RoleTemplate> = match env::var("ROLE_TEMPLATES_FILE") {
+            Ok(d) if !d.trim().is_empty() => {
+                let contents =
+                    fs::read_to_string(d.trim()).expect("Failed to read ROLE_TEMPLATES_FILE");
+                serde_json::from_str(&contents)
+                    .expect("ROLE_TEMPLATES_FILE must contain valid JSON")
+            }
+            _ => vec![],
+        };
+
+        let session_cookie_enabled = match env::var("SESSION_COOKIE_ENABLED") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("SESSION_COOKIE_ENABLED must be a boolean");
+                res
+            }
+            Err(_) => false,
+        };
+
+        let session_cookie_name = match env::var("SESSION_COOKIE_NAME") {
+            Ok(d) => {
+                let name = d.trim().to_string();
+                if name.is_empty() {
+                    panic!("SESSION_COOKIE_NAME must not be empty");
+                }
+                name
+            }
+            Err(_) => String::from("auth_rs_session"),
+        };
+
+        let session_cookie_secure = match env::var("SESSION_COOKIE_SECURE") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("SESSION_COOKIE_SECURE must be a boolean");
+                res
+            }
+            Err(_) => true,
+        };
+
+        let session_cookie_http_only = match env::var("SESSION_COOKIE_HTTP_ONLY") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("SESSION_COOKIE_HTTP_ONLY must be a boolean");
+                res
+            }
+            Err(_) => true,
+        };
+
+        let session_cookie_same_site = match env::var("SESSION_COOKIE_SAME_SITE") {
+            Ok(d) => match d.trim().to_lowercase().as_str() {
+                "strict" => SameSite::Strict,
+                "lax" => SameSite::Lax,
+                "none" => SameSite::None,
+                _ => panic!("SESSION_COOKIE_SAME_SITE must be either strict, lax or none"),
+            },
+            Err(_) => SameSite::Strict,
+        };
+
+        let session_cookie_allow_insecure = match env::var("SESSION_COOKIE_ALLOW_INSECURE") {
+            Ok(d) => {
+                let res: bool = d
+                    .trim()
+                    .parse()
+                    .expect("SESSION_COOKIE_ALLOW_INSECURE must be a boolean");
+                res
+            }
+            Err(_) => false,
+        };
+
+        // Cookies without the Secure attribute are sent over plain HTTP, which is only acceptable in development
+        if !session_cookie_secure && !session_cookie_allow_insecure {
+            panic!("SESSION_COOKIE_SECURE can only be disabled if SESSION_COOKIE_ALLOW_INSECURE is enabled");
+        }
+
+        // Browsers reject cookies with SameSite=None that do not have the Secure attribute
+        if session_cookie_same_site == SameSite::None && !session_cookie_secure {
+            panic!("SESSION_COOKIE_SAME_SITE can only be none if SESSION_COOKIE_SECURE is enabled");
+        }
+
+        // Browsers send the session cookie along with cross-origin requests, so only trusted origins may read responses
+        if session_cookie_enabled && cors_allowed_origins.is_empty() {
+            panic!("CORS_ALLOWED_ORIGINS must be set if SESSION_COOKIE_ENABLED is enabled");
+        }
+
         let enable_openapi = match env::var("ENABLE_OPENAPI") {
             Ok(d) => {
                 let res: bool = d.trim().parse().expect("ENABLE_OPENAPI must be a boolean");
@@ -199,19 +1031,104 @@ impl EnvReader {
             role_collection,
             user_collection,
             audit_collection,
+            session_collection,
+            token_collection,
+            migration_collection,
+            invite_collection,
             create_indexes,
             audit_enabled,
             audit_ttl,
+            audit_system_actor_id,
+            audit_failure_policy,
+            audit_batch_size,
+            audit_flush_interval,
         );
 
-        let server_config = ServerConfig::new(addr, port, max_limit, workers);
+        let server_config = ServerConfig::new(
+            addr,
+            port,
+            max_page_size,
+            workers,
+            route_prefix,
+            prefix_health,
+            idempotency_ttl,
+            allow_self_registration,
+            registration_mode,
+            invite_ttl,
+            import_conflict_policy,
+            tenancy,
+            default_roles,
+            require_default_role,
+            disclose_disabled_accounts,
+            trusted_proxies,
+            cors_allowed_origins,
+            rate_limit,
+            rate_limit_window,
+            enforce_json_content_type,
+            dev_mode,
+        );
 
         Config::new(
             server_config,
             db_config,
             default_user_config,
             generate_default_user,
-            JwtConfig::new(jwt_secret, jwt_expiration),
+            JwtConfig::new(
+                jwt_secret,
+                previous_jwt_secrets,
+                jwt_expiration,
+                jwt_issuer,
+                jwt_audience,
+                jwt_algorithm,
+                rsa_keys,
+                jwt_roles_claim,
+                jwt_permissions_claim,
+                jwt_max_claims_size,
+                jwt_max_sessions,
+                token_format,
+                paseto_private_key,
+                jwt_refresh_expiration,
+                token_mode,
+                jwt_leeway,
+            ),
+            PasswordConfig::new(
+                password_hash_algo,
+                bcrypt_cost,
+                password_pepper,
+                password_max_length,
+                password_history_size,
+                password_max_age_days,
+                argon2_memory_cost,
+                argon2_time_cost,
+                argon2_parallelism,
+                argon2_target_hash_time,
+                argon2_autotune,
+            ),
+            EmailConfig::new(
+                allowed_email_domains,
+                blocked_email_domains,
+                block_disposable_emails,
+                disposable_email_domains,
+                email_change_verification,
+            ),
+            NameConfig::new(name_normalization, name_max_length),
+            LoginDefenseConfig::new(
+                login_defense,
+                login_max_attempts,
+                login_lockout_duration,
+                login_throttle_delay,
+                login_throttle_max_delay,
+                login_disclose_lockout,
+            ),
+            role_templates,
+            CookieConfig::new(
+                session_cookie_enabled,
+                session_cookie_name,
+                session_cookie_secure,
+                session_cookie_http_only,
+                session_cookie_same_site,
+                session_cookie_allow_insecure,
+            ),
             enable_openapi,
         )
         .await
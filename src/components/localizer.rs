@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+/// The locale that is used if a client does not accept any of the supported locales.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// The user-facing messages that can be localized. Placeholders in a message are written as `{}` and are replaced by
+/// the arguments in order.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Message {
+    UsernameRequired,
+    PasswordRequired,
+    EmptyUsername,
+    EmptyPassword,
+    PasswordTooLong,
+    PasswordReused,
+    EmailDomainNotAllowed,
+    InviteTokenRequired,
+    InviteTokenInvalid,
+    InviteEmailMismatch,
+}
+
+const EN: [(Message, &str); 10] = [
+    (Message::UsernameRequired, "Username is required"),
+    (Message::PasswordRequired, "Password is required"),
+    (Message::EmptyUsername, "Empty usernames are not allowed"),
+    (Message::EmptyPassword, "Empty passwords are not allowed"),
+    (
+        Message::PasswordTooLong,
+        "Passwords may not be longer than {} bytes",
+    ),
+    (
+        Message::PasswordReused,
+        "Passwords may not match any of the last {} passwords",
+    ),
+    (
+        Message::EmailDomainNotAllowed,
+        "Email addresses from this domain are not allowed",
+    ),
+    (
+        Message::InviteTokenRequired,
+        "An invite token is required to register",
+    ),
+    (
+        Message::InviteTokenInvalid,
+        "The invite token is invalid, expired or already used",
+    ),
+    (
+        Message::InviteEmailMismatch,
+        "The email address does not match the invite",
+    ),
+];
+
+const NL: [(Message, &str); 10] = [
+    (Message::UsernameRequired, "Gebruikersnaam is verplicht"),
+    (Message::PasswordRequired, "Wachtwoord is verplicht"),
+    (
+        Message::EmptyUsername,
+        "Lege gebruikersnamen zijn niet toegestaan",
+    ),
+    (
+        Message::EmptyPassword,
+        "Lege wachtwoorden zijn niet toegestaan",
+    ),
+    (
+        Message::PasswordTooLong,
+        "Wachtwoorden mogen niet langer zijn dan {} bytes",
+    ),
+    (
+        Message::PasswordReused,
+        "Wachtwoorden mogen niet overeenkomen met een van de laatste {} wachtwoorden",
+    ),
+    (
+        Message::EmailDomainNotAllowed,
+        "E-mailadressen van dit domein zijn niet toegestaan",
+    ),
+    (
+        Message::InviteTokenRequired,
+        "Een uitnodigingstoken is vereist om te registreren",
+    ),
+    (
+        Message::InviteTokenInvalid,
+        "Het uitnodigingstoken is ongeldig, verlopen of al gebruikt",
+    ),
+    (
+        Message::InviteEmailMismatch,
+        "Het e-mailadres komt niet overeen met de uitnodiging",
+    ),
+];
+
+/// Looks up the user-facing messages in the locale that a client prefers. Messages that are missing from a locale fall
+/// back to the DEFAULT_LOCALE.
+pub struct Localizer {
+    locales: HashMap<&'static str, HashMap<Message, &'static str>>,
+}
+
+impl Localizer {
+    /// # Summary
+    ///
+    /// Create a new Localizer with the built-in locales.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let localizer = Localizer::new();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Localizer` - The new Localizer.
+    pub fn new() -> Localizer {
+        let mut locales = HashMap::new();
+        locales.insert(DEFAULT_LOCALE, HashMap::from(EN));
+        locales.insert("nl", HashMap::from(NL));
+
+        Localizer { locales }
+    }
+
+    /// # Summary
+    ///
+    /// Pick the supported locale that a client prefers, based on the value of its Accept-Language header. Languages are
+    /// tried in the order of their quality, and a language with a region, such as `nl-BE`, also matches its primary
+    /// language.
+    ///
+    /// # Arguments
+    ///
+    /// * `accept_language` - The value of the Accept-Language header, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let locale = localizer.negotiate(Some("nl-BE,nl;q=0.9,en;q=0.8"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `&'static str` - The supported locale, or the DEFAULT_LOCALE if none of the languages is supported.
+    pub fn negotiate(&self, accept_language: Option<&str>) -> &'static str {
+        let accept_language = match accept_language {
+            Some(a) => a,
+            None => return DEFAULT_LOCALE,
+        };
+
+        let mut languages: Vec<(String, f32)> = accept_language
+            .split(',')
+            .filter_map(|l| {
+                let mut parts = l.split(';');
+                let tag = parts.next()?.trim().to_ascii_lowercase();
+                let quality = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+                    .unwrap_or(1.0);
+
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // The sort is stable, so languages with the same quality keep the order of the header
+        languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        for (tag, _) in &languages {
+            if tag == "*" {
+                return DEFAULT_LOCALE;
+            }
+
+            let primary = tag.split('-').next().unwrap_or_default();
+            for candidate in [tag.as_str(), primary] {
+                if let Some((locale, _)) = self.locales.get_key_value(candidate) {
+                    return locale;
+                }
+            }
+        }
+
+        DEFAULT_LOCALE
+    }
+
+    /// # Summary
+    ///
+    /// Look up a message in a locale and fill in its placeholders.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The Message.
+    /// * `locale` - The locale, as returned by negotiate.
+    /// * `args` - The values of the placeholders of the message, in order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let text = localizer.localize(Message::PasswordTooLong, "nl", &["128"]);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The localized message.
+    pub fn localize(&self, message: Message, locale: &str, args: &[&str]) -> String {
+        let text = self
+            .locales
+            .get(locale)
+            .and_then(|m| m.get(&message))
+            .or_else(|| self.locales[DEFAULT_LOCALE].get(&message))
+            .copied()
+            .unwrap_or_default();
+
+        args.iter()
+            .fold(String::from(text), |t, a| t.replacen("{}", a, 1))
+    }
+}
+
+impl Default for Localizer {
+    fn default() -> Self {
+        Localizer::new()
+    }
+}
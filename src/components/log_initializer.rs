@@ -0,0 +1,64 @@
+use crate::web::middleware::request_id;
+use chrono::{SecondsFormat, Utc};
+use env_logger::Env;
+use serde_json::json;
+use std::io::Write;
+
+#[derive(Clone, Copy)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+pub struct LogInitializer {}
+
+impl LogInitializer {
+    /// # Summary
+    ///
+    /// Initialize the global logger using the given format.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The LogFormat that should be used to write log lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// LogInitializer::init(LogFormat::Json);
+    /// ```
+    pub fn init(format: LogFormat) {
+        let mut builder = env_logger::Builder::from_env(Env::default().default_filter_or("info"));
+
+        match format {
+            LogFormat::Text => builder.format(|buf, record| {
+                let request_id = match request_id::current() {
+                    Some(id) => format!(" request_id={}", id),
+                    None => String::new(),
+                };
+
+                writeln!(
+                    buf,
+                    "[{} {:<5} {}{}] {}",
+                    buf.timestamp(),
+                    record.level(),
+                    record.target(),
+                    request_id,
+                    record.args()
+                )
+            }),
+            LogFormat::Json => builder.format(|buf, record| {
+                let line = json!({
+                    "timestamp": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+                    "level": record.level().to_string(),
+                    "target": record.target(),
+                    "message": record.args().to_string(),
+                    "request_id": request_id::current(),
+                });
+
+                writeln!(buf, "{}", line)
+            }),
+        };
+
+        builder.init();
+    }
+}
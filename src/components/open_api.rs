@@ -25,44 +25,91 @@ impl Modify for SecurityAddon {
 #[openapi(
     paths(
         crate::web::controller::authentication::authentication_controller::login,
+        crate::web::controller::authentication::authentication_controller::refresh,
         crate::web::controller::authentication::authentication_controller::register,
         crate::web::controller::authentication::authentication_controller::current_user,
+        crate::web::controller::authentication::authentication_controller::introspect,
+        crate::web::controller::authentication::authentication_controller::change_password,
+        crate::web::controller::authz::authz_controller::check,
         crate::web::controller::health::health_controller::health,
+        crate::web::controller::health::health_controller::live,
+        crate::web::controller::health::health_controller::ready,
+        crate::web::controller::version::version_controller::version,
+        crate::web::controller::well_known::well_known_controller::jwks,
         crate::web::controller::permission::permission_controller::create_permission,
         crate::web::controller::permission::permission_controller::find_all_permissions,
         crate::web::controller::permission::permission_controller::find_by_id,
         crate::web::controller::permission::permission_controller::update_permission,
         crate::web::controller::permission::permission_controller::delete_permission,
         crate::web::controller::role::role_controller::create,
+        crate::web::controller::role::role_controller::create_from_template,
         crate::web::controller::role::role_controller::find_all_roles,
         crate::web::controller::role::role_controller::find_by_id,
         crate::web::controller::role::role_controller::update,
         crate::web::controller::role::role_controller::delete,
+        crate::web::controller::role::role_controller::add_permissions,
+        crate::web::controller::role::role_controller::remove_permission,
         crate::web::controller::user::user_controller::create,
         crate::web::controller::user::user_controller::find_all,
+        crate::web::controller::user::user_controller::search_users,
         crate::web::controller::user::user_controller::find_by_id,
         crate::web::controller::user::user_controller::update,
         crate::web::controller::user::user_controller::update_self,
+        crate::web::controller::user::user_controller::find_own_permissions,
+        crate::web::controller::user::user_controller::patch_self,
+        crate::web::controller::user::user_controller::confirm_email_change,
         crate::web::controller::user::user_controller::update_password,
         crate::web::controller::user::user_controller::admin_update_password,
+        crate::web::controller::user::user_controller::admin_reset_password,
         crate::web::controller::user::user_controller::delete,
         crate::web::controller::user::user_controller::delete_self,
+        crate::web::controller::session::session_controller::find_own_sessions,
+        crate::web::controller::session::session_controller::revoke_own_session,
+        crate::web::controller::session::session_controller::revoke_all_own_sessions,
         crate::web::controller::audit::audit_controller::find_all,
+        crate::web::controller::invite::invite_controller::create,
+        crate::web::controller::invite::invite_controller::find_all,
+        crate::web::controller::admin::admin_controller::export_users,
+        crate::web::controller::admin::admin_controller::export_audits,
+        crate::web::controller::admin::admin_controller::import_users,
+        crate::web::controller::audit::audit_controller::stats,
+        crate::web::controller::audit::audit_controller::delete_before,
         crate::web::controller::audit::audit_controller::find_by_id,
     ),
     components(
         schemas(
             crate::errors::internal_server_error::InternalServerError,
+            crate::errors::service_unavailable::ServiceUnavailable,
+            crate::errors::too_many_requests::TooManyRequests,
+            crate::errors::unsupported_media_type::UnsupportedMediaType,
             crate::errors::bad_request::BadRequest,
+            crate::errors::conflict::Conflict,
+            crate::errors::forbidden::Forbidden,
+            crate::errors::not_found::NotFound,
             crate::web::dto::permission::create_permission::CreatePermission,
             crate::web::dto::permission::permission_dto::PermissionDto,
             crate::web::dto::permission::update_permission::UpdatePermission,
             crate::web::controller::health::health_controller::HealthResponse,
+            crate::web::controller::health::health_controller::LivenessResponse,
+            crate::web::controller::health::health_controller::ReadinessResponse,
+            crate::web::controller::version::version_controller::VersionResponse,
+            crate::services::jwt::jwk::Jwk,
+            crate::services::jwt::jwk::JwkSet,
             crate::web::dto::authentication::login_request::LoginRequest,
             crate::web::dto::authentication::login_response::LoginResponse,
+            crate::web::dto::authentication::refresh_request::RefreshRequest,
+            crate::web::dto::authentication::account_locked_response::AccountLockedResponse,
+            crate::web::dto::authentication::password_change_required_response::PasswordChangeRequiredResponse,
+            crate::web::dto::authentication::introspect_response::IntrospectResponse,
+            crate::web::dto::authentication::change_password_request::ChangePasswordRequest,
             crate::web::dto::authentication::register_request::RegisterRequest,
+            crate::web::dto::authz::check_permissions::CheckPermissionsRequest,
+            crate::web::dto::authz::check_permissions::CheckPermissionsResponse,
             crate::web::dto::user::user_dto::SimpleUserDto,
+            crate::web::dto::user::user_dto::DetailedUserDto,
+            crate::web::dto::session::session_dto::SessionDto,
             crate::web::dto::role::role_dto::SimpleRoleDto,
+            crate::web::dto::role::role_dto::DetailedRoleDto,
             crate::web::dto::permission::permission_dto::SimplePermissionDto,
             crate::web::dto::role::role_dto::RoleDto,
             crate::web::dto::role::create_role::CreateRole,
@@ -71,10 +118,29 @@ impl Modify for SecurityAddon {
             crate::web::dto::user::user_dto::UserDto,
             crate::web::dto::user::update_user::UpdateUser,
             crate::web::dto::user::update_user::UpdateOwnUser,
+            crate::web::dto::user::update_user::PatchOwnUser,
+            crate::web::dto::user::update_user::ConfirmEmailChange,
             crate::web::dto::user::update_password::UpdatePassword,
             crate::web::dto::user::update_password::AdminUpdatePassword,
+            crate::web::dto::user::update_password::AdminResetPassword,
+            crate::web::dto::user::update_password::AdminResetPasswordResponse,
             crate::web::dto::audit::audit_dto::AuditDto,
+            crate::web::dto::audit::audit_actor_dto::AuditActorDto,
+            crate::web::dto::common::page::AuditPage,
+            crate::web::dto::common::page::InvitePage,
+            crate::web::dto::invite::create_invite::CreateInvite,
+            crate::web::dto::invite::invite_dto::InviteDto,
+            crate::web::dto::common::page::PermissionPage,
+            crate::web::dto::permission::delete_permission_response::DeletePermissionResponse,
+            crate::web::dto::common::page::RolePage,
+            crate::web::dto::common::page::SessionPage,
+            crate::web::dto::common::page::UserPage,
+            crate::web::dto::user::user_export::UserExport,
+            crate::web::dto::user::user_import_response::UserImportResponse,
+            crate::web::dto::audit::audit_stats_dto::AuditStatsDto,
+            crate::web::dto::audit::delete_audits::DeleteAuditsResponse,
             crate::web::dto::audit::audit_dto::ActionDto,
+            crate::web::dto::audit::audit_dto::LoginOutcomeDto,
             crate::web::dto::audit::audit_dto::ResourceIdTypeDto,
             crate::web::dto::audit::audit_dto::ResourceTypeDto,
         )
@@ -82,3 +148,39 @@ impl Modify for SecurityAddon {
     modifiers(&SecurityAddon)
 )]
 pub struct ApiDoc;
+
+impl ApiDoc {
+    /// # Summary
+    ///
+    /// Rewrites the documented paths so that they match the configured route prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `openapi` - A mutable reference to the OpenAPI specification.
+    /// * `route_prefix` - The prefix under which the API routes are mounted.
+    /// * `prefix_health` - Whether the health endpoint is mounted under the route prefix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut openapi = ApiDoc::openapi();
+    /// ApiDoc::apply_route_prefix(&mut openapi, "/api/v2", false);
+    /// ```
+    pub fn apply_route_prefix(
+        openapi: &mut openapi::OpenApi,
+        route_prefix: &str,
+        prefix_health: bool,
+    ) {
+        let paths = std::mem::take(&mut openapi.paths.paths);
+        for (path, item) in paths {
+            let new_path = match path.strip_prefix("/api/v1") {
+                Some(p) => format!("{}{}", route_prefix, p),
+                None if prefix_health && path.starts_with("/health") => {
+                    format!("{}{}", route_prefix, path)
+                }
+                None => path,
+            };
+            openapi.paths.paths.insert(new_path, item);
+        }
+    }
+}
@@ -1,5 +1,11 @@
 pub mod config;
+pub mod cookie_config;
 pub mod db_config;
 pub mod default_user_config;
+pub mod email_config;
 pub mod jwt_config;
+pub mod login_defense_config;
+pub mod name_config;
+pub mod password_config;
+pub mod role_template;
 pub mod server_config;
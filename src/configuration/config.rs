@@ -1,35 +1,68 @@
+use crate::components::localizer::Localizer;
+use crate::configuration::cookie_config::CookieConfig;
 use crate::configuration::db_config::DbConfig;
 use crate::configuration::default_user_config::DefaultUserConfig;
-use crate::configuration::jwt_config::JwtConfig;
+use crate::configuration::email_config::EmailConfig;
+use crate::configuration::jwt_config::{JwtConfig, TokenFormat, TokenMode};
+use crate::configuration::login_defense_config::LoginDefenseConfig;
+use crate::configuration::name_config::NameConfig;
+use crate::configuration::password_config::PasswordConfig;
+use crate::configuration::role_template::RoleTemplate;
 use crate::configuration::server_config::ServerConfig;
 use crate::repository::audit::audit_model::Audit;
 use crate::repository::audit::audit_repository::AuditRepository;
+use crate::repository::invite::invite_model::Invite;
+use crate::repository::invite::invite_repository::InviteRepository;
+use crate::repository::migration::migration_repository::MigrationRepository;
 use crate::repository::permission::permission_model::Permission;
 use crate::repository::permission::permission_repository::PermissionRepository;
 use crate::repository::role::role_model::Role;
 use crate::repository::role::role_repository::RoleRepository;
+use crate::repository::session::session_model::Session;
+use crate::repository::session::session_repository::SessionRepository;
+use crate::repository::token::token_model::OpaqueToken;
+use crate::repository::token::token_repository::TokenRepository;
 use crate::repository::user::user_model::User;
 use crate::repository::user::user_repository::UserRepository;
+use crate::services::audit::audit_buffer::AuditBuffer;
 use crate::services::audit::audit_service::AuditService;
+use crate::services::email::email_service::EmailService;
+use crate::services::event::event_service::EventService;
+use crate::services::event::log_event_publisher::LogEventPublisher;
+use crate::services::idempotency::idempotency_service::IdempotencyService;
+use crate::services::idempotency::memory_idempotency_store::MemoryIdempotencyStore;
+use crate::services::invite::invite_service::InviteService;
 use crate::services::jwt::jwt_service::JwtService;
+use crate::services::login_defense::login_defense_service::LoginDefenseService;
+use crate::services::migration::migration_service::MigrationService;
+use crate::services::paseto::paseto_service::PasetoService;
 use crate::services::password::password_service::PasswordService;
 use crate::services::permission::permission_service::PermissionService;
 use crate::services::role::role_service::RoleService;
+use crate::services::session::session_service::SessionService;
+use crate::services::token::opaque_token_service::OpaqueTokenService;
+use crate::services::token::token_service::TokenService;
 use crate::services::user::user_service::UserService;
 use crate::services::Services;
-use log::{error, info};
+use log::{error, info, warn};
 use mongodb::bson::doc;
 use mongodb::bson::oid::ObjectId;
 use mongodb::error::ErrorKind;
-use mongodb::options::{ClientOptions, IndexOptions, ServerApi, ServerApiVersion};
+use mongodb::options::{
+    ClientOptions, Collation, CollationStrength, IndexOptions, ServerApi, ServerApiVersion,
+};
 use mongodb::{Client, Database, IndexModel};
 use regex::Regex;
+use std::sync::Arc;
+use std::time::Duration;
 
-#[derive(Clone)]
 pub struct Config {
     pub server_config: ServerConfig,
     pub database: Database,
     pub services: Services,
+    pub token_service: Box<dyn TokenService>,
+    pub cookie_config: CookieConfig,
+    pub localizer: Localizer,
     pub open_api: bool,
 }
 
@@ -45,17 +78,30 @@ impl Config {
     /// * `default_user_config` - A DefaultUserConfig instance.
     /// * `generate_default_user` - A bool that indicates whether to generate a default user or not.
     /// * `jwt_config` - A JwtConfig instance.
+    /// * `password_config` - A PasswordConfig instance.
+    /// * `email_config` - An EmailConfig instance.
+    /// * `name_config` - A NameConfig instance.
+    /// * `login_defense_config` - A LoginDefenseConfig instance.
+    /// * `role_templates` - The RoleTemplates that can be used to create Roles.
+    /// * `cookie_config` - A CookieConfig instance.
     /// * `open_api` - A bool that indicates whether to enable OpenAPI or not.
     ///
     /// # Returns
     ///
     /// A Config instance.
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         server_config: ServerConfig,
         db_config: DbConfig,
         default_user_config: DefaultUserConfig,
         generate_default_user: bool,
         jwt_config: JwtConfig,
+        password_config: PasswordConfig,
+        email_config: EmailConfig,
+        name_config: NameConfig,
+        login_defense_config: LoginDefenseConfig,
+        role_templates: Vec<RoleTemplate>,
+        cookie_config: CookieConfig,
         open_api: bool,
     ) -> Config {
         let mut client_options = match ClientOptions::parse(&db_config.connection_string).await {
@@ -84,23 +130,112 @@ impl Config {
             Ok(d) => d,
             Err(e) => panic!("Failed to initialize Audit repository: {:?}", e),
         };
+        let session_repository = match SessionRepository::new(db_config.session_collection.clone())
+        {
+            Ok(d) => d,
+            Err(e) => panic!("Failed to initialize Session repository: {:?}", e),
+        };
+        let token_repository = match TokenRepository::new(db_config.token_collection.clone()) {
+            Ok(d) => d,
+            Err(e) => panic!("Failed to initialize Token repository: {:?}", e),
+        };
+        let invite_repository = match InviteRepository::new(db_config.invite_collection.clone()) {
+            Ok(d) => d,
+            Err(e) => panic!("Failed to initialize Invite repository: {:?}", e),
+        };
+
+        let migration_repository = match MigrationRepository::new(
+            db_config.migration_collection.clone(),
+            db_config.user_collection.clone(),
+            db_config.role_collection.clone(),
+            db_config.permission_collection.clone(),
+            db_config.audit_collection.clone(),
+        ) {
+            Ok(d) => d,
+            Err(e) => panic!("Failed to initialize Migration repository: {:?}", e),
+        };
 
         let email_regex = Regex::new(
             r"^([a-z0-9_+]([a-z0-9_+.]*[a-z0-9_+])?)@([a-z0-9]+([\-.][a-z0-9]+)*\.[a-z]{2,6})",
         )
         .unwrap();
 
-        let user_repository =
-            match UserRepository::new(db_config.user_collection.clone(), email_regex.clone()) {
-                Ok(d) => d,
-                Err(e) => panic!("Failed to initialize User repository: {:?}", e),
-            };
+        let user_repository = match UserRepository::new(
+            db_config.user_collection.clone(),
+            email_regex.clone(),
+            server_config.tenancy,
+        ) {
+            Ok(d) => d,
+            Err(e) => panic!("Failed to initialize User repository: {:?}", e),
+        };
 
-        let permission_service = PermissionService::new(permission_repository);
-        let role_service = RoleService::new(role_repository);
-        let user_service = UserService::new(user_repository);
-        let audit_service = AuditService::new(audit_repository, db_config.audit_enabled);
+        let transaction_client = if Config::supports_transactions(&db).await {
+            info!("Creating Users and deleting Roles and Permissions in transactions");
+            Some(client.clone())
+        } else {
+            info!("Transactions are not supported by the deployment, creating Users and deleting Roles and Permissions without transactions");
+            None
+        };
+        let permission_service = PermissionService::new(
+            permission_repository,
+            name_config.clone(),
+            transaction_client.clone(),
+        );
+        let role_service = RoleService::new(
+            role_repository,
+            name_config,
+            role_templates,
+            transaction_client.clone(),
+        );
+        let user_service = UserService::new(user_repository, transaction_client);
+        let system_actor_id = match ObjectId::parse_str(&db_config.audit_system_actor_id) {
+            Ok(d) => d,
+            Err(e) => panic!("Invalid audit system actor ID: {}", e),
+        };
+        let audit_buffer = if db_config.audit_enabled && db_config.audit_batch_size > 0 {
+            info!(
+                "Writing audits in batches of {} or every {} ms",
+                db_config.audit_batch_size, db_config.audit_flush_interval
+            );
+            Some(AuditBuffer::start(
+                audit_repository.clone(),
+                db.clone(),
+                db_config.audit_batch_size,
+                Duration::from_millis(db_config.audit_flush_interval),
+            ))
+        } else {
+            None
+        };
+        let audit_service = AuditService::new(
+            audit_repository,
+            db_config.audit_enabled,
+            system_actor_id,
+            db_config.audit_failure_policy,
+            audit_buffer,
+        );
+        let session_service = SessionService::new(session_repository, jwt_config.max_sessions);
+        let token_mode = jwt_config.mode;
+        let token_service: Box<dyn TokenService> = match (token_mode, jwt_config.format) {
+            (TokenMode::Opaque, _) => Box::new(OpaqueTokenService::new(
+                jwt_config.clone(),
+                token_repository,
+                db.clone(),
+            )),
+            (TokenMode::Stateless, TokenFormat::Jwt) => {
+                Box::new(JwtService::new(jwt_config.clone()))
+            }
+            (TokenMode::Stateless, TokenFormat::Paseto) => {
+                Box::new(PasetoService::new(jwt_config.clone()))
+            }
+        };
         let jwt_service = JwtService::new(jwt_config);
+        let password_service = PasswordService::new(password_config);
+        let idempotency_service = IdempotencyService::new(
+            Arc::new(MemoryIdempotencyStore::new()),
+            server_config.idempotency_ttl,
+        );
+        let email_service = EmailService::new(email_config);
+        let invite_service = InviteService::new(invite_repository, server_config.invite_ttl);
 
         let services = Services::new(
             permission_service,
@@ -108,26 +243,53 @@ impl Config {
             user_service,
             jwt_service,
             audit_service,
+            password_service,
+            idempotency_service,
+            email_service,
+            session_service,
+            MigrationService::new(migration_repository),
+            LoginDefenseService::new(login_defense_config),
+            EventService::new(Arc::new(LogEventPublisher::new())),
+            invite_service,
         );
 
         let cfg = Config {
             server_config,
             database: db,
             services,
+            token_service,
+            cookie_config,
+            localizer: Localizer::new(),
             open_api,
         };
 
+        match cfg.services.migration_service.run(&cfg.database).await {
+            Ok(v) if v.is_empty() => info!("The database schema is up to date"),
+            Ok(v) => info!("Applied migrations: {:?}", v),
+            Err(e) => panic!("Failed to apply migrations: {}", e),
+        }
+
         if generate_default_user {
             cfg.initialize_database(default_user_config, email_regex)
                 .await;
         }
 
+        cfg.initialize_role_templates().await;
+        cfg.verify_default_role().await;
+
         if db_config.create_indexes {
             cfg.create_permission_indexes(&db_config.permission_collection)
                 .await;
             cfg.create_role_indexes(&db_config.role_collection).await;
             cfg.create_user_indexes(&db_config.user_collection).await;
             cfg.create_audit_indexes(&db_config.audit_collection).await;
+            cfg.create_session_indexes(&db_config.session_collection)
+                .await;
+            cfg.create_invite_indexes(&db_config.invite_collection)
+                .await;
+            if token_mode == TokenMode::Opaque {
+                cfg.create_token_indexes(&db_config.token_collection).await;
+            }
             cfg.create_or_delete_audit_ttl_index(db_config.audit_ttl, &db_config.audit_collection)
                 .await;
         }
@@ -135,6 +297,67 @@ impl Config {
         cfg
     }
 
+    /// # Summary
+    ///
+    /// Check whether the deployment supports multi-document transactions, which requires a replica set or a sharded
+    /// cluster.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to check.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the deployment supports transactions.
+    async fn supports_transactions(db: &Database) -> bool {
+        match db.run_command(doc! { "hello": 1 }, None).await {
+            Ok(hello) => {
+                hello.contains_key("setName")
+                    || hello
+                        .get_str("msg")
+                        .map(|m| m == "isdbgrid")
+                        .unwrap_or(false)
+            }
+            Err(e) => {
+                error!(
+                    "Failed to determine whether transactions are supported: {}",
+                    e
+                );
+                false
+            }
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the database can be reached within the given amount of time.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The maximum amount of time to wait for the database to respond.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the database responded to a ping in time.
+    pub async fn ping_database(&self, timeout: Duration) -> bool {
+        match actix_web::rt::time::timeout(
+            timeout,
+            self.database.run_command(doc! { "ping": 1 }, None),
+        )
+        .await
+        {
+            Ok(Ok(_)) => true,
+            Ok(Err(e)) => {
+                error!("Failed to ping the database: {}", e);
+                false
+            }
+            Err(_) => {
+                error!("Timed out while pinging the database");
+                false
+            }
+        }
+    }
+
     /// # Summary
     ///
     /// Find or create a permission.
@@ -268,7 +491,7 @@ impl Config {
         match self
             .services
             .user_service
-            .find_by_username(&default_user_config.username, &self.database)
+            .find_by_username(&default_user_config.username, None, &self.database)
             .await
         {
             Ok(user) => {
@@ -284,18 +507,21 @@ impl Config {
         match self
             .services
             .user_service
-            .find_by_username(&default_user_config.username, &self.database)
+            .find_by_username(&default_user_config.username, None, &self.database)
             .await
         {
             Ok(d) => {
                 if d.is_none() {
-                    let password_hash =
-                        match PasswordService::hash_password(default_user_config.password) {
-                            Ok(e) => e,
-                            Err(e) => {
-                                panic!("Failed to hash password: {}", e);
-                            }
-                        };
+                    let password_hash = match self
+                        .services
+                        .password_service
+                        .hash_password(&default_user_config.password)
+                    {
+                        Ok(e) => e,
+                        Err(e) => {
+                            panic!("Failed to hash password: {}", e);
+                        }
+                    };
 
                     let user = User::new(
                         default_user_config.username,
@@ -346,6 +572,27 @@ impl Config {
             .await
             .expect("Creating an index should succeed");
 
+        // Names are unique regardless of their case
+        let collation = Collation::builder()
+            .locale("en")
+            .strength(CollationStrength::Secondary)
+            .build();
+        let options = IndexOptions::builder()
+            .name(String::from("name_unique"))
+            .unique(true)
+            .collation(collation)
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "name": 1u32 })
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Permission>(permission_collection)
+            .create_index(model, None)
+            .await
+            .expect("Creating a unique index on the Permission name should succeed");
+
         let options = IndexOptions::builder().build();
         let model = IndexModel::builder()
             .keys(doc! { "name": "text" })
@@ -384,6 +631,27 @@ impl Config {
             .await
             .expect("Creating an index should succeed");
 
+        // Names are unique regardless of their case
+        let collation = Collation::builder()
+            .locale("en")
+            .strength(CollationStrength::Secondary)
+            .build();
+        let options = IndexOptions::builder()
+            .name(String::from("name_unique"))
+            .unique(true)
+            .collation(collation)
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "name": 1u32 })
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Role>(role_collection)
+            .create_index(model, None)
+            .await
+            .expect("Creating a unique index on the Role name should succeed");
+
         let options = IndexOptions::builder().build();
         let model = IndexModel::builder()
             .keys(doc! { "name": "text" })
@@ -434,6 +702,23 @@ impl Config {
             .await
             .expect("Creating an index should succeed");
 
+        if self.server_config.tenancy {
+            // Usernames and email addresses are looked up within a tenant
+            for key in ["username", "email"] {
+                let options = IndexOptions::builder().build();
+                let model = IndexModel::builder()
+                    .keys(doc! { "tenantId": 1u32, key: 1u32 })
+                    .options(options)
+                    .build();
+
+                self.database
+                    .collection::<User>(user_collection)
+                    .create_index(model, None)
+                    .await
+                    .expect("Creating an index should succeed");
+            }
+        }
+
         let options = IndexOptions::builder().build();
         let model = IndexModel::builder()
             .keys(doc! { "username": "text", "email": "text", "firstName": "text", "lastName": "text"})
@@ -510,6 +795,131 @@ impl Config {
             .expect("Creating an index should succeed");
     }
 
+    /// # Summary
+    ///
+    /// Create default indexes for the Session collection, including a TTL index that removes expired Sessions.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_collection` - A string slice that holds the name of the Session collection.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the indexes could not be created.
+    pub async fn create_session_indexes(&self, session_collection: &str) {
+        info!("Creating indexes for the Session collection");
+
+        let options = IndexOptions::builder().unique(true).build();
+        let model = IndexModel::builder()
+            .keys(doc! { "jti": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Session>(session_collection)
+            .create_index(model, None)
+            .await
+            .expect("Creating an index should succeed");
+
+        let options = IndexOptions::builder().build();
+        let model = IndexModel::builder()
+            .keys(doc! { "userId": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Session>(session_collection)
+            .create_index(model, None)
+            .await
+            .expect("Creating an index should succeed");
+
+        // Expired Sessions are removed by MongoDB as soon as their token expires
+        let options = IndexOptions::builder()
+            .expire_after(Some(std::time::Duration::from_secs(0)))
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "expiresAt": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Session>(session_collection)
+            .create_index(model, None)
+            .await
+            .expect("Creating an index should succeed");
+    }
+
+    /// # Summary
+    ///
+    /// Create a TTL index for the collection that holds the opaque tokens, which removes expired tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_collection` - A string slice that holds the name of the collection that holds the opaque tokens.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the index could not be created.
+    pub async fn create_token_indexes(&self, token_collection: &str) {
+        info!("Creating indexes for the Token collection");
+
+        let options = IndexOptions::builder()
+            .expire_after(Some(std::time::Duration::from_secs(0)))
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "expiresAt": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<OpaqueToken>(token_collection)
+            .create_index(model, None)
+            .await
+            .expect("Creating an index should succeed");
+    }
+
+    /// # Summary
+    ///
+    /// Create default indexes for the Invite collection, including a TTL index that removes expired Invites.
+    ///
+    /// # Arguments
+    ///
+    /// * `invite_collection` - A string slice that holds the name of the Invite collection.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the indexes could not be created.
+    pub async fn create_invite_indexes(&self, invite_collection: &str) {
+        info!("Creating indexes for the Invite collection");
+
+        let options = IndexOptions::builder().unique(true).build();
+        let model = IndexModel::builder()
+            .keys(doc! { "tokenHash": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Invite>(invite_collection)
+            .create_index(model, None)
+            .await
+            .expect("Creating an index should succeed");
+
+        // Expired Invites are removed by MongoDB, whether they were used or not
+        let options = IndexOptions::builder()
+            .expire_after(Some(std::time::Duration::from_secs(0)))
+            .build();
+        let model = IndexModel::builder()
+            .keys(doc! { "expiresAt": 1u32})
+            .options(options)
+            .build();
+
+        self.database
+            .collection::<Invite>(invite_collection)
+            .create_index(model, None)
+            .await
+            .expect("Creating an index should succeed");
+    }
+
     /// # Summary
     ///
     /// Create or delete a TTL index for the Audit collection.
@@ -571,6 +981,79 @@ impl Config {
         }
     }
 
+    /// # Summary
+    ///
+    /// Check whether the default Roles that are assigned to self-registered Users exist. Missing Roles are skipped with a
+    /// warning.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if none of the default Roles exist while self-registration is allowed and a default Role
+    /// is required, or if the database connection is invalid.
+    async fn verify_default_role(&self) {
+        if !self.server_config.allow_self_registration
+            || self.server_config.default_roles.is_empty()
+        {
+            return;
+        }
+
+        match self
+            .services
+            .role_service
+            .find_by_names(&self.server_config.default_roles, &self.database)
+            .await
+        {
+            Ok(roles) if !roles.is_empty() => {}
+            Ok(_) => {
+                if self.server_config.require_default_role {
+                    panic!("None of the default roles exist, create them or set REQUIRE_DEFAULT_ROLE to false to allow users to register without roles");
+                }
+                warn!("None of the default roles exist, users that register themselves will not have any roles");
+            }
+            Err(e) => panic!("Failed to find the default roles: {}", e),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create a Role for every RoleTemplate that does not have a Role with the same name yet.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if a Role could not be created, for example because a Permission of a RoleTemplate does not exist.
+    pub async fn initialize_role_templates(&self) {
+        for template in &self.services.role_service.templates {
+            match self
+                .services
+                .role_service
+                .find_by_name(&template.name, &self.database)
+                .await
+            {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    if let Err(e) = self
+                        .services
+                        .role_service
+                        .create_from_template(
+                            &template.name,
+                            None,
+                            &self.database,
+                            &self.services.permission_service,
+                            &self.services.audit_service,
+                        )
+                        .await
+                    {
+                        panic!(
+                            "Failed to create role from template {}: {}",
+                            template.name, e
+                        );
+                    }
+                }
+                Err(e) => panic!("Failed to find role: {:?}", e),
+            }
+        }
+    }
+
     /// # Summary
     ///
     /// Initialize the database.
@@ -650,6 +1133,12 @@ impl Config {
                 Some("The ability to read users".to_string()),
             )
             .await;
+        let read_user_details = self
+            .find_or_create_permission(
+                "CAN_READ_USER_DETAILS",
+                Some("The ability to read the account state of users".to_string()),
+            )
+            .await;
         let update_user = self
             .find_or_create_permission(
                 "CAN_UPDATE_USER",
@@ -663,12 +1152,44 @@ impl Config {
             )
             .await;
 
+        let export_user = self
+            .find_or_create_permission(
+                "CAN_EXPORT_USER",
+                Some("The ability to export users, including their password hashes".to_string()),
+            )
+            .await;
+        let import_user = self
+            .find_or_create_permission(
+                "CAN_IMPORT_USER",
+                Some("The ability to import users, including their password hashes".to_string()),
+            )
+            .await;
+
         let read_audit = self
             .find_or_create_permission(
                 "CAN_READ_AUDIT",
                 Some("The ability to read audits".to_string()),
             )
             .await;
+        let delete_audit = self
+            .find_or_create_permission(
+                "CAN_DELETE_AUDIT",
+                Some("The ability to delete audits".to_string()),
+            )
+            .await;
+
+        let create_invite = self
+            .find_or_create_permission(
+                "CAN_CREATE_INVITE",
+                Some("The ability to create registration invites".to_string()),
+            )
+            .await;
+        let read_invite = self
+            .find_or_create_permission(
+                "CAN_READ_INVITE",
+                Some("The ability to read registration invites".to_string()),
+            )
+            .await;
 
         let can_update_self = self
             .find_or_create_permission(
@@ -699,9 +1220,15 @@ impl Config {
                     delete_delete.id.to_hex(),
                     create_user.id.to_hex(),
                     read_user.id.to_hex(),
+                    read_user_details.id.to_hex(),
                     update_user.id.to_hex(),
                     delete_user.id.to_hex(),
+                    export_user.id.to_hex(),
+                    import_user.id.to_hex(),
                     read_audit.id.to_hex(),
+                    delete_audit.id.to_hex(),
+                    create_invite.id.to_hex(),
+                    read_invite.id.to_hex(),
                 ]),
             )
             .await;
@@ -0,0 +1,53 @@
+use actix_web::cookie::SameSite;
+
+#[derive(Clone)]
+pub struct CookieConfig {
+    pub enabled: bool,
+    pub name: String,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: SameSite,
+    pub allow_insecure: bool,
+}
+
+impl CookieConfig {
+    /// # Summary
+    ///
+    /// Create a new CookieConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether a session cookie that holds the JWT is set on login and accepted instead of a bearer token.
+    /// * `name` - The name of the session cookie.
+    /// * `secure` - Whether the session cookie has the `Secure` attribute.
+    /// * `http_only` - Whether the session cookie has the `HttpOnly` attribute.
+    /// * `same_site` - The `SameSite` attribute of the session cookie.
+    /// * `allow_insecure` - Whether the session cookie may be set over plain HTTP, which should only be used in development.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let cookie_config = CookieConfig::new(true, String::from("auth_rs_session"), true, true, SameSite::Strict, false);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `CookieConfig` - The new CookieConfig.
+    pub fn new(
+        enabled: bool,
+        name: String,
+        secure: bool,
+        http_only: bool,
+        same_site: SameSite,
+        allow_insecure: bool,
+    ) -> CookieConfig {
+        CookieConfig {
+            enabled,
+            name,
+            secure,
+            http_only,
+            same_site,
+            allow_insecure,
+        }
+    }
+}
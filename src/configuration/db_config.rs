@@ -1,5 +1,14 @@
 use serde::{Deserialize, Serialize};
 
+/// What happens to an operation if its Audit cannot be written.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuditFailurePolicy {
+    /// The operation fails with the audit error.
+    Fail,
+    /// The audit error is logged and the operation proceeds.
+    Warn,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct DbConfig {
     pub connection_string: String,
@@ -8,9 +17,17 @@ pub struct DbConfig {
     pub role_collection: String,
     pub user_collection: String,
     pub audit_collection: String,
+    pub session_collection: String,
+    pub token_collection: String,
+    pub migration_collection: String,
+    pub invite_collection: String,
     pub create_indexes: bool,
     pub audit_enabled: bool,
     pub audit_ttl: u64,
+    pub audit_system_actor_id: String,
+    pub audit_failure_policy: AuditFailurePolicy,
+    pub audit_batch_size: usize,
+    pub audit_flush_interval: u64,
 }
 
 impl DbConfig {
@@ -26,9 +43,17 @@ impl DbConfig {
     /// * `role_collection` - A String that holds the role collection name.
     /// * `user_collection` - A String that holds the user collection name.
     /// * `audit_collection` - A String that holds the audit collection name.
+    /// * `session_collection` - A String that holds the session collection name.
+    /// * `token_collection` - A String that holds the name of the collection that holds the opaque tokens.
+    /// * `migration_collection` - A String that holds the name of the collection that records the applied migrations.
+    /// * `invite_collection` - A String that holds the name of the collection that holds the registration invites.
     /// * `create_indexes` - A bool that indicates whether to create indexes or not.
     /// * `audit_enabled` - A bool that indicates whether auditing is enabled or not.
     /// * `audit_ttl` - A u64 that holds the audit TTL.
+    /// * `audit_system_actor_id` - A String that holds the ID of the actor that is audited for system actions.
+    /// * `audit_failure_policy` - The AuditFailurePolicy that decides whether a failed audit write fails the operation.
+    /// * `audit_batch_size` - A usize that holds the amount of audits that are written in one batch, or 0 to disable it.
+    /// * `audit_flush_interval` - A u64 that holds the amount of milliseconds after which buffered audits are written.
     ///
     /// # Returns
     ///
@@ -41,9 +66,17 @@ impl DbConfig {
         role_collection: String,
         user_collection: String,
         audit_collection: String,
+        session_collection: String,
+        token_collection: String,
+        migration_collection: String,
+        invite_collection: String,
         create_indexes: bool,
         audit_enabled: bool,
         audit_ttl: u64,
+        audit_system_actor_id: String,
+        audit_failure_policy: AuditFailurePolicy,
+        audit_batch_size: usize,
+        audit_flush_interval: u64,
     ) -> DbConfig {
         DbConfig {
             connection_string,
@@ -52,9 +85,17 @@ impl DbConfig {
             role_collection,
             user_collection,
             audit_collection,
+            session_collection,
+            token_collection,
+            migration_collection,
+            invite_collection,
             create_indexes,
             audit_enabled,
             audit_ttl,
+            audit_system_actor_id,
+            audit_failure_policy,
+            audit_batch_size,
+            audit_flush_interval,
         }
     }
 }
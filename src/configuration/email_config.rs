@@ -0,0 +1,47 @@
+#[derive(Clone)]
+pub struct EmailConfig {
+    pub allowed_domains: Vec<String>,
+    pub blocked_domains: Vec<String>,
+    pub block_disposable: bool,
+    pub disposable_domains: Option<Vec<String>>,
+    pub verify_changes: bool,
+}
+
+impl EmailConfig {
+    /// # Summary
+    ///
+    /// Create a new EmailConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `allowed_domains` - The domains that email addresses must belong to when registering. Empty allows all domains.
+    /// * `blocked_domains` - The domains that email addresses may not belong to when registering.
+    /// * `block_disposable` - Whether email addresses of disposable email providers are rejected when registering.
+    /// * `disposable_domains` - The disposable email domains to use instead of the bundled list.
+    /// * `verify_changes` - Whether a changed email address only takes effect after it has been confirmed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let email_config = EmailConfig::new(vec![String::from("*.codedead.com")], vec![], true, None, false);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `EmailConfig` - The new EmailConfig.
+    pub fn new(
+        allowed_domains: Vec<String>,
+        blocked_domains: Vec<String>,
+        block_disposable: bool,
+        disposable_domains: Option<Vec<String>>,
+        verify_changes: bool,
+    ) -> EmailConfig {
+        EmailConfig {
+            allowed_domains,
+            blocked_domains,
+            block_disposable,
+            disposable_domains,
+            verify_changes,
+        }
+    }
+}
@@ -1,7 +1,71 @@
+use jsonwebtoken::Algorithm;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TokenFormat {
+    Jwt,
+    Paseto,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TokenMode {
+    Stateless,
+    Opaque,
+}
+
+#[derive(Clone)]
+pub struct RsaKey {
+    pub kid: String,
+    pub private_key: Option<String>,
+    pub public_key: String,
+}
+
+impl RsaKey {
+    /// # Summary
+    ///
+    /// Create a new RsaKey.
+    ///
+    /// # Arguments
+    ///
+    /// * `kid` - The key ID that identifies the key in the JWT header and the JWKS.
+    /// * `private_key` - The PEM encoded private key, if the key can be used for signing.
+    /// * `public_key` - The PEM encoded public key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let rsa_key = RsaKey::new(String::from("key-1"), Some(private_pem), public_pem);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `RsaKey` - The new RsaKey.
+    pub fn new(kid: String, private_key: Option<String>, public_key: String) -> RsaKey {
+        RsaKey {
+            kid,
+            private_key,
+            public_key,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct JwtConfig {
     pub jwt_secret: String,
+    pub previous_jwt_secrets: Vec<String>,
     pub jwt_expiration: usize,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+    pub algorithm: Algorithm,
+    pub rsa_keys: Vec<RsaKey>,
+    pub roles_claim: bool,
+    pub permissions_claim: bool,
+    pub max_claims_size: usize,
+    pub max_sessions: usize,
+    pub format: TokenFormat,
+    pub paseto_private_key: Option<String>,
+    pub refresh_expiration: usize,
+    pub mode: TokenMode,
+    pub leeway: u64,
 }
 
 impl JwtConfig {
@@ -11,22 +75,68 @@ impl JwtConfig {
     ///
     /// # Arguments
     ///
-    /// * `jwt_secret` - The secret to use for signing and verifying JWTs.
+    /// * `jwt_secret` - The secret to use for signing and verifying JWTs, if HS256 is used.
+    /// * `previous_jwt_secrets` - Previous secrets that are still accepted when verifying JWTs, if HS256 is used.
     /// * `jwt_expiration` - The expiration time in seconds.
+    /// * `issuer` - The optional issuer that is added to and required in JWTs.
+    /// * `audience` - The optional audience that is added to and required in JWTs.
+    /// * `algorithm` - The algorithm to use for signing and verifying JWTs.
+    /// * `rsa_keys` - The RSA keys to use if RS256 is used. The first key is used for signing.
+    /// * `roles_claim` - Whether the Role names of the User should be added to the JWT.
+    /// * `permissions_claim` - Whether the effective Permission names of the User should be added to the JWT.
+    /// * `max_claims_size` - The maximum combined size in bytes of the Role and Permission names in the JWT.
+    /// * `max_sessions` - The maximum number of active sessions per User, or zero for no limit.
+    /// * `format` - The format of the issued tokens.
+    /// * `paseto_private_key` - The PEM encoded Ed25519 private key that signs PASETO tokens, if PASETO is used.
+    /// * `refresh_expiration` - The expiration time of refresh tokens in seconds, or zero to not issue refresh tokens.
+    /// * `mode` - Whether self-contained tokens or opaque tokens that are stored in the database are issued.
+    /// * `leeway` - The amount of seconds that the `exp` claim may have passed, to allow for clock skew between nodes.
     ///
     /// # Example
     ///
     /// ```
-    /// let jwt_config = JwtConfig::new(String::from("secret"), 3600);
+    /// let jwt_config = JwtConfig::new(String::from("secret"), vec![], 3600, None, None, Algorithm::HS256, vec![], false, false, 4096, 0, TokenFormat::Jwt, None, 0, TokenMode::Stateless, 60);
     /// ```
     ///
     /// # Returns
     ///
     /// * `JwtConfig` - The new JwtConfig.
-    pub fn new(jwt_secret: String, jwt_expiration: usize) -> JwtConfig {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        jwt_secret: String,
+        previous_jwt_secrets: Vec<String>,
+        jwt_expiration: usize,
+        issuer: Option<String>,
+        audience: Option<String>,
+        algorithm: Algorithm,
+        rsa_keys: Vec<RsaKey>,
+        roles_claim: bool,
+        permissions_claim: bool,
+        max_claims_size: usize,
+        max_sessions: usize,
+        format: TokenFormat,
+        paseto_private_key: Option<String>,
+        refresh_expiration: usize,
+        mode: TokenMode,
+        leeway: u64,
+    ) -> JwtConfig {
         JwtConfig {
             jwt_secret,
+            previous_jwt_secrets,
             jwt_expiration,
+            issuer,
+            audience,
+            algorithm,
+            rsa_keys,
+            roles_claim,
+            permissions_claim,
+            max_claims_size,
+            max_sessions,
+            format,
+            paseto_private_key,
+            refresh_expiration,
+            mode,
+            leeway,
         }
     }
 }
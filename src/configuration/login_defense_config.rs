@@ -0,0 +1,58 @@
+#[derive(Clone, Copy, PartialEq)]
+pub enum LoginDefense {
+    None,
+    Lockout,
+    Throttle,
+}
+
+#[derive(Clone)]
+pub struct LoginDefenseConfig {
+    pub defense: LoginDefense,
+    pub max_attempts: u32,
+    pub lockout_duration: u64,
+    pub throttle_delay: u64,
+    pub throttle_max_delay: u64,
+    pub disclose_lockout: bool,
+}
+
+impl LoginDefenseConfig {
+    /// # Summary
+    ///
+    /// Create a new LoginDefenseConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `defense` - The strategy that is used to slow down brute-force attacks on passwords.
+    /// * `max_attempts` - The number of consecutive failed logins after which an account is locked.
+    /// * `lockout_duration` - The amount of seconds an account stays locked.
+    /// * `throttle_delay` - The delay in milliseconds after the first failed login, which doubles with every further failed login.
+    /// * `throttle_max_delay` - The maximum delay in milliseconds.
+    /// * `disclose_lockout` - Whether the end of a lock is returned to the client when a login is refused.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let login_defense_config = LoginDefenseConfig::new(LoginDefense::Lockout, 5, 900, 500, 10000, false);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `LoginDefenseConfig` - The new LoginDefenseConfig.
+    pub fn new(
+        defense: LoginDefense,
+        max_attempts: u32,
+        lockout_duration: u64,
+        throttle_delay: u64,
+        throttle_max_delay: u64,
+        disclose_lockout: bool,
+    ) -> LoginDefenseConfig {
+        LoginDefenseConfig {
+            defense,
+            max_attempts,
+            lockout_duration,
+            throttle_delay,
+            throttle_max_delay,
+            disclose_lockout,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+#[derive(Clone, Copy)]
+pub enum NameNormalization {
+    None,
+    Uppercase,
+    Slug,
+}
+
+#[derive(Clone)]
+pub struct NameConfig {
+    pub normalization: NameNormalization,
+    pub max_length: usize,
+}
+
+impl NameConfig {
+    /// # Summary
+    ///
+    /// Create a new NameConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `normalization` - How the names of Roles and Permissions are normalized before they are stored or looked up.
+    /// * `max_length` - The maximum length of a Role or Permission name in characters, after normalization.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let name_config = NameConfig::new(NameNormalization::None, 64);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `NameConfig` - The new NameConfig.
+    pub fn new(normalization: NameNormalization, max_length: usize) -> NameConfig {
+        NameConfig {
+            normalization,
+            max_length,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Normalize a Role or Permission name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name to normalize.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let name_config = NameConfig::new(NameNormalization::Slug, 64);
+    /// assert_eq!(name_config.normalize(" can read-reports "), "CAN_READ_REPORTS");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The trimmed name, converted to uppercase if the normalization is Uppercase,
+    ///   or converted to uppercase with every run of other characters than letters and digits replaced by an underscore if the normalization is Slug.
+    ///   The `:` and `*` characters of wildcard Permissions, such as `users:*`, are kept by every normalization.
+    pub fn normalize(&self, name: &str) -> String {
+        let name = name.trim();
+        match self.normalization {
+            NameNormalization::None => name.to_string(),
+            NameNormalization::Uppercase => name.to_uppercase(),
+            NameNormalization::Slug => {
+                let mut slug = String::with_capacity(name.len());
+                for c in name.chars() {
+                    if c.is_alphanumeric() {
+                        slug.extend(c.to_uppercase());
+                    } else if c == ':' || c == '*' {
+                        // A separator never ends in an underscore, so that `users :*` becomes `USERS:*`
+                        slug.truncate(slug.trim_end_matches('_').len());
+                        slug.push(c);
+                    } else if !slug.is_empty() && !slug.ends_with(['_', ':']) {
+                        slug.push('_');
+                    }
+                }
+                slug.trim_end_matches('_').to_string()
+            }
+        }
+    }
+}
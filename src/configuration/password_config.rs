@@ -0,0 +1,78 @@
+#[derive(Clone, Copy)]
+pub enum PasswordHashAlgorithm {
+    Argon2,
+    Bcrypt,
+}
+
+#[derive(Clone)]
+pub struct PasswordConfig {
+    pub algorithm: PasswordHashAlgorithm,
+    pub bcrypt_cost: u32,
+    pub pepper: Option<String>,
+    pub max_length: usize,
+    pub history_size: usize,
+    pub max_age_days: u64,
+    pub argon2_memory_cost: u32,
+    pub argon2_time_cost: u32,
+    pub argon2_parallelism: u32,
+    pub argon2_target_hash_time: u64,
+    pub argon2_autotune: bool,
+}
+
+impl PasswordConfig {
+    /// # Summary
+    ///
+    /// Create a new PasswordConfig.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm that is used to hash new passwords.
+    /// * `bcrypt_cost` - The cost that is used when hashing passwords with bcrypt.
+    /// * `pepper` - The optional server-side secret that is appended to passwords before hashing and verifying.
+    /// * `max_length` - The maximum length of a password in bytes, to bound the cost of hashing it.
+    /// * `history_size` - The number of previous passwords that may not be reused, or zero to allow reuse.
+    /// * `max_age_days` - The number of days after which a password expires, or zero if passwords never expire.
+    /// * `argon2_memory_cost` - The amount of memory in KiB that is used when hashing passwords with Argon2.
+    /// * `argon2_time_cost` - The number of iterations that are used when hashing passwords with Argon2.
+    /// * `argon2_parallelism` - The number of lanes that are used when hashing passwords with Argon2.
+    /// * `argon2_target_hash_time` - The amount of milliseconds hashing a password with Argon2 should take, or zero to skip the calibration at startup.
+    /// * `argon2_autotune` - Whether the Argon2 time cost is increased at startup to reach the target hash time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let password_config = PasswordConfig::new(PasswordHashAlgorithm::Argon2, 12, None, 256, 0, 0, 19456, 2, 1, 0, false);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `PasswordConfig` - The new PasswordConfig.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        algorithm: PasswordHashAlgorithm,
+        bcrypt_cost: u32,
+        pepper: Option<String>,
+        max_length: usize,
+        history_size: usize,
+        max_age_days: u64,
+        argon2_memory_cost: u32,
+        argon2_time_cost: u32,
+        argon2_parallelism: u32,
+        argon2_target_hash_time: u64,
+        argon2_autotune: bool,
+    ) -> PasswordConfig {
+        PasswordConfig {
+            algorithm,
+            bcrypt_cost,
+            pepper,
+            max_length,
+            history_size,
+            max_age_days,
+            argon2_memory_cost,
+            argon2_time_cost,
+            argon2_parallelism,
+            argon2_target_hash_time,
+            argon2_autotune,
+        }
+    }
+}
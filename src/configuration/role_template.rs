@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RoleTemplate {
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+}
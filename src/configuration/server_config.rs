@@ -1,9 +1,43 @@
+use std::net::IpAddr;
+
+/// The value that DEV_MODE must be set to in order to enable the debugging endpoints.
+pub const DEV_MODE_ACKNOWLEDGEMENT: &str = "I_UNDERSTAND_THIS_IS_NOT_FOR_PRODUCTION";
+
+#[derive(Clone, Copy)]
+pub enum ImportConflictPolicy {
+    Skip,
+    Overwrite,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RegistrationMode {
+    Open,
+    Invite,
+}
+
 #[derive(Clone)]
 pub struct ServerConfig {
     pub address: String,
     pub port: u16,
-    pub max_limit: i64,
+    pub max_page_size: i64,
     pub workers: usize,
+    pub route_prefix: String,
+    pub prefix_health: bool,
+    pub idempotency_ttl: u64,
+    pub allow_self_registration: bool,
+    pub registration_mode: RegistrationMode,
+    pub invite_ttl: u64,
+    pub import_conflict_policy: ImportConflictPolicy,
+    pub tenancy: bool,
+    pub default_roles: Vec<String>,
+    pub require_default_role: bool,
+    pub disclose_disabled_accounts: bool,
+    pub trusted_proxies: Vec<IpAddr>,
+    pub cors_allowed_origins: Vec<String>,
+    pub rate_limit: u32,
+    pub rate_limit_window: u64,
+    pub enforce_json_content_type: bool,
+    pub dev_mode: bool,
 }
 
 impl ServerConfig {
@@ -15,24 +49,81 @@ impl ServerConfig {
     ///
     /// * `address` - The address of the ServerConfig.
     /// * `port` - The port of the ServerConfig.
-    /// * `max_limit` - The maximum amount of entity records that can be retrieved in one call.
+    /// * `max_page_size` - The maximum amount of entity records that can be retrieved in one page. Larger limits are capped.
     /// * `workers` - The number of workers to start (per bind address).
+    /// * `route_prefix` - The prefix under which the API routes are mounted.
+    /// * `prefix_health` - Whether the health endpoint should be mounted under the route prefix.
+    /// * `idempotency_ttl` - The amount of seconds an idempotency key and its response are retained.
+    /// * `allow_self_registration` - Whether Users are allowed to register themselves.
+    /// * `registration_mode` - Whether Users can register themselves freely or only with an invite token.
+    /// * `invite_ttl` - The amount of seconds an invite can be used after it was created.
+    /// * `import_conflict_policy` - What to do when an imported User has the same ID as an existing User.
+    /// * `tenancy` - Whether Users belong to a tenant, which scopes the uniqueness of their username and email address.
+    /// * `default_roles` - The names of the Roles that are assigned to Users that register themselves.
+    /// * `require_default_role` - Whether at least one of the default Roles must exist for Users to register themselves.
+    /// * `disclose_disabled_accounts` - Whether a login to a disabled account is refused with an informative response.
+    /// * `trusted_proxies` - The IP addresses of the proxies whose `Forwarded` and `X-Forwarded-For` headers are trusted.
+    /// * `cors_allowed_origins` - The origins that may send credentialed cross-origin requests, or empty to allow any origin without credentials.
+    /// * `rate_limit` - The maximum amount of requests of a client IP address within a window, or 0 to disable rate limiting.
+    /// * `rate_limit_window` - The length of a rate limit window in seconds.
+    /// * `enforce_json_content_type` - Whether request bodies that are not sent as JSON are refused with a 415 Unsupported Media Type.
+    /// * `dev_mode` - Whether the debugging endpoints, which expose token claims and permissions, are mounted.
     ///
     /// # Example
     ///
     /// ```
-    /// let server_config = ServerConfig::new(String::from("address"), 8080);
+    /// let server_config = ServerConfig::new(String::from("address"), 8080, 100, 0, String::from("/api/v1"), false, 86400, true, RegistrationMode::Open, 604800, ImportConflictPolicy::Skip, false, vec![String::from("DEFAULT")], true, false, vec![], vec![], 0, 60, true, false);
     /// ```
     ///
     /// # Returns
     ///
     /// * `ServerConfig` - The new ServerConfig.
-    pub fn new(address: String, port: u16, max_limit: i64, workers: usize) -> ServerConfig {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        address: String,
+        port: u16,
+        max_page_size: i64,
+        workers: usize,
+        route_prefix: String,
+        prefix_health: bool,
+        idempotency_ttl: u64,
+        allow_self_registration: bool,
+        registration_mode: RegistrationMode,
+        invite_ttl: u64,
+        import_conflict_policy: ImportConflictPolicy,
+        tenancy: bool,
+        default_roles: Vec<String>,
+        require_default_role: bool,
+        disclose_disabled_accounts: bool,
+        trusted_proxies: Vec<IpAddr>,
+        cors_allowed_origins: Vec<String>,
+        rate_limit: u32,
+        rate_limit_window: u64,
+        enforce_json_content_type: bool,
+        dev_mode: bool,
+    ) -> ServerConfig {
         ServerConfig {
             address,
             port,
-            max_limit,
+            max_page_size,
             workers,
+            route_prefix,
+            prefix_health,
+            idempotency_ttl,
+            allow_self_registration,
+            registration_mode,
+            invite_ttl,
+            import_conflict_policy,
+            tenancy,
+            default_roles,
+            require_default_role,
+            disclose_disabled_accounts,
+            trusted_proxies,
+            cors_allowed_origins,
+            rate_limit,
+            rate_limit_window,
+            enforce_json_content_type,
+            dev_mode,
         }
     }
 }
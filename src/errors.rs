@@ -1,2 +1,8 @@
 pub mod bad_request;
+pub mod conflict;
+pub mod forbidden;
 pub mod internal_server_error;
+pub mod not_found;
+pub mod service_unavailable;
+pub mod too_many_requests;
+pub mod unsupported_media_type;
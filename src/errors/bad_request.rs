@@ -1,11 +1,16 @@
 use chrono::{DateTime, Utc};
 use serde::Serialize;
+use serde_json::error::Category;
 use std::time::SystemTime;
 use utoipa::ToSchema;
 
 #[derive(Serialize, ToSchema)]
 pub struct BadRequest {
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field: Option<String>,
+    #[serde(rename = "validValues", skip_serializing_if = "Option::is_none")]
+    valid_values: Option<Vec<String>>,
     timestamp: String,
     #[serde(rename(serialize = "errorCode", deserialize = "errorCode"))]
     error_code: u16,
@@ -35,8 +40,328 @@ impl BadRequest {
 
         BadRequest {
             message: String::from(message),
+            field: None,
+            valid_values: None,
             timestamp: now,
             error_code: 400,
         }
     }
+
+    /// # Summary
+    ///
+    /// Create a new BadRequest that refers to the input field that caused it.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The error message.
+    /// * `field` - The name of the offending input field, if known.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let bad_request = BadRequest::for_field("Username already taken", Some("username"));
+    /// ```
+    /// # Returns
+    ///
+    /// * `BadRequest` - The new BadRequest.
+    ///
+    pub fn for_field(message: &str, field: Option<&str>) -> BadRequest {
+        let mut bad_request = BadRequest::new(message);
+        bad_request.field = field.map(String::from);
+        bad_request
+    }
+
+    /// # Summary
+    ///
+    /// Create a new BadRequest for a JSON body that could not be parsed. The body is parsed before it is deserialized
+    /// into the type of the request, so these errors are never caused by a single field.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The serde_json Error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let bad_request = BadRequest::from_json_error(&error);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BadRequest` - The new BadRequest.
+    pub fn from_json_error(error: &serde_json::Error) -> BadRequest {
+        match error.classify() {
+            Category::Eof if error.line() == 1 && error.column() == 0 => {
+                BadRequest::new("The request body is empty")
+            }
+            Category::Eof | Category::Syntax => {
+                BadRequest::new(&format!("The request body is not valid JSON: {}", error))
+            }
+            Category::Data | Category::Io => {
+                BadRequest::new(&format!("The request body is invalid: {}", error))
+            }
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create a new BadRequest for a JSON body that could not be deserialized into the type of the request. The field
+    /// is the path of the offending value in the body, such as `roles[0]`. If the error was caused by an unknown enum
+    /// value, the valid values are listed.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The Error, along with the path at which it occurred.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let bad_request = BadRequest::from_deserialize_error(&error);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BadRequest` - The new BadRequest.
+    pub fn from_deserialize_error(
+        error: &serde_path_to_error::Error<serde_json::Error>,
+    ) -> BadRequest {
+        // The path of the root of the body is `.`
+        let path = Some(error.path().to_string()).filter(|p| p != ".");
+        let message = error.inner().to_string();
+
+        if let Some(field) = missing_field(&message) {
+            let field = match path {
+                Some(p) => format!("{}.{}", p, field),
+                None => field,
+            };
+            return BadRequest::for_field(
+                &format!("The required field `{}` is missing", field),
+                Some(&field),
+            );
+        }
+
+        if let Some((value, valid_values)) = unknown_variant(&message) {
+            return BadRequest::for_invalid_value(&value, path.as_deref(), valid_values);
+        }
+
+        BadRequest::for_field(
+            &format!("The request body is invalid: {}", message),
+            path.as_deref(),
+        )
+    }
+
+    /// # Summary
+    ///
+    /// Create a new BadRequest for a query string that could not be deserialized into the type of the request. If the
+    /// error was caused by an unknown enum value, the valid values are listed and the parameter that holds the value is
+    /// named.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The serde_urlencoded Error.
+    /// * `query` - The query string of the request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let bad_request = BadRequest::from_query_error(&error, req.query_string());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BadRequest` - The new BadRequest.
+    pub fn from_query_error(error: &serde_urlencoded::de::Error, query: &str) -> BadRequest {
+        let message = error.to_string();
+
+        if let Some(field) = missing_field(&message) {
+            return BadRequest::for_field(
+                &format!("The required query parameter `{}` is missing", field),
+                Some(&field),
+            );
+        }
+
+        if let Some((value, valid_values)) = unknown_variant(&message) {
+            // The error does not name the parameter, so it is the one that holds the invalid value
+            let parameters: Vec<(String, String)> =
+                serde_urlencoded::from_str(query).unwrap_or_default();
+            let field = parameters
+                .into_iter()
+                .find(|(_, v)| *v == value)
+                .map(|(k, _)| k);
+            return BadRequest::for_invalid_value(&value, field.as_deref(), valid_values);
+        }
+
+        BadRequest::new(&format!("The query string is invalid: {}", message))
+    }
+
+    /// # Summary
+    ///
+    /// Create a new BadRequest for a value that is not one of the valid values of a field.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The invalid value.
+    /// * `field` - The name of the offending input field, if known.
+    /// * `valid_values` - The valid values of the field.
+    ///
+    /// # Returns
+    ///
+    /// * `BadRequest` - The new BadRequest.
+    fn for_invalid_value(
+        value: &str,
+        field: Option<&str>,
+        valid_values: Vec<String>,
+    ) -> BadRequest {
+        let message = match field {
+            Some(f) => format!(
+                "Invalid value `{}` for `{}`, expected one of: {}",
+                value,
+                f,
+                valid_values.join(", ")
+            ),
+            None => format!(
+                "Invalid value `{}`, expected one of: {}",
+                value,
+                valid_values.join(", ")
+            ),
+        };
+
+        let mut bad_request = BadRequest::for_field(&message, field);
+        bad_request.valid_values = Some(valid_values);
+        bad_request
+    }
+}
+
+/// # Summary
+///
+/// Get the name of the missing field from the message of a deserialization error. The message is created by
+/// `serde::de::Error::missing_field`, whose format is covered by the tests below.
+///
+/// # Arguments
+///
+/// * `message` - The message of the error.
+///
+/// # Returns
+///
+/// * `Option<String>` - The name of the missing field, or None if the error was not caused by a missing field.
+fn missing_field(message: &str) -> Option<String> {
+    let rest = message.strip_prefix("missing field `")?;
+    let field = rest.strip_suffix('`')?;
+    Some(String::from(field))
+}
+
+/// # Summary
+///
+/// Get the invalid value and the valid values from the message of a deserialization error. The message is created by
+/// `serde::de::Error::unknown_variant`, whose format is covered by the tests below.
+///
+/// # Arguments
+///
+/// * `message` - The message of the error.
+///
+/// # Returns
+///
+/// * `Option<(String, Vec<String>)>` - The invalid value and the valid values, or None if the error was not caused by
+///   an unknown enum value.
+fn unknown_variant(message: &str) -> Option<(String, Vec<String>)> {
+    let rest = message.strip_prefix("unknown variant `")?;
+    let (value, expected) = rest.split_once("`, expected ")?;
+
+    // Every valid value is enclosed in backticks
+    let valid_values: Vec<String> = expected
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(String::from)
+        .collect();
+
+    Some((String::from(value), valid_values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Kind {
+        Create,
+        Update,
+        Delete,
+    }
+
+    // The fields are only deserialized, never read
+    #[derive(Deserialize)]
+    struct Item {
+        #[serde(rename = "kind")]
+        _kind: Kind,
+    }
+
+    #[derive(Deserialize)]
+    struct Request {
+        #[serde(rename = "items")]
+        _items: Vec<Item>,
+    }
+
+    fn json_error(body: &str) -> BadRequest {
+        let value: serde_json::Value = serde_json::from_str(body).unwrap();
+        let error = serde_path_to_error::deserialize::<_, Request>(value)
+            .err()
+            .unwrap();
+        BadRequest::from_deserialize_error(&error)
+    }
+
+    #[test]
+    fn unknown_variant_names_the_field_and_the_valid_values() {
+        let bad_request = json_error(r#"{"items":[{"kind":"archive"}]}"#);
+
+        assert_eq!(bad_request.field.as_deref(), Some("items[0].kind"));
+        assert_eq!(
+            bad_request.valid_values,
+            Some(vec![
+                String::from("create"),
+                String::from("update"),
+                String::from("delete")
+            ])
+        );
+        assert_eq!(
+            bad_request.message,
+            "Invalid value `archive` for `items[0].kind`, expected one of: create, update, delete"
+        );
+    }
+
+    #[test]
+    fn missing_field_is_named_by_its_path() {
+        let bad_request = json_error(r#"{"items":[{}]}"#);
+
+        assert_eq!(bad_request.field.as_deref(), Some("items[0].kind"));
+        assert_eq!(
+            bad_request.message,
+            "The required field `items[0].kind` is missing"
+        );
+    }
+
+    #[test]
+    fn missing_field_at_the_root_is_named() {
+        let bad_request = json_error("{}");
+
+        assert_eq!(bad_request.field.as_deref(), Some("items"));
+    }
+
+    #[test]
+    fn unknown_query_variant_names_the_parameter() {
+        #[derive(Deserialize)]
+        struct Query {
+            #[serde(rename = "kind")]
+            _kind: Kind,
+        }
+
+        let query = "page=1&kind=archive";
+        let error = serde_urlencoded::from_str::<Query>(query).err().unwrap();
+        let bad_request = BadRequest::from_query_error(&error, query);
+
+        assert_eq!(bad_request.field.as_deref(), Some("kind"));
+        assert_eq!(bad_request.valid_values.map(|v| v.len()), Some(3));
+    }
 }
@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::SystemTime;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct Conflict {
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conflict: Option<String>,
+    timestamp: String,
+    #[serde(rename(serialize = "errorCode", deserialize = "errorCode"))]
+    error_code: u16,
+}
+
+impl Conflict {
+    /// # Summary
+    ///
+    /// Create a new Conflict.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The error message.
+    /// * `conflict` - The name of the input field whose value conflicts with an existing entity, if known.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let conflict = Conflict::new("Role name already taken", Some("name"));
+    /// ```
+    /// # Returns
+    ///
+    /// * `Conflict` - The new Conflict.
+    ///
+    pub fn new(message: &str, conflict: Option<&str>) -> Conflict {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        Conflict {
+            message: String::from(message),
+            conflict: conflict.map(String::from),
+            timestamp: now,
+            error_code: 409,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::SystemTime;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct Forbidden {
+    message: String,
+    reason: String,
+    timestamp: String,
+    #[serde(rename(serialize = "errorCode", deserialize = "errorCode"))]
+    error_code: u16,
+}
+
+impl Forbidden {
+    /// # Summary
+    ///
+    /// Create a new Forbidden.
+    ///
+    /// # Arguments
+    ///
+    /// * `reason` - The machine-readable reason why the request was refused.
+    /// * `message` - The error message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let forbidden = Forbidden::new("password_expired", "The password has expired");
+    /// ```
+    /// # Returns
+    ///
+    /// * `Forbidden` - The new Forbidden.
+    ///
+    pub fn new(reason: &str, message: &str) -> Forbidden {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        Forbidden {
+            message: String::from(message),
+            reason: String::from(reason),
+            timestamp: now,
+            error_code: 403,
+        }
+    }
+}
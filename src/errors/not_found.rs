@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::SystemTime;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct NotFound {
+    message: String,
+    #[serde(rename(serialize = "resourceType", deserialize = "resourceType"))]
+    resource_type: String,
+    #[serde(rename(serialize = "resourceId", deserialize = "resourceId"))]
+    resource_id: String,
+    timestamp: String,
+    #[serde(rename(serialize = "errorCode", deserialize = "errorCode"))]
+    error_code: u16,
+}
+
+impl NotFound {
+    /// # Summary
+    ///
+    /// Create a new NotFound.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource_type` - The type of the resource that could not be found.
+    /// * `resource_id` - The ID of the resource that could not be found.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let not_found = NotFound::new("User", "id");
+    /// ```
+    /// # Returns
+    ///
+    /// * `NotFound` - The new NotFound.
+    ///
+    pub fn new(resource_type: &str, resource_id: &str) -> NotFound {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        NotFound {
+            message: format!("{} with ID {} was not found", resource_type, resource_id),
+            resource_type: String::from(resource_type),
+            resource_id: String::from(resource_id),
+            timestamp: now,
+            error_code: 404,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn new_serializes_the_resource_of_every_type() {
+        for resource_type in ["User", "Role", "Permission", "Session"] {
+            let not_found = NotFound::new(resource_type, "65b2a8f0c1d2e3f4a5b6c7d8");
+            let value = serde_json::to_value(&not_found).unwrap();
+
+            assert_eq!(
+                value["message"],
+                json!(format!(
+                    "{} with ID 65b2a8f0c1d2e3f4a5b6c7d8 was not found",
+                    resource_type
+                ))
+            );
+            assert_eq!(value["resourceType"], json!(resource_type));
+            assert_eq!(value["resourceId"], json!("65b2a8f0c1d2e3f4a5b6c7d8"));
+            assert_eq!(value["errorCode"], json!(404));
+            assert!(matches!(value["timestamp"], Value::String(_)));
+        }
+    }
+}
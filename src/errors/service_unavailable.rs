@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::SystemTime;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct ServiceUnavailable {
+    message: String,
+    timestamp: String,
+    #[serde(rename(serialize = "errorCode", deserialize = "errorCode"))]
+    error_code: u16,
+}
+
+impl ServiceUnavailable {
+    /// # Summary
+    ///
+    /// Create a new ServiceUnavailable.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The error message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let service_unavailable = ServiceUnavailable::new("Service Unavailable");
+    /// ```
+    /// # Returns
+    ///
+    /// * `ServiceUnavailable` - The new ServiceUnavailable.
+    ///
+    pub fn new(message: &str) -> ServiceUnavailable {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        ServiceUnavailable {
+            message: String::from(message),
+            timestamp: now,
+            error_code: 503,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::SystemTime;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct TooManyRequests {
+    message: String,
+    timestamp: String,
+    #[serde(rename(serialize = "errorCode", deserialize = "errorCode"))]
+    error_code: u16,
+}
+
+impl TooManyRequests {
+    /// # Summary
+    ///
+    /// Create a new TooManyRequests.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The error message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let too_many_requests = TooManyRequests::new("Too Many Requests");
+    /// ```
+    /// # Returns
+    ///
+    /// * `TooManyRequests` - The new TooManyRequests.
+    ///
+    pub fn new(message: &str) -> TooManyRequests {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        TooManyRequests {
+            message: String::from(message),
+            timestamp: now,
+            error_code: 429,
+        }
+    }
+}
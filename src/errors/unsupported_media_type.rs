@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::SystemTime;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub struct UnsupportedMediaType {
+    message: String,
+    timestamp: String,
+    #[serde(rename(serialize = "errorCode", deserialize = "errorCode"))]
+    error_code: u16,
+}
+
+impl UnsupportedMediaType {
+    /// # Summary
+    ///
+    /// Create a new UnsupportedMediaType.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The error message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let unsupported_media_type = UnsupportedMediaType::new("Unsupported Media Type");
+    /// ```
+    /// # Returns
+    ///
+    /// * `UnsupportedMediaType` - The new UnsupportedMediaType.
+    ///
+    pub fn new(message: &str) -> UnsupportedMediaType {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        UnsupportedMediaType {
+            message: String::from(message),
+            timestamp: now,
+            error_code: 415,
+        }
+    }
+}
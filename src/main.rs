@@ -1,13 +1,19 @@
 use crate::components::env_reader::EnvReader;
+use crate::components::log_initializer::LogInitializer;
 use crate::components::open_api::ApiDoc;
 use crate::web::controller::Controller;
-use actix_cors::Cors;
+use crate::web::extractors::{json_config, query_config};
+use crate::web::middleware::content_type::ContentTypeMiddleware;
+use crate::web::middleware::cors;
+use crate::web::middleware::etag::EtagMiddleware;
+use crate::web::middleware::rate_limit::{RateLimitMiddleware, RateLimiter};
+use crate::web::middleware::request_id::{self, RequestIdMiddleware};
 use actix_web::middleware::Logger;
 use actix_web::{web as a_web, App, HttpServer};
 use actix_web_grants::GrantsMiddleware;
 use dotenvy::dotenv;
-use env_logger::Env;
-use log::info;
+use log::{info, warn};
+use std::sync::Arc;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
@@ -34,28 +40,57 @@ mod web;
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
-    env_logger::init_from_env(Env::default().default_filter_or("info"));
+    LogInitializer::init(EnvReader::read_log_format());
 
-    let config = EnvReader::read_configuration().await;
+    let config = a_web::Data::new(EnvReader::read_configuration().await);
 
     let addr = config.server_config.address.clone();
     let port = config.server_config.port;
     let workers = config.server_config.workers;
 
+    if config.server_config.dev_mode {
+        warn!("DEV_MODE is enabled, which exposes token claims and permissions on the debug endpoints. Never enable it in production!");
+    }
+
     info!("Starting server at {}:{}", addr, port);
 
-    let openapi = ApiDoc::openapi();
+    let mut openapi = ApiDoc::openapi();
+    ApiDoc::apply_route_prefix(
+        &mut openapi,
+        &config.server_config.route_prefix,
+        config.server_config.prefix_health,
+    );
+
+    // The RateLimiter is created once, so that its state is shared by all workers
+    let rate_limiter = Arc::new(RateLimiter::new(
+        config.server_config.rate_limit,
+        config.server_config.rate_limit_window,
+    ));
+
+    // The AuditService is kept outside of the server, so that its buffered audits can be written after it stopped
+    let audit_service = config.services.audit_service.clone();
 
     let mut server = HttpServer::new(move || {
-        let logger = Logger::default();
+        let logger = Logger::new(&format!(
+            "{} %{{{}}}i",
+            r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T"#,
+            request_id::REQUEST_ID_HEADER
+        ));
         let mut app = App::new()
-            .wrap(logger)
+            .wrap(EtagMiddleware)
+            .wrap(ContentTypeMiddleware)
             .wrap(GrantsMiddleware::with_extractor(
                 web::extractors::jwt_extractor::extract,
             ))
-            .app_data(a_web::Data::new(config.clone()))
-            .wrap(Cors::permissive())
-            .configure(Controller::configure_routes);
+            // The rate limiter wraps the grants, so that refused requests never reach the JWT extractor
+            .wrap(RateLimitMiddleware::new(rate_limiter.clone()))
+            .wrap(logger)
+            .app_data(config.clone())
+            .app_data(json_config::json_config())
+            .app_data(query_config::query_config())
+            .wrap(cors::cors(&config.server_config))
+            .wrap(RequestIdMiddleware)
+            .configure(|cfg| Controller::configure_routes(cfg, &config.server_config));
 
         if config.open_api {
             app = app.service(
@@ -72,5 +107,10 @@ async fn main() -> std::io::Result<()> {
         server = server.workers(workers);
     }
 
-    server.run().await
+    let res = server.run().await;
+
+    // Buffered audits are written before exiting, so that a graceful shutdown does not lose them
+    audit_service.flush().await;
+
+    res
 }
@@ -1,4 +1,40 @@
+use mongodb::error::{Error, ErrorKind, WriteFailure};
+
 pub mod audit;
+pub mod cursor;
+pub mod invite;
+pub mod migration;
 pub mod permission;
 pub mod role;
+pub mod session;
+pub mod token;
 pub mod user;
+
+const DUPLICATE_KEY_CODE: i32 = 11000;
+
+/// # Summary
+///
+/// Check whether a MongoDB error was caused by a write that violates a unique index.
+///
+/// # Arguments
+///
+/// * `e` - The MongoDB error.
+///
+/// # Example
+///
+/// ```
+/// if is_duplicate_key(&e) {
+///     return Err(Error::NameAlreadyTaken);
+/// }
+/// ```
+///
+/// # Returns
+///
+/// * `bool` - True if the error is a duplicate key error.
+pub fn is_duplicate_key(e: &Error) -> bool {
+    match e.kind.as_ref() {
+        ErrorKind::Write(WriteFailure::WriteError(w)) => w.code == DUPLICATE_KEY_CODE,
+        ErrorKind::Command(c) => c.code == DUPLICATE_KEY_CODE,
+        _ => false,
+    }
+}
@@ -1,11 +1,15 @@
+use crate::web::middleware::request_id;
 use chrono::{DateTime, Utc};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::time::SystemTime;
 
 #[derive(Serialize, Deserialize)]
 pub enum ResourceType {
+    #[serde(rename = "audit")]
+    Audit,
     #[serde(rename = "permission")]
     Permission,
     #[serde(rename = "role")]
@@ -28,6 +32,7 @@ impl Display for ResourceType {
     /// A std::fmt::Result.
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            ResourceType::Audit => write!(f, "Audit"),
             ResourceType::Permission => write!(f, "Permission"),
             ResourceType::Role => write!(f, "Role"),
             ResourceType::User => write!(f, "User"),
@@ -101,6 +106,10 @@ pub enum Action {
     Update,
     #[serde(rename = "delete")]
     Delete,
+    #[serde(rename = "search")]
+    Search,
+    #[serde(rename = "login")]
+    Login,
 }
 
 impl Display for Action {
@@ -120,6 +129,54 @@ impl Display for Action {
             Action::Create => write!(f, "Create"),
             Action::Update => write!(f, "Update"),
             Action::Delete => write!(f, "Delete"),
+            Action::Search => write!(f, "Search"),
+            Action::Login => write!(f, "Login"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum LoginOutcome {
+    #[serde(rename = "OK")]
+    Ok,
+    #[serde(rename = "BAD_PASSWORD")]
+    BadPassword,
+    #[serde(rename = "USER_NOT_FOUND")]
+    UserNotFound,
+    #[serde(rename = "DISABLED")]
+    Disabled,
+    #[serde(rename = "LOCKED")]
+    Locked,
+    #[serde(rename = "MFA_REQUIRED")]
+    MfaRequired,
+    #[serde(rename = "PASSWORD_EXPIRED")]
+    PasswordExpired,
+    #[serde(rename = "PASSWORD_CHANGE_REQUIRED")]
+    PasswordChangeRequired,
+}
+
+impl Display for LoginOutcome {
+    /// # Summary
+    ///
+    /// Display the LoginOutcome.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - A mutable reference to a Formatter.
+    ///
+    /// # Returns
+    ///
+    /// A std::fmt::Result.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoginOutcome::Ok => write!(f, "OK"),
+            LoginOutcome::BadPassword => write!(f, "BAD_PASSWORD"),
+            LoginOutcome::UserNotFound => write!(f, "USER_NOT_FOUND"),
+            LoginOutcome::Disabled => write!(f, "DISABLED"),
+            LoginOutcome::Locked => write!(f, "LOCKED"),
+            LoginOutcome::MfaRequired => write!(f, "MFA_REQUIRED"),
+            LoginOutcome::PasswordExpired => write!(f, "PASSWORD_EXPIRED"),
+            LoginOutcome::PasswordChangeRequired => write!(f, "PASSWORD_CHANGE_REQUIRED"),
         }
     }
 }
@@ -137,6 +194,14 @@ pub struct Audit {
     pub resource_id_type: ResourceIdType,
     #[serde(rename = "resourceType")]
     pub resource_type: ResourceType,
+    #[serde(rename = "requestId")]
+    pub request_id: Option<String>,
+    #[serde(default)]
+    pub system: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<LoginOutcome>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<BTreeMap<String, String>>,
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     #[serde(rename = "createdAt")]
     pub created_at: DateTime<Utc>,
@@ -154,6 +219,9 @@ impl Audit {
     /// * `resource_id` - The resource id of the Audit.
     /// * `resource_id_type` - The resource id type of the Audit.
     /// * `resource_type` - The resource type of the Audit.
+    /// * `system` - Whether the action was initiated by the system instead of a User.
+    ///
+    /// The ID of the request that is currently being handled, if any, is attached to the Audit.
     ///
     /// # Example
     ///
@@ -170,6 +238,7 @@ impl Audit {
         resource_id: ObjectId,
         resource_id_type: ResourceIdType,
         resource_type: ResourceType,
+        system: bool,
     ) -> Audit {
         let now: DateTime<Utc> = SystemTime::now().into();
 
@@ -180,6 +249,10 @@ impl Audit {
             resource_id,
             resource_id_type,
             resource_type,
+            request_id: request_id::current(),
+            system,
+            reason: None,
+            details: None,
             created_at: now,
         }
     }
@@ -200,8 +273,14 @@ impl Display for Audit {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Audit {{ id: {}, user_id: {}, action: {}, resource_id: {}, resource_type: {}, created_at: {} }}",
-            self.id, self.user_id.to_hex(), self.action, self.resource_id.to_hex(), self.resource_type, self.created_at
+            "Audit {{ id: {}, user_id: {}, action: {}, resource_id: {}, resource_type: {}, system: {}, created_at: {} }}",
+            self.id, self.user_id.to_hex(), self.action, self.resource_id.to_hex(), self.resource_type, self.system, self.created_at
         )
     }
 }
+
+pub struct AuditStats {
+    pub total: u64,
+    pub by_action: BTreeMap<String, u64>,
+    pub by_resource_type: BTreeMap<String, u64>,
+}
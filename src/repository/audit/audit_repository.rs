@@ -1,47 +1,35 @@
-use crate::repository::audit::audit_model::Audit;
+use crate::repository::audit::audit_model::{Audit, AuditStats, ResourceIdType};
+use crate::repository::cursor;
+use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
-use mongodb::bson::doc;
 use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Bson, Document};
 use mongodb::error::Error as MongodbError;
-use mongodb::options::FindOptions;
-use mongodb::Database;
-use std::fmt::{Display, Formatter};
+use mongodb::options::{FindOptions, InsertManyOptions};
+use mongodb::{ClientSession, Cursor, Database};
+use std::collections::BTreeMap;
 
 #[derive(Clone)]
 pub struct AuditRepository {
     pub collection: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum Error {
+    #[error("Invalid Audit ID: {0}")]
     InvalidId(String),
+    #[error("Empty Audit collection")]
     EmptyCollection,
+    #[error("Empty text search")]
     EmptyTextSearch,
-    MongoDb(MongodbError),
+    #[error("Invalid cursor: {0}")]
+    InvalidCursor(String),
+    #[error("MongoDb Error: {0}")]
+    MongoDb(#[from] MongodbError),
+    #[error("ObjectId Error: {0}")]
     ObjectId(String),
-}
-
-impl Display for Error {
-    /// # Summary
-    ///
-    /// Display the Error.
-    ///
-    /// # Arguments
-    ///
-    /// * `f` - A mutable reference to a Formatter.
-    ///
-    /// # Returns
-    ///
-    /// A std::fmt::Result.
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Error::InvalidId(id) => write!(f, "Invalid Audit ID: {}", id),
-            Error::EmptyCollection => write!(f, "Empty Audit collection"),
-            Error::EmptyTextSearch => write!(f, "Empty text search"),
-            Error::MongoDb(e) => write!(f, "MongoDb Error: {}", e),
-            Error::ObjectId(e) => write!(f, "ObjectId Error: {}", e),
-        }
-    }
+    #[error("The Audit buffer is closed")]
+    BufferClosed,
 }
 
 impl AuditRepository {
@@ -87,6 +75,60 @@ impl AuditRepository {
         }
     }
 
+    /// # Summary
+    ///
+    /// Create a batch of Audits. The batch is written unordered, so that one failed Audit does not prevent the others
+    /// from being written.
+    ///
+    /// # Arguments
+    ///
+    /// * `audits` - The Audits to create.
+    /// * `db` - The Database to create the Audits in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn create_many(&self, audits: Vec<Audit>, db: &Database) -> Result<(), Error> {
+        let options = InsertManyOptions::builder().ordered(false).build();
+        match db
+            .collection::<Audit>(&self.collection)
+            .insert_many(audits, options)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create a new Audit as part of the transaction of a ClientSession.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit` - The Audit to create.
+    /// * `db` - The Database to create the Audit in.
+    /// * `session` - The ClientSession whose transaction the Audit is created in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn create_with_session(
+        &self,
+        audit: Audit,
+        db: &Database,
+        session: &mut ClientSession,
+    ) -> Result<(), Error> {
+        match db
+            .collection::<Audit>(&self.collection)
+            .insert_one_with_session(audit, None, session)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
     /// # Summary
     ///
     /// Find an Audit by id.
@@ -125,6 +167,7 @@ impl AuditRepository {
     ///
     /// * `limit` - The limit of Audits to find.
     /// * `page` - The page of Audits to find.
+    /// * `system` - Only find system Audits if true, or only User Audits if false.
     /// * `db` - The Database to find the Audits in.
     ///
     /// # Returns
@@ -134,6 +177,7 @@ impl AuditRepository {
         &self,
         limit: Option<i64>,
         page: Option<i64>,
+        system: Option<bool>,
         db: &Database,
     ) -> Result<Vec<Audit>, Error> {
         let mut skip: Option<u64> = None;
@@ -153,7 +197,7 @@ impl AuditRepository {
 
         match db
             .collection::<Audit>(&self.collection)
-            .find(None, find_options)
+            .find(system_filter(system), find_options)
             .await
         {
             Ok(r) => Ok(r.try_collect().await.unwrap_or_else(|_| vec![])),
@@ -161,6 +205,390 @@ impl AuditRepository {
         }
     }
 
+    /// # Summary
+    ///
+    /// Count the Audit entities, optionally only the ones that match a text search.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to search for, or None to count all Audits.
+    /// * `system` - Only count system Audits if true, or only User Audits if false.
+    /// * `db` - The database to use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit_repository = AuditRepository::new("audit".to_string()).unwrap();
+    /// let total = audit_repository.count(None, None, &db).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of matching Audits.
+    pub async fn count(
+        &self,
+        text: Option<&str>,
+        system: Option<bool>,
+        db: &Database,
+    ) -> Result<u64, Error> {
+        let mut filter = system_filter(system).unwrap_or_default();
+        match text {
+            Some("") => return Err(Error::EmptyTextSearch),
+            Some(t) => {
+                filter.insert("$text", doc! { "$search": t });
+            }
+            None => {}
+        }
+
+        match db
+            .collection::<Audit>(&self.collection)
+            .count_documents(filter, None)
+            .await
+        {
+            Ok(c) => Ok(c),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find a page of Audit entities that were created after the entity that the cursor points to, ordered by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `after` - The opaque cursor of the previous page, or None to start at the first page.
+    /// * `limit` - The limit of Audits to find.
+    /// * `system` - Only find system Audits if true, or only User Audits if false.
+    /// * `db` - The database to use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit_repository = AuditRepository::new("audit".to_string()).unwrap();
+    /// let db = mongodb::Client::with_uri_str("mongodb://localhost:27017")
+    ///    .await
+    ///    .unwrap()
+    ///    .database("test");
+    ///
+    /// let (audits, next_cursor) = audit_repository.find_after(None, Some(100), None, &db).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Vec<Audit>, Option<String>), Error>` - The page of Audits and the cursor of the next page, if any.
+    pub async fn find_after(
+        &self,
+        after: Option<&str>,
+        limit: Option<i64>,
+        system: Option<bool>,
+        db: &Database,
+    ) -> Result<(Vec<Audit>, Option<String>), Error> {
+        let mut filter = system_filter(system).unwrap_or_default();
+
+        if let Some(a) = after {
+            let id = match cursor::decode(a) {
+                Some(id) => id,
+                None => return Err(Error::InvalidCursor(a.to_string())),
+            };
+            filter.insert("_id", doc! { "$gt": id });
+        }
+
+        let find_options = FindOptions::builder()
+            .limit(limit)
+            .sort(doc! { "_id": 1 })
+            .build();
+
+        let audits: Vec<Audit> = match db
+            .collection::<Audit>(&self.collection)
+            .find(filter, find_options)
+            .await
+        {
+            Ok(r) => r.try_collect().await.unwrap_or_else(|_| vec![]),
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        let next_cursor = cursor::next(audits.last().map(|a| &a.id), audits.len(), limit);
+        Ok((audits, next_cursor))
+    }
+
+    /// # Summary
+    ///
+    /// Find the Audit entities of a single resource that were created within a date range, ordered by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource_id` - The ID of the resource.
+    /// * `resource_id_type` - The type of the ID of the resource.
+    /// * `from` - The inclusive start of the date range, or None to find from the first Audit.
+    /// * `to` - The exclusive end of the date range, or None to find up to the last Audit.
+    /// * `limit` - The limit of Audits to find.
+    /// * `page` - The page of Audits to find.
+    /// * `db` - The database to use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit_repository = AuditRepository::new("audit".to_string()).unwrap();
+    /// let db = mongodb::Client::with_uri_str("mongodb://localhost:27017")
+    ///    .await
+    ///    .unwrap()
+    ///    .database("test");
+    ///
+    /// let audits = audit_repository.find_by_resource("65b2c1b4e3a1f2d3c4b5a697", &ResourceIdType::UserId, None, None, Some(100), Some(1), &db).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Audit>, Error>` - The result of the operation.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn find_by_resource(
+        &self,
+        resource_id: &str,
+        resource_id_type: &ResourceIdType,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Audit>, Error> {
+        let filter = resource_filter(resource_id, resource_id_type, from, to)?;
+
+        let mut skip: Option<u64> = None;
+
+        if let Some(l) = limit {
+            if l > 1 {
+                if let Some(p) = page {
+                    if p > 1 {
+                        let res = u64::try_from((p - 1) * l).unwrap_or(0);
+                        skip = Some(res);
+                    }
+                }
+            }
+        }
+
+        let find_options = FindOptions::builder()
+            .limit(limit)
+            .skip(skip)
+            .sort(doc! { "_id": 1 })
+            .build();
+
+        match db
+            .collection::<Audit>(&self.collection)
+            .find(filter, find_options)
+            .await
+        {
+            Ok(r) => Ok(r.try_collect().await.unwrap_or_else(|_| vec![])),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Count the Audit entities of a single resource that were created within a date range.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource_id` - The ID of the resource.
+    /// * `resource_id_type` - The type of the ID of the resource.
+    /// * `from` - The inclusive start of the date range, or None to count from the first Audit.
+    /// * `to` - The exclusive end of the date range, or None to count up to the last Audit.
+    /// * `db` - The database to use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit_repository = AuditRepository::new("audit".to_string()).unwrap();
+    /// let total = audit_repository.count_by_resource("65b2c1b4e3a1f2d3c4b5a697", &ResourceIdType::UserId, None, None, &db).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of matching Audits.
+    pub async fn count_by_resource(
+        &self,
+        resource_id: &str,
+        resource_id_type: &ResourceIdType,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        db: &Database,
+    ) -> Result<u64, Error> {
+        let filter = resource_filter(resource_id, resource_id_type, from, to)?;
+
+        match db
+            .collection::<Audit>(&self.collection)
+            .count_documents(filter, None)
+            .await
+        {
+            Ok(c) => Ok(c),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Count the Audit entities that were created within a date range, grouped by action and by resource type.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The inclusive start of the date range, or None to count from the first Audit.
+    /// * `to` - The exclusive end of the date range, or None to count up to the last Audit.
+    /// * `db` - The database to use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit_repository = AuditRepository::new("audit".to_string()).unwrap();
+    /// let db = mongodb::Client::with_uri_str("mongodb://localhost:27017")
+    ///    .await
+    ///    .unwrap()
+    ///    .database("test");
+    ///
+    /// let stats = audit_repository.stats(None, None, &db).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AuditStats, Error>` - The result of the operation.
+    pub async fn stats(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        db: &Database,
+    ) -> Result<AuditStats, Error> {
+        let mut range = Document::new();
+        if let Some(f) = from {
+            range.insert("$gte", f);
+        }
+        if let Some(t) = to {
+            range.insert("$lt", t);
+        }
+
+        let mut pipeline = vec![];
+        if !range.is_empty() {
+            pipeline.push(doc! { "$match": { "createdAt": range } });
+        }
+        pipeline.push(doc! {
+            "$facet": {
+                "byAction": [{ "$group": { "_id": "$action", "count": { "$sum": 1 } } }],
+                "byResourceType": [{ "$group": { "_id": "$resourceType", "count": { "$sum": 1 } } }],
+            }
+        });
+
+        let result: Vec<Document> = match db
+            .collection::<Audit>(&self.collection)
+            .aggregate(pipeline, None)
+            .await
+        {
+            Ok(r) => match r.try_collect().await {
+                Ok(d) => d,
+                Err(e) => return Err(Error::MongoDb(e)),
+            },
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        let facets = result.into_iter().next().unwrap_or_default();
+        let by_action = group_counts(&facets, "byAction");
+        let by_resource_type = group_counts(&facets, "byResourceType");
+
+        Ok(AuditStats {
+            total: by_action.values().sum(),
+            by_action,
+            by_resource_type,
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Delete all Audit entities that were created before a date.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - The exclusive date before which the Audits were created.
+    /// * `db` - The Database to delete the Audits from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit_repository = AuditRepository::new("audit".to_string()).unwrap();
+    /// let db = mongodb::Client::with_uri_str("mongodb://localhost:27017")
+    ///    .await
+    ///    .unwrap()
+    ///    .database("test");
+    ///
+    /// let result = audit_repository.delete_before(Utc::now(), &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of deleted Audits.
+    pub async fn delete_before(&self, before: DateTime<Utc>, db: &Database) -> Result<u64, Error> {
+        match db
+            .collection::<Audit>(&self.collection)
+            .delete_many(doc! { "createdAt": { "$lt": before } }, None)
+            .await
+        {
+            Ok(r) => Ok(r.deleted_count),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Open a cursor over the Audit entities within a date range, ordered by ID, so that they can be exported without
+    /// loading the entire collection into memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The inclusive start of the date range, or None to export from the first Audit.
+    /// * `to` - The exclusive end of the date range, or None to export up to the last Audit.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit_repository = AuditRepository::new("audit".to_string()).unwrap();
+    /// let db = mongodb::Client::with_uri_str("mongodb://localhost:27017")
+    ///    .await
+    ///    .unwrap()
+    ///    .database("test");
+    ///
+    /// let cursor = audit_repository.export(None, None, &db).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Cursor<Audit>, Error>` - The result of the operation.
+    pub async fn export(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        db: &Database,
+    ) -> Result<Cursor<Audit>, Error> {
+        let mut range = Document::new();
+        if let Some(f) = from {
+            range.insert("$gte", f);
+        }
+        if let Some(t) = to {
+            range.insert("$lt", t);
+        }
+
+        let filter = if range.is_empty() {
+            None
+        } else {
+            Some(doc! { "createdAt": range })
+        };
+        let find_options = FindOptions::builder().sort(doc! { "_id": 1 }).build();
+
+        match db
+            .collection::<Audit>(&self.collection)
+            .find(filter, find_options)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
     /// # Summary
     ///
     /// Search for Audit entities.
@@ -170,6 +598,7 @@ impl AuditRepository {
     /// * `text` - The text to search for.
     /// * `page` - The page of Audits to find.
     /// * `limit` - The limit of Audits to find.
+    /// * `system` - Only find system Audits if true, or only User Audits if false.
     /// * `db` - The database to use.
     ///
     /// # Example
@@ -181,7 +610,7 @@ impl AuditRepository {
     ///    .unwrap()
     ///    .database("test");
     ///
-    /// let result = audit_repository.search("", Some(100), Some(1), &db).await;
+    /// let result = audit_repository.search("", Some(100), Some(1), None, &db).await;
     /// ```
     ///
     /// # Returns
@@ -192,6 +621,7 @@ impl AuditRepository {
         text: &str,
         limit: Option<i64>,
         page: Option<i64>,
+        system: Option<bool>,
         db: &Database,
     ) -> Result<Vec<Audit>, Error> {
         if text.is_empty() {
@@ -213,11 +643,14 @@ impl AuditRepository {
 
         let find_options = FindOptions::builder().limit(limit).skip(skip).build();
 
-        let filter = doc! {
+        let mut filter = doc! {
             "$text": {
                 "$search": text,
             },
         };
+        if let Some(system_filter) = system_filter(system) {
+            filter.extend(system_filter);
+        }
 
         let cursor = match db
             .collection::<Audit>(&self.collection)
@@ -231,3 +664,104 @@ impl AuditRepository {
         Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
     }
 }
+
+/// # Summary
+///
+/// Create the filter that matches either system or User Audits.
+///
+/// # Arguments
+///
+/// * `system` - Only match system Audits if true, or only User Audits if false.
+///
+/// # Returns
+///
+/// * `Option<Document>` - The filter, or None if all Audits should match.
+fn system_filter(system: Option<bool>) -> Option<Document> {
+    // Audits that were created before the system flag was introduced were all created by Users
+    match system {
+        Some(true) => Some(doc! { "system": true }),
+        Some(false) => Some(doc! { "system": { "$ne": true } }),
+        None => None,
+    }
+}
+
+/// # Summary
+///
+/// Create the filter that matches the Audits of a single resource within a date range.
+///
+/// # Arguments
+///
+/// * `resource_id` - The ID of the resource.
+/// * `resource_id_type` - The type of the ID of the resource.
+/// * `from` - The inclusive start of the date range, if any.
+/// * `to` - The exclusive end of the date range, if any.
+///
+/// # Returns
+///
+/// * `Result<Document, Error>` - The filter, or an error if the resource ID is invalid.
+fn resource_filter(
+    resource_id: &str,
+    resource_id_type: &ResourceIdType,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<Document, Error> {
+    let resource_id = match ObjectId::parse_str(resource_id) {
+        Ok(id) => id,
+        Err(_) => return Err(Error::InvalidId(resource_id.to_string())),
+    };
+
+    // A unit variant always serializes to its renamed string
+    let mut filter = doc! {
+        "resourceId": resource_id,
+        "resourceIdType": mongodb::bson::to_bson(resource_id_type).unwrap_or_default(),
+    };
+
+    let mut range = Document::new();
+    if let Some(f) = from {
+        range.insert("$gte", f);
+    }
+    if let Some(t) = to {
+        range.insert("$lt", t);
+    }
+    if !range.is_empty() {
+        filter.insert("createdAt", range);
+    }
+
+    Ok(filter)
+}
+
+/// # Summary
+///
+/// Read the counts of a $group stage inside a $facet result.
+///
+/// # Arguments
+///
+/// * `facets` - The document that is returned by the $facet stage.
+/// * `key` - The name of the facet.
+///
+/// # Returns
+///
+/// * `BTreeMap<String, u64>` - The count of every group, keyed by the grouped value.
+fn group_counts(facets: &Document, key: &str) -> BTreeMap<String, u64> {
+    let mut counts = BTreeMap::new();
+
+    for group in facets.get_array(key).into_iter().flatten() {
+        let group = match group.as_document() {
+            Some(g) => g,
+            None => continue,
+        };
+        let name = match group.get_str("_id") {
+            Ok(n) => n.to_string(),
+            Err(_) => continue,
+        };
+        let count = match group.get("count") {
+            Some(Bson::Int32(c)) => u64::try_from(*c).unwrap_or(0),
+            Some(Bson::Int64(c)) => u64::try_from(*c).unwrap_or(0),
+            _ => 0,
+        };
+
+        counts.insert(name, count);
+    }
+
+    counts
+}
@@ -0,0 +1,74 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use mongodb::bson::oid::ObjectId;
+
+pub const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
+/// # Summary
+///
+/// Encode the ObjectId of the last entity of a page into an opaque cursor.
+///
+/// # Arguments
+///
+/// * `id` - The ObjectId of the last entity of the page.
+///
+/// # Example
+///
+/// ```
+/// let cursor = encode(&ObjectId::new());
+/// ```
+///
+/// # Returns
+///
+/// * `String` - The opaque cursor.
+pub fn encode(id: &ObjectId) -> String {
+    URL_SAFE_NO_PAD.encode(id.bytes())
+}
+
+/// # Summary
+///
+/// Decode an opaque cursor into the ObjectId of the last entity of the previous page.
+///
+/// # Arguments
+///
+/// * `cursor` - The opaque cursor.
+///
+/// # Example
+///
+/// ```
+/// let id = decode("ZbLBtOOh8tPEtaaX");
+/// ```
+///
+/// # Returns
+///
+/// * `Option<ObjectId>` - The ObjectId, or None if the cursor is invalid.
+pub fn decode(cursor: &str) -> Option<ObjectId> {
+    let bytes: [u8; 12] = URL_SAFE_NO_PAD.decode(cursor).ok()?.try_into().ok()?;
+    Some(ObjectId::from_bytes(bytes))
+}
+
+/// # Summary
+///
+/// Get the cursor of the next page, if the current page is full.
+///
+/// # Arguments
+///
+/// * `last_id` - The ObjectId of the last entity of the current page.
+/// * `count` - The number of entities in the current page.
+/// * `limit` - The limit of entities per page.
+///
+/// # Example
+///
+/// ```
+/// let next_cursor = next(Some(&ObjectId::new()), 10, Some(10));
+/// ```
+///
+/// # Returns
+///
+/// * `Option<String>` - The cursor of the next page, or None if there is no next page.
+pub fn next(last_id: Option<&ObjectId>, count: usize, limit: Option<i64>) -> Option<String> {
+    match (last_id, limit) {
+        (Some(id), Some(l)) if l > 0 && count as i64 >= l => Some(encode(id)),
+        _ => None,
+    }
+}
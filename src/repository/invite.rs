@@ -0,0 +1,2 @@
+pub mod invite_model;
+pub mod invite_repository;
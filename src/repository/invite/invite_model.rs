@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Invite {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "tokenHash")]
+    pub token_hash: String,
+    pub email: Option<String>,
+    pub roles: Option<Vec<ObjectId>>,
+    #[serde(rename = "createdBy")]
+    pub created_by: String,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+    #[serde(rename = "usedAt")]
+    pub used_at: Option<mongodb::bson::DateTime>,
+    #[serde(rename = "usedBy")]
+    pub used_by: Option<String>,
+}
+
+impl Invite {
+    /// # Summary
+    ///
+    /// Create a new Invite.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_hash` - The hash of the invite token, so that the token itself is never stored.
+    /// * `email` - The email address that the Invite is bound to, if any.
+    /// * `roles` - The IDs of the Roles that are assigned to the User that registers with the Invite, if any.
+    /// * `created_by` - The ID of the User that created the Invite.
+    /// * `expires_at` - The time at which the Invite expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invite = Invite::new(String::from("hash"), None, None, user.id.to_hex(), expires_at);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Invite` - The new Invite.
+    pub fn new(
+        token_hash: String,
+        email: Option<String>,
+        roles: Option<Vec<ObjectId>>,
+        created_by: String,
+        expires_at: DateTime<Utc>,
+    ) -> Invite {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        Invite {
+            id: ObjectId::new(),
+            token_hash,
+            email,
+            roles,
+            created_by,
+            created_at: now,
+            expires_at,
+            used_at: None,
+            used_by: None,
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use crate::repository::invite::invite_model::Invite;
+use futures::TryStreamExt;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime as BsonDateTime;
+use mongodb::bson::{doc, Document};
+use mongodb::error::Error as MongodbError;
+use mongodb::options::FindOptions;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct InviteRepository {
+    pub collection: String,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("Empty Invite collection")]
+    EmptyCollection,
+    #[error("Failed to generate invite token: {0}")]
+    TokenGeneration(String),
+    #[error("MongoDb Error: {0}")]
+    MongoDb(#[from] MongodbError),
+}
+
+impl InviteRepository {
+    /// # Summary
+    ///
+    /// Create a new InviteRepository.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The collection name.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<InviteRepository, Error>` - The result of the operation.
+    pub fn new(collection: String) -> Result<InviteRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(InviteRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new Invite.
+    ///
+    /// # Arguments
+    ///
+    /// * `invite` - The Invite to create.
+    /// * `db` - The Database to create the Invite in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn create(&self, invite: Invite, db: &Database) -> Result<(), Error> {
+        match db
+            .collection::<Invite>(&self.collection)
+            .insert_one(invite, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find a page of Invites, newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The limit of Invites to find.
+    /// * `page` - The page of Invites to find.
+    /// * `db` - The Database to find the Invites in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Invite>, Error>` - The result of the operation.
+    pub async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Invite>, Error> {
+        let skip = match (limit, page) {
+            (Some(l), Some(p)) if p > 1 => Some(u64::try_from((p - 1) * l).unwrap_or(0)),
+            _ => None,
+        };
+
+        let find_options = FindOptions::builder()
+            .sort(doc! { "createdAt": -1 })
+            .limit(limit)
+            .skip(skip)
+            .build();
+
+        let cursor = match db
+            .collection::<Invite>(&self.collection)
+            .find(None, find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        match cursor.try_collect().await {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Count all Invites.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to count the Invites in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The result of the operation.
+    pub async fn count(&self, db: &Database) -> Result<u64, Error> {
+        match db
+            .collection::<Invite>(&self.collection)
+            .count_documents(None, None)
+            .await
+        {
+            Ok(c) => Ok(c),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Mark an unused Invite that has not expired yet as used, in a single atomic operation, so that an Invite can
+    /// never be used twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `token_hash` - The hash of the invite token.
+    /// * `db` - The Database to find the Invite in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<Invite>, Error>` - The Invite as it was before it was used, or None if there is no usable Invite.
+    pub async fn consume(&self, token_hash: &str, db: &Database) -> Result<Option<Invite>, Error> {
+        let now = BsonDateTime::now();
+
+        match db
+            .collection::<Invite>(&self.collection)
+            .find_one_and_update(
+                consume_filter(token_hash, now),
+                doc! { "$set": { "usedAt": now } },
+                None,
+            )
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Record the User that registered with a used Invite.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Invite.
+    /// * `user_id` - The ID of the User that registered with the Invite.
+    /// * `db` - The Database to update the Invite in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn set_used_by(
+        &self,
+        id: &ObjectId,
+        user_id: &str,
+        db: &Database,
+    ) -> Result<(), Error> {
+        match db
+            .collection::<Invite>(&self.collection)
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "usedBy": user_id } },
+                None,
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Mark a used Invite as unused again, because the registration that used it failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Invite.
+    /// * `db` - The Database to update the Invite in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn release(&self, id: &ObjectId, db: &Database) -> Result<(), Error> {
+        match db
+            .collection::<Invite>(&self.collection)
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "usedAt": null, "usedBy": null } },
+                None,
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
+
+/// # Summary
+///
+/// Create the filter that matches the Invite with the given token hash, if it has not been used and has not expired.
+///
+/// # Arguments
+///
+/// * `token_hash` - The hash of the invite token.
+/// * `now` - The current time.
+///
+/// # Returns
+///
+/// * `Document` - The filter.
+fn consume_filter(token_hash: &str, now: BsonDateTime) -> Document {
+    doc! {
+        "tokenHash": token_hash,
+        "usedAt": null,
+        "expiresAt": { "$gt": now },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use mongodb::bson::{to_document, Bson};
+
+    /// Evaluate a filter that only uses equality, null and $gt conditions against a document, like MongoDB would.
+    fn matches(filter: &Document, document: &Document) -> bool {
+        filter.iter().all(|(key, condition)| {
+            let value = document.get(key).unwrap_or(&Bson::Null);
+            match condition {
+                Bson::Document(c) => match (c.get("$gt"), value) {
+                    (Some(Bson::DateTime(min)), Bson::DateTime(v)) => v > min,
+                    _ => false,
+                },
+                c => c == value,
+            }
+        })
+    }
+
+    fn invite(expires_in: Duration) -> Invite {
+        Invite::new(
+            String::from("hash"),
+            None,
+            None,
+            ObjectId::new().to_hex(),
+            Utc::now() + expires_in,
+        )
+    }
+
+    fn is_consumable(invite: &Invite, token_hash: &str) -> bool {
+        matches(
+            &consume_filter(token_hash, BsonDateTime::now()),
+            &to_document(invite).unwrap(),
+        )
+    }
+
+    #[test]
+    fn consume_filter_matches_an_unused_invite_that_has_not_expired() {
+        assert!(is_consumable(&invite(Duration::hours(1)), "hash"));
+    }
+
+    #[test]
+    fn consume_filter_rejects_another_token() {
+        assert!(!is_consumable(&invite(Duration::hours(1)), "other"));
+    }
+
+    #[test]
+    fn consume_filter_rejects_a_used_invite() {
+        let mut invite = invite(Duration::hours(1));
+        invite.used_at = Some(BsonDateTime::now());
+
+        assert!(!is_consumable(&invite, "hash"));
+    }
+
+    #[test]
+    fn consume_filter_rejects_an_expired_invite() {
+        assert!(!is_consumable(&invite(Duration::hours(-1)), "hash"));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod migration_model;
+pub mod migration_repository;
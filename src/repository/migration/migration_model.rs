@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// The versions and names of all migrations, in the order in which they are applied.
+/// Versions must never be reused or reordered once they have been released.
+pub const MIGRATIONS: [(i32, &str); 4] = [
+    (1, "add_version_to_users_and_roles"),
+    (2, "add_system_flag_to_audits"),
+    (3, "add_password_changed_at_to_users"),
+    (4, "merge_duplicate_role_and_permission_names"),
+];
+
+#[derive(Serialize, Deserialize)]
+pub struct Migration {
+    #[serde(rename = "_id")]
+    pub version: i32,
+    pub name: String,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "appliedAt")]
+    pub applied_at: DateTime<Utc>,
+}
+
+impl Migration {
+    /// # Summary
+    ///
+    /// Create a new Migration that records that a migration was applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The version of the migration.
+    /// * `name` - The name of the migration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let migration = Migration::new(1, "add_version_to_users_and_roles");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Migration` - The new Migration.
+    pub fn new(version: i32, name: &str) -> Migration {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        Migration {
+            version,
+            name: name.to_string(),
+            applied_at: now,
+        }
+    }
+}
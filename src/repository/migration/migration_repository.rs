@@ -0,0 +1,360 @@
+use crate::repository::is_duplicate_key;
+use crate::repository::migration::migration_model::Migration;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, Bson, Document};
+use mongodb::error::Error as MongodbError;
+use mongodb::options::FindOneOptions;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct MigrationRepository {
+    pub collection: String,
+    pub user_collection: String,
+    pub role_collection: String,
+    pub permission_collection: String,
+    pub audit_collection: String,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("Empty Migration collection")]
+    EmptyCollection,
+    #[error("Unknown Migration version: {0}")]
+    UnknownVersion(i32),
+    #[error("MongoDb Error: {0}")]
+    MongoDb(#[from] MongodbError),
+}
+
+impl MigrationRepository {
+    /// # Summary
+    ///
+    /// Create a new MigrationRepository.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The name of the collection that records the applied migrations.
+    /// * `user_collection` - The name of the User collection.
+    /// * `role_collection` - The name of the Role collection.
+    /// * `permission_collection` - The name of the Permission collection.
+    /// * `audit_collection` - The name of the Audit collection.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let migration_repository = MigrationRepository::new(String::from("migrations"), String::from("users"), String::from("roles"), String::from("permissions"), String::from("audits"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<MigrationRepository, Error>` - The result of the operation.
+    pub fn new(
+        collection: String,
+        user_collection: String,
+        role_collection: String,
+        permission_collection: String,
+        audit_collection: String,
+    ) -> Result<MigrationRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(MigrationRepository {
+            collection,
+            user_collection,
+            role_collection,
+            permission_collection,
+            audit_collection,
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Find the versions of all migrations that have been applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<i32>, Error>` - The applied versions.
+    pub async fn find_applied_versions(&self, db: &Database) -> Result<Vec<i32>, Error> {
+        let cursor = match db
+            .collection::<Migration>(&self.collection)
+            .find(None, None)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        match cursor.try_collect::<Vec<Migration>>().await {
+            Ok(d) => Ok(d.into_iter().map(|m| m.version).collect()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find the version of the most recent migration that has been applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<i32>, Error>` - The current schema version, or None if no migration has been applied yet.
+    pub async fn current_version(&self, db: &Database) -> Result<Option<i32>, Error> {
+        let find_options = FindOneOptions::builder().sort(doc! { "_id": -1 }).build();
+
+        match db
+            .collection::<Migration>(&self.collection)
+            .find_one(None, find_options)
+            .await
+        {
+            Ok(d) => Ok(d.map(|m| m.version)),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Apply a migration. Every migration only touches the documents that have not been migrated yet, so that it can
+    /// safely be applied more than once.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The version of the migration.
+    /// * `db` - The Database.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of documents that were migrated.
+    pub async fn apply(&self, version: i32, db: &Database) -> Result<u64, Error> {
+        match version {
+            1 => {
+                let filter = doc! { "version": { "$exists": false } };
+                let update = doc! { "$set": { "version": 0_i64 } };
+                let users = self
+                    .update_many(
+                        &self.user_collection,
+                        filter.clone(),
+                        vec![update.clone()],
+                        db,
+                    )
+                    .await?;
+                let roles = self
+                    .update_many(&self.role_collection, filter, vec![update], db)
+                    .await?;
+                Ok(users + roles)
+            }
+            2 => {
+                self.update_many(
+                    &self.audit_collection,
+                    doc! { "system": { "$exists": false } },
+                    vec![doc! { "$set": { "system": false } }],
+                    db,
+                )
+                .await
+            }
+            3 => {
+                // Passwords of Users that were created before the change date was stored were last changed at creation
+                self.update_many(
+                    &self.user_collection,
+                    doc! { "passwordChangedAt": { "$exists": false } },
+                    vec![doc! { "$set": { "passwordChangedAt": "$createdAt" } }],
+                    db,
+                )
+                .await
+            }
+            4 => {
+                // The names must be unique regardless of their case before the unique name indexes can be created
+                let permissions = self
+                    .merge_duplicate_names(
+                        &self.permission_collection,
+                        None,
+                        &self.role_collection,
+                        "permissions",
+                        db,
+                    )
+                    .await?;
+                let roles = self
+                    .merge_duplicate_names(
+                        &self.role_collection,
+                        Some("permissions"),
+                        &self.user_collection,
+                        "roles",
+                        db,
+                    )
+                    .await?;
+                Ok(permissions + roles)
+            }
+            _ => Err(Error::UnknownVersion(version)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Record that a migration was applied. Recording a migration that another instance already recorded is not
+    /// an error.
+    ///
+    /// # Arguments
+    ///
+    /// * `migration` - The Migration to record.
+    /// * `db` - The Database.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn record(&self, migration: Migration, db: &Database) -> Result<(), Error> {
+        match db
+            .collection::<Migration>(&self.collection)
+            .insert_one(migration, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) if is_duplicate_key(&e) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Trim the names of the documents of a collection and merge the documents whose names only differ in case. The
+    /// oldest document of every group is kept, the references to the others are moved to it, and the others are
+    /// deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The name of the collection whose names must be unique.
+    /// * `merged_field` - The array field whose values are merged into the kept document, if any.
+    /// * `referencing_collection` - The name of the collection that refers to the documents.
+    /// * `reference_field` - The array field of the referencing collection that holds the IDs of the documents.
+    /// * `db` - The Database.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of documents that were trimmed or merged.
+    async fn merge_duplicate_names(
+        &self,
+        collection: &str,
+        merged_field: Option<&str>,
+        referencing_collection: &str,
+        reference_field: &str,
+        db: &Database,
+    ) -> Result<u64, Error> {
+        let trimmed = self
+            .update_many(
+                collection,
+                doc! { "name": { "$regex": "^\\s|\\s$" } },
+                vec![doc! { "$set": { "name": { "$trim": { "input": "$name" } } } }],
+                db,
+            )
+            .await?;
+
+        let pipeline = vec![
+            doc! { "$sort": { "_id": 1 } },
+            doc! { "$group": {
+                "_id": { "$toLower": "$name" },
+                "ids": { "$push": "$_id" },
+                "count": { "$sum": 1 },
+            } },
+            doc! { "$match": { "count": { "$gt": 1 } } },
+        ];
+        let groups: Vec<Document> = db
+            .collection::<Document>(collection)
+            .aggregate(pipeline, None)
+            .await?
+            .try_collect()
+            .await?;
+
+        let mut merged: u64 = 0;
+        for group in groups {
+            let ids = match group.get_array("ids") {
+                Ok(i) => i.clone(),
+                Err(_) => continue,
+            };
+            let (keep, duplicates) = match ids.split_first() {
+                Some((k, d)) => (k.clone(), d.to_vec()),
+                None => continue,
+            };
+
+            if let Some(field) = merged_field {
+                let values: Vec<Bson> = db
+                    .collection::<Document>(collection)
+                    .find(doc! { "_id": { "$in": duplicates.clone() } }, None)
+                    .await?
+                    .try_collect::<Vec<Document>>()
+                    .await?
+                    .iter()
+                    .filter_map(|d| d.get_array(field).ok())
+                    .flatten()
+                    .cloned()
+                    .collect();
+                db.collection::<Document>(collection)
+                    .update_one(
+                        doc! { "_id": keep.clone() },
+                        doc! { "$addToSet": { field: { "$each": values } } },
+                        None,
+                    )
+                    .await?;
+            }
+
+            let references = db.collection::<Document>(referencing_collection);
+            references
+                .update_many(
+                    doc! { reference_field: { "$in": duplicates.clone() } },
+                    doc! { "$addToSet": { reference_field: keep } },
+                    None,
+                )
+                .await?;
+            references
+                .update_many(
+                    doc! { reference_field: { "$in": duplicates.clone() } },
+                    doc! { "$pull": { reference_field: { "$in": duplicates.clone() } } },
+                    None,
+                )
+                .await?;
+
+            let deleted = db
+                .collection::<Document>(collection)
+                .delete_many(doc! { "_id": { "$in": duplicates } }, None)
+                .await?;
+            merged += deleted.deleted_count;
+        }
+
+        Ok(trimmed + merged)
+    }
+
+    /// # Summary
+    ///
+    /// Update all documents of a collection that match a filter, using an aggregation pipeline.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The name of the collection.
+    /// * `filter` - The filter that matches the documents to update.
+    /// * `pipeline` - The aggregation pipeline that updates the documents.
+    /// * `db` - The Database.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of documents that were updated.
+    async fn update_many(
+        &self,
+        collection: &str,
+        filter: Document,
+        pipeline: Vec<Document>,
+        db: &Database,
+    ) -> Result<u64, Error> {
+        match db
+            .collection::<Document>(collection)
+            .update_many(filter, pipeline, None)
+            .await
+        {
+            Ok(r) => Ok(r.modified_count),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
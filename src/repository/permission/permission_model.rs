@@ -5,6 +5,31 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::time::SystemTime;
 
+/// The Permission entities that are created when the database is initialized and that the API itself relies on.
+pub const PROTECTED_PERMISSIONS: [&str; 21] = [
+    "CAN_CREATE_PERMISSION",
+    "CAN_READ_PERMISSION",
+    "CAN_UPDATE_PERMISSION",
+    "CAN_DELETE_PERMISSION",
+    "CAN_CREATE_ROLE",
+    "CAN_READ_ROLE",
+    "CAN_UPDATE_ROLE",
+    "CAN_DELETE_ROLE",
+    "CAN_CREATE_USER",
+    "CAN_READ_USER",
+    "CAN_READ_USER_DETAILS",
+    "CAN_UPDATE_USER",
+    "CAN_DELETE_USER",
+    "CAN_EXPORT_USER",
+    "CAN_IMPORT_USER",
+    "CAN_READ_AUDIT",
+    "CAN_DELETE_AUDIT",
+    "CAN_CREATE_INVITE",
+    "CAN_READ_INVITE",
+    "CAN_UPDATE_SELF",
+    "CAN_DELETE_SELF",
+];
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Permission {
     #[serde(rename = "_id")]
@@ -17,6 +42,8 @@ pub struct Permission {
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     #[serde(rename = "updatedAt")]
     pub updated_at: DateTime<Utc>,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
 }
 
 impl Permission {
@@ -47,6 +74,84 @@ impl Permission {
             description,
             created_at: now,
             updated_at: now,
+            updated_by: None,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the Permission is one of the protected Permission entities that cannot be deleted.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the name of the Permission is in PROTECTED_PERMISSIONS, regardless of its case.
+    pub fn is_protected(&self) -> bool {
+        PROTECTED_PERMISSIONS
+            .iter()
+            .any(|p| p.eq_ignore_ascii_case(&self.name))
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the Permission is a wildcard that can match other Permission names.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the Permission is the global `*` Permission or ends with `:*`.
+    pub fn is_wildcard(&self) -> bool {
+        self.wildcard_prefix().is_some()
+    }
+
+    /// # Summary
+    ///
+    /// Get the prefix of the Permission names that a wildcard Permission satisfies. Hierarchical names are separated by
+    /// colons, so `users:*` satisfies every longer name that starts with `users:`, such as `users:read` and
+    /// `users:read:own`, while `*` satisfies every Permission.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let permission = Permission::new(String::from("users:*"), None);
+    /// assert_eq!(permission.wildcard_prefix(), Some("users:"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<&str>` - The prefix, which is empty for the global `*` Permission, or None if the Permission is not a
+    ///   wildcard.
+    pub fn wildcard_prefix(&self) -> Option<&str> {
+        match self.name.strip_suffix('*') {
+            Some(prefix) if prefix.is_empty() || prefix.ends_with(':') => Some(prefix),
+            _ => None,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the Permission satisfies a required Permission name, either by name or as a wildcard.
+    ///
+    /// # Arguments
+    ///
+    /// * `required` - The name of the required Permission.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let permission = Permission::new(String::from("users:*"), None);
+    /// assert!(permission.matches("users:read"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the Permission satisfies the required Permission.
+    pub fn matches(&self, required: &str) -> bool {
+        if self.name == required {
+            return true;
+        }
+
+        match self.wildcard_prefix() {
+            Some(prefix) => required.len() > prefix.len() && required.starts_with(prefix),
+            None => false,
         }
     }
 }
@@ -82,6 +187,7 @@ impl From<CreatePermission> for Permission {
             description: permission.description,
             created_at: now,
             updated_at: now,
+            updated_by: None,
         }
     }
 }
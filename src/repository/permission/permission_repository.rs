@@ -1,15 +1,15 @@
 use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::is_duplicate_key;
 use crate::repository::permission::permission_model::Permission;
 use crate::repository::role::role_repository::Error as RoleError;
 use crate::services::role::role_service::RoleService;
 use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
-use mongodb::bson::doc;
 use mongodb::bson::oid::ObjectId;
 use mongodb::bson::Bson;
+use mongodb::bson::{doc, Document};
 use mongodb::options::FindOptions;
-use mongodb::{error::Error as MongoError, Database};
-use std::fmt;
+use mongodb::{error::Error as MongoError, ClientSession, Database};
 use std::fmt::Debug;
 use std::time::SystemTime;
 
@@ -18,51 +18,55 @@ pub struct PermissionRepository {
     pub collection: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, thiserror::Error)]
 pub enum Error {
+    #[error("Invalid Permission ID: {0}")]
     InvalidId(String),
+    #[error("Empty collection")]
     EmptyCollection,
+    #[error("Empty Permission ID")]
     EmptyId,
+    #[error("Empty Permission name")]
     EmptyName,
+    #[error("Permission name is longer than {0} characters")]
+    NameTooLong(usize),
+    #[error("Empty text search")]
     EmptyTextSearch,
+    #[error("Permission name already taken")]
     NameAlreadyTaken,
+    #[error("Permission is protected: {0}")]
+    Protected(String),
+    #[error("Permission not found: {0}")]
     PermissionNotFound(String),
-    MongoDb(MongoError),
-    Role(RoleError),
-    Audit(AuditError),
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[from] MongoError),
+    #[error("Role error: {0}")]
+    Role(#[from] RoleError),
+    #[error("Audit error: {0}")]
+    Audit(#[from] AuditError),
 }
 
-impl fmt::Display for Error {
+impl Error {
     /// # Summary
     ///
-    /// Display the error message.
-    ///
-    /// # Arguments
-    ///
-    /// * `f` - The formatter.
+    /// Get the name of the input field that caused the Error, if any.
     ///
     /// # Example
     ///
     /// ```
-    /// let error = Error::EmptyCollection;
-    /// println!("{}", error);
+    /// let error = Error::EmptyName;
+    /// assert_eq!(error.field(), Some("name"));
     /// ```
     ///
     /// # Returns
     ///
-    /// * `fmt::Result` - The result of the display.
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &self {
-            Error::InvalidId(id) => write!(f, "Invalid Permission ID: {}", id),
-            Error::EmptyCollection => write!(f, "Empty collection"),
-            Error::EmptyId => write!(f, "Empty Permission ID"),
-            Error::EmptyName => write!(f, "Empty Permission name"),
-            Error::EmptyTextSearch => write!(f, "Empty text search"),
-            Error::NameAlreadyTaken => write!(f, "Permission name already taken"),
-            Error::PermissionNotFound(id) => write!(f, "Permission not found: {}", id),
-            Error::MongoDb(e) => write!(f, "MongoDB error: {}", e),
-            Error::Role(e) => write!(f, "Role error: {}", e),
-            Error::Audit(e) => write!(f, "Audit error: {}", e),
+    /// * `Option<&'static str>` - The name of the field, or None if the Error is not caused by a specific field.
+    pub fn field(&self) -> Option<&'static str> {
+        match self {
+            Error::InvalidId(_) | Error::EmptyId => Some("id"),
+            Error::EmptyName | Error::NameTooLong(_) | Error::NameAlreadyTaken => Some("name"),
+            Error::EmptyTextSearch => Some("text"),
+            _ => None,
         }
     }
 }
@@ -135,6 +139,7 @@ impl PermissionRepository {
             .await
         {
             Ok(r) => r,
+            Err(e) if is_duplicate_key(&e) => return Err(Error::NameAlreadyTaken),
             Err(e) => return Err(Error::MongoDb(e)),
         };
 
@@ -211,13 +216,14 @@ impl PermissionRepository {
     /// # Arguments
     ///
     /// * `id_vec` - The vector of IDs to find.
+    /// * `preserve_order` - Whether the Permissions should be returned in the order of `id_vec`.
     /// * `db` - The database to use.
     ///
     /// # Example
     ///
     /// ```
     /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// let permissions = permission_repository.find_by_id_vec(vec![String::from("permission_id")], &db).await;
+    /// let permissions = permission_repository.find_by_id_vec(vec![String::from("permission_id")], true, &db).await;
     /// ```
     ///
     /// # Returns
@@ -226,6 +232,7 @@ impl PermissionRepository {
     pub async fn find_by_id_vec(
         &self,
         id_vec: Vec<String>,
+        preserve_order: bool,
         db: &Database,
     ) -> Result<Vec<Permission>, Error> {
         let mut oid_vec: Vec<ObjectId> = vec![];
@@ -242,7 +249,7 @@ impl PermissionRepository {
 
         let filter = doc! {
             "_id": {
-                "$in": oid_vec,
+                "$in": &oid_vec,
             },
         };
 
@@ -255,7 +262,18 @@ impl PermissionRepository {
             Err(e) => return Err(Error::MongoDb(e)),
         };
 
-        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+        let mut permissions: Vec<Permission> =
+            cursor.try_collect().await.unwrap_or_else(|_| vec![]);
+        if preserve_order {
+            permissions.sort_by_key(|permission| {
+                oid_vec
+                    .iter()
+                    .position(|oid| oid == &permission.id)
+                    .unwrap_or(usize::MAX)
+            });
+        }
+
+        Ok(permissions)
     }
 
     /// # Summary
@@ -363,6 +381,58 @@ impl PermissionRepository {
         Ok(permission)
     }
 
+    /// # Summary
+    ///
+    /// Find the Permissions whose name is longer than and starts with one of the given prefixes. The prefixes are
+    /// matched case-sensitively and anchored, so that the query can use the index on the name.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefixes` - The prefixes of the names of the Permissions to find.
+    /// * `db` - The database to use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let permission_repository = PermissionRepository::new(String::from("permissions"));
+    /// let permissions = permission_repository.find_by_name_prefixes(&["users:"], &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Permission>, Error>` - The result of the operation.
+    pub async fn find_by_name_prefixes(
+        &self,
+        prefixes: &[&str],
+        db: &Database,
+    ) -> Result<Vec<Permission>, Error> {
+        if prefixes.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let conditions: Vec<Document> = prefixes
+            .iter()
+            .map(|p| {
+                let re = mongodb::bson::Regex {
+                    pattern: format!("^{}.", regex::escape(p)),
+                    options: String::from("s"),
+                };
+                doc! { "name": { "$regex": Bson::RegularExpression(re) } }
+            })
+            .collect();
+
+        let cursor = match db
+            .collection::<Permission>(&self.collection)
+            .find(doc! { "$or": conditions }, None)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
     /// # Summary
     ///
     /// Update a Permission.
@@ -417,6 +487,7 @@ impl PermissionRepository {
                 "name": permission.name,
                 "description": permission.description,
                 "updated_at": now,
+                "updatedBy": permission.updated_by,
             }
         };
 
@@ -426,6 +497,7 @@ impl PermissionRepository {
             .await
         {
             Ok(d) => d,
+            Err(e) if is_duplicate_key(&e) => return Err(Error::NameAlreadyTaken),
             Err(e) => return Err(Error::MongoDb(e)),
         };
 
@@ -438,7 +510,7 @@ impl PermissionRepository {
 
     /// # Summary
     ///
-    /// Delete a Permission.
+    /// Delete a Permission and remove it from all Roles that reference it.
     ///
     /// # Arguments
     ///
@@ -450,18 +522,18 @@ impl PermissionRepository {
     ///
     /// ```
     /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// permission_repository.delete(String::from("permission_id"), &db, role_repository).await;
+    /// let affected_roles = permission_repository.delete(String::from("permission_id"), &db, role_repository).await;
     /// ```
     ///
     /// # Returns
     ///
-    /// * `Result<(), Error>` - The result of the operation.
+    /// * `Result<u64, Error>` - The number of Roles the Permission was removed from, or an Error.
     pub async fn delete(
         &self,
         id: &str,
         db: &Database,
         role_service: &RoleService,
-    ) -> Result<(), Error> {
+    ) -> Result<u64, Error> {
         if id.is_empty() {
             return Err(Error::EmptyId);
         }
@@ -482,16 +554,104 @@ impl PermissionRepository {
             .delete_one(filter, None)
             .await
         {
-            Ok(_) => {
-                match role_service.delete_permission_from_all_roles(id, db).await {
-                    Ok(_) => (),
-                    Err(e) => return Err(Error::Role(e)),
-                };
+            Ok(r) => {
+                if r.deleted_count == 0 {
+                    return Err(Error::PermissionNotFound(id.to_string()));
+                }
             }
             Err(e) => return Err(Error::MongoDb(e)),
         };
 
-        Ok(())
+        match role_service.delete_permission_from_all_roles(id, db).await {
+            Ok(count) => Ok(count),
+            Err(e) => Err(Error::Role(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Permission as part of the transaction of a ClientSession. The references to the Permission are not
+    /// removed, so that they can be removed in the same transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Permission to delete.
+    /// * `db` - The database to use.
+    /// * `session` - The ClientSession whose transaction the Permission is deleted in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut session = client.start_session(None).await?;
+    /// session.start_transaction(None).await?;
+    /// permission_repository.delete_with_session("permission_id", &db, &mut session).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn delete_with_session(
+        &self,
+        id: &str,
+        db: &Database,
+        session: &mut ClientSession,
+    ) -> Result<(), Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        match db
+            .collection::<Permission>(&self.collection)
+            .delete_one_with_session(doc! { "_id": target_object_id }, None, session)
+            .await
+        {
+            Ok(r) if r.deleted_count == 0 => Err(Error::PermissionNotFound(id.to_string())),
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Count the Permission entities, optionally only the ones that match a text search.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to search for, or None to count all Permission entities.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let permission_repository = PermissionRepository::new(String::from("permissions")).unwrap();
+    /// let total = permission_repository.count(None, &db).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of matching Permission entities.
+    pub async fn count(&self, text: Option<&str>, db: &Database) -> Result<u64, Error> {
+        let filter = match text {
+            Some("") => return Err(Error::EmptyTextSearch),
+            Some(t) => Some(doc! { "$text": { "$search": t } }),
+            None => None,
+        };
+
+        match db
+            .collection::<Permission>(&self.collection)
+            .count_documents(filter, None)
+            .await
+        {
+            Ok(c) => Ok(c),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
     }
 
     /// # Summary
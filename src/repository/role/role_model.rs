@@ -5,6 +5,9 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::time::SystemTime;
 
+/// The Role entities that are created when the database is initialized and that registration relies on.
+pub const PROTECTED_ROLES: [&str; 2] = ["ADMIN", "DEFAULT"];
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Role {
     #[serde(rename = "_id")]
@@ -18,6 +21,10 @@ pub struct Role {
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     #[serde(rename = "updatedAt")]
     pub updated_at: DateTime<Utc>,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
+    #[serde(default)]
+    pub version: u64,
 }
 
 impl Role {
@@ -54,8 +61,23 @@ impl Role {
             permissions,
             created_at: now,
             updated_at: now,
+            updated_by: None,
+            version: 0,
         }
     }
+
+    /// # Summary
+    ///
+    /// Check whether the Role is one of the protected Role entities that cannot be deleted.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the name of the Role is in PROTECTED_ROLES, regardless of its case.
+    pub fn is_protected(&self) -> bool {
+        PROTECTED_ROLES
+            .iter()
+            .any(|r| r.eq_ignore_ascii_case(&self.name))
+    }
 }
 
 impl From<CreateRole> for Role {
@@ -106,6 +128,8 @@ impl From<CreateRole> for Role {
             permissions,
             created_at: now,
             updated_at: now,
+            updated_by: None,
+            version: 0,
         }
     }
 }
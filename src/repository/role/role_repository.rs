@@ -1,4 +1,5 @@
 use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::is_duplicate_key;
 use crate::repository::role::role_model::Role;
 use crate::repository::user::user_repository::Error as UserError;
 use crate::services::user::user_service::UserService;
@@ -7,10 +8,10 @@ use futures::TryStreamExt;
 use mongodb::bson::doc;
 use mongodb::bson::oid::ObjectId;
 use mongodb::bson::Bson;
+use mongodb::bson::DateTime as BsonDateTime;
 use mongodb::error::Error as MongoError;
-use mongodb::options::FindOptions;
-use mongodb::Database;
-use std::fmt;
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use mongodb::{ClientSession, Database};
 use std::time::SystemTime;
 
 #[derive(Clone)]
@@ -18,44 +19,62 @@ pub struct RoleRepository {
     pub collection: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, thiserror::Error)]
 pub enum Error {
+    #[error("Invalid Role ID: {0}")]
     InvalidId(String),
+    #[error("Empty collection")]
     EmptyCollection,
+    #[error("Empty Role ID")]
     EmptyId,
+    #[error("Empty Role name")]
     EmptyName,
+    #[error("Role name is longer than {0} characters")]
+    NameTooLong(usize),
+    #[error("Empty text search")]
     EmptyTextSearch,
+    #[error("Role name already taken")]
     NameAlreadyTaken,
+    #[error("Role is protected: {0}")]
+    Protected(String),
+    #[error("Role not found: {0}")]
     RoleNotFound(String),
-    MongoDb(MongoError),
-    User(UserError),
-    Audit(AuditError),
+    #[error("Role template not found: {0}")]
+    TemplateNotFound(String),
+    #[error("Permission error: {0}")]
+    Permission(String),
+    #[error("Role was modified by another request: {0}")]
+    Conflict(String),
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[from] MongoError),
+    #[error("User error: {0}")]
+    User(#[from] UserError),
+    #[error("Audit error: {0}")]
+    Audit(#[from] AuditError),
 }
 
-impl fmt::Display for Error {
+impl Error {
     /// # Summary
     ///
-    /// Formats the value using the given formatter.
+    /// Get the name of the input field that caused the Error, if any.
     ///
-    /// # Arguments
+    /// # Example
     ///
-    /// * `f` - A reference to a formatter.
+    /// ```
+    /// let error = Error::EmptyName;
+    /// assert_eq!(error.field(), Some("name"));
+    /// ```
     ///
     /// # Returns
     ///
-    /// A fmt::Result.
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match &self {
-            Error::InvalidId(id) => write!(f, "Invalid Role ID: {}", id),
-            Error::EmptyCollection => write!(f, "Empty collection"),
-            Error::EmptyId => write!(f, "Empty Role ID"),
-            Error::EmptyName => write!(f, "Empty Role name"),
-            Error::EmptyTextSearch => write!(f, "Empty text search"),
-            Error::NameAlreadyTaken => write!(f, "Role name already taken"),
-            Error::RoleNotFound(id) => write!(f, "Role not found: {}", id),
-            Error::MongoDb(e) => write!(f, "MongoDB error: {}", e),
-            Error::User(e) => write!(f, "User error: {}", e),
-            Error::Audit(e) => write!(f, "Audit error: {}", e),
+    /// * `Option<&'static str>` - The name of the field, or None if the Error is not caused by a specific field.
+    pub fn field(&self) -> Option<&'static str> {
+        match self {
+            Error::InvalidId(_) | Error::EmptyId => Some("id"),
+            Error::EmptyName | Error::NameTooLong(_) | Error::NameAlreadyTaken => Some("name"),
+            Error::Permission(_) => Some("permissions"),
+            Error::EmptyTextSearch => Some("text"),
+            _ => None,
         }
     }
 }
@@ -143,6 +162,7 @@ impl RoleRepository {
             .await
         {
             Ok(_) => (),
+            Err(e) if is_duplicate_key(&e) => return Err(Error::NameAlreadyTaken),
             Err(e) => return Err(Error::MongoDb(e)),
         };
 
@@ -223,6 +243,7 @@ impl RoleRepository {
     /// # Arguments
     ///
     /// * `ids` - A vector of Strings that holds the IDs.
+    /// * `preserve_order` - Whether the Roles should be returned in the order of `ids`.
     /// * `db` - A reference to a Database instance.
     ///
     /// # Example
@@ -233,7 +254,7 @@ impl RoleRepository {
     ///   Err(e) => panic!("Failed to initialize Role repository: {:?}", e),
     /// };
     ///
-    /// let roles = match role_repository.find_by_id_vec(vec!["id".to_string()], &db).await {
+    /// let roles = match role_repository.find_by_id_vec(vec!["id".to_string()], true, &db).await {
     ///   Ok(d) => d,
     ///   Err(e) => panic!("Failed to find Roles by ID: {:?}", e),
     /// };
@@ -245,6 +266,7 @@ impl RoleRepository {
     pub async fn find_by_id_vec(
         &self,
         ids: Vec<String>,
+        preserve_order: bool,
         db: &Database,
     ) -> Result<Vec<Role>, Error> {
         let mut oid_vec: Vec<ObjectId> = vec![];
@@ -263,7 +285,7 @@ impl RoleRepository {
 
         let filter = doc! {
             "_id": {
-                "$in": oid_vec,
+                "$in": &oid_vec,
             },
         };
 
@@ -276,7 +298,17 @@ impl RoleRepository {
             Err(e) => return Err(Error::MongoDb(e)),
         };
 
-        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+        let mut roles: Vec<Role> = cursor.try_collect().await.unwrap_or_else(|_| vec![]);
+        if preserve_order {
+            roles.sort_by_key(|role| {
+                oid_vec
+                    .iter()
+                    .position(|oid| oid == &role.id)
+                    .unwrap_or(usize::MAX)
+            });
+        }
+
+        Ok(roles)
     }
 
     /// # Summary
@@ -422,8 +454,17 @@ impl RoleRepository {
         }
 
         let role_id = role.id;
-        let filter = doc! {
-            "_id": &role_id,
+        // Documents that were created before versioning was introduced do not have a version yet
+        let filter = if role.version == 0 {
+            doc! {
+                "_id": &role_id,
+                "$or": [{ "version": 0_i64 }, { "version": { "$exists": false } }],
+            }
+        } else {
+            doc! {
+                "_id": &role_id,
+                "version": role.version as i64,
+            }
         };
 
         let now: DateTime<Utc> = SystemTime::now().into();
@@ -435,23 +476,35 @@ impl RoleRepository {
                 "description": role.description,
                 "permissions": role.permissions,
                 "updated_at": now,
-            }
+                "updatedBy": role.updated_by,
+            },
+            "$inc": {
+                "version": 1_i64,
+            },
         };
 
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
         let role = match db
             .collection::<Role>(&self.collection)
-            .find_one_and_update(filter, update, None)
+            .find_one_and_update(filter, update, options)
             .await
         {
             Ok(d) => d,
+            Err(e) if is_duplicate_key(&e) => return Err(Error::NameAlreadyTaken),
             Err(e) => return Err(Error::MongoDb(e)),
         };
 
-        if role.is_none() {
-            return Err(Error::RoleNotFound(role_id.to_hex()));
+        match role {
+            Some(r) => Ok(r),
+            None => match self.find_by_id(&role_id.to_hex(), db).await {
+                Ok(Some(_)) => Err(Error::Conflict(role_id.to_hex())),
+                Ok(None) => Err(Error::RoleNotFound(role_id.to_hex())),
+                Err(e) => Err(e),
+            },
         }
-
-        Ok(role.unwrap())
     }
 
     /// # Summary
@@ -507,6 +560,7 @@ impl RoleRepository {
             .delete_one(filter, None)
             .await
         {
+            Ok(r) if r.deleted_count == 0 => Err(Error::RoleNotFound(id.to_string())),
             Ok(_) => match user_service.delete_role_from_all_users(id, db).await {
                 Ok(_) => Ok(()),
                 Err(e) => Err(Error::User(e)),
@@ -517,7 +571,7 @@ impl RoleRepository {
 
     /// # Summary
     ///
-    /// Delete a permission from all roles.
+    /// Delete a permission from all roles using a single update.
     ///
     /// # Arguments
     ///
@@ -533,19 +587,19 @@ impl RoleRepository {
     /// };
     ///
     /// match role_repository.delete_permission_from_all_roles("permission_id", &db).await {
-    ///   Ok(_) => (),
+    ///   Ok(count) => println!("Updated {} roles", count),
     ///   Err(e) => panic!("Failed to delete permission from all roles: {:?}", e),
     /// };
     /// ```
     ///
     /// # Returns
     ///
-    /// A Result with an empty return value or an Error.
+    /// A Result with the number of roles that were modified or an Error.
     pub async fn delete_permission_from_all_roles(
         &self,
         permission_id: &str,
         db: &Database,
-    ) -> Result<(), Error> {
+    ) -> Result<u64, Error> {
         if permission_id.is_empty() {
             return Err(Error::EmptyId);
         }
@@ -557,12 +611,17 @@ impl RoleRepository {
             }
         };
 
-        let filter = doc! {};
+        let filter = doc! {
+            "permissions": target_object_id,
+        };
 
         let update = doc! {
             "$pull": {
                 "permissions": target_object_id,
-            }
+            },
+            "$inc": {
+                "version": 1_i64,
+            },
         };
 
         match db
@@ -570,11 +629,348 @@ impl RoleRepository {
             .update_many(filter, update, None)
             .await
         {
+            Ok(r) => Ok(r.modified_count),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Role as part of the transaction of a ClientSession. The references to the Role are not removed, so that
+    /// they can be removed in the same transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the ID of the Role.
+    /// * `db` - A reference to a Database instance.
+    /// * `session` - The ClientSession whose transaction the Role is deleted in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut session = client.start_session(None).await?;
+    /// session.start_transaction(None).await?;
+    /// role_repository.delete_with_session("id", &db, &mut session).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with an empty return value or an Error.
+    pub async fn delete_with_session(
+        &self,
+        id: &str,
+        db: &Database,
+        session: &mut ClientSession,
+    ) -> Result<(), Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        match db
+            .collection::<Role>(&self.collection)
+            .delete_one_with_session(doc! { "_id": target_object_id }, None, session)
+            .await
+        {
+            Ok(r) if r.deleted_count == 0 => Err(Error::RoleNotFound(id.to_string())),
             Ok(_) => Ok(()),
             Err(e) => Err(Error::MongoDb(e)),
         }
     }
 
+    /// # Summary
+    ///
+    /// Delete a permission from all roles using a single update, as part of the transaction of a ClientSession.
+    ///
+    /// # Arguments
+    ///
+    /// * `permission_id` - A string slice that holds the permission ID.
+    /// * `db` - A reference to a Database instance.
+    /// * `session` - The ClientSession whose transaction the roles are updated in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut session = client.start_session(None).await?;
+    /// session.start_transaction(None).await?;
+    /// let count = role_repository.delete_permission_from_all_roles_with_session("permission_id", &db, &mut session).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the number of roles that were modified or an Error.
+    pub async fn delete_permission_from_all_roles_with_session(
+        &self,
+        permission_id: &str,
+        db: &Database,
+        session: &mut ClientSession,
+    ) -> Result<u64, Error> {
+        if permission_id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(permission_id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "permissions": target_object_id,
+        };
+
+        let update = doc! {
+            "$pull": {
+                "permissions": target_object_id,
+            },
+            "$inc": {
+                "version": 1_i64,
+            },
+        };
+
+        match db
+            .collection::<Role>(&self.collection)
+            .update_many_with_session(filter, update, None, session)
+            .await
+        {
+            Ok(r) => Ok(r.modified_count),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Add Permissions to a Role. Permissions that are already assigned to the Role are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the Role ID.
+    /// * `permissions` - A vector of Permission ObjectIds to add.
+    /// * `updated_by` - The ID of the User that is updating the Role.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_repository = match RoleRepository::new("roles".to_string()) {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to initialize Role repository: {:?}", e),
+    /// };
+    ///
+    /// match role_repository.add_permissions("role_id", vec![ObjectId::new()], None, &db).await {
+    ///   Ok(d) => println!("Role: {}", d),
+    ///   Err(e) => panic!("Failed to add permissions to role: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the updated Role or an Error.
+    pub async fn add_permissions(
+        &self,
+        id: &str,
+        permissions: Vec<ObjectId>,
+        updated_by: Option<String>,
+        db: &Database,
+    ) -> Result<Role, Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        // Roles without permissions store a null value, which $addToSet cannot append to
+        match db
+            .collection::<Role>(&self.collection)
+            .update_one(
+                doc! {
+                    "_id": target_object_id,
+                    "permissions": Bson::Null,
+                },
+                doc! {
+                    "$set": {
+                        "permissions": [],
+                    }
+                },
+                None,
+            )
+            .await
+        {
+            Ok(_) => (),
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+        };
+
+        let update = doc! {
+            "$addToSet": {
+                "permissions": {
+                    "$each": permissions,
+                },
+            },
+            "$set": {
+                "updatedAt": BsonDateTime::now(),
+                "updatedBy": &updated_by,
+            },
+            "$inc": {
+                "version": 1_i64,
+            },
+        };
+
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        match db
+            .collection::<Role>(&self.collection)
+            .find_one_and_update(filter, update, options)
+            .await
+        {
+            Ok(d) => match d {
+                Some(role) => Ok(role),
+                None => Err(Error::RoleNotFound(id.to_string())),
+            },
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Remove a Permission from a Role. Removing a Permission that is not assigned to the Role is a no-op.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - A string slice that holds the Role ID.
+    /// * `permission_id` - A string slice that holds the Permission ID.
+    /// * `updated_by` - The ID of the User that is updating the Role.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_repository = match RoleRepository::new("roles".to_string()) {
+    ///   Ok(d) => d,
+    ///   Err(e) => panic!("Failed to initialize Role repository: {:?}", e),
+    /// };
+    ///
+    /// match role_repository.remove_permission("role_id", "permission_id", None, &db).await {
+    ///   Ok(d) => println!("Role: {}", d),
+    ///   Err(e) => panic!("Failed to remove permission from role: {:?}", e),
+    /// };
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the updated Role or an Error.
+    pub async fn remove_permission(
+        &self,
+        id: &str,
+        permission_id: &str,
+        updated_by: Option<String>,
+        db: &Database,
+    ) -> Result<Role, Error> {
+        if id.is_empty() || permission_id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let permission_object_id = match ObjectId::parse_str(permission_id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+        };
+
+        let update = doc! {
+            "$pull": {
+                "permissions": permission_object_id,
+            },
+            "$set": {
+                "updatedAt": BsonDateTime::now(),
+                "updatedBy": &updated_by,
+            },
+            "$inc": {
+                "version": 1_i64,
+            },
+        };
+
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        match db
+            .collection::<Role>(&self.collection)
+            .find_one_and_update(filter, update, options)
+            .await
+        {
+            Ok(d) => match d {
+                Some(role) => Ok(role),
+                None => Err(Error::RoleNotFound(id.to_string())),
+            },
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Count the Role entities, optionally only the ones that match a text search.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to search for, or None to count all Role entities.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_repository = RoleRepository::new(String::from("roles")).unwrap();
+    /// let total = role_repository.count(None, &db).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of matching Role entities.
+    pub async fn count(&self, text: Option<&str>, db: &Database) -> Result<u64, Error> {
+        let filter = match text {
+            Some("") => return Err(Error::EmptyTextSearch),
+            Some(t) => Some(doc! { "$text": { "$search": t } }),
+            None => None,
+        };
+
+        match db
+            .collection::<Role>(&self.collection)
+            .count_documents(filter, None)
+            .await
+        {
+            Ok(c) => Ok(c),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
     /// # Summary
     ///
     /// Search for Roles.
@@ -0,0 +1,2 @@
+pub mod session_model;
+pub mod session_repository;
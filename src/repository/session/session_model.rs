@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Session {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    #[serde(rename = "userId")]
+    pub user_id: ObjectId,
+    pub jti: String,
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Session {
+    /// # Summary
+    ///
+    /// Create a new Session.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that the Session belongs to.
+    /// * `jti` - The ID of the token that was issued for the Session.
+    /// * `user_agent` - The optional User-Agent of the client that started the Session.
+    /// * `expires_at` - The time at which the token of the Session expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let session = Session::new(user.id, String::from("jti"), None, expires_at);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Session` - The new Session.
+    pub fn new(
+        user_id: ObjectId,
+        jti: String,
+        user_agent: Option<String>,
+        expires_at: DateTime<Utc>,
+    ) -> Session {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        Session {
+            id: ObjectId::new(),
+            user_id,
+            jti,
+            user_agent,
+            created_at: now,
+            expires_at,
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use crate::repository::session::session_model::Session;
+use futures::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime as BsonDateTime;
+use mongodb::error::Error as MongodbError;
+use mongodb::options::FindOptions;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct SessionRepository {
+    pub collection: String,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid Session ID: {0}")]
+    InvalidId(String),
+    #[error("Empty Session collection")]
+    EmptyCollection,
+    #[error("Session not found: {0}")]
+    SessionNotFound(String),
+    #[error("MongoDb Error: {0}")]
+    MongoDb(#[from] MongodbError),
+}
+
+impl SessionRepository {
+    /// # Summary
+    ///
+    /// Create a new SessionRepository.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The collection name.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SessionRepository, Error>` - The result of the operation.
+    pub fn new(collection: String) -> Result<SessionRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(SessionRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new Session.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The Session to create.
+    /// * `db` - The Database to create the Session in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn create(&self, session: Session, db: &Database) -> Result<(), Error> {
+        match db
+            .collection::<Session>(&self.collection)
+            .insert_one(session, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find all Sessions of a User that have not expired yet, oldest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `db` - The Database to find the Sessions in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Session>, Error>` - The result of the operation.
+    pub async fn find_by_user_id(
+        &self,
+        user_id: &ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Session>, Error> {
+        let filter = doc! {
+            "userId": user_id,
+            "expiresAt": { "$gt": BsonDateTime::now() },
+        };
+        let find_options = FindOptions::builder().sort(doc! { "createdAt": 1 }).build();
+
+        let cursor = match db
+            .collection::<Session>(&self.collection)
+            .find(filter, find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        match cursor.try_collect().await {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a Session exists for a token ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `jti` - The ID of the token.
+    /// * `db` - The Database to find the Session in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, Error>` - The result of the operation.
+    pub async fn exists_by_jti(&self, jti: &str, db: &Database) -> Result<bool, Error> {
+        match db
+            .collection::<Session>(&self.collection)
+            .count_documents(doc! { "jti": jti }, None)
+            .await
+        {
+            Ok(c) => Ok(c > 0),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Session of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Session.
+    /// * `user_id` - The ID of the User that the Session belongs to.
+    /// * `db` - The Database to delete the Session from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn delete(&self, id: &str, user_id: &ObjectId, db: &Database) -> Result<(), Error> {
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        match db
+            .collection::<Session>(&self.collection)
+            .delete_one(doc! { "_id": target_object_id, "userId": user_id }, None)
+            .await
+        {
+            Ok(r) => {
+                if r.deleted_count == 0 {
+                    return Err(Error::SessionNotFound(String::from(id)));
+                }
+                Ok(())
+            }
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete the Session of a token ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `jti` - The ID of the token.
+    /// * `db` - The Database to delete the Session from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, Error>` - Whether a Session was deleted.
+    pub async fn delete_by_jti(&self, jti: &str, db: &Database) -> Result<bool, Error> {
+        match db
+            .collection::<Session>(&self.collection)
+            .delete_one(doc! { "jti": jti }, None)
+            .await
+        {
+            Ok(r) => Ok(r.deleted_count > 0),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete multiple Sessions by their IDs.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The IDs of the Sessions.
+    /// * `db` - The Database to delete the Sessions from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of deleted Sessions.
+    pub async fn delete_by_id_vec(&self, ids: Vec<ObjectId>, db: &Database) -> Result<u64, Error> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        match db
+            .collection::<Session>(&self.collection)
+            .delete_many(doc! { "_id": { "$in": ids } }, None)
+            .await
+        {
+            Ok(r) => Ok(r.deleted_count),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Delete all Sessions of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `db` - The Database to delete the Sessions from.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of deleted Sessions.
+    pub async fn delete_by_user_id(&self, user_id: &ObjectId, db: &Database) -> Result<u64, Error> {
+        match db
+            .collection::<Session>(&self.collection)
+            .delete_many(doc! { "userId": user_id }, None)
+            .await
+        {
+            Ok(r) => Ok(r.deleted_count),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod token_model;
+pub mod token_repository;
@@ -0,0 +1,42 @@
+use crate::services::token::token_service::Claims;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OpaqueToken {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub claims: Claims,
+    #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    #[serde(rename = "expiresAt")]
+    pub expires_at: DateTime<Utc>,
+}
+
+impl OpaqueToken {
+    /// # Summary
+    ///
+    /// Create a new OpaqueToken.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The hash of the opaque token, so that the token itself is never stored.
+    /// * `claims` - The Claims that the opaque token refers to.
+    /// * `expires_at` - The time at which the opaque token expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let opaque_token = OpaqueToken::new(String::from("hash"), claims, expires_at);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `OpaqueToken` - The new OpaqueToken.
+    pub fn new(id: String, claims: Claims, expires_at: DateTime<Utc>) -> OpaqueToken {
+        OpaqueToken {
+            id,
+            claims,
+            expires_at,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::repository::token::token_model::OpaqueToken;
+use mongodb::bson::doc;
+use mongodb::error::Error as MongodbError;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct TokenRepository {
+    pub collection: String,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("Empty Token collection")]
+    EmptyCollection,
+    #[error("MongoDb Error: {0}")]
+    MongoDb(#[from] MongodbError),
+}
+
+impl TokenRepository {
+    /// # Summary
+    ///
+    /// Create a new TokenRepository.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - The collection name.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TokenRepository, Error>` - The result of the operation.
+    pub fn new(collection: String) -> Result<TokenRepository, Error> {
+        if collection.is_empty() {
+            return Err(Error::EmptyCollection);
+        }
+
+        Ok(TokenRepository { collection })
+    }
+
+    /// # Summary
+    ///
+    /// Create a new OpaqueToken.
+    ///
+    /// # Arguments
+    ///
+    /// * `opaque_token` - The OpaqueToken to create.
+    /// * `db` - The Database to create the OpaqueToken in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn create(&self, opaque_token: OpaqueToken, db: &Database) -> Result<(), Error> {
+        match db
+            .collection::<OpaqueToken>(&self.collection)
+            .insert_one(opaque_token, None)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find an OpaqueToken by the hash of the token.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The hash of the token.
+    /// * `db` - The Database to find the OpaqueToken in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<OpaqueToken>, Error>` - The result of the operation.
+    pub async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<OpaqueToken>, Error> {
+        match db
+            .collection::<OpaqueToken>(&self.collection)
+            .find_one(doc! { "_id": id }, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+}
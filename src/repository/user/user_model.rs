@@ -1,8 +1,11 @@
 use crate::web::dto::authentication::register_request::RegisterRequest;
 use crate::web::dto::user::create_user::CreateUser;
+use crate::web::dto::user::user_export::UserExport;
 use chrono::{DateTime, Utc};
 use mongodb::bson::oid::ObjectId;
-use serde::{Deserialize, Serialize};
+use mongodb::bson::DateTime as BsonDateTime;
+use serde::ser::Error;
+use serde::{Deserialize, Serialize, Serializer};
 use std::fmt::{Display, Formatter};
 use std::time::SystemTime;
 
@@ -10,13 +13,20 @@ use std::time::SystemTime;
 pub struct User {
     #[serde(rename = "_id")]
     pub id: ObjectId,
+    #[serde(rename = "tenantId", default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
     pub username: String,
     pub email: Option<String>,
     #[serde(rename = "firstName")]
     pub first_name: Option<String>,
     #[serde(rename = "lastName")]
     pub last_name: Option<String>,
+    #[serde(serialize_with = "serialize_password")]
     pub password: String,
+    #[serde(rename = "passwordHistory", default)]
+    pub password_history: Vec<String>,
+    #[serde(rename = "passwordChangedAt", default)]
+    pub password_changed_at: Option<BsonDateTime>,
     pub roles: Option<Vec<ObjectId>>,
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     #[serde(rename = "createdAt")]
@@ -24,10 +34,92 @@ pub struct User {
     #[serde(with = "mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     #[serde(rename = "updatedAt")]
     pub updated_at: DateTime<Utc>,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
+    #[serde(default)]
+    pub version: u64,
     pub enabled: bool,
+    #[serde(rename = "failedLoginAttempts", default)]
+    pub failed_login_attempts: u32,
+    #[serde(rename = "lockedUntil", default)]
+    pub locked_until: Option<BsonDateTime>,
+    #[serde(rename = "mustChangePassword", default)]
+    pub must_change_password: bool,
+    #[serde(
+        rename = "pendingEmail",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub pending_email: Option<String>,
+    #[serde(
+        rename = "emailChangeToken",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub email_change_token: Option<String>,
+    #[serde(
+        rename = "emailChangeExpiresAt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub email_change_expires_at: Option<BsonDateTime>,
+    #[serde(
+        rename = "lastLoginAt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_login_at: Option<BsonDateTime>,
+}
+
+/// # Summary
+///
+/// Serialize the password hash of a User, but only when the User is written to the database. Serializing a User in any
+/// other way fails, so that neither the password hash nor the other secrets of the User can end up in a response body.
+/// Responses use DTOs instead.
+///
+/// # Arguments
+///
+/// * `password` - The password hash of the User.
+/// * `serializer` - The Serializer.
+///
+/// # Returns
+///
+/// * `Result<S::Ok, S::Error>` - The result of the operation.
+fn serialize_password<S: Serializer>(password: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    // The driver writes documents with the raw BSON serializer, which is the only one that is not human-readable. The
+    // serializer of `bson::to_document` is human-readable, so it fails as well instead of storing a User without its
+    // password hash
+    if serializer.is_human_readable() {
+        Err(S::Error::custom(
+            "a User can only be serialized by the database driver",
+        ))
+    } else {
+        serializer.serialize_str(password)
+    }
 }
 
 impl User {
+    /// # Summary
+    ///
+    /// Get the time at which the password of the User was last changed. Users that were created before this was
+    /// tracked fall back to their creation time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let changed_at = user.password_changed_at();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `DateTime<Utc>` - The time at which the password was last changed.
+    pub fn password_changed_at(&self) -> DateTime<Utc> {
+        match self.password_changed_at {
+            Some(d) => d.to_chrono(),
+            None => self.created_at,
+        }
+    }
+
     /// # Summary
     ///
     /// Create a new User.
@@ -86,15 +178,27 @@ impl User {
 
         User {
             id: ObjectId::new(),
+            tenant_id: None,
             username,
             email,
             first_name,
             last_name,
             password,
+            password_history: vec![],
+            password_changed_at: Some(BsonDateTime::from_chrono(now)),
             roles,
             created_at: now,
             updated_at: now,
+            updated_by: None,
+            version: 0,
             enabled,
+            failed_login_attempts: 0,
+            locked_until: None,
+            must_change_password: false,
+            pending_email: None,
+            email_change_token: None,
+            email_change_expires_at: None,
+            last_login_at: None,
         }
     }
 }
@@ -145,15 +249,27 @@ impl From<CreateUser> for User {
 
         User {
             id: ObjectId::new(),
+            tenant_id: value.tenant_id,
             username: value.username,
             email: value.email,
             first_name: value.first_name,
             last_name: value.last_name,
             password: value.password,
+            password_history: vec![],
+            password_changed_at: Some(BsonDateTime::from_chrono(now)),
             roles,
             created_at: now,
             updated_at: now,
+            updated_by: None,
+            version: 0,
             enabled: true,
+            failed_login_attempts: 0,
+            locked_until: None,
+            must_change_password: false,
+            pending_email: None,
+            email_change_token: None,
+            email_change_expires_at: None,
+            last_login_at: None,
         }
     }
 }
@@ -161,7 +277,8 @@ impl From<CreateUser> for User {
 impl From<RegisterRequest> for User {
     /// # Summary
     ///
-    /// Convert a RegisterRequest into a User.
+    /// Convert a RegisterRequest into a User. The password is left empty, so that the plaintext password is never
+    /// copied into the User. The hash of the password must be set afterwards.
     ///
     /// # Arguments
     ///
@@ -189,16 +306,103 @@ impl From<RegisterRequest> for User {
 
         User {
             id: ObjectId::new(),
+            tenant_id: value.tenant_id,
             username: value.username,
             email: value.email,
             first_name: value.first_name,
             last_name: value.last_name,
-            password: value.password,
+            password: String::new(),
+            password_history: vec![],
+            password_changed_at: Some(BsonDateTime::from_chrono(now)),
             roles: None,
             created_at: now,
             updated_at: now,
+            updated_by: None,
+            version: 0,
             enabled: true,
+            failed_login_attempts: 0,
+            locked_until: None,
+            must_change_password: false,
+            pending_email: None,
+            email_change_token: None,
+            email_change_expires_at: None,
+            last_login_at: None,
+        }
+    }
+}
+
+impl TryFrom<UserExport> for User {
+    type Error = String;
+
+    /// # Summary
+    ///
+    /// Convert a UserExport back into a User, preserving its ID and password hashes.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The UserExport to convert.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user = User::try_from(user_export)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, String>` - The User, or a description of the invalid field.
+    fn try_from(value: UserExport) -> Result<Self, Self::Error> {
+        let id = ObjectId::parse_str(&value.id).map_err(|_| format!("Invalid ID: {}", value.id))?;
+
+        if value.username.is_empty() {
+            return Err(String::from("Empty username"));
+        }
+
+        if value.password_hash.is_empty() {
+            return Err(String::from("Empty password hash"));
+        }
+
+        let mut roles: Vec<ObjectId> = vec![];
+        for role in &value.role_ids {
+            roles
+                .push(ObjectId::parse_str(role).map_err(|_| format!("Invalid Role ID: {}", role))?);
         }
+
+        let parse_date = |date: &str| -> Result<DateTime<Utc>, String> {
+            DateTime::parse_from_rfc3339(date)
+                .map(|d| d.with_timezone(&Utc))
+                .map_err(|_| format!("Invalid date: {}", date))
+        };
+
+        let password_changed_at = match &value.password_changed_at {
+            Some(d) => Some(BsonDateTime::from_chrono(parse_date(d)?)),
+            None => None,
+        };
+
+        Ok(User {
+            id,
+            tenant_id: value.tenant_id,
+            username: value.username,
+            email: value.email,
+            first_name: value.first_name,
+            last_name: value.last_name,
+            password: value.password_hash,
+            password_history: value.password_history_hashes,
+            password_changed_at,
+            roles: Some(roles),
+            created_at: parse_date(&value.created_at)?,
+            updated_at: parse_date(&value.updated_at)?,
+            updated_by: value.updated_by,
+            version: 0,
+            enabled: value.enabled,
+            failed_login_attempts: 0,
+            locked_until: None,
+            must_change_password: false,
+            pending_email: None,
+            email_change_token: None,
+            email_change_expires_at: None,
+            last_login_at: None,
+        })
     }
 }
 
@@ -236,7 +440,7 @@ impl Display for User {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "User: [id: {}, username: {}, email: {}, first_name: {}, last_name: {}, password: {}, roles: {:?}, created_at: {}, updated_at: {}, enabled: {}]",
+            "User: [id: {}, username: {}, email: {}, first_name: {}, last_name: {}, roles: {:?}, created_at: {}, updated_at: {}, enabled: {}]",
             self.id.to_hex(),
             self.username,
             match &self.email {
@@ -245,7 +449,6 @@ impl Display for User {
             },
             self.first_name.clone().unwrap_or(String::from("")),
             self.last_name.clone().unwrap_or(String::from("")),
-            self.password,
             match &self.roles {
                 None => String::from("None"),
                 Some(r) => format!("{:?}", r)
@@ -256,3 +459,52 @@ impl Display for User {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::{from_slice, to_document, to_raw_document_buf};
+
+    fn user() -> User {
+        let mut user = User::new(
+            String::from("username"),
+            Some(String::from("user@codedead.com")),
+            None,
+            None,
+            String::from("$argon2id$v=19$m=19456,t=2,p=1$c2FsdA$aGFzaA"),
+            None,
+            true,
+        );
+        user.password_history = vec![String::from("$2b$12$previous")];
+        user.pending_email = Some(String::from("new@codedead.com"));
+        user.email_change_token = Some(String::from("token-hash"));
+        user
+    }
+
+    fn assert_secrets_kept(user: &User) {
+        assert_eq!(
+            user.password,
+            "$argon2id$v=19$m=19456,t=2,p=1$c2FsdA$aGFzaA"
+        );
+        assert_eq!(user.password_history, vec![String::from("$2b$12$previous")]);
+        assert_eq!(user.email_change_token.as_deref(), Some("token-hash"));
+    }
+
+    #[test]
+    fn a_bson_document_cannot_be_created() {
+        // Serializing a User into a Document would drop the password hash silently if it did not fail
+        assert!(to_document(&user()).is_err());
+    }
+
+    #[test]
+    fn the_raw_bson_document_of_the_driver_keeps_the_secrets() {
+        // The driver serializes documents into raw BSON before they are written to the database
+        let document = to_raw_document_buf(&user()).unwrap();
+        assert_secrets_kept(&from_slice::<User>(document.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn json_cannot_be_created() {
+        assert!(serde_json::to_value(user()).is_err());
+    }
+}
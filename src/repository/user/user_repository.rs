@@ -1,73 +1,96 @@
 use crate::repository::audit::audit_repository::Error as AuditError;
+use crate::repository::cursor;
 use crate::repository::user::user_model::User;
 use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
 use mongodb::bson::oid::ObjectId;
-use mongodb::bson::{doc, Bson};
+use mongodb::bson::DateTime as BsonDateTime;
+use mongodb::bson::{doc, Bson, Document};
 use mongodb::error::Error as MongoError;
-use mongodb::options::FindOptions;
-use mongodb::Database;
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use mongodb::{ClientSession, Cursor, Database};
 use regex::Regex;
-use std::fmt::{Display, Formatter};
 use std::time::SystemTime;
 
 #[derive(Clone)]
 pub struct UserRepository {
     pub collection: String,
     pub email_regex: Regex,
+    pub tenancy: bool,
 }
 
-#[derive(Clone, Debug)]
+pub enum UserFilter<'a> {
+    All,
+    Role(Vec<String>),
+    Text(&'a str),
+    Partial(&'a str),
+    CreatedBetween(Option<DateTime<Utc>>, Option<DateTime<Utc>>),
+}
+
+pub enum ImportOutcome {
+    Created,
+    Overwritten,
+    Skipped,
+}
+
+#[derive(Clone, Debug, thiserror::Error)]
 pub enum Error {
+    #[error("Invalid User ID: {0}")]
     InvalidId(String),
+    #[error("Empty User ID")]
     EmptyId,
+    #[error("Empty username")]
     EmptyUsername,
+    #[error("Empty collection")]
     EmptyCollection,
+    #[error("Empty email")]
     EmptyEmail,
+    #[error("Empty password")]
     EmptyPassword,
+    #[error("Empty text search")]
     EmptyTextSearch,
+    #[error("User not found: {0}")]
     UserNotFound(String),
+    #[error("User was modified by another request: {0}")]
+    Conflict(String),
+    #[error("Username already taken")]
     UsernameAlreadyTaken,
+    #[error("Email already taken")]
     EmailAlreadyTaken,
+    #[error("Invalid email address: {0}")]
     InvalidEmail(String),
-    MongoDb(MongoError),
-    Audit(AuditError),
+    #[error("Invalid cursor: {0}")]
+    InvalidCursor(String),
+    #[error("MongoDB error: {0}")]
+    MongoDb(#[from] MongoError),
+    #[error("Audit error: {0}")]
+    Audit(#[from] AuditError),
 }
 
-impl Display for Error {
+impl Error {
     /// # Summary
     ///
-    /// Display the Error.
-    ///
-    /// # Arguments
-    ///
-    /// * `f` - The Formatter.
+    /// Get the name of the input field that caused the Error, if any.
     ///
     /// # Example
     ///
     /// ```
-    /// let error = Error::InvalidEmail(String::from("email"));
-    /// println!("{}", error);
+    /// let error = Error::EmptyUsername;
+    /// assert_eq!(error.field(), Some("username"));
     /// ```
     ///
     /// # Returns
     ///
-    /// * `std::fmt::Result` - The result of the operation.
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match &self {
-            Error::InvalidId(id) => write!(f, "Invalid User ID: {}", id),
-            Error::EmptyId => write!(f, "Empty User ID"),
-            Error::EmptyUsername => write!(f, "Empty username"),
-            Error::EmptyCollection => write!(f, "Empty collection"),
-            Error::EmptyEmail => write!(f, "Empty email"),
-            Error::EmptyPassword => write!(f, "Empty password"),
-            Error::EmptyTextSearch => write!(f, "Empty text search"),
-            Error::UserNotFound(id) => write!(f, "User not found: {}", id),
-            Error::UsernameAlreadyTaken => write!(f, "Username already taken"),
-            Error::EmailAlreadyTaken => write!(f, "Email already taken"),
-            Error::InvalidEmail(email) => write!(f, "Invalid email address: {}", email),
-            Error::MongoDb(e) => write!(f, "MongoDB error: {}", e),
-            Error::Audit(e) => write!(f, "Audit error: {}", e),
+    /// * `Option<&'static str>` - The name of the field, or None if the Error is not caused by a specific field.
+    pub fn field(&self) -> Option<&'static str> {
+        match self {
+            Error::InvalidId(_) | Error::EmptyId => Some("id"),
+            Error::EmptyUsername | Error::UsernameAlreadyTaken => Some("username"),
+            Error::EmptyEmail | Error::EmailAlreadyTaken | Error::InvalidEmail(_) => Some("email"),
+            Error::EmptyPassword => Some("password"),
+            Error::EmptyTextSearch => Some("text"),
+            Error::InvalidCursor(_) => Some("after"),
+            _ => None,
         }
     }
 }
@@ -81,6 +104,7 @@ impl UserRepository {
     ///
     /// * `collection` - The name of the collection.
     /// * `email_regex` - The email regex.
+    /// * `tenancy` - Whether usernames and email addresses are only unique within the tenant of a User.
     ///
     /// # Example
     ///
@@ -90,13 +114,17 @@ impl UserRepository {
     ///
     /// let email_regex = Regex::new(r"^[a-zA-Z0-9_.+-]+@[a-zA-Z0-9-]+\.[a-
     /// zA-Z0-9-.]+$").unwrap();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false);
     /// ```
     ///
     /// # Returns
     ///
     /// * `Result<UserRepository, Error>` - The result of the operation.
-    pub fn new(collection: String, email_regex: Regex) -> Result<UserRepository, Error> {
+    pub fn new(
+        collection: String,
+        email_regex: Regex,
+        tenancy: bool,
+    ) -> Result<UserRepository, Error> {
         if collection.is_empty() {
             return Err(Error::EmptyCollection);
         }
@@ -104,9 +132,30 @@ impl UserRepository {
         Ok(UserRepository {
             collection,
             email_regex,
+            tenancy,
         })
     }
 
+    /// # Summary
+    ///
+    /// Restrict a filter to the User entities of a tenant, if tenancy is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The filter to restrict.
+    /// * `tenant_id` - The ID of the tenant, or None for User entities that do not belong to a tenant.
+    ///
+    /// # Returns
+    ///
+    /// * `Document` - The restricted filter.
+    fn in_tenant(&self, mut filter: Document, tenant_id: Option<&str>) -> Document {
+        if self.tenancy {
+            // A null value also matches User entities that were created before tenancy was enabled
+            filter.insert("tenantId", tenant_id);
+        }
+        filter
+    }
+
     /// # Summary
     ///
     /// Create a new User entity.
@@ -137,11 +186,101 @@ impl UserRepository {
     ///
     /// * `Result<User, Error>` - The result of the operation.
     pub async fn create(&self, user: User, db: &Database) -> Result<User, Error> {
+        self.check_available(&user, db).await?;
+
+        let user_id = user.id.to_hex();
+
+        let collection = db.collection::<User>(&self.collection);
+        let result = collection.insert_one(user, None).await;
+
+        match result {
+            Ok(_) => {}
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        match self.find_by_id(&user_id, db).await {
+            Ok(user) => match user {
+                Some(u) => Ok(u),
+                None => Err(Error::UserNotFound(user_id)),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create a new User entity as part of the transaction of a ClientSession.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User entity.
+    /// * `db` - The Database.
+    /// * `session` - The ClientSession whose transaction the User entity is created in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut session = client.start_session(None).await?;
+    /// session.start_transaction(None).await?;
+    /// let user = user_repository.create_with_session(user, &db, &mut session).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<User, Error>` - The result of the operation.
+    pub async fn create_with_session(
+        &self,
+        user: User,
+        db: &Database,
+        session: &mut ClientSession,
+    ) -> Result<User, Error> {
+        self.check_available(&user, db).await?;
+
+        let user_id = user.id;
+
+        let collection = db.collection::<User>(&self.collection);
+        match collection
+            .insert_one_with_session(user, None, session)
+            .await
+        {
+            Ok(_) => {}
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        // The new User is not visible outside of the transaction until it is committed
+        match collection
+            .find_one_with_session(doc! { "_id": user_id }, None, session)
+            .await
+        {
+            Ok(Some(u)) => Ok(u),
+            Ok(None) => Err(Error::UserNotFound(user_id.to_hex())),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check that the email address of a new User entity is valid and that its username and email address are not
+    /// taken yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The new User entity.
+    /// * `db` - The Database.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the check.
+    async fn check_available(&self, user: &User, db: &Database) -> Result<(), Error> {
         if user.email.is_some() && !self.email_regex.is_match(&user.email.clone().unwrap()) {
-            return Err(Error::InvalidEmail(user.email.unwrap()));
+            return Err(Error::InvalidEmail(user.email.clone().unwrap()));
         } else if user.email.is_some() {
             match self
-                .find_by_email(&user.email.clone().unwrap().to_lowercase(), db)
+                .find_by_email(
+                    &user.email.clone().unwrap().to_lowercase(),
+                    user.tenant_id.as_deref(),
+                    db,
+                )
                 .await
             {
                 Ok(user) => {
@@ -155,7 +294,10 @@ impl UserRepository {
             };
         }
 
-        match self.find_by_username(&user.username, db).await {
+        match self
+            .find_by_username(&user.username, user.tenant_id.as_deref(), db)
+            .await
+        {
             Ok(user) => {
                 if user.is_some() {
                     return Err(Error::UsernameAlreadyTaken);
@@ -166,23 +308,7 @@ impl UserRepository {
             }
         };
 
-        let user_id = user.id.to_hex();
-
-        let collection = db.collection::<User>(&self.collection);
-        let result = collection.insert_one(user, None).await;
-
-        match result {
-            Ok(_) => {}
-            Err(e) => return Err(Error::MongoDb(e)),
-        };
-
-        match self.find_by_id(&user_id, db).await {
-            Ok(user) => match user {
-                Some(u) => Ok(u),
-                None => Err(Error::UserNotFound(user_id)),
-            },
-            Err(e) => Err(e),
-        }
+        Ok(())
     }
 
     /// # Summary
@@ -241,11 +367,11 @@ impl UserRepository {
 
     /// # Summary
     ///
-    /// Find a User entity by its ID.
+    /// Count the User entities that match a filter.
     ///
     /// # Arguments
     ///
-    /// * `id` - The ID of the User entity.
+    /// * `filter` - The UserFilter that the User entities must match.
     /// * `db` - The Database.
     ///
     /// # Example
@@ -253,94 +379,95 @@ impl UserRepository {
     /// ```
     /// let db = Database::new();
     /// let user_repository = UserRepository::new(String::from("users"), email_regex);
-    /// let user = user_repository.find_by_id(&String::from("id"), &db);
+    /// let total = user_repository.count(UserFilter::All, &db).await.unwrap();
     /// ```
     ///
     /// # Returns
     ///
-    /// * `Result<Option<User>, Error>` - The result of the operation.
-    pub async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<User>, Error> {
-        if id.is_empty() {
-            return Err(Error::EmptyId);
-        }
-
-        let target_object_id = match ObjectId::parse_str(id) {
-            Ok(res) => res,
-            Err(e) => {
-                return Err(Error::InvalidId(e.to_string()));
+    /// * `Result<u64, Error>` - The number of matching User entities.
+    pub async fn count(&self, filter: UserFilter<'_>, db: &Database) -> Result<u64, Error> {
+        let filter = match filter {
+            UserFilter::All => None,
+            UserFilter::Role(role_ids) => Some(role_filter(role_ids)?),
+            UserFilter::Text("") => return Err(Error::EmptyTextSearch),
+            UserFilter::Text(text) => Some(text_filter(text)),
+            UserFilter::Partial(query) if query.trim().is_empty() => {
+                return Err(Error::EmptyTextSearch)
             }
-        };
-
-        let filter = doc! {
-            "_id": target_object_id,
+            UserFilter::Partial(query) => Some(partial_filter(query)),
+            UserFilter::CreatedBetween(from, to) => Some(created_filter(from, to)),
         };
 
         match db
             .collection::<User>(&self.collection)
-            .find_one(filter, None)
+            .count_documents(filter, None)
             .await
         {
-            Ok(d) => Ok(d),
+            Ok(c) => Ok(c),
             Err(e) => Err(Error::MongoDb(e)),
         }
     }
 
     /// # Summary
     ///
-    /// Find a User entity by its username.
+    /// Find a page of User entities that were created after the entity that the cursor points to, ordered by ID.
     ///
     /// # Arguments
     ///
-    /// * `username` - The username of the User entity.
-    /// * `db` - The Database.
+    /// * `after` - The opaque cursor of the previous page, or None to start at the first page.
+    /// * `limit` - The maximum number of Users to return.
+    /// * `db` - The Database to be used.
     ///
     /// # Example
     ///
     /// ```
-    /// let db = Database::new();
-    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
-    /// let user = user_repository.find_by_username(&String::from("username"), &db);
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let db = mongodb::Database::new();
+    /// let (users, next_cursor) = user_repository.find_after(None, Some(10), &db).await.unwrap();
     /// ```
     ///
     /// # Returns
     ///
-    /// * `Result<Option<User>, Error>` - The result of the operation.
-    pub async fn find_by_username(
+    /// * `Result<(Vec<User>, Option<String>), Error>` - The page of Users and the cursor of the next page, if any.
+    pub async fn find_after(
         &self,
-        username: &str,
+        after: Option<&str>,
+        limit: Option<i64>,
         db: &Database,
-    ) -> Result<Option<User>, Error> {
-        if username.is_empty() {
-            return Err(Error::EmptyUsername);
-        }
-
-        let regex_pattern = format!("^{}$", regex::escape(username));
-        let re = mongodb::bson::Regex {
-            pattern: regex_pattern,
-            options: String::from("i"),
+    ) -> Result<(Vec<User>, Option<String>), Error> {
+        let filter = match after {
+            Some(a) => match cursor::decode(a) {
+                Some(id) => Some(doc! { "_id": { "$gt": id } }),
+                None => return Err(Error::InvalidCursor(a.to_string())),
+            },
+            None => None,
         };
 
-        let filter = doc! { "username": { "$regex": Bson::RegularExpression(re) } };
+        let find_options = FindOptions::builder()
+            .limit(limit)
+            .sort(doc! { "_id": 1 })
+            .build();
 
-        let user = match db
+        let users: Vec<User> = match db
             .collection::<User>(&self.collection)
-            .find_one(filter, None)
+            .find(filter, find_options)
             .await
         {
-            Ok(d) => d,
+            Ok(d) => d.try_collect().await.unwrap_or_else(|_| vec![]),
             Err(e) => return Err(Error::MongoDb(e)),
         };
 
-        Ok(user)
+        let next_cursor = cursor::next(users.last().map(|u| &u.id), users.len(), limit);
+        Ok((users, next_cursor))
     }
 
     /// # Summary
     ///
-    /// Find a User entity by its email.
+    /// Open a cursor over all User entities, ordered by ID, so that they can be exported without loading the entire
+    /// collection into memory.
     ///
     /// # Arguments
     ///
-    /// * `email` - The email of the User entity.
     /// * `db` - The Database.
     ///
     /// # Example
@@ -348,40 +475,33 @@ impl UserRepository {
     /// ```
     /// let db = Database::new();
     /// let user_repository = UserRepository::new(String::from("users"), email_regex);
-    /// let user = user_repository.find_by_email(&String::from("email"), &db);
+    /// let cursor = user_repository.export(&db).await.unwrap();
     /// ```
     ///
     /// # Returns
     ///
-    /// * `Result<Option<User>, Error>` - The result of the operation.
-    pub async fn find_by_email(&self, email: &str, db: &Database) -> Result<Option<User>, Error> {
-        if email.is_empty() {
-            return Err(Error::EmptyEmail);
-        }
+    /// * `Result<Cursor<User>, Error>` - The result of the operation.
+    pub async fn export(&self, db: &Database) -> Result<Cursor<User>, Error> {
+        let find_options = FindOptions::builder().sort(doc! { "_id": 1 }).build();
 
-        let filter = doc! {
-            "email": email,
-        };
-
-        let user = match db
+        match db
             .collection::<User>(&self.collection)
-            .find_one(filter, None)
+            .find(None, find_options)
             .await
         {
-            Ok(d) => d,
-            Err(e) => return Err(Error::MongoDb(e)),
-        };
-
-        Ok(user)
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
     }
 
     /// # Summary
     ///
-    /// Insert a User entity.
+    /// Import a User entity while preserving its ID and password hashes.
     ///
     /// # Arguments
     ///
-    /// * `user` - The User entity to insert.
+    /// * `user` - The User entity to import.
+    /// * `overwrite` - Whether an existing User with the same ID should be overwritten instead of skipped.
     /// * `db` - The Database.
     ///
     /// # Example
@@ -389,117 +509,638 @@ impl UserRepository {
     /// ```
     /// let db = Database::new();
     /// let user_repository = UserRepository::new(String::from("users"), email_regex);
-    /// let user = User::new(String::from("username"), String::from("email"), String::from("password"));
+    /// let outcome = user_repository.import(user, false, &db).await.unwrap();
+    /// ```
     ///
-    /// user.first_name = String::from("first_name");
+    /// # Returns
     ///
-    /// let user = user_repository.update(user, &db);
-    /// ```
-    pub async fn update(&self, user: User, db: &Database) -> Result<User, Error> {
-        if user.email.is_some() && !self.email_regex.is_match(&user.email.clone().unwrap()) {
-            return Err(Error::InvalidEmail(user.email.unwrap()));
-        } else if user.email.is_some() {
-            match self
-                .find_by_email(&user.email.clone().unwrap().to_lowercase(), db)
-                .await
+    /// * `Result<ImportOutcome, Error>` - Whether the User was created, overwritten or skipped.
+    pub async fn import(
+        &self,
+        mut user: User,
+        overwrite: bool,
+        db: &Database,
+    ) -> Result<ImportOutcome, Error> {
+        let existing = self.find_by_id(&user.id.to_hex(), db).await?;
+        if existing.is_some() && !overwrite {
+            return Ok(ImportOutcome::Skipped);
+        }
+
+        if let Some(email) = &user.email {
+            if !self.email_regex.is_match(email) {
+                return Err(Error::InvalidEmail(email.clone()));
+            }
+
+            if let Some(u) = self
+                .find_by_email(&email.to_lowercase(), user.tenant_id.as_deref(), db)
+                .await?
             {
-                Ok(user) => {
-                    if user.is_some() {
-                        return Err(Error::EmailAlreadyTaken);
-                    }
-                }
-                Err(e) => {
-                    return Err(e);
+                if u.id != user.id {
+                    return Err(Error::EmailAlreadyTaken);
                 }
-            };
+            }
         }
 
-        match self
-            .find_by_username(&user.username.to_lowercase(), db)
-            .await
+        if let Some(u) = self
+            .find_by_username(&user.username.to_lowercase(), user.tenant_id.as_deref(), db)
+            .await?
         {
-            Ok(u) => {
-                if let Some(p) = u {
-                    if p.id != user.id {
-                        return Err(Error::UsernameAlreadyTaken);
-                    }
-                }
+            if u.id != user.id {
+                return Err(Error::UsernameAlreadyTaken);
             }
-            Err(e) => {
-                return Err(e);
-            }
-        };
-
-        let user_id = user.id;
-        let filter = doc! {
-            "_id": &user_id,
-        };
-
-        let now: DateTime<Utc> = SystemTime::now().into();
-        let now: String = now.to_rfc3339();
-
-        let update = doc! {
-            "$set": {
-                "username": user.username,
-                "email": user.email,
-                "firstName": user.first_name,
-                "lastName": user.last_name,
-                "roles": user.roles,
-                "updated_at": now,
-                "enabled": user.enabled,
-            },
-        };
+        }
 
         let collection = db.collection::<User>(&self.collection);
-        let result = collection.find_one_and_update(filter, update, None).await;
-
-        match result {
-            Ok(user) => {
-                if let Some(u) = user {
-                    Ok(u)
-                } else {
-                    Err(Error::UserNotFound(user_id.to_hex()))
+        match existing {
+            Some(e) => {
+                // Bump the version, so that concurrent updates that were based on the old document are rejected
+                user.version = e.version + 1;
+                match collection
+                    .replace_one(doc! { "_id": user.id }, user, None)
+                    .await
+                {
+                    Ok(_) => Ok(ImportOutcome::Overwritten),
+                    Err(e) => Err(Error::MongoDb(e)),
                 }
             }
-            Err(e) => Err(Error::MongoDb(e)),
+            None => match collection.insert_one(user, None).await {
+                Ok(_) => Ok(ImportOutcome::Created),
+                Err(e) => Err(Error::MongoDb(e)),
+            },
         }
     }
 
     /// # Summary
     ///
-    /// Update the password of a User entity.
+    /// Find a User entity by its ID.
     ///
     /// # Arguments
     ///
-    /// * `id` - The id of the User entity.
-    /// * `password` - The new password of the User entity.
+    /// * `id` - The ID of the User entity.
+    /// * `db` - The Database.
     ///
     /// # Example
     ///
     /// ```
     /// let db = Database::new();
     /// let user_repository = UserRepository::new(String::from("users"), email_regex);
-    ///
-    /// user_repository.update_password(&String::from("id"), &String::from("password"), &db);
+    /// let user = user_repository.find_by_id(&String::from("id"), &db);
     /// ```
     ///
     /// # Returns
     ///
-    /// * `Result<(), Error>` - The result of the operation.
-    pub async fn update_password(
-        &self,
-        id: &str,
-        password: &str,
-        db: &Database,
-    ) -> Result<(), Error> {
+    /// * `Result<Option<User>, Error>` - The result of the operation.
+    pub async fn find_by_id(&self, id: &str, db: &Database) -> Result<Option<User>, Error> {
         if id.is_empty() {
             return Err(Error::EmptyId);
         }
 
-        if password.is_empty() {
-            return Err(Error::EmptyPassword);
-        }
-
+        let target_object_id = match ObjectId::parse_str(id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "_id": target_object_id,
+        };
+
+        match db
+            .collection::<User>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => Ok(d),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find all User entities whose ID is in a list of IDs, using a single query.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The IDs of the User entities.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false);
+    /// let users = user_repository.find_by_id_vec(vec![ObjectId::new()], &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<User>, Error>` - The User entities that were found, in no particular order.
+    pub async fn find_by_id_vec(
+        &self,
+        ids: Vec<ObjectId>,
+        db: &Database,
+    ) -> Result<Vec<User>, Error> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let filter = doc! {
+            "_id": {
+                "$in": ids,
+            },
+        };
+
+        match db
+            .collection::<User>(&self.collection)
+            .find(filter, None)
+            .await
+        {
+            Ok(d) => Ok(d.try_collect().await.unwrap_or_else(|_| vec![])),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Find a User entity by its username.
+    ///
+    /// # Arguments
+    ///
+    /// * `username` - The username of the User entity.
+    /// * `tenant_id` - The ID of the tenant of the User entity, which is ignored if tenancy is disabled.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false);
+    /// let user = user_repository.find_by_username(&String::from("username"), None, &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<User>, Error>` - The result of the operation.
+    pub async fn find_by_username(
+        &self,
+        username: &str,
+        tenant_id: Option<&str>,
+        db: &Database,
+    ) -> Result<Option<User>, Error> {
+        if username.is_empty() {
+            return Err(Error::EmptyUsername);
+        }
+
+        let regex_pattern = format!("^{}$", regex::escape(username));
+        let re = mongodb::bson::Regex {
+            pattern: regex_pattern,
+            options: String::from("i"),
+        };
+
+        let filter = self.in_tenant(
+            doc! { "username": { "$regex": Bson::RegularExpression(re) } },
+            tenant_id,
+        );
+
+        let user = match db
+            .collection::<User>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(user)
+    }
+
+    /// # Summary
+    ///
+    /// Find a User entity by its username or its email.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - The username or email of the User entity.
+    /// * `tenant_id` - The ID of the tenant of the User entity, which is ignored if tenancy is disabled.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false);
+    /// let user = user_repository.find_by_username_or_email(&String::from("username"), None, &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<User>, Error>` - The result of the operation.
+    pub async fn find_by_username_or_email(
+        &self,
+        identifier: &str,
+        tenant_id: Option<&str>,
+        db: &Database,
+    ) -> Result<Option<User>, Error> {
+        if identifier.is_empty() {
+            return Err(Error::EmptyUsername);
+        }
+
+        let regex_pattern = format!("^{}$", regex::escape(identifier));
+        let re = mongodb::bson::Regex {
+            pattern: regex_pattern,
+            options: String::from("i"),
+        };
+
+        let filter = self.in_tenant(
+            doc! {
+                "$or": [
+                    { "username": { "$regex": Bson::RegularExpression(re) } },
+                    { "email": identifier.to_lowercase() },
+                ]
+            },
+            tenant_id,
+        );
+
+        let user = match db
+            .collection::<User>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(user)
+    }
+
+    /// # Summary
+    ///
+    /// Find a User entity by its email.
+    ///
+    /// # Arguments
+    ///
+    /// * `email` - The email of the User entity.
+    /// * `tenant_id` - The ID of the tenant of the User entity, which is ignored if tenancy is disabled.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex, false);
+    /// let user = user_repository.find_by_email(&String::from("email"), None, &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<User>, Error>` - The result of the operation.
+    pub async fn find_by_email(
+        &self,
+        email: &str,
+        tenant_id: Option<&str>,
+        db: &Database,
+    ) -> Result<Option<User>, Error> {
+        if email.is_empty() {
+            return Err(Error::EmptyEmail);
+        }
+
+        let filter = self.in_tenant(
+            doc! {
+                "email": email,
+            },
+            tenant_id,
+        );
+
+        let user = match db
+            .collection::<User>(&self.collection)
+            .find_one(filter, None)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(user)
+    }
+
+    /// # Summary
+    ///
+    /// Insert a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User entity to insert.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let user = User::new(String::from("username"), String::from("email"), String::from("password"));
+    ///
+    /// user.first_name = String::from("first_name");
+    ///
+    /// let user = user_repository.update(user, &db);
+    /// ```
+    pub async fn update(&self, user: User, db: &Database) -> Result<User, Error> {
+        // A pending email address is only checked for uniqueness once it is confirmed
+        if let Some(email) = &user.pending_email {
+            if !self.email_regex.is_match(email) {
+                return Err(Error::InvalidEmail(email.clone()));
+            }
+        }
+
+        if user.email.is_some() && !self.email_regex.is_match(&user.email.clone().unwrap()) {
+            return Err(Error::InvalidEmail(user.email.unwrap()));
+        } else if user.email.is_some() {
+            match self
+                .find_by_email(
+                    &user.email.clone().unwrap().to_lowercase(),
+                    user.tenant_id.as_deref(),
+                    db,
+                )
+                .await
+            {
+                Ok(user) => {
+                    if user.is_some() {
+                        return Err(Error::EmailAlreadyTaken);
+                    }
+                }
+                Err(e) => {
+                    return Err(e);
+                }
+            };
+        }
+
+        match self
+            .find_by_username(&user.username.to_lowercase(), user.tenant_id.as_deref(), db)
+            .await
+        {
+            Ok(u) => {
+                if let Some(p) = u {
+                    if p.id != user.id {
+                        return Err(Error::UsernameAlreadyTaken);
+                    }
+                }
+            }
+            Err(e) => {
+                return Err(e);
+            }
+        };
+
+        let user_id = user.id;
+        // Documents that were created before versioning was introduced do not have a version yet
+        let filter = if user.version == 0 {
+            doc! {
+                "_id": &user_id,
+                "$or": [{ "version": 0_i64 }, { "version": { "$exists": false } }],
+            }
+        } else {
+            doc! {
+                "_id": &user_id,
+                "version": user.version as i64,
+            }
+        };
+
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        let update = doc! {
+            "$set": {
+                "username": user.username,
+                "email": user.email,
+                "firstName": user.first_name,
+                "lastName": user.last_name,
+                "roles": user.roles,
+                "updated_at": now,
+                "updatedBy": user.updated_by,
+                "enabled": user.enabled,
+                "pendingEmail": user.pending_email,
+                "emailChangeToken": user.email_change_token,
+                "emailChangeExpiresAt": user.email_change_expires_at,
+            },
+            "$inc": {
+                "version": 1_i64,
+            },
+        };
+
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let collection = db.collection::<User>(&self.collection);
+        let result = collection
+            .find_one_and_update(filter, update, options)
+            .await;
+
+        match result {
+            Ok(user) => {
+                if let Some(u) = user {
+                    Ok(u)
+                } else {
+                    match self.find_by_id(&user_id.to_hex(), db).await {
+                        Ok(Some(_)) => Err(Error::Conflict(user_id.to_hex())),
+                        Ok(None) => Err(Error::UserNotFound(user_id.to_hex())),
+                        Err(e) => Err(e),
+                    }
+                }
+            }
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Atomically increment the number of consecutive failed logins of a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let failed_login_attempts = user_repository.record_failed_login(&user.id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u32, Error>` - The number of consecutive failed logins, including this one.
+    pub async fn record_failed_login(&self, id: &ObjectId, db: &Database) -> Result<u32, Error> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        match db
+            .collection::<User>(&self.collection)
+            .find_one_and_update(
+                doc! { "_id": id },
+                doc! { "$inc": { "failedLoginAttempts": 1_i32 } },
+                options,
+            )
+            .await
+        {
+            Ok(Some(u)) => Ok(u.failed_login_attempts),
+            Ok(None) => Err(Error::UserNotFound(id.to_hex())),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Lock a User entity until the given time. The number of consecutive failed logins is reset, so that the User
+    /// gets a fresh set of attempts once the lock expires.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity.
+    /// * `until` - The time at which the lock expires.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// user_repository.lock(&user.id, BsonDateTime::now(), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn lock(
+        &self,
+        id: &ObjectId,
+        until: BsonDateTime,
+        db: &Database,
+    ) -> Result<(), Error> {
+        match db
+            .collection::<User>(&self.collection)
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "lockedUntil": until, "failedLoginAttempts": 0_i32 } },
+                None,
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Reset the number of consecutive failed logins and the lock of a User entity after a successful login.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// user_repository.reset_failed_logins(&user.id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn reset_failed_logins(&self, id: &ObjectId, db: &Database) -> Result<(), Error> {
+        match db
+            .collection::<User>(&self.collection)
+            .update_one(
+                doc! { "_id": id },
+                doc! {
+                    "$set": { "failedLoginAttempts": 0_i32 },
+                    "$unset": { "lockedUntil": "" },
+                },
+                None,
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Record the time of a successful login of a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// user_repository.record_login(&user.id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn record_login(&self, id: &ObjectId, db: &Database) -> Result<(), Error> {
+        match db
+            .collection::<User>(&self.collection)
+            .update_one(
+                doc! { "_id": id },
+                doc! { "$set": { "lastLoginAt": BsonDateTime::now() } },
+                None,
+            )
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Update the password of a User entity. Changing the password also clears the failed login attempts and the
+    /// lockout of the User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the User entity.
+    /// * `password` - The new password of the User entity.
+    /// * `password_history` - The new password history of the User entity, or None to leave it untouched.
+    /// * `must_change_password` - Whether the User must change the password on the next login.
+    /// * `updated_by` - The ID of the User that is updating the password.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    ///
+    /// user_repository.update_password(&String::from("id"), &String::from("password"), None, false, None, &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn update_password(
+        &self,
+        id: &str,
+        password: &str,
+        password_history: Option<Vec<String>>,
+        must_change_password: bool,
+        updated_by: Option<String>,
+        db: &Database,
+    ) -> Result<(), Error> {
+        if id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        if password.is_empty() {
+            return Err(Error::EmptyPassword);
+        }
+
         let target_object_id = match ObjectId::parse_str(id) {
             Ok(res) => res,
             Err(e) => {
@@ -514,13 +1155,30 @@ impl UserRepository {
         let now: DateTime<Utc> = SystemTime::now().into();
         let now: String = now.to_rfc3339();
 
-        let update = doc! {
-            "$set": {
-                "password": password,
-                "updated_at": now,
+        let mut set = doc! {
+            "password": password,
+            "updated_at": now,
+            "updatedBy": updated_by,
+        };
+
+        let mut update = doc! {
+            "$inc": {
+                "version": 1_i64,
             },
         };
 
+        // The history, change time, flag and lockout are left untouched if the same password is only re-hashed
+        if let Some(h) = password_history {
+            set.insert("passwordHistory", h);
+            set.insert("passwordChangedAt", BsonDateTime::now());
+            set.insert("mustChangePassword", must_change_password);
+            // A new password lifts the lockout, so that a locked out User can log in with it immediately
+            set.insert("failedLoginAttempts", 0_i32);
+            update.insert("$unset", doc! { "lockedUntil": "" });
+        }
+
+        update.insert("$set", set);
+
         let collection = db.collection::<User>(&self.collection);
         let result = collection.update_one(filter, update, None).await;
 
@@ -613,12 +1271,17 @@ impl UserRepository {
             }
         };
 
-        let filter = doc! {};
+        let filter = doc! {
+            "roles": target_object_id,
+        };
 
         let update = doc! {
             "$pull": {
                 "roles": target_object_id,
-            }
+            },
+            "$inc": {
+                "version": 1_i64,
+            },
         };
 
         match db
@@ -631,6 +1294,67 @@ impl UserRepository {
         }
     }
 
+    /// # Summary
+    ///
+    /// Delete a role from all users as part of the transaction of a ClientSession.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_id` - The id of the role.
+    /// * `db` - The Database.
+    /// * `session` - The ClientSession whose transaction the users are updated in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let mut session = client.start_session(None).await?;
+    /// session.start_transaction(None).await?;
+    /// user_repository.delete_role_from_all_users_with_session("role_id", &db, &mut session).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn delete_role_from_all_users_with_session(
+        &self,
+        role_id: &str,
+        db: &Database,
+        session: &mut ClientSession,
+    ) -> Result<(), Error> {
+        if role_id.is_empty() {
+            return Err(Error::EmptyId);
+        }
+
+        let target_object_id = match ObjectId::parse_str(role_id) {
+            Ok(res) => res,
+            Err(e) => {
+                return Err(Error::InvalidId(e.to_string()));
+            }
+        };
+
+        let filter = doc! {
+            "roles": target_object_id,
+        };
+
+        let update = doc! {
+            "$pull": {
+                "roles": target_object_id,
+            },
+            "$inc": {
+                "version": 1_i64,
+            },
+        };
+
+        match db
+            .collection::<User>(&self.collection)
+            .update_many_with_session(filter, update, None, session)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::MongoDb(e)),
+        }
+    }
+
     /// # Summary
     ///
     /// Search for users.
@@ -679,12 +1403,192 @@ impl UserRepository {
 
         let find_options = FindOptions::builder().limit(limit).skip(skip).build();
 
-        let filter = doc! {
-            "$text": {
-                "$search": text,
-            },
+        let cursor = match db
+            .collection::<User>(&self.collection)
+            .find(text_filter(text), find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
+    /// # Summary
+    ///
+    /// Search for users whose username, email, first name or last name contain the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The (partial) text to search for.
+    /// * `limit` - The limit of the result.
+    /// * `page` - The page of the result.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let users = user_repository.search_partial(&String::from("jan"), Some(10), Some(1), &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<User>, Error>` - The result of the operation.
+    pub async fn search_partial(
+        &self,
+        query: &str,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<User>, Error> {
+        if query.trim().is_empty() {
+            return Err(Error::EmptyTextSearch);
+        }
+
+        let mut skip: Option<u64> = None;
+
+        if let Some(l) = limit {
+            if l > 1 {
+                if let Some(p) = page {
+                    if p > 1 {
+                        let res = u64::try_from((p - 1) * l).unwrap_or(0);
+                        skip = Some(res);
+                    }
+                }
+            }
+        }
+
+        let find_options = FindOptions::builder()
+            .limit(limit)
+            .skip(skip)
+            .sort(doc! { "username": 1 })
+            .build();
+
+        let cursor = match db
+            .collection::<User>(&self.collection)
+            .find(partial_filter(query), find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
+        };
+
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
+    /// # Summary
+    ///
+    /// Find all User entities that have at least one of the given roles.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_ids` - The IDs of the roles.
+    /// * `limit` - The limit of the result.
+    /// * `page` - The page of the result.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let users = user_repository.find_by_role(vec![String::from("role_id")], Some(10), Some(1), &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<User>, Error>` - The result of the operation.
+    pub async fn find_by_role(
+        &self,
+        role_ids: Vec<String>,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<User>, Error> {
+        let filter = role_filter(role_ids)?;
+
+        let mut skip: Option<u64> = None;
+
+        if let Some(l) = limit {
+            if l > 1 {
+                if let Some(p) = page {
+                    if p > 1 {
+                        let res = u64::try_from((p - 1) * l).unwrap_or(0);
+                        skip = Some(res);
+                    }
+                }
+            }
+        }
+
+        let find_options = FindOptions::builder().limit(limit).skip(skip).build();
+
+        let cursor = match db
+            .collection::<User>(&self.collection)
+            .find(filter, find_options)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => return Err(Error::MongoDb(e)),
         };
 
+        Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
+    }
+
+    /// # Summary
+    ///
+    /// Find all User entities that were created within a date range, ordered by their creation time.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The inclusive start of the range, or None for an open start.
+    /// * `to` - The exclusive end of the range, or None for an open end.
+    /// * `limit` - The limit of the result.
+    /// * `page` - The page of the result.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let db = Database::new();
+    /// let user_repository = UserRepository::new(String::from("users"), email_regex);
+    /// let users = user_repository.find_by_date_range(Some(Utc::now() - Duration::days(30)), None, Some(10), Some(1), &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<User>, Error>` - The result of the operation.
+    pub async fn find_by_date_range(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<User>, Error> {
+        let filter = created_filter(from, to);
+
+        let mut skip: Option<u64> = None;
+
+        if let Some(l) = limit {
+            if l > 1 {
+                if let Some(p) = page {
+                    if p > 1 {
+                        let res = u64::try_from((p - 1) * l).unwrap_or(0);
+                        skip = Some(res);
+                    }
+                }
+            }
+        }
+
+        let find_options = FindOptions::builder()
+            .limit(limit)
+            .skip(skip)
+            .sort(doc! { "createdAt": 1, "_id": 1 })
+            .build();
+
         let cursor = match db
             .collection::<User>(&self.collection)
             .find(filter, find_options)
@@ -697,3 +1601,111 @@ impl UserRepository {
         Ok(cursor.try_collect().await.unwrap_or_else(|_| vec![]))
     }
 }
+
+/// # Summary
+///
+/// Create the filter that matches Users that have at least one of the given Roles.
+///
+/// # Arguments
+///
+/// * `role_ids` - The IDs of the Roles.
+///
+/// # Returns
+///
+/// * `Result<Document, Error>` - The filter, or an Error if an ID is invalid or no IDs were given.
+fn role_filter(role_ids: Vec<String>) -> Result<Document, Error> {
+    let mut oid_vec: Vec<ObjectId> = vec![];
+    for id in role_ids {
+        match ObjectId::parse_str(&id) {
+            Ok(oid) => oid_vec.push(oid),
+            Err(e) => return Err(Error::InvalidId(e.to_string())),
+        }
+    }
+
+    if oid_vec.is_empty() {
+        return Err(Error::EmptyId);
+    }
+
+    Ok(doc! {
+        "roles": {
+            "$in": oid_vec,
+        },
+    })
+}
+
+/// # Summary
+///
+/// Create the filter that matches Users that were created within a date range.
+///
+/// # Arguments
+///
+/// * `from` - The inclusive start of the range, or None for an open start.
+/// * `to` - The exclusive end of the range, or None for an open end.
+///
+/// # Returns
+///
+/// * `Document` - The filter.
+fn created_filter(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Document {
+    let mut created_at = Document::new();
+    if let Some(f) = from {
+        created_at.insert("$gte", BsonDateTime::from_chrono(f));
+    }
+    if let Some(t) = to {
+        created_at.insert("$lt", BsonDateTime::from_chrono(t));
+    }
+
+    if created_at.is_empty() {
+        return Document::new();
+    }
+
+    doc! {
+        "createdAt": created_at,
+    }
+}
+
+/// # Summary
+///
+/// Create the filter that matches Users using the text index.
+///
+/// # Arguments
+///
+/// * `text` - The text to search for.
+///
+/// # Returns
+///
+/// * `Document` - The filter.
+fn text_filter(text: &str) -> Document {
+    doc! {
+        "$text": {
+            "$search": text,
+        },
+    }
+}
+
+/// # Summary
+///
+/// Create the filter that matches Users with a username, email, first name or last name that contains the query.
+///
+/// # Arguments
+///
+/// * `query` - The (partial) query to search for.
+///
+/// # Returns
+///
+/// * `Document` - The filter.
+fn partial_filter(query: &str) -> Document {
+    // The text index only matches whole words, so a case-insensitive regex is used to match partial input
+    let re = mongodb::bson::Regex {
+        pattern: regex::escape(query.trim()),
+        options: String::from("i"),
+    };
+
+    doc! {
+        "$or": [
+            { "username": { "$regex": Bson::RegularExpression(re.clone()) } },
+            { "email": { "$regex": Bson::RegularExpression(re.clone()) } },
+            { "firstName": { "$regex": Bson::RegularExpression(re.clone()) } },
+            { "lastName": { "$regex": Bson::RegularExpression(re) } },
+        ]
+    }
+}
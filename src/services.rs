@@ -1,14 +1,31 @@
 use crate::services::audit::audit_service::AuditService;
+use crate::services::email::email_service::EmailService;
+use crate::services::event::event_service::EventService;
+use crate::services::idempotency::idempotency_service::IdempotencyService;
+use crate::services::invite::invite_service::InviteService;
 use crate::services::jwt::jwt_service::JwtService;
+use crate::services::login_defense::login_defense_service::LoginDefenseService;
+use crate::services::migration::migration_service::MigrationService;
+use crate::services::password::password_service::PasswordService;
 use crate::services::permission::permission_service::PermissionService;
 use crate::services::role::role_service::RoleService;
+use crate::services::session::session_service::SessionService;
 use crate::services::user::user_service::UserService;
 
 pub mod audit;
+pub mod email;
+pub mod event;
+pub mod idempotency;
+pub mod invite;
 pub mod jwt;
+pub mod login_defense;
+pub mod migration;
+pub mod paseto;
 pub mod password;
 pub mod permission;
 pub mod role;
+pub mod session;
+pub mod token;
 pub mod user;
 
 #[derive(Clone)]
@@ -18,6 +35,14 @@ pub struct Services {
     pub user_service: UserService,
     pub jwt_service: JwtService,
     pub audit_service: AuditService,
+    pub password_service: PasswordService,
+    pub idempotency_service: IdempotencyService,
+    pub email_service: EmailService,
+    pub session_service: SessionService,
+    pub migration_service: MigrationService,
+    pub login_defense_service: LoginDefenseService,
+    pub event_service: EventService,
+    pub invite_service: InviteService,
 }
 
 impl Services {
@@ -32,16 +57,33 @@ impl Services {
     /// * `user_service` - The UserService.
     /// * `jwt_service` - The JwtService.
     /// * `audit_service` - The AuditService.
+    /// * `password_service` - The PasswordService.
+    /// * `idempotency_service` - The IdempotencyService.
+    /// * `email_service` - The EmailService.
+    /// * `session_service` - The SessionService.
+    /// * `migration_service` - The MigrationService.
+    /// * `login_defense_service` - The LoginDefenseService.
+    /// * `event_service` - The EventService.
+    /// * `invite_service` - The InviteService.
     ///
     /// # Returns
     ///
     /// A new instance of Services.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         permission_service: PermissionService,
         role_service: RoleService,
         user_service: UserService,
         jwt_service: JwtService,
         audit_service: AuditService,
+        password_service: PasswordService,
+        idempotency_service: IdempotencyService,
+        email_service: EmailService,
+        session_service: SessionService,
+        migration_service: MigrationService,
+        login_defense_service: LoginDefenseService,
+        event_service: EventService,
+        invite_service: InviteService,
     ) -> Services {
         Services {
             permission_service,
@@ -49,6 +91,14 @@ impl Services {
             user_service,
             jwt_service,
             audit_service,
+            password_service,
+            idempotency_service,
+            email_service,
+            session_service,
+            migration_service,
+            login_defense_service,
+            event_service,
+            invite_service,
         }
     }
 }
@@ -1 +1,2 @@
+pub mod audit_buffer;
 pub mod audit_service;
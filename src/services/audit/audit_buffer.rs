@@ -0,0 +1,164 @@
+use crate::repository::audit::audit_model::Audit;
+use crate::repository::audit::audit_repository::{AuditRepository, Error};
+use log::{error, info};
+use mongodb::Database;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{interval, MissedTickBehavior};
+
+enum AuditCommand {
+    Write(Audit),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Buffers Audits in memory and writes them in batches from a background task, so that an audited operation does not
+/// wait for its Audit to be written.
+#[derive(Clone)]
+pub struct AuditBuffer {
+    sender: mpsc::Sender<AuditCommand>,
+}
+
+impl AuditBuffer {
+    /// # Summary
+    ///
+    /// Create a new AuditBuffer and start the background task that writes the buffered Audits. The task must be started
+    /// from within the runtime of the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit_repository` - The AuditRepository that writes the Audits.
+    /// * `db` - The Database to write the Audits in.
+    /// * `batch_size` - The amount of buffered Audits at which they are written, before the flush interval elapses.
+    /// * `flush_interval` - The interval at which the buffered Audits are written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit_buffer = AuditBuffer::start(audit_repository, db.clone(), 100, Duration::from_millis(1000));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `AuditBuffer` - The new AuditBuffer.
+    pub fn start(
+        audit_repository: AuditRepository,
+        db: Database,
+        batch_size: usize,
+        flush_interval: Duration,
+    ) -> AuditBuffer {
+        // The channel holds one extra batch, so that requests only have to wait if the database cannot keep up
+        let (sender, receiver) = mpsc::channel(batch_size * 2);
+        tokio::spawn(Self::run(
+            receiver,
+            audit_repository,
+            db,
+            batch_size,
+            flush_interval,
+        ));
+
+        AuditBuffer { sender }
+    }
+
+    /// # Summary
+    ///
+    /// Add an Audit to the buffer. This only waits if the buffer is full.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit` - The Audit to write.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation, which fails if the background task has stopped.
+    pub async fn push(&self, audit: Audit) -> Result<(), Error> {
+        self.sender
+            .send(AuditCommand::Write(audit))
+            .await
+            .map_err(|_| Error::BufferClosed)
+    }
+
+    /// # Summary
+    ///
+    /// Write all buffered Audits and wait until they are written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// audit_buffer.flush().await;
+    /// ```
+    pub async fn flush(&self) {
+        let (done, wait) = oneshot::channel();
+        if self.sender.send(AuditCommand::Flush(done)).await.is_ok() {
+            let _ = wait.await;
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Receive the buffered Audits and write them whenever the batch is full, the flush interval elapses or a flush is
+    /// requested.
+    ///
+    /// # Arguments
+    ///
+    /// * `receiver` - The receiving end of the buffer.
+    /// * `audit_repository` - The AuditRepository that writes the Audits.
+    /// * `db` - The Database to write the Audits in.
+    /// * `batch_size` - The amount of buffered Audits at which they are written.
+    /// * `flush_interval` - The interval at which the buffered Audits are written.
+    async fn run(
+        mut receiver: mpsc::Receiver<AuditCommand>,
+        audit_repository: AuditRepository,
+        db: Database,
+        batch_size: usize,
+        flush_interval: Duration,
+    ) {
+        let mut batch: Vec<Audit> = Vec::with_capacity(batch_size);
+        let mut ticker = interval(flush_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                command = receiver.recv() => match command {
+                    Some(AuditCommand::Write(audit)) => {
+                        batch.push(audit);
+                        if batch.len() >= batch_size {
+                            Self::write(&mut batch, &audit_repository, &db).await;
+                        }
+                    }
+                    Some(AuditCommand::Flush(done)) => {
+                        Self::write(&mut batch, &audit_repository, &db).await;
+                        let _ = done.send(());
+                    }
+                    None => {
+                        Self::write(&mut batch, &audit_repository, &db).await;
+                        break;
+                    }
+                },
+                _ = ticker.tick() => Self::write(&mut batch, &audit_repository, &db).await,
+            }
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Write and clear a batch of Audits. A failed write is logged, because the audited operations have completed
+    /// already.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - The batch of Audits.
+    /// * `audit_repository` - The AuditRepository that writes the Audits.
+    /// * `db` - The Database to write the Audits in.
+    async fn write(batch: &mut Vec<Audit>, audit_repository: &AuditRepository, db: &Database) {
+        if batch.is_empty() {
+            return;
+        }
+
+        let audits = std::mem::take(batch);
+        let count = audits.len();
+        info!("Writing {} buffered audits", count);
+        if let Err(e) = audit_repository.create_many(audits, db).await {
+            error!("Failed to write {} buffered audits: {}", count, e);
+        }
+    }
+}
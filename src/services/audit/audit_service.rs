@@ -1,12 +1,25 @@
-use crate::repository::audit::audit_model::Audit;
+use crate::configuration::db_config::AuditFailurePolicy;
+use crate::repository::audit::audit_model::{
+    Action, Audit, AuditStats, LoginOutcome, ResourceIdType, ResourceType,
+};
 use crate::repository::audit::audit_repository::{AuditRepository, Error};
-use log::info;
-use mongodb::Database;
+use crate::services::audit::audit_buffer::AuditBuffer;
+use chrono::{DateTime, Utc};
+use log::{error, info};
+use mongodb::bson::oid::ObjectId;
+use mongodb::{ClientSession, Cursor, Database};
+use std::collections::BTreeMap;
+
+/// The default ID of the actor that is recorded for actions that are initiated by the system.
+pub const DEFAULT_SYSTEM_ACTOR_ID: &str = "000000000000000000000000";
 
 #[derive(Clone)]
 pub struct AuditService {
     pub audit_repository: AuditRepository,
     pub enabled: bool,
+    pub system_actor_id: ObjectId,
+    pub failure_policy: AuditFailurePolicy,
+    pub buffer: Option<AuditBuffer>,
 }
 
 impl AuditService {
@@ -18,17 +31,118 @@ impl AuditService {
     ///
     /// * `audit_repository` - The AuditRepository.
     /// * `enabled` - Whether or not the AuditService is enabled.
+    /// * `system_actor_id` - The ID of the actor that is recorded for actions that are initiated by the system.
+    /// * `failure_policy` - The AuditFailurePolicy that decides whether a failed write fails the audited operation.
+    /// * `buffer` - The AuditBuffer that writes the Audits in batches, or None to write every Audit immediately.
     ///
     /// # Returns
     ///
     /// * `AuditService` - The AuditService.
-    pub fn new(audit_repository: AuditRepository, enabled: bool) -> AuditService {
+    pub fn new(
+        audit_repository: AuditRepository,
+        enabled: bool,
+        system_actor_id: ObjectId,
+        failure_policy: AuditFailurePolicy,
+        buffer: Option<AuditBuffer>,
+    ) -> AuditService {
         AuditService {
             audit_repository,
             enabled,
+            system_actor_id,
+            failure_policy,
+            buffer,
         }
     }
 
+    /// # Summary
+    ///
+    /// Create a new Audit for an action that was initiated by a User, or by the system if no User is given.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that initiated the action, or None if the system initiated the action.
+    /// * `action` - The action of the Audit.
+    /// * `resource_id` - The resource id of the Audit.
+    /// * `resource_id_type` - The resource id type of the Audit.
+    /// * `resource_type` - The resource type of the Audit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit = audit_service.new_audit(None, Create, user.id, ResourceIdType::UserId, ResourceType::User);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Audit` - The new Audit.
+    pub fn new_audit(
+        &self,
+        user_id: Option<ObjectId>,
+        action: Action,
+        resource_id: ObjectId,
+        resource_id_type: ResourceIdType,
+        resource_type: ResourceType,
+    ) -> Audit {
+        match user_id {
+            Some(user_id) => Audit::new(
+                user_id,
+                action,
+                resource_id,
+                resource_id_type,
+                resource_type,
+                false,
+            ),
+            None => Audit::new(
+                self.system_actor_id,
+                action,
+                resource_id,
+                resource_id_type,
+                resource_type,
+                true,
+            ),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create a new Audit for a login attempt. A login attempt for a username or email address that does not belong to
+    /// any User is recorded as a system action, because there is no User to attribute it to.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that attempted to log in, or None if no User was found.
+    /// * `outcome` - The outcome of the login attempt.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit = audit_service.new_login_audit(Some(user.id), LoginOutcome::BadPassword);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Audit` - The new Audit.
+    pub fn new_login_audit(&self, user_id: Option<ObjectId>, outcome: LoginOutcome) -> Audit {
+        let mut audit = match user_id {
+            Some(id) => self.new_audit(
+                Some(id),
+                Action::Login,
+                id,
+                ResourceIdType::UserId,
+                ResourceType::User,
+            ),
+            None => self.new_audit(
+                None,
+                Action::Login,
+                self.system_actor_id,
+                ResourceIdType::None,
+                ResourceType::User,
+            ),
+        };
+        audit.reason = Some(outcome);
+        audit
+    }
+
     /// # Summary
     ///
     /// Create a new Audit.
@@ -40,14 +154,72 @@ impl AuditService {
     ///
     /// # Returns
     ///
-    /// * `Result<(), Error>` - The result of the operation.
+    /// * `Result<(), Error>` - The result of the operation. If the AuditFailurePolicy is Warn, a failed write is logged
+    ///   and Ok is returned, so that the audited operation proceeds. If a buffer is used, the Audit is written later on
+    ///   and a failed write is only logged.
     pub async fn create(&self, audit: Audit, db: &Database) -> Result<(), Error> {
         if !self.enabled {
             return Ok(());
         }
 
         info!("Creating audit: {}", audit);
-        self.audit_repository.create(audit, db).await
+        let res = match &self.buffer {
+            Some(b) => b.push(audit).await,
+            None => self.audit_repository.create(audit, db).await,
+        };
+        match res {
+            Err(e) if self.failure_policy == AuditFailurePolicy::Warn => {
+                error!("Failed to create Audit, proceeding without it: {}", e);
+                Ok(())
+            }
+            res => res,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create a new Audit as part of the transaction of a ClientSession, so that it is only stored if the transaction
+    /// is committed. A failed write always fails the transaction, regardless of the AuditFailurePolicy, because the
+    /// transaction cannot be committed after a failed write.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit` - The Audit to create.
+    /// * `db` - The Database to create the Audit in.
+    /// * `session` - The ClientSession whose transaction the Audit is created in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn create_with_session(
+        &self,
+        audit: Audit,
+        db: &Database,
+        session: &mut ClientSession,
+    ) -> Result<(), Error> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        info!("Creating audit: {}", audit);
+        self.audit_repository
+            .create_with_session(audit, db, session)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Write the Audits that are buffered, if a buffer is used, and wait until they are written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// audit_service.flush().await;
+    /// ```
+    pub async fn flush(&self) {
+        if let Some(b) = &self.buffer {
+            b.flush().await;
+        }
     }
 
     /// # Summary
@@ -75,6 +247,7 @@ impl AuditService {
     ///
     /// * `limit` - The limit of Audits to find.
     /// * `page` - The page of Audits to find.
+    /// * `system` - Only find system Audits if true, or only User Audits if false.
     /// * `db` - The Database to find the Audits in.
     ///
     /// # Returns
@@ -84,10 +257,239 @@ impl AuditService {
         &self,
         limit: Option<i64>,
         page: Option<i64>,
+        system: Option<bool>,
         db: &Database,
     ) -> Result<Vec<Audit>, Error> {
         info!("Finding all audits");
-        self.audit_repository.find_all(limit, page, db).await
+        self.audit_repository
+            .find_all(limit, page, system, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Count the Audits, optionally only the ones that match a text search.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to search for, or None to count all Audits.
+    /// * `system` - Only count system Audits if true, or only User Audits if false.
+    /// * `db` - The Database to count the Audits in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of matching Audits.
+    pub async fn count(
+        &self,
+        text: Option<&str>,
+        system: Option<bool>,
+        db: &Database,
+    ) -> Result<u64, Error> {
+        info!("Counting audits");
+        self.audit_repository.count(text, system, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find a page of Audits after the given cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `after` - The opaque cursor of the previous page, or None to start at the first page.
+    /// * `limit` - The limit of Audits to find.
+    /// * `system` - Only find system Audits if true, or only User Audits if false.
+    /// * `db` - The Database to find the Audits in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Vec<Audit>, Option<String>), Error>` - The page of Audits and the cursor of the next page, if any.
+    pub async fn find_after(
+        &self,
+        after: Option<&str>,
+        limit: Option<i64>,
+        system: Option<bool>,
+        db: &Database,
+    ) -> Result<(Vec<Audit>, Option<String>), Error> {
+        info!("Finding audits after cursor: {:?}", after);
+        self.audit_repository
+            .find_after(after, limit, system, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Find the Audits of a single resource within a date range.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource_id` - The ID of the resource.
+    /// * `resource_id_type` - The type of the ID of the resource.
+    /// * `from` - The inclusive start of the date range, or None to find from the first Audit.
+    /// * `to` - The exclusive end of the date range, or None to find up to the last Audit.
+    /// * `limit` - The limit of Audits to find.
+    /// * `page` - The page of Audits to find.
+    /// * `db` - The Database to find the Audits in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Audit>, Error>` - The result of the operation.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn find_by_resource(
+        &self,
+        resource_id: &str,
+        resource_id_type: &ResourceIdType,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Audit>, Error> {
+        info!(
+            "Finding audits of resource: {} ({})",
+            resource_id, resource_id_type
+        );
+        self.audit_repository
+            .find_by_resource(resource_id, resource_id_type, from, to, limit, page, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Count the Audits of a single resource within a date range.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource_id` - The ID of the resource.
+    /// * `resource_id_type` - The type of the ID of the resource.
+    /// * `from` - The inclusive start of the date range, or None to count from the first Audit.
+    /// * `to` - The exclusive end of the date range, or None to count up to the last Audit.
+    /// * `db` - The Database to count the Audits in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of matching Audits.
+    pub async fn count_by_resource(
+        &self,
+        resource_id: &str,
+        resource_id_type: &ResourceIdType,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        db: &Database,
+    ) -> Result<u64, Error> {
+        info!(
+            "Counting audits of resource: {} ({})",
+            resource_id, resource_id_type
+        );
+        self.audit_repository
+            .count_by_resource(resource_id, resource_id_type, from, to, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Count the Audits within a date range, grouped by action and by resource type.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The inclusive start of the date range, or None to count from the first Audit.
+    /// * `to` - The exclusive end of the date range, or None to count up to the last Audit.
+    /// * `db` - The Database to count the Audits in.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<AuditStats, Error>` - The result of the operation.
+    pub async fn stats(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        db: &Database,
+    ) -> Result<AuditStats, Error> {
+        info!("Counting audits between {:?} and {:?}", from, to);
+        self.audit_repository.stats(from, to, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Delete all Audit entities that were created before a date. The deletion itself is audited afterwards, along with
+    /// the date and the number of deleted Audits.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - The exclusive date before which the Audits were created.
+    /// * `user_id` - The ID of the User that is deleting the Audits.
+    /// * `db` - The Database to delete the Audits from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit_repository = AuditRepository::new("audit".to_string()).unwrap();
+    /// let audit_service = AuditService::new(audit_repository, true, ObjectId::new(), AuditFailurePolicy::Fail, None);
+    /// let db = mongodb::Client::with_uri_str("mongodb://localhost:27017")
+    ///    .unwrap()
+    ///    .database("test");
+    /// let res = audit_service.delete_before(Utc::now(), ObjectId::new(), &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of deleted Audits.
+    pub async fn delete_before(
+        &self,
+        before: DateTime<Utc>,
+        user_id: ObjectId,
+        db: &Database,
+    ) -> Result<u64, Error> {
+        info!("Deleting audits created before {}", before);
+        let deleted = self.audit_repository.delete_before(before, db).await?;
+
+        let mut audit = self.new_audit(
+            Some(user_id),
+            Action::Delete,
+            self.system_actor_id,
+            ResourceIdType::None,
+            ResourceType::Audit,
+        );
+        audit.details = Some(BTreeMap::from([
+            (String::from("before"), before.to_rfc3339()),
+            (String::from("deletedCount"), deleted.to_string()),
+        ]));
+        self.create(audit, db).await?;
+
+        Ok(deleted)
+    }
+
+    /// # Summary
+    ///
+    /// Open a cursor over the Audit entities within a date range, to export them.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The inclusive start of the date range, or None to export from the first Audit.
+    /// * `to` - The exclusive end of the date range, or None to export up to the last Audit.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit_repository = AuditRepository::new("audit".to_string()).unwrap();
+    /// let audit_service = AuditService::new(audit_repository, true, ObjectId::new(), AuditFailurePolicy::Fail, None);
+    /// let db = mongodb::Client::with_uri_str("mongodb://localhost:27017")
+    ///    .unwrap()
+    ///    .database("test");
+    /// let cursor = audit_service.export(None, None, &db).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Cursor<Audit>, Error>` - The result of the operation.
+    pub async fn export(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        db: &Database,
+    ) -> Result<Cursor<Audit>, Error> {
+        info!("Exporting audits between {:?} and {:?}", from, to);
+        self.audit_repository.export(from, to, db).await
     }
 
     /// # Summary
@@ -99,17 +501,18 @@ impl AuditService {
     /// * `text` - The text to search for.
     /// * `limit` - The limit of Audits to find.
     /// * `page` - The page of Audits to find.
+    /// * `system` - Only find system Audits if true, or only User Audits if false.
     /// * `db` - The database to use.
     ///
     /// # Example
     ///
     /// ```
     /// let audit_repository = AuditRepository::new("audit".to_string()).unwrap();
-    /// let audit_service = AuditService::new(audit_repository, true);
+    /// let audit_service = AuditService::new(audit_repository, true, ObjectId::new(), AuditFailurePolicy::Fail, None);
     /// let db = mongodb::Client::with_uri_str("mongodb://localhost:27017")
     ///    .unwrap()
     ///    .database("test");
-    /// let res = audit_service.search("test", 1, 10, &db).await;
+    /// let res = audit_service.search("test", 1, 10, None, &db).await;
     /// ```
     ///
     /// # Returns
@@ -120,9 +523,12 @@ impl AuditService {
         text: &str,
         limit: Option<i64>,
         page: Option<i64>,
+        system: Option<bool>,
         db: &Database,
     ) -> Result<Vec<Audit>, Error> {
         info!("Searching for audits: {}", text);
-        self.audit_repository.search(text, limit, page, db).await
+        self.audit_repository
+            .search(text, limit, page, system, db)
+            .await
     }
 }
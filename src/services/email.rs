@@ -0,0 +1,3 @@
+pub mod disposable_email_policy;
+pub mod email_policy;
+pub mod email_service;
@@ -0,0 +1,86 @@
+use crate::services::email::email_policy::EmailPolicy;
+use std::collections::HashSet;
+
+const DISPOSABLE_DOMAINS: &str = include_str!("disposable_domains.txt");
+
+#[derive(Clone)]
+pub struct DisposableEmailPolicy {
+    domains: HashSet<String>,
+}
+
+impl DisposableEmailPolicy {
+    /// # Summary
+    ///
+    /// Create a new DisposableEmailPolicy.
+    ///
+    /// # Arguments
+    ///
+    /// * `domains` - The known disposable email domains. The bundled list is used if None.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let policy = DisposableEmailPolicy::new(None);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `DisposableEmailPolicy` - The new DisposableEmailPolicy.
+    pub fn new(domains: Option<Vec<String>>) -> DisposableEmailPolicy {
+        let domains = match domains {
+            Some(d) => d,
+            None => Self::parse_domains(DISPOSABLE_DOMAINS),
+        };
+
+        DisposableEmailPolicy {
+            domains: domains
+                .into_iter()
+                .map(|d| d.trim().to_lowercase())
+                .filter(|d| !d.is_empty())
+                .collect(),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Parse a list of domains that contains one domain per line. Empty lines and lines starting with `#` are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents` - The list of domains.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let domains = DisposableEmailPolicy::parse_domains("mailinator.com\nyopmail.com");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<String>` - The domains.
+    pub fn parse_domains(contents: &str) -> Vec<String> {
+        contents
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(String::from)
+            .collect()
+    }
+}
+
+impl EmailPolicy for DisposableEmailPolicy {
+    fn is_allowed(&self, domain: &str) -> bool {
+        // Subdomains of disposable email providers are disposable as well
+        let mut current = domain;
+        loop {
+            if self.domains.contains(current) {
+                return false;
+            }
+
+            match current.split_once('.') {
+                Some((_, parent)) => current = parent,
+                None => return true,
+            }
+        }
+    }
+}
@@ -0,0 +1,14 @@
+pub trait EmailPolicy: Send + Sync {
+    /// # Summary
+    ///
+    /// Check whether email addresses of a domain are allowed to be used.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The lowercase domain of the email address.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating whether the domain is allowed.
+    fn is_allowed(&self, domain: &str) -> bool;
+}
@@ -0,0 +1,281 @@
+use crate::configuration::email_config::EmailConfig;
+use crate::repository::user::user_model::User;
+use crate::services::email::disposable_email_policy::DisposableEmailPolicy;
+use crate::services::email::email_policy::EmailPolicy;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use mongodb::bson::DateTime as BsonDateTime;
+use ring::digest::{digest, SHA256};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const EMAIL_CHANGE_TOKEN_TTL_HOURS: i64 = 24;
+
+#[derive(Clone)]
+pub struct EmailService {
+    allowed_domains: Vec<String>,
+    blocked_domains: Vec<String>,
+    pub policies: Vec<Arc<dyn EmailPolicy>>,
+    pub verify_changes: bool,
+}
+
+impl EmailService {
+    /// # Summary
+    ///
+    /// Create a new EmailService.
+    ///
+    /// # Arguments
+    ///
+    /// * `email_config` - The EmailConfig that holds the allowed and blocked domains and the EmailPolicy settings.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let email_config = EmailConfig::new(vec![String::from("*.codedead.com")], vec![], true, None, false);
+    /// let email_service = EmailService::new(email_config);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `EmailService` - The new EmailService.
+    pub fn new(email_config: EmailConfig) -> EmailService {
+        let mut policies: Vec<Arc<dyn EmailPolicy>> = vec![];
+        if email_config.block_disposable {
+            policies.push(Arc::new(DisposableEmailPolicy::new(
+                email_config.disposable_domains,
+            )));
+        }
+
+        EmailService {
+            allowed_domains: normalize_domains(email_config.allowed_domains),
+            blocked_domains: normalize_domains(email_config.blocked_domains),
+            policies,
+            verify_changes: email_config.verify_changes,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the domain of an email address is allowed to be used. If an allowlist is configured, an email
+    /// address is required.
+    ///
+    /// # Arguments
+    ///
+    /// * `email` - The optional email address to check.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if !email_service.is_domain_allowed(Some("user@codedead.com")) {
+    ///     // Reject the email address
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the domain is in the allowlist (or no allowlist is configured), not in the blocklist and
+    ///   allowed by all EmailPolicy instances.
+    pub fn is_domain_allowed(&self, email: Option<&str>) -> bool {
+        let email = match email {
+            Some(e) => e,
+            None => return self.allowed_domains.is_empty(),
+        };
+
+        let domain = match email.rsplit_once('@') {
+            Some((_, d)) => d.trim().to_lowercase(),
+            None => return false,
+        };
+
+        if !self.allowed_domains.is_empty()
+            && !self
+                .allowed_domains
+                .iter()
+                .any(|p| domain_matches(&domain, p))
+        {
+            return false;
+        }
+
+        if self
+            .blocked_domains
+            .iter()
+            .any(|p| domain_matches(&domain, p))
+        {
+            return false;
+        }
+
+        self.policies.iter().all(|p| p.is_allowed(&domain))
+    }
+
+    /// # Summary
+    ///
+    /// Generate a token that confirms a change of email address, along with the hash of the token and the time at
+    /// which it expires. Only the hash is stored, so that the token cannot be read from the database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (token, token_hash, expires_at) = email_service.generate_change_token();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `(String, String, BsonDateTime)` - The token, the hash of the token and the time at which it expires.
+    pub fn generate_change_token(&self) -> (String, String, BsonDateTime) {
+        let expires_at: DateTime<Utc> = Utc::now() + Duration::hours(EMAIL_CHANGE_TOKEN_TTL_HOURS);
+        let token = Uuid::new_v4().simple().to_string();
+        let token_hash = hash_change_token(&token);
+        (token, token_hash, BsonDateTime::from_chrono(expires_at))
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a token confirms the pending email address of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User that has a pending email address.
+    /// * `token` - The token to check.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if email_service.is_change_token_valid(&user, "token") {
+    ///     // Apply the pending email address
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the User has a pending email address and the token matches and has not expired.
+    pub fn is_change_token_valid(&self, user: &User, token: &str) -> bool {
+        let (expected, expires_at) = match (&user.email_change_token, user.email_change_expires_at)
+        {
+            (Some(t), Some(e)) if user.pending_email.is_some() => (t, e),
+            _ => return false,
+        };
+
+        let token = hash_change_token(token);
+        if expires_at.to_chrono() <= Utc::now() || expected.len() != token.len() {
+            return false;
+        }
+
+        // Compare every byte, so that the time it takes does not reveal how much of the hash is correct
+        expected
+            .bytes()
+            .zip(token.bytes())
+            .fold(0_u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+    }
+}
+
+/// # Summary
+///
+/// Hash a token that confirms a change of email address.
+///
+/// # Arguments
+///
+/// * `token` - The token to hash.
+///
+/// # Returns
+///
+/// * `String` - The URL-safe Base64 encoded SHA-256 hash of the token.
+fn hash_change_token(token: &str) -> String {
+    URL_SAFE_NO_PAD.encode(digest(&SHA256, token.as_bytes()))
+}
+
+/// # Summary
+///
+/// Trim and lowercase a list of domain patterns and remove the empty ones.
+///
+/// # Arguments
+///
+/// * `domains` - The domain patterns to normalize.
+///
+/// # Returns
+///
+/// * `Vec<String>` - The normalized domain patterns.
+fn normalize_domains(domains: Vec<String>) -> Vec<String> {
+    domains
+        .into_iter()
+        .map(|d| d.trim().to_lowercase())
+        .filter(|d| !d.is_empty())
+        .collect()
+}
+
+/// # Summary
+///
+/// Check whether a domain matches a domain pattern. A pattern that starts with `*.` matches all subdomains of the
+/// domain that follows it, but not the domain itself.
+///
+/// # Arguments
+///
+/// * `domain` - The lowercase domain to check.
+/// * `pattern` - The lowercase domain pattern.
+///
+/// # Returns
+///
+/// * `bool` - True if the domain matches the pattern.
+fn domain_matches(domain: &str, pattern: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(parent) => domain
+            .strip_suffix(parent)
+            .map(|sub| sub.len() > 1 && sub.ends_with('.'))
+            .unwrap_or(false),
+        None => domain == pattern,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email_service(allowed_domains: &[&str], blocked_domains: &[&str]) -> EmailService {
+        EmailService::new(EmailConfig::new(
+            allowed_domains.iter().map(|d| String::from(*d)).collect(),
+            blocked_domains.iter().map(|d| String::from(*d)).collect(),
+            false,
+            None,
+            false,
+        ))
+    }
+
+    #[test]
+    fn domain_matches_only_matches_subdomains_of_a_wildcard() {
+        assert!(domain_matches("mail.a.com", "*.a.com"));
+        assert!(domain_matches("x.mail.a.com", "*.a.com"));
+        assert!(!domain_matches("a.com", "*.a.com"));
+        assert!(!domain_matches("xa.com", "*.a.com"));
+        assert!(!domain_matches(".a.com", "*.a.com"));
+        assert!(!domain_matches("a.com.evil.com", "*.a.com"));
+    }
+
+    #[test]
+    fn domain_matches_matches_a_domain_exactly() {
+        assert!(domain_matches("a.com", "a.com"));
+        assert!(!domain_matches("mail.a.com", "a.com"));
+        assert!(!domain_matches("xa.com", "a.com"));
+    }
+
+    #[test]
+    fn is_domain_allowed_applies_the_allowlist_and_the_blocklist() {
+        let email_service = email_service(&["*.a.com", "b.com"], &["blocked.a.com"]);
+
+        assert!(email_service.is_domain_allowed(Some("user@mail.a.com")));
+        assert!(email_service.is_domain_allowed(Some("user@B.com ")));
+        assert!(!email_service.is_domain_allowed(Some("user@a.com")));
+        assert!(!email_service.is_domain_allowed(Some("user@xa.com")));
+        assert!(!email_service.is_domain_allowed(Some("user@blocked.a.com")));
+        assert!(!email_service.is_domain_allowed(Some("user")));
+        assert!(!email_service.is_domain_allowed(None));
+    }
+
+    #[test]
+    fn is_domain_allowed_allows_all_domains_without_an_allowlist() {
+        let email_service = email_service(&[], &["*.a.com"]);
+
+        assert!(email_service.is_domain_allowed(Some("user@a.com")));
+        assert!(!email_service.is_domain_allowed(Some("user@mail.a.com")));
+        assert!(email_service.is_domain_allowed(None));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod event_publisher;
+pub mod event_service;
+pub mod log_event_publisher;
@@ -0,0 +1,57 @@
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Copy)]
+pub enum PasswordChangeMethod {
+    #[serde(rename = "self")]
+    SelfService,
+    #[serde(rename = "admin")]
+    Admin,
+    #[serde(rename = "reset")]
+    Reset,
+}
+
+#[derive(Serialize)]
+pub struct PasswordChanged {
+    #[serde(rename = "userId")]
+    pub user_id: String,
+    pub email: Option<String>,
+    #[serde(rename = "changedBy")]
+    pub changed_by: String,
+    pub method: PasswordChangeMethod,
+    pub ip: Option<String>,
+    pub timestamp: String,
+}
+
+#[derive(Serialize)]
+pub struct EmailChangeRequested {
+    #[serde(rename = "userId")]
+    pub user_id: String,
+    pub email: String,
+    pub token: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: String,
+    pub timestamp: String,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type")]
+pub enum Event {
+    #[serde(rename = "passwordChanged")]
+    PasswordChanged(PasswordChanged),
+    #[serde(rename = "emailChangeRequested")]
+    EmailChangeRequested(EmailChangeRequested),
+}
+
+pub trait EventPublisher: Send + Sync {
+    /// # Summary
+    ///
+    /// Publish an Event, for example to notify the User it concerns.
+    ///
+    /// Implementations must not block the request that triggered the Event for long and must handle their own
+    /// delivery failures, as the action that triggered the Event has already been completed.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The Event to publish.
+    fn publish(&self, event: &Event);
+}
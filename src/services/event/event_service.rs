@@ -0,0 +1,110 @@
+use crate::services::event::event_publisher::{
+    EmailChangeRequested, Event, EventPublisher, PasswordChangeMethod, PasswordChanged,
+};
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::DateTime as BsonDateTime;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+#[derive(Clone)]
+pub struct EventService {
+    pub publisher: Arc<dyn EventPublisher>,
+}
+
+impl EventService {
+    /// # Summary
+    ///
+    /// Create a new EventService.
+    ///
+    /// # Arguments
+    ///
+    /// * `publisher` - The EventPublisher that delivers the Events.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let event_service = EventService::new(Arc::new(LogEventPublisher::new()));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `EventService` - The new EventService.
+    pub fn new(publisher: Arc<dyn EventPublisher>) -> EventService {
+        EventService { publisher }
+    }
+
+    /// # Summary
+    ///
+    /// Publish that the password of a User was changed, so that the User can be alerted of changes it did not make.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User whose password was changed.
+    /// * `email` - The email address of the User whose password was changed, if any.
+    /// * `changed_by` - The ID of the User that changed the password.
+    /// * `method` - How the password was changed.
+    /// * `ip` - The IP address of the client that changed the password, if known.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// event_service.password_changed(&user.id, user.email.clone(), &user.id, PasswordChangeMethod::SelfService, None);
+    /// ```
+    pub fn password_changed(
+        &self,
+        user_id: &ObjectId,
+        email: Option<String>,
+        changed_by: &ObjectId,
+        method: PasswordChangeMethod,
+        ip: Option<String>,
+    ) {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        self.publisher
+            .publish(&Event::PasswordChanged(PasswordChanged {
+                user_id: user_id.to_hex(),
+                email,
+                changed_by: changed_by.to_hex(),
+                method,
+                ip,
+                timestamp: now.to_rfc3339(),
+            }));
+    }
+
+    /// # Summary
+    ///
+    /// Publish that a User requested to change its email address, so that the confirmation token can be delivered to
+    /// the new email address.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that requested the change.
+    /// * `email` - The new email address, which has not been confirmed yet.
+    /// * `token` - The token that confirms the change.
+    /// * `expires_at` - The time at which the token expires.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// event_service.email_change_requested(&user.id, String::from("new@codedead.com"), String::from("token"), expires_at);
+    /// ```
+    pub fn email_change_requested(
+        &self,
+        user_id: &ObjectId,
+        email: String,
+        token: String,
+        expires_at: BsonDateTime,
+    ) {
+        let now: DateTime<Utc> = SystemTime::now().into();
+
+        self.publisher
+            .publish(&Event::EmailChangeRequested(EmailChangeRequested {
+                user_id: user_id.to_hex(),
+                email,
+                token,
+                expires_at: expires_at.to_chrono().to_rfc3339(),
+                timestamp: now.to_rfc3339(),
+            }));
+    }
+}
@@ -0,0 +1,59 @@
+use crate::services::event::event_publisher::{Event, EventPublisher};
+use log::{error, info};
+use serde_json::Value;
+
+pub const EVENT_LOG_TARGET: &str = "auth_rs::events";
+/// The fields of an Event that hold secrets and are replaced before the Event is written to the log.
+const REDACTED_FIELDS: [&str; 1] = ["token"];
+
+#[derive(Clone)]
+pub struct LogEventPublisher {}
+
+impl LogEventPublisher {
+    /// # Summary
+    ///
+    /// Create a new LogEventPublisher.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let event_publisher = LogEventPublisher::new();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `LogEventPublisher` - The new LogEventPublisher.
+    pub fn new() -> LogEventPublisher {
+        LogEventPublisher {}
+    }
+}
+
+impl EventPublisher for LogEventPublisher {
+    /// # Summary
+    ///
+    /// Publish an Event by writing it as JSON to the log, so that it can be picked up by a log shipper. Secrets, such
+    /// as the token of an email change, are redacted.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The Event to publish.
+    fn publish(&self, event: &Event) {
+        let mut value = match serde_json::to_value(event) {
+            Ok(v) => v,
+            Err(e) => {
+                error!("Failed to serialize event: {}", e);
+                return;
+            }
+        };
+
+        if let Value::Object(fields) = &mut value {
+            for field in REDACTED_FIELDS {
+                if let Some(v) = fields.get_mut(field) {
+                    *v = Value::from("[REDACTED]");
+                }
+            }
+        }
+
+        info!(target: EVENT_LOG_TARGET, "{}", value);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod idempotency_service;
+pub mod idempotency_store;
+pub mod memory_idempotency_store;
@@ -0,0 +1,180 @@
+use crate::services::idempotency::idempotency_store::{
+    IdempotencyState, IdempotencyStore, StoredResponse,
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use log::info;
+use ring::digest::{Context, SHA256};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+const MAX_KEY_LENGTH: usize = 255;
+/// The amount of time a key is claimed while its request is processed. The claim is released when the request ends, so
+/// this only matters if the process stops halfway through a request.
+const IN_PROGRESS_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+pub struct IdempotencyService {
+    pub store: Arc<dyn IdempotencyStore>,
+    pub ttl: Duration,
+}
+
+impl IdempotencyService {
+    /// # Summary
+    ///
+    /// Create a new IdempotencyService.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - The IdempotencyStore that retains the idempotency keys and their responses.
+    /// * `ttl` - The amount of seconds an idempotency key is retained.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let idempotency_service = IdempotencyService::new(Arc::new(MemoryIdempotencyStore::new()), 86400);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `IdempotencyService` - The new IdempotencyService.
+    pub fn new(store: Arc<dyn IdempotencyStore>, ttl: u64) -> IdempotencyService {
+        IdempotencyService {
+            store,
+            ttl: Duration::from_secs(ttl),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether an idempotency key is acceptable.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The idempotency key.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the key is not empty and does not exceed the maximum length.
+    pub fn is_valid_key(key: &str) -> bool {
+        !key.is_empty() && key.len() <= MAX_KEY_LENGTH
+    }
+
+    /// # Summary
+    ///
+    /// Scope an idempotency key to an endpoint and to the fields that identify the caller, so that the same key sent by
+    /// different callers, or to different endpoints, does not replay the response of another request.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - The name of the endpoint.
+    /// * `key` - The idempotency key that was sent by the client.
+    /// * `fingerprint` - The fields of the request that identify the caller. Secrets must never be part of it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let key = IdempotencyService::scoped_key("register", "5d6b0a3e", &["example", "example@codedead.com"]);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The scoped key.
+    pub fn scoped_key(scope: &str, key: &str, fingerprint: &[&str]) -> String {
+        let mut context = Context::new(&SHA256);
+        for part in fingerprint {
+            // Every part is prefixed with its length, so that moving characters between parts changes the hash
+            context.update(&(part.len() as u64).to_be_bytes());
+            context.update(part.as_bytes());
+        }
+
+        format!(
+            "{}:{}:{}",
+            scope,
+            key,
+            URL_SAFE_NO_PAD.encode(context.finish())
+        )
+    }
+
+    /// # Summary
+    ///
+    /// Claim an idempotency key before processing a request. A new claim is returned as an IdempotencyGuard, which
+    /// releases the key if it is dropped before the response is stored, for example because the client disconnected.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The idempotency key.
+    ///
+    /// # Returns
+    ///
+    /// * `(IdempotencyState, Option<IdempotencyGuard>)` - The state of the idempotency key, and the IdempotencyGuard if
+    ///   the key was claimed by this call.
+    pub fn begin(&self, key: &str) -> (IdempotencyState, Option<IdempotencyGuard>) {
+        let state = self.store.begin(key, IN_PROGRESS_TTL);
+        if !matches!(state, IdempotencyState::New) {
+            info!("Replaying request with idempotency key: {}", key);
+            return (state, None);
+        }
+
+        let guard = IdempotencyGuard {
+            service: self.clone(),
+            key: String::from(key),
+            completed: false,
+        };
+        (state, Some(guard))
+    }
+
+    /// # Summary
+    ///
+    /// Store the response of a processed request so that it is returned when the key is replayed.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The idempotency key.
+    /// * `response` - The response of the request.
+    pub fn complete(&self, key: &str, response: StoredResponse) {
+        self.store.complete(key, response, self.ttl);
+    }
+
+    /// # Summary
+    ///
+    /// Release an idempotency key after a request failed unexpectedly, so that it can be retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The idempotency key.
+    pub fn release(&self, key: &str) {
+        self.store.release(key);
+    }
+}
+
+/// Holds the claim of an idempotency key while its request is processed. The key is released when the guard is dropped
+/// without completing it, so that the client can retry the request.
+pub struct IdempotencyGuard {
+    service: IdempotencyService,
+    key: String,
+    completed: bool,
+}
+
+impl IdempotencyGuard {
+    /// # Summary
+    ///
+    /// Store the response of the request, so that it is returned when the key is replayed.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The response of the request.
+    pub fn complete(mut self, response: StoredResponse) {
+        self.service.complete(&self.key, response);
+        self.completed = true;
+    }
+}
+
+impl Drop for IdempotencyGuard {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.service.release(&self.key);
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub body: Option<String>,
+}
+
+impl StoredResponse {
+    /// # Summary
+    ///
+    /// Create a new StoredResponse without a body.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The HTTP status code of the response.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let stored_response = StoredResponse::empty(200);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `StoredResponse` - The new StoredResponse.
+    pub fn empty(status: u16) -> StoredResponse {
+        StoredResponse { status, body: None }
+    }
+
+    /// # Summary
+    ///
+    /// Create a new StoredResponse with a JSON body.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The HTTP status code of the response.
+    /// * `body` - The value that is serialized as the body of the response.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let stored_response = StoredResponse::json(400, &BadRequest::new("Bad Request"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `StoredResponse` - The new StoredResponse.
+    pub fn json<T: serde::Serialize>(status: u16, body: &T) -> StoredResponse {
+        StoredResponse {
+            status,
+            body: serde_json::to_string(body).ok(),
+        }
+    }
+}
+
+pub enum IdempotencyState {
+    New,
+    InProgress,
+    Completed(StoredResponse),
+}
+
+pub trait IdempotencyStore: Send + Sync {
+    /// # Summary
+    ///
+    /// Claim an idempotency key, unless it was already claimed and has not expired yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The idempotency key.
+    /// * `ttl` - The amount of time the key is claimed while the request is processed.
+    ///
+    /// # Returns
+    ///
+    /// * `IdempotencyState` - `New` if the key was claimed by this call, otherwise the state of the earlier request.
+    fn begin(&self, key: &str, ttl: Duration) -> IdempotencyState;
+
+    /// # Summary
+    ///
+    /// Store the response of a request that claimed an idempotency key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The idempotency key.
+    /// * `response` - The response that is returned when the key is replayed.
+    /// * `ttl` - The amount of time the response is retained.
+    fn complete(&self, key: &str, response: StoredResponse, ttl: Duration);
+
+    /// # Summary
+    ///
+    /// Release an idempotency key so that the request can be retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The idempotency key.
+    fn release(&self, key: &str);
+}
@@ -0,0 +1,131 @@
+use crate::services::idempotency::idempotency_store::{
+    IdempotencyState, IdempotencyStore, StoredResponse,
+};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct Entry {
+    response: Option<StoredResponse>,
+    expires_at: Instant,
+}
+
+pub struct MemoryIdempotencyStore {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl MemoryIdempotencyStore {
+    /// # Summary
+    ///
+    /// Create a new MemoryIdempotencyStore.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let store = MemoryIdempotencyStore::new();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MemoryIdempotencyStore` - The new MemoryIdempotencyStore.
+    pub fn new() -> MemoryIdempotencyStore {
+        MemoryIdempotencyStore {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for MemoryIdempotencyStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdempotencyStore for MemoryIdempotencyStore {
+    fn begin(&self, key: &str, ttl: Duration) -> IdempotencyState {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+        entries.retain(|_, e| e.expires_at > now);
+
+        if let Some(entry) = entries.get(key) {
+            return match &entry.response {
+                Some(r) => IdempotencyState::Completed(r.clone()),
+                None => IdempotencyState::InProgress,
+            };
+        }
+
+        entries.insert(
+            String::from(key),
+            Entry {
+                response: None,
+                expires_at: now + ttl,
+            },
+        );
+        IdempotencyState::New
+    }
+
+    fn complete(&self, key: &str, response: StoredResponse, ttl: Duration) {
+        self.entries.lock().unwrap().insert(
+            String::from(key),
+            Entry {
+                response: Some(response),
+                expires_at: Instant::now() + ttl,
+            },
+        );
+    }
+
+    fn release(&self, key: &str) {
+        self.entries.lock().unwrap().remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn begin_reports_a_key_in_progress_until_it_is_completed() {
+        let store = MemoryIdempotencyStore::new();
+
+        assert!(matches!(store.begin("key", TTL), IdempotencyState::New));
+        assert!(matches!(
+            store.begin("key", TTL),
+            IdempotencyState::InProgress
+        ));
+        assert!(matches!(store.begin("other", TTL), IdempotencyState::New));
+
+        store.complete("key", StoredResponse::empty(204), TTL);
+
+        match store.begin("key", TTL) {
+            IdempotencyState::Completed(r) => assert_eq!(r.status, 204),
+            _ => panic!("expected the completed response"),
+        }
+    }
+
+    #[test]
+    fn release_allows_the_key_to_be_retried() {
+        let store = MemoryIdempotencyStore::new();
+
+        assert!(matches!(store.begin("key", TTL), IdempotencyState::New));
+        store.release("key");
+
+        assert!(matches!(store.begin("key", TTL), IdempotencyState::New));
+    }
+
+    #[test]
+    fn begin_forgets_expired_keys() {
+        let store = MemoryIdempotencyStore::new();
+
+        assert!(matches!(
+            store.begin("key", Duration::ZERO),
+            IdempotencyState::New
+        ));
+        assert!(matches!(store.begin("key", TTL), IdempotencyState::New));
+
+        store.complete("key", StoredResponse::empty(204), Duration::ZERO);
+
+        assert!(matches!(store.begin("key", TTL), IdempotencyState::New));
+    }
+}
@@ -0,0 +1 @@
+pub mod invite_service;
@@ -0,0 +1,318 @@
+use crate::repository::invite::invite_model::Invite;
+use crate::repository::invite::invite_repository::{Error, InviteRepository};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{Duration, Utc};
+use log::{error, info};
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+use ring::digest::{digest, SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use tokio::runtime::Handle;
+
+const TOKEN_LENGTH: usize = 32;
+
+#[derive(Clone)]
+pub struct InviteService {
+    pub invite_repository: InviteRepository,
+    pub ttl: u64,
+    random: SystemRandom,
+}
+
+impl InviteService {
+    /// # Summary
+    ///
+    /// Create a new InviteService.
+    ///
+    /// # Arguments
+    ///
+    /// * `invite_repository` - The InviteRepository.
+    /// * `ttl` - The amount of seconds an Invite can be used after it was created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invite_repository = InviteRepository::new(String::from("invites")).unwrap();
+    /// let invite_service = InviteService::new(invite_repository, 604800);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `InviteService` - The new InviteService.
+    pub fn new(invite_repository: InviteRepository, ttl: u64) -> InviteService {
+        InviteService {
+            invite_repository,
+            ttl,
+            random: SystemRandom::new(),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Hash an invite token, so that a leaked database does not contain usable invites.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The invite token.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The hash of the invite token.
+    fn hash(token: &str) -> String {
+        URL_SAFE_NO_PAD.encode(digest(&SHA256, token.as_bytes()))
+    }
+
+    /// # Summary
+    ///
+    /// Create a new Invite with a random single-use token.
+    ///
+    /// # Arguments
+    ///
+    /// * `email` - The email address that the Invite is bound to, if any.
+    /// * `roles` - The IDs of the Roles that are assigned to the User that registers with the Invite, if any.
+    /// * `created_by` - The ID of the User that creates the Invite.
+    /// * `db` - The Database to create the Invite in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (token, invite) = invite_service.create(None, None, user.id.to_hex(), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(String, Invite), Error>` - The invite token and the Invite. The token is not stored, so it can only be
+    ///   handed out now.
+    pub async fn create(
+        &self,
+        email: Option<String>,
+        roles: Option<Vec<ObjectId>>,
+        created_by: String,
+        db: &Database,
+    ) -> Result<(String, Invite), Error> {
+        let mut bytes = [0u8; TOKEN_LENGTH];
+        if let Err(e) = self.random.fill(&mut bytes) {
+            return Err(Error::TokenGeneration(e.to_string()));
+        }
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+
+        let ttl = i64::try_from(self.ttl).unwrap_or(i64::MAX);
+        let expires_at = Utc::now() + Duration::seconds(ttl);
+        let invite = Invite::new(
+            InviteService::hash(&token),
+            email,
+            roles,
+            created_by,
+            expires_at,
+        );
+
+        info!(
+            "Creating Invite {} by User {}",
+            invite.id, invite.created_by
+        );
+        self.invite_repository.create(invite.clone(), db).await?;
+
+        Ok((token, invite))
+    }
+
+    /// # Summary
+    ///
+    /// Find a page of Invites, newest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The limit of Invites to find.
+    /// * `page` - The page of Invites to find.
+    /// * `db` - The Database to find the Invites in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invites = invite_service.find_all(Some(100), Some(1), &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Invite>, Error>` - The result of the operation.
+    pub async fn find_all(
+        &self,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<Invite>, Error> {
+        self.invite_repository.find_all(limit, page, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Count all Invites.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to count the Invites in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let total = invite_service.count(&db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The result of the operation.
+    pub async fn count(&self, db: &Database) -> Result<u64, Error> {
+        self.invite_repository.count(db).await
+    }
+
+    /// # Summary
+    ///
+    /// Use the Invite of an invite token, if it is unused and has not expired yet. The used Invite is returned as an
+    /// InviteGuard, which releases the Invite if it is dropped before the registration completes, for example because
+    /// the client disconnected.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The invite token.
+    /// * `db` - The Database to find the Invite in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invite_guard = invite_service.consume("token", &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<InviteGuard>, Error>` - The InviteGuard of the used Invite, or None if the token does not refer
+    ///   to a usable Invite.
+    pub async fn consume(&self, token: &str, db: &Database) -> Result<Option<InviteGuard>, Error> {
+        let invite = self
+            .invite_repository
+            .consume(&InviteService::hash(token), db)
+            .await?;
+
+        Ok(invite.map(|invite| InviteGuard {
+            service: self.clone(),
+            db: db.clone(),
+            invite,
+            completed: false,
+        }))
+    }
+
+    /// # Summary
+    ///
+    /// Record the User that registered with a used Invite.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Invite.
+    /// * `user_id` - The ID of the User that registered with the Invite.
+    /// * `db` - The Database to update the Invite in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// invite_service.set_used_by(&invite.id, &user_id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn set_used_by(
+        &self,
+        id: &ObjectId,
+        user_id: &str,
+        db: &Database,
+    ) -> Result<(), Error> {
+        self.invite_repository.set_used_by(id, user_id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Make a used Invite usable again, because the registration that used it failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Invite.
+    /// * `db` - The Database to update the Invite in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// invite_service.release(&invite.id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn release(&self, id: &ObjectId, db: &Database) -> Result<(), Error> {
+        info!("Releasing Invite {}", id);
+        self.invite_repository.release(id, db).await
+    }
+}
+
+/// Holds a used Invite while the registration that used it is processed. The Invite is released when the guard is
+/// dropped without completing or releasing it, so that the invite token can be used again.
+pub struct InviteGuard {
+    service: InviteService,
+    db: Database,
+    pub invite: Invite,
+    completed: bool,
+}
+
+impl InviteGuard {
+    /// # Summary
+    ///
+    /// Keep the Invite used and record the User that registered with it. A failure to record the User is only logged,
+    /// because the User was created already.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User that registered with the Invite.
+    pub async fn complete(mut self, user_id: &str) {
+        self.completed = true;
+        if let Err(e) = self
+            .service
+            .set_used_by(&self.invite.id, user_id, &self.db)
+            .await
+        {
+            error!(
+                "Failed to record the user of invite {}: {}",
+                self.invite.id, e
+            );
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Make the Invite usable again, because the registration that used it failed. A failure is only logged, because
+    /// the registration has failed already.
+    pub async fn release(mut self) {
+        if let Err(e) = self.service.release(&self.invite.id, &self.db).await {
+            error!("Failed to release invite {}: {}", self.invite.id, e);
+        }
+        self.completed = true;
+    }
+}
+
+impl Drop for InviteGuard {
+    fn drop(&mut self) {
+        if self.completed {
+            return;
+        }
+
+        // The release cannot be awaited here, so it is spawned on the runtime that was processing the registration
+        let service = self.service.clone();
+        let db = self.db.clone();
+        let id = self.invite.id;
+        if let Ok(handle) = Handle::try_current() {
+            handle.spawn(async move {
+                if let Err(e) = service.release(&id, &db).await {
+                    error!("Failed to release invite {}: {}", id, e);
+                }
+            });
+        } else {
+            error!("Failed to release invite {}: no runtime is available", id);
+        }
+    }
+}
@@ -1 +1,2 @@
+pub mod jwk;
 pub mod jwt_service;
@@ -0,0 +1,78 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use simple_asn1::ASN1Block;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
+pub struct Jwk {
+    pub kty: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub alg: String,
+    pub kid: String,
+    pub n: String,
+    pub e: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
+pub struct JwkSet {
+    pub keys: Vec<Jwk>,
+}
+
+impl Jwk {
+    /// # Summary
+    ///
+    /// Create a new Jwk from a PEM encoded RSA public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `kid` - The key ID.
+    /// * `public_key` - The PEM encoded public key, either in the SubjectPublicKeyInfo or in the PKCS#1 format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let jwk = Jwk::from_rsa_public_key("key-1", &public_pem);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Jwk, String>` - The Jwk or an error message.
+    pub fn from_rsa_public_key(kid: &str, public_key: &str) -> Result<Jwk, String> {
+        let pem = match pem::parse(public_key) {
+            Ok(p) => p,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        let mut blocks = match simple_asn1::from_der(&pem.contents) {
+            Ok(b) => b,
+            Err(e) => return Err(e.to_string()),
+        };
+
+        // A SubjectPublicKeyInfo wraps the PKCS#1 RSAPublicKey in a bit string
+        if let Some(ASN1Block::Sequence(_, items)) = blocks.first() {
+            if let Some(ASN1Block::BitString(_, _, bytes)) = items.get(1) {
+                blocks = match simple_asn1::from_der(bytes) {
+                    Ok(b) => b,
+                    Err(e) => return Err(e.to_string()),
+                };
+            }
+        }
+
+        match blocks.first() {
+            Some(ASN1Block::Sequence(_, items)) => match (items.first(), items.get(1)) {
+                (Some(ASN1Block::Integer(_, n)), Some(ASN1Block::Integer(_, e))) => Ok(Jwk {
+                    kty: String::from("RSA"),
+                    key_use: String::from("sig"),
+                    alg: String::from("RS256"),
+                    kid: kid.to_string(),
+                    n: URL_SAFE_NO_PAD.encode(n.to_bytes_be().1),
+                    e: URL_SAFE_NO_PAD.encode(e.to_bytes_be().1),
+                }),
+                _ => Err(String::from("Invalid RSA public key")),
+            },
+            _ => Err(String::from("Invalid RSA public key")),
+        }
+    }
+}
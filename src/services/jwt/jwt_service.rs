@@ -1,122 +1,122 @@
 use crate::configuration::jwt_config::JwtConfig;
-use jsonwebtoken::{encode, DecodingKey, EncodingKey, Header};
+use crate::services::jwt::jwk::{Jwk, JwkSet};
+use crate::services::token::token_service::{Claims, Error, TokenService};
+use futures::future::{ready, BoxFuture};
+use jsonwebtoken::{
+    decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
 use log::error;
-use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Formatter};
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Claims {
-    exp: usize,
-    iat: usize,
-    sub: String,
-}
-
-impl Claims {
-    /// # Summary
-    ///
-    /// Create a new Claims.
-    ///
-    /// # Arguments
-    ///
-    /// * `sub` - The subject of the Claims.
-    /// * `exp` - The expiration time of the Claims.
-    /// * `iat` - The issued at time of the Claims.
-    pub fn new(sub: String, exp: usize, iat: usize) -> Claims {
-        Claims { sub, exp, iat }
-    }
-}
 
-pub enum Error {
-    InvalidToken(String),
+#[derive(Clone)]
+pub struct JwtService {
+    pub jwt_config: JwtConfig,
+    encoding_key: EncodingKey,
+    decoding_keys: Vec<(Option<String>, DecodingKey)>,
+    jwk_set: JwkSet,
 }
 
-impl Display for Error {
+impl JwtService {
     /// # Summary
     ///
-    /// Display the Error.
+    /// Create a new JwtService.
     ///
     /// # Arguments
     ///
-    /// * `f` - The Formatter.
+    /// * `jwt_config` - The configuration for the JwtService.
     ///
     /// # Example
     ///
     /// ```
-    /// let error = Error::InvalidToken(String::from("message"));
-    /// println!("{}", error);
+    /// let jwt_service = JwtService::new(jwt_config);
     /// ```
     ///
     /// # Returns
     ///
-    /// * `std::fmt::Result` - The result of the operation.
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Error::InvalidToken(message) => write!(f, "Invalid token: {}", message),
+    /// * `JwtService` - The new JwtService.
+    pub fn new(jwt_config: JwtConfig) -> JwtService {
+        let mut decoding_keys: Vec<(Option<String>, DecodingKey)> = vec![];
+        let mut jwks: Vec<Jwk> = vec![];
+
+        let encoding_key = match jwt_config.algorithm {
+            Algorithm::RS256 => {
+                for k in &jwt_config.rsa_keys {
+                    let decoding_key = DecodingKey::from_rsa_pem(k.public_key.as_bytes())
+                        .unwrap_or_else(|e| panic!("Invalid RSA public key {}: {}", k.kid, e));
+                    decoding_keys.push((Some(k.kid.clone()), decoding_key));
+
+                    match Jwk::from_rsa_public_key(&k.kid, &k.public_key) {
+                        Ok(j) => jwks.push(j),
+                        Err(e) => panic!("Invalid RSA public key {}: {}", k.kid, e),
+                    }
+                }
+
+                let private_key = match jwt_config.rsa_keys.first() {
+                    Some(k) => match &k.private_key {
+                        Some(p) => p,
+                        None => panic!("No RSA private key specified for {}", k.kid),
+                    },
+                    None => panic!("No RSA keys specified"),
+                };
+
+                EncodingKey::from_rsa_pem(private_key.as_bytes())
+                    .unwrap_or_else(|e| panic!("Invalid RSA private key: {}", e))
+            }
+            _ => {
+                decoding_keys.push((
+                    None,
+                    DecodingKey::from_secret(jwt_config.jwt_secret.as_bytes()),
+                ));
+
+                for s in &jwt_config.previous_jwt_secrets {
+                    decoding_keys.push((None, DecodingKey::from_secret(s.as_bytes())));
+                }
+
+                EncodingKey::from_secret(jwt_config.jwt_secret.as_bytes())
+            }
+        };
+
+        JwtService {
+            jwt_config,
+            encoding_key,
+            decoding_keys,
+            jwk_set: JwkSet { keys: jwks },
         }
     }
-}
 
-#[derive(Clone)]
-pub struct JwtService {
-    pub jwt_config: JwtConfig,
-}
-
-impl JwtService {
     /// # Summary
     ///
-    /// Create a new JwtService.
-    ///
-    /// # Arguments
-    ///
-    /// * `jwt_config` - The configuration for the JwtService.
+    /// Get the public keys that can be used to verify JWT tokens.
     ///
     /// # Example
     ///
     /// ```
-    /// let jwt_service = JwtService::new(jwt_config);
+    /// let jwk_set = jwt_service.jwk_set();
     /// ```
     ///
     /// # Returns
     ///
-    /// * `JwtService` - The new JwtService.
-    pub fn new(jwt_config: JwtConfig) -> JwtService {
-        JwtService { jwt_config }
+    /// * `&JwkSet` - The public keys. The set is empty if a symmetric algorithm is used.
+    pub fn jwk_set(&self) -> &JwkSet {
+        &self.jwk_set
     }
 
     /// # Summary
     ///
-    /// Generate a JWT token.
+    /// Sign Claims using the current key.
     ///
     /// # Arguments
     ///
-    /// * `subject` - The subject of the JWT token.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let token = jwt_service.generate_jwt_token("subject");
-    /// ```
+    /// * `claims` - The Claims to sign.
     ///
     /// # Returns
     ///
-    /// * `Option<String>` - The JWT token.
-    pub fn generate_jwt_token(&self, subject: &str) -> Option<String> {
-        let now = chrono::Utc::now();
-        let exp = now + chrono::Duration::seconds(self.jwt_config.jwt_expiration as i64);
-        let iat = now;
-
-        let claims = Claims::new(
-            String::from(subject),
-            exp.timestamp() as usize,
-            iat.timestamp() as usize,
-        );
-
-        match encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.jwt_config.jwt_secret.as_bytes()),
-        ) {
-            Ok(t) => Some(t),
+    /// * `Option<(String, Claims)>` - The JWT token and the Claims that it contains.
+    fn encode_claims(&self, claims: Claims) -> Option<(String, Claims)> {
+        let mut header = Header::new(self.jwt_config.algorithm);
+        header.kid = self.decoding_keys.first().and_then(|(kid, _)| kid.clone());
+
+        match encode(&header, &claims, &self.encoding_key) {
+            Ok(t) => Some((t, claims)),
             Err(e) => {
                 error!("Error generating JWT token: {}", e.to_string());
                 None
@@ -126,34 +126,114 @@ impl JwtService {
 
     /// # Summary
     ///
-    /// Verify a JWT token.
+    /// Verify the signature and registered claims of a JWT token and decode its Claims.
     ///
     /// # Arguments
     ///
-    /// * `token` - The JWT token to verify.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// let sub = jwt_service.verify_jwt_token("token");
-    /// ```
+    /// * `token` - The JWT token to decode.
     ///
     /// # Returns
     ///
-    /// * `Result<String, Error>` - The result of the operation.
-    pub fn verify_jwt_token(&self, token: &str) -> Result<String, Error> {
-        let token_data = jsonwebtoken::decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(self.jwt_config.jwt_secret.as_bytes()),
-            &jsonwebtoken::Validation::default(),
-        );
-
-        match token_data {
-            Ok(t) => Ok(t.claims.sub),
+    /// * `Result<Claims, Error>` - The result of the operation.
+    fn decode_claims(&self, token: &str) -> Result<Claims, Error> {
+        let header = match decode_header(token) {
+            Ok(h) => h,
             Err(e) => {
-                error!("Error verifying JWT token: {}", e.to_string());
-                Err(Error::InvalidToken(e.to_string()))
+                error!("Error verifying JWT token: {}", e);
+                return Err(Error::InvalidToken(e.to_string()));
             }
+        };
+
+        // Tokens without a key ID are tried against every key without a key ID, starting with the current one
+        let decoding_keys: Vec<&DecodingKey> = match &header.kid {
+            Some(kid) => self
+                .decoding_keys
+                .iter()
+                .filter(|(k, _)| k.as_deref() == Some(kid.as_str()))
+                .map(|(_, k)| k)
+                .collect(),
+            None => self
+                .decoding_keys
+                .iter()
+                .filter(|(k, _)| k.is_none())
+                .map(|(_, k)| k)
+                .collect(),
+        };
+
+        if decoding_keys.is_empty() {
+            error!("Error verifying JWT token: unknown key ID");
+            return Err(Error::InvalidToken(String::from("Unknown key ID")));
         }
+
+        let mut validation = Validation::new(self.jwt_config.algorithm);
+        validation.leeway = self.jwt_config.leeway;
+        if let Some(iss) = &self.jwt_config.issuer {
+            validation.set_issuer(&[iss]);
+        }
+        if let Some(aud) = &self.jwt_config.audience {
+            validation.set_audience(&[aud]);
+        }
+        let mut last_error = None;
+        for decoding_key in decoding_keys {
+            match jsonwebtoken::decode::<Claims>(token, decoding_key, &validation) {
+                Ok(t) => return Ok(t.claims),
+                Err(e) => {
+                    // Only a signature mismatch can be resolved by trying another key
+                    let is_signature_error =
+                        matches!(e.kind(), jsonwebtoken::errors::ErrorKind::InvalidSignature);
+                    last_error = Some(e);
+                    if !is_signature_error {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let e = last_error.unwrap();
+        error!("Error verifying JWT token: {}", e);
+        Err(Error::InvalidToken(e.to_string()))
+    }
+}
+
+impl TokenService for JwtService {
+    /// # Summary
+    ///
+    /// Get the configuration that determines the lifetime and the registered claims of the issued JWT tokens.
+    ///
+    /// # Returns
+    ///
+    /// * `&JwtConfig` - The JwtConfig.
+    fn config(&self) -> &JwtConfig {
+        &self.jwt_config
+    }
+
+    /// # Summary
+    ///
+    /// Sign Claims into a JWT token.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The Claims to sign.
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Option<(String, Claims)>>` - The JWT token and the Claims that it contains.
+    fn encode(&self, claims: Claims) -> BoxFuture<'_, Option<(String, Claims)>> {
+        Box::pin(ready(self.encode_claims(claims)))
+    }
+
+    /// # Summary
+    ///
+    /// Verify a JWT token and decode its Claims.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The JWT token to decode.
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<Claims, Error>>` - The result of the operation.
+    fn decode<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Result<Claims, Error>> {
+        Box::pin(ready(self.decode_claims(token)))
     }
 }
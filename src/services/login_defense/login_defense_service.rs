@@ -0,0 +1,144 @@
+use crate::configuration::login_defense_config::{LoginDefense, LoginDefenseConfig};
+use crate::repository::user::user_model::User;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use mongodb::bson::DateTime as BsonDateTime;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct LoginDefenseService {
+    pub login_defense_config: LoginDefenseConfig,
+}
+
+impl LoginDefenseService {
+    /// # Summary
+    ///
+    /// Create a new LoginDefenseService.
+    ///
+    /// # Arguments
+    ///
+    /// * `login_defense_config` - The LoginDefenseConfig that determines how brute-force attacks are slowed down.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let login_defense_config = LoginDefenseConfig::new(LoginDefense::Lockout, 5, 900, 500, 10000, false);
+    /// let login_defense_service = LoginDefenseService::new(login_defense_config);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `LoginDefenseService` - The new LoginDefenseService.
+    pub fn new(login_defense_config: LoginDefenseConfig) -> LoginDefenseService {
+        LoginDefenseService {
+            login_defense_config,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether failed logins need to be counted.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if a login defense is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.login_defense_config.defense != LoginDefense::None
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a User is currently locked out.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User that is trying to log in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if login_defense_service.is_locked(&user) {
+    ///     // Reject the login
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the lockout strategy is used and the lock of the User has not expired yet.
+    pub fn is_locked(&self, user: &User) -> bool {
+        if self.login_defense_config.defense != LoginDefense::Lockout {
+            return false;
+        }
+
+        match user.locked_until {
+            Some(l) => l.to_chrono() > Utc::now(),
+            None => false,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Get the delay that is imposed before a login attempt of a User is verified.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User that is trying to log in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if let Some(d) = login_defense_service.delay(&user) {
+    ///     actix_web::rt::time::sleep(d).await;
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Duration>` - The delay, which doubles with every consecutive failed login up to the maximum delay,
+    ///   or None if the throttle strategy is not used or the previous login succeeded.
+    pub fn delay(&self, user: &User) -> Option<Duration> {
+        if self.login_defense_config.defense != LoginDefense::Throttle
+            || user.failed_login_attempts == 0
+        {
+            return None;
+        }
+
+        // Cap the exponent, as the delay reaches any sensible maximum long before that
+        let exponent = (user.failed_login_attempts - 1).min(32);
+        let delay = self
+            .login_defense_config
+            .throttle_delay
+            .saturating_mul(1_u64 << exponent)
+            .min(self.login_defense_config.throttle_max_delay);
+
+        Some(Duration::from_millis(delay))
+    }
+
+    /// # Summary
+    ///
+    /// Get the time until which a User is locked after a failed login.
+    ///
+    /// # Arguments
+    ///
+    /// * `failed_login_attempts` - The number of consecutive failed logins, including the last one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let locked_until = login_defense_service.lock_until(5);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<BsonDateTime>` - The end of the lock, or None if the User should not be locked.
+    pub fn lock_until(&self, failed_login_attempts: u32) -> Option<BsonDateTime> {
+        if self.login_defense_config.defense != LoginDefense::Lockout
+            || failed_login_attempts < self.login_defense_config.max_attempts
+        {
+            return None;
+        }
+
+        let until: DateTime<Utc> =
+            Utc::now() + ChronoDuration::seconds(self.login_defense_config.lockout_duration as i64);
+        Some(BsonDateTime::from_chrono(until))
+    }
+}
@@ -0,0 +1,97 @@
+use crate::repository::migration::migration_model::{Migration, MIGRATIONS};
+use crate::repository::migration::migration_repository::{Error, MigrationRepository};
+use log::info;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct MigrationService {
+    pub migration_repository: MigrationRepository,
+}
+
+impl MigrationService {
+    /// # Summary
+    ///
+    /// Create a new MigrationService.
+    ///
+    /// # Arguments
+    ///
+    /// * `migration_repository` - The MigrationRepository.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let migration_service = MigrationService::new(migration_repository);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `MigrationService` - The new MigrationService.
+    pub fn new(migration_repository: MigrationRepository) -> MigrationService {
+        MigrationService {
+            migration_repository,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Apply all migrations that have not been applied yet, in order, and record every applied migration.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to migrate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let applied = migration_service.run(&db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<i32>, Error>` - The versions of the migrations that were applied.
+    pub async fn run(&self, db: &Database) -> Result<Vec<i32>, Error> {
+        let applied_versions = self.migration_repository.find_applied_versions(db).await?;
+        let mut applied: Vec<i32> = vec![];
+
+        for (version, name) in MIGRATIONS {
+            if applied_versions.contains(&version) {
+                continue;
+            }
+
+            info!("Applying migration {}: {}", version, name);
+            let migrated = self.migration_repository.apply(version, db).await?;
+            self.migration_repository
+                .record(Migration::new(version, name), db)
+                .await?;
+            info!(
+                "Applied migration {}, {} documents migrated",
+                version, migrated
+            );
+
+            applied.push(version);
+        }
+
+        Ok(applied)
+    }
+
+    /// # Summary
+    ///
+    /// Find the current schema version, which is the version of the most recent migration that was applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let schema_version = migration_service.current_version(&db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<i32>, Error>` - The current schema version, or None if no migration has been applied yet.
+    pub async fn current_version(&self, db: &Database) -> Result<Option<i32>, Error> {
+        self.migration_repository.current_version(db).await
+    }
+}
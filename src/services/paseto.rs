@@ -0,0 +1 @@
+pub mod paseto_service;
@@ -0,0 +1,366 @@
+use crate::configuration::jwt_config::JwtConfig;
+use crate::services::token::token_service::{Claims, Error, TokenService};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use futures::future::{ready, BoxFuture};
+use log::error;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde_json::Value;
+use std::sync::Arc;
+
+const HEADER: &str = "v4.public.";
+const SIGNATURE_LENGTH: usize = 64;
+const DATE_CLAIMS: [&str; 3] = ["exp", "iat", "nbf"];
+
+#[derive(Clone)]
+pub struct PasetoService {
+    pub jwt_config: JwtConfig,
+    key_pair: Arc<Ed25519KeyPair>,
+}
+
+impl PasetoService {
+    /// # Summary
+    ///
+    /// Create a new PasetoService that issues and verifies PASETO v4.public tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `jwt_config` - The configuration for the PasetoService, which must contain the Ed25519 private key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let paseto_service = PasetoService::new(jwt_config);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `PasetoService` - The new PasetoService.
+    pub fn new(jwt_config: JwtConfig) -> PasetoService {
+        let private_key = match &jwt_config.paseto_private_key {
+            Some(k) => k,
+            None => panic!("No PASETO private key specified"),
+        };
+
+        let pem =
+            pem::parse(private_key).unwrap_or_else(|e| panic!("Invalid PASETO private key: {}", e));
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&pem.contents)
+            .unwrap_or_else(|e| panic!("Invalid PASETO private key: {}", e));
+
+        PasetoService {
+            jwt_config,
+            key_pair: Arc::new(key_pair),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create the pre-authentication encoding of the pieces of a token, which is the message that is signed.
+    ///
+    /// # Arguments
+    ///
+    /// * `pieces` - The pieces to encode.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<u8>` - The pre-authentication encoding.
+    fn pae(pieces: &[&[u8]]) -> Vec<u8> {
+        let le64 = |n: usize| ((n as u64) & (u64::MAX >> 1)).to_le_bytes();
+
+        let mut output = le64(pieces.len()).to_vec();
+        for piece in pieces {
+            output.extend_from_slice(&le64(piece.len()));
+            output.extend_from_slice(piece);
+        }
+        output
+    }
+
+    /// # Summary
+    ///
+    /// Sign a message into a PASETO v4.public token without a footer or implicit assertion.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_pair` - The Ed25519 key pair to sign the message with.
+    /// * `message` - The message to sign.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The PASETO token.
+    fn sign(key_pair: &Ed25519KeyPair, message: &[u8]) -> String {
+        let signature = key_pair.sign(&PasetoService::pae(&[HEADER.as_bytes(), message, b"", b""]));
+
+        let mut body = message.to_vec();
+        body.extend_from_slice(signature.as_ref());
+        format!("{}{}", HEADER, URL_SAFE_NO_PAD.encode(body))
+    }
+
+    /// # Summary
+    ///
+    /// Verify the signature of a PASETO v4.public token, including its footer, without an implicit assertion.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key` - The Ed25519 public key to verify the signature with.
+    /// * `token` - The PASETO token to verify.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<u8>, Error>` - The signed message.
+    fn verify(public_key: &[u8], token: &str) -> Result<Vec<u8>, Error> {
+        // The header is checked explicitly, so that a token of another version or purpose is never accepted
+        let body = match token.strip_prefix(HEADER) {
+            Some(b) => b,
+            None => {
+                return Err(PasetoService::invalid(
+                    "Unsupported token version or purpose",
+                ))
+            }
+        };
+
+        let (body, footer) = match body.split_once('.') {
+            Some((b, f)) => match URL_SAFE_NO_PAD.decode(f) {
+                Ok(f) => (b, f),
+                Err(_) => return Err(PasetoService::invalid("Malformed footer")),
+            },
+            None => (body, vec![]),
+        };
+
+        let mut message = match URL_SAFE_NO_PAD.decode(body) {
+            Ok(b) if b.len() > SIGNATURE_LENGTH => b,
+            _ => return Err(PasetoService::invalid("Malformed token")),
+        };
+
+        let signature = message.split_off(message.len() - SIGNATURE_LENGTH);
+        if UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(
+                &PasetoService::pae(&[HEADER.as_bytes(), &message, &footer, b""]),
+                &signature,
+            )
+            .is_err()
+        {
+            return Err(PasetoService::invalid("Invalid signature"));
+        }
+
+        Ok(message)
+    }
+
+    /// # Summary
+    ///
+    /// Log and create an Error for a token that could not be verified.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The reason why the token could not be verified.
+    ///
+    /// # Returns
+    ///
+    /// * `Error` - The Error.
+    fn invalid(message: &str) -> Error {
+        error!("Error verifying PASETO token: {}", message);
+        Error::InvalidToken(String::from(message))
+    }
+
+    /// # Summary
+    ///
+    /// Sign Claims into a PASETO v4.public token. The `exp`, `iat` and `nbf` claims are encoded as RFC 3339 dates.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The Claims to sign.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<(String, Claims)>` - The PASETO token and the Claims that it contains.
+    fn encode_claims(&self, claims: Claims) -> Option<(String, Claims)> {
+        let mut payload = match serde_json::to_value(&claims) {
+            Ok(Value::Object(p)) => p,
+            Ok(_) => return None,
+            Err(e) => {
+                error!("Error generating PASETO token: {}", e);
+                return None;
+            }
+        };
+
+        for claim in DATE_CLAIMS {
+            let date = match payload.get(claim) {
+                Some(d) => d.as_i64().and_then(|t| Utc.timestamp_opt(t, 0).single())?,
+                None => continue,
+            };
+            payload.insert(
+                String::from(claim),
+                Value::String(date.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            );
+        }
+
+        let body = match serde_json::to_vec(&payload) {
+            Ok(b) => b,
+            Err(e) => {
+                error!("Error generating PASETO token: {}", e);
+                return None;
+            }
+        };
+
+        Some((PasetoService::sign(&self.key_pair, &body), claims))
+    }
+
+    /// # Summary
+    ///
+    /// Verify the signature, expiration, issuer and audience of a PASETO v4.public token and decode its Claims.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The PASETO token to decode.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Claims, Error>` - The result of the operation.
+    fn decode_claims(&self, token: &str) -> Result<Claims, Error> {
+        let message = PasetoService::verify(self.key_pair.public_key().as_ref(), token)?;
+
+        let mut payload = match serde_json::from_slice::<Value>(&message) {
+            Ok(Value::Object(p)) => p,
+            _ => return Err(PasetoService::invalid("Malformed claims")),
+        };
+
+        for claim in DATE_CLAIMS {
+            let date = match payload.get(claim) {
+                Some(d) => d,
+                None => continue,
+            };
+
+            let timestamp = match date.as_str().map(DateTime::parse_from_rfc3339) {
+                Some(Ok(d)) => d.timestamp(),
+                _ => {
+                    return Err(PasetoService::invalid(&format!(
+                        "Malformed {} claim",
+                        claim
+                    )))
+                }
+            };
+            payload.insert(String::from(claim), Value::from(timestamp));
+        }
+
+        let claims: Claims = match serde_json::from_value(Value::Object(payload)) {
+            Ok(c) => c,
+            Err(_) => return Err(PasetoService::invalid("Malformed claims")),
+        };
+
+        if (claims.exp as i64) + (self.jwt_config.leeway as i64) < Utc::now().timestamp() {
+            return Err(PasetoService::invalid("Token has expired"));
+        }
+
+        if self.jwt_config.issuer.is_some() && claims.iss != self.jwt_config.issuer {
+            return Err(PasetoService::invalid("Invalid issuer"));
+        }
+
+        if self.jwt_config.audience.is_some() && claims.aud != self.jwt_config.audience {
+            return Err(PasetoService::invalid("Invalid audience"));
+        }
+
+        Ok(claims)
+    }
+}
+
+impl TokenService for PasetoService {
+    /// # Summary
+    ///
+    /// Get the configuration that determines the lifetime and the registered claims of the issued PASETO tokens.
+    ///
+    /// # Returns
+    ///
+    /// * `&JwtConfig` - The JwtConfig.
+    fn config(&self) -> &JwtConfig {
+        &self.jwt_config
+    }
+
+    /// # Summary
+    ///
+    /// Sign Claims into a PASETO token.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The Claims to sign.
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Option<(String, Claims)>>` - The PASETO token and the Claims that it contains.
+    fn encode(&self, claims: Claims) -> BoxFuture<'_, Option<(String, Claims)>> {
+        Box::pin(ready(self.encode_claims(claims)))
+    }
+
+    /// # Summary
+    ///
+    /// Verify a PASETO token and decode its Claims.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The PASETO token to decode.
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<Claims, Error>>` - The result of the operation.
+    fn decode<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Result<Claims, Error>> {
+        Box::pin(ready(self.decode_claims(token)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The v4.public test vectors of the PASETO specification, see https://github.com/paseto-standard/test-vectors
+    const SECRET_KEY: &str = "b4cbfb43df4ce210727d953e4a713307fa19bb7d9f85041438d9e11b942a3774";
+    const PUBLIC_KEY: &str = "1eb9dbbbbc047c03fd70604e0071f0987e16b28b757225c11f00415d0e20b1a2";
+    const MESSAGE: &str =
+        r#"{"data":"this is a signed message","exp":"2022-01-01T00:00:00+00:00"}"#;
+    const TOKEN_4_S_1: &str = "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9bg_XBBzds8lTZShVlwwKSgeKpLT3yukTw6JUz3W4h_ExsQV-P0V54zemZDcAxFaSeef1QlXEFtkqxT1ciiQEDA";
+    const TOKEN_4_S_2: &str = "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9v3Jt8mx_TdM2ceTGoqwrh4yDFn0XsHvvV_D0DtwQxVrJEBMl0F2caAdgnpKlt4p7xBnx1HcO-SPo8FPp214HDw.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NOeTlEZmdMMVc2MGhhTiJ9";
+    const TOKEN_4_S_3: &str = "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNpZ25lZCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9NPWciuD3d0o5eXJXG5pJy-DiVEoyPYWs1YSTwWHNJq6DZD3je5gf-0M4JR9ipdUSJbIovzmBECeaWmaqcaP0DQ.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NOeTlEZmdMMVc2MGhhTiJ9";
+
+    fn hex(value: &str) -> Vec<u8> {
+        (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn sign_matches_test_vector_4_s_1() {
+        let key_pair =
+            Ed25519KeyPair::from_seed_and_public_key(&hex(SECRET_KEY), &hex(PUBLIC_KEY)).unwrap();
+
+        assert_eq!(
+            PasetoService::sign(&key_pair, MESSAGE.as_bytes()),
+            TOKEN_4_S_1
+        );
+    }
+
+    #[test]
+    fn verify_accepts_test_vectors_4_s_1_and_4_s_2() {
+        for token in [TOKEN_4_S_1, TOKEN_4_S_2] {
+            assert_eq!(
+                PasetoService::verify(&hex(PUBLIC_KEY), token).ok(),
+                Some(MESSAGE.as_bytes().to_vec())
+            );
+        }
+    }
+
+    #[test]
+    fn verify_rejects_test_vector_4_s_3_without_its_implicit_assertion() {
+        assert!(PasetoService::verify(&hex(PUBLIC_KEY), TOKEN_4_S_3).is_err());
+    }
+
+    #[test]
+    fn verify_rejects_a_tampered_message() {
+        let token = TOKEN_4_S_1.replacen("eyJkYXRh", "eyJkYXRb", 1);
+        assert!(PasetoService::verify(&hex(PUBLIC_KEY), &token).is_err());
+    }
+
+    #[test]
+    fn verify_rejects_other_versions_and_purposes() {
+        let token = TOKEN_4_S_1.replacen("v4.public.", "v3.public.", 1);
+        assert!(PasetoService::verify(&hex(PUBLIC_KEY), &token).is_err());
+    }
+}
@@ -1 +1,4 @@
+pub mod argon2_hasher;
+pub mod bcrypt_hasher;
+pub mod password_hasher;
 pub mod password_service;
@@ -0,0 +1,84 @@
+use crate::services::password::password_hasher::PasswordHasher;
+use argon2::{
+    password_hash::{
+        rand_core::OsRng, PasswordHash, PasswordHasher as _, PasswordVerifier, SaltString,
+    },
+    Algorithm, Argon2, Params, Version,
+};
+
+#[derive(Clone)]
+pub struct Argon2Hasher {
+    params: Params,
+}
+
+impl Argon2Hasher {
+    /// # Summary
+    ///
+    /// Create a new Argon2Hasher that hashes passwords with Argon2id.
+    ///
+    /// # Arguments
+    ///
+    /// * `memory_cost` - The amount of memory in KiB.
+    /// * `time_cost` - The number of iterations.
+    /// * `parallelism` - The number of lanes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let hasher = Argon2Hasher::new(19456, 2, 1);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Argon2Hasher` - The new Argon2Hasher.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the parameters are out of the range that Argon2 supports.
+    pub fn new(memory_cost: u32, time_cost: u32, parallelism: u32) -> Argon2Hasher {
+        let params = Params::new(memory_cost, time_cost, parallelism, None)
+            .unwrap_or_else(|e| panic!("Invalid Argon2 parameters: {}", e));
+
+        Argon2Hasher { params }
+    }
+
+    /// # Summary
+    ///
+    /// Get the number of iterations that are used to hash passwords.
+    ///
+    /// # Returns
+    ///
+    /// * `u32` - The time cost.
+    pub fn time_cost(&self) -> u32 {
+        self.params.t_cost()
+    }
+}
+
+impl PasswordHasher for Argon2Hasher {
+    fn hash_password(&self, password: &str) -> Result<String, String> {
+        let salt = SaltString::generate(&mut OsRng);
+
+        let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone());
+        match argon2.hash_password(password.as_bytes(), &salt) {
+            Ok(e) => Ok(e.to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn verify_password(&self, password: &str, hash: &str) -> bool {
+        let parsed_hash = match PasswordHash::new(hash) {
+            Ok(h) => h,
+            Err(_) => return false,
+        };
+
+        // The parameters are read from the hash, so that hashes with other parameters can still be verified. The argon2
+        // crate compares the hash outputs in constant time
+        Argon2::default()
+            .verify_password(password.as_bytes(), &parsed_hash)
+            .is_ok()
+    }
+
+    fn is_supported_hash(&self, hash: &str) -> bool {
+        hash.starts_with("$argon2")
+    }
+}
@@ -0,0 +1,49 @@
+use crate::services::password::password_hasher::PasswordHasher;
+
+#[derive(Clone)]
+pub struct BcryptHasher {
+    pub cost: u32,
+}
+
+impl BcryptHasher {
+    /// # Summary
+    ///
+    /// Create a new BcryptHasher.
+    ///
+    /// # Arguments
+    ///
+    /// * `cost` - The cost that is used when hashing passwords.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let hasher = BcryptHasher::new(12);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BcryptHasher` - The new BcryptHasher.
+    pub fn new(cost: u32) -> BcryptHasher {
+        BcryptHasher { cost }
+    }
+}
+
+impl PasswordHasher for BcryptHasher {
+    fn hash_password(&self, password: &str) -> Result<String, String> {
+        match bcrypt::hash(password, self.cost) {
+            Ok(e) => Ok(e),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn verify_password(&self, password: &str, hash: &str) -> bool {
+        // The bcrypt crate compares the hash outputs in constant time
+        bcrypt::verify(password, hash).unwrap_or(false)
+    }
+
+    fn is_supported_hash(&self, hash: &str) -> bool {
+        ["$2a$", "$2b$", "$2x$", "$2y$"]
+            .iter()
+            .any(|prefix| hash.starts_with(prefix))
+    }
+}
@@ -0,0 +1,43 @@
+pub trait PasswordHasher: Send + Sync {
+    /// # Summary
+    ///
+    /// Hash a password.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to hash.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the hashed password or an error.
+    fn hash_password(&self, password: &str) -> Result<String, String>;
+
+    /// # Summary
+    ///
+    /// Verify a password against a hash that was created by this PasswordHasher.
+    ///
+    /// Implementations must compare the hashes in constant time.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to verify.
+    /// * `hash` - The hash to verify against.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating whether the password is valid.
+    fn verify_password(&self, password: &str, hash: &str) -> bool;
+
+    /// # Summary
+    ///
+    /// Check whether a hash was created by the algorithm of this PasswordHasher.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash to check.
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating whether the hash can be verified by this PasswordHasher.
+    fn is_supported_hash(&self, hash: &str) -> bool;
+}
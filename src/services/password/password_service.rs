@@ -1,39 +1,303 @@
-use argon2::{
-    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
-};
+use crate::configuration::password_config::{PasswordConfig, PasswordHashAlgorithm};
+use crate::services::password::argon2_hasher::Argon2Hasher;
+use crate::services::password::bcrypt_hasher::BcryptHasher;
+use crate::services::password::password_hasher::PasswordHasher;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use log::{error, info, warn};
+use ring::hmac;
+use std::sync::Arc;
+use std::time::Instant;
+use uuid::Uuid;
+use zeroize::Zeroizing;
 
 #[derive(Clone)]
-pub struct PasswordService {}
+pub struct PasswordService {
+    pub hasher: Arc<dyn PasswordHasher>,
+    pub hashers: Vec<Arc<dyn PasswordHasher>>,
+    pepper: Option<hmac::Key>,
+    pub max_length: usize,
+    pub max_new_length: usize,
+    pub history_size: usize,
+    pub max_age_days: u64,
+    pub hash_time: Option<u64>,
+}
+
+/// The upper bound of the Argon2 time cost when it is tuned automatically, so that a misconfigured target cannot make
+/// every login take seconds.
+const MAX_AUTOTUNE_TIME_COST: u32 = 16;
+
+const CALIBRATION_PASSWORD: &str = "calibration-password";
+
+/// The number of bytes of its input that bcrypt uses. The remaining bytes are silently ignored.
+const BCRYPT_MAX_INPUT_LENGTH: usize = 72;
 
 impl PasswordService {
     /// # Summary
     ///
-    /// Hash a password.
+    /// Create a new PasswordService.
     ///
     /// # Arguments
     ///
-    /// * `password` - The password to hash.
+    /// * `password_config` - The PasswordConfig that determines which algorithm is used to hash new passwords.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let password_config = PasswordConfig::new(PasswordHashAlgorithm::Argon2, 12, None, 256, 0, 0, 19456, 2, 1, 0, false);
+    /// let password_service = PasswordService::new(password_config);
+    /// ```
     ///
     /// # Returns
     ///
-    /// A Result containing the hashed password or an error.
-    pub fn hash_password(password: String) -> Result<String, String> {
-        let password = &password.as_bytes();
-        let argon2 = Argon2::default();
+    /// * `PasswordService` - The new PasswordService.
+    pub fn new(password_config: PasswordConfig) -> PasswordService {
+        let mut argon2_hasher = Argon2Hasher::new(
+            password_config.argon2_memory_cost,
+            password_config.argon2_time_cost,
+            password_config.argon2_parallelism,
+        );
+
+        let hash_time = match password_config.algorithm {
+            PasswordHashAlgorithm::Argon2 if password_config.argon2_target_hash_time > 0 => {
+                let (hasher, hash_time) =
+                    PasswordService::calibrate(argon2_hasher, &password_config);
+                argon2_hasher = hasher;
+                Some(hash_time)
+            }
+            _ => None,
+        };
+
+        let argon2: Arc<dyn PasswordHasher> = Arc::new(argon2_hasher);
+        let bcrypt: Arc<dyn PasswordHasher> =
+            Arc::new(BcryptHasher::new(password_config.bcrypt_cost));
+
+        let hasher = match password_config.algorithm {
+            PasswordHashAlgorithm::Argon2 => argon2.clone(),
+            PasswordHashAlgorithm::Bcrypt => bcrypt.clone(),
+        };
 
-        let salt = SaltString::generate(&mut OsRng);
+        // New passwords that bcrypt would truncate are rejected, otherwise passwords that share the first bytes would
+        // match. A peppered password is always hashed as a short HMAC, so the full password is used either way.
+        let max_new_length = match (&password_config.algorithm, &password_config.pepper) {
+            (PasswordHashAlgorithm::Bcrypt, None)
+                if password_config.max_length > BCRYPT_MAX_INPUT_LENGTH =>
+            {
+                warn!(
+                    "bcrypt only uses the first {} bytes of a password, limiting the length of new passwords to {}",
+                    BCRYPT_MAX_INPUT_LENGTH, BCRYPT_MAX_INPUT_LENGTH
+                );
+                BCRYPT_MAX_INPUT_LENGTH
+            }
+            _ => password_config.max_length,
+        };
 
-        match argon2.hash_password(password, &salt) {
-            Ok(e) => Ok(e.to_string()),
-            Err(e) => Err(e.to_string()),
+        let pepper = password_config
+            .pepper
+            .map(|p| hmac::Key::new(hmac::HMAC_SHA256, p.as_bytes()));
+
+        PasswordService {
+            hasher,
+            hashers: vec![argon2, bcrypt],
+            pepper,
+            max_length: password_config.max_length,
+            max_new_length,
+            history_size: password_config.history_size,
+            max_age_days: password_config.max_age_days,
+            hash_time,
         }
     }
 
+    /// # Summary
+    ///
+    /// Measure how long hashing a password with Argon2 takes and compare it with the target hash time. If auto-tuning
+    /// is enabled and hashing is faster than the target, the time cost is increased, up to `MAX_AUTOTUNE_TIME_COST`.
+    /// The time cost is never decreased, so a slow server logs a warning instead of weakening the hashes.
+    ///
+    /// # Arguments
+    ///
+    /// * `hasher` - The Argon2Hasher with the configured parameters.
+    /// * `password_config` - The PasswordConfig that contains the target hash time.
+    ///
+    /// # Returns
+    ///
+    /// * `(Argon2Hasher, u64)` - The Argon2Hasher to use and the measured hash time in milliseconds.
+    fn calibrate(hasher: Argon2Hasher, password_config: &PasswordConfig) -> (Argon2Hasher, u64) {
+        let target = std::time::Duration::from_millis(password_config.argon2_target_hash_time);
+        let measure = |h: &Argon2Hasher| {
+            let start = Instant::now();
+            let _ = h.hash_password(CALIBRATION_PASSWORD);
+            start.elapsed()
+        };
+
+        let mut hasher = hasher;
+        let mut elapsed = measure(&hasher);
+
+        if password_config.argon2_autotune
+            && elapsed < target
+            && hasher.time_cost() < MAX_AUTOTUNE_TIME_COST
+        {
+            // The hash time grows linearly with the time cost
+            let time_cost = (hasher.time_cost() as f64 * target.as_secs_f64()
+                / elapsed.as_secs_f64().max(f64::EPSILON))
+            .ceil()
+            .min(MAX_AUTOTUNE_TIME_COST as f64) as u32;
+
+            info!(
+                "Increasing the Argon2 time cost from {} to {} to reach the target hash time of {} ms",
+                hasher.time_cost(),
+                time_cost,
+                target.as_millis()
+            );
+            hasher = Argon2Hasher::new(
+                password_config.argon2_memory_cost,
+                time_cost,
+                password_config.argon2_parallelism,
+            );
+            elapsed = measure(&hasher);
+        }
+
+        if elapsed < target {
+            warn!(
+                "Hashing a password with Argon2 takes {} ms, which is less than the target of {} ms. Increase ARGON2_TIME_COST or ARGON2_MEMORY_COST, or enable ARGON2_AUTOTUNE",
+                elapsed.as_millis(),
+                target.as_millis()
+            );
+        } else if elapsed > target * 2 {
+            warn!(
+                "Hashing a password with Argon2 takes {} ms, which is more than twice the target of {} ms. Logins will be slow, decrease ARGON2_TIME_COST or ARGON2_MEMORY_COST",
+                elapsed.as_millis(),
+                target.as_millis()
+            );
+        } else {
+            info!(
+                "Hashing a password with Argon2 takes {} ms",
+                elapsed.as_millis()
+            );
+        }
+
+        (hasher, elapsed.as_millis() as u64)
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a password exceeds the maximum length.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The plaintext password to check.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if password_service.exceeds_max_length("password") {
+    ///     // Reject the password
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the password is longer than the maximum length in bytes.
+    pub fn exceeds_max_length(&self, password: &str) -> bool {
+        password.len() > self.max_length
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a new password exceeds the maximum length. This is stricter than exceeds_max_length if the
+    /// configured algorithm would otherwise ignore a part of the password, while existing passwords that are longer
+    /// can still be verified.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The new plaintext password to check.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if password_service.exceeds_max_new_length("password") {
+    ///     // Reject the password
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the password is longer than the maximum length of new passwords in bytes.
+    pub fn exceeds_max_new_length(&self, password: &str) -> bool {
+        password.len() > self.max_new_length
+    }
+
+    /// # Summary
+    ///
+    /// Apply the pepper to a password, if a pepper is configured. The password is replaced by its HMAC-SHA256 keyed
+    /// with the pepper, so that every byte of the password and the pepper is used, even by algorithms that truncate
+    /// their input.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to apply the pepper to.
+    ///
+    /// # Returns
+    ///
+    /// The peppered password, which is zeroed when it is dropped.
+    fn apply_pepper(&self, password: &str) -> Zeroizing<String> {
+        match &self.pepper {
+            Some(k) => {
+                Zeroizing::new(URL_SAFE_NO_PAD.encode(hmac::sign(k, password.as_bytes()).as_ref()))
+            }
+            None => Zeroizing::new(String::from(password)),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Hash a password using the configured algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password to hash.
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the hashed password or an error.
+    pub fn hash_password(&self, password: &str) -> Result<String, String> {
+        self.hasher.hash_password(&self.apply_pepper(password))
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a hash was created using the configured algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `phc` - The stored hash to check.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if !password_service.preferred_algo_matches(&user.password) {
+    ///     // Re-hash the password using the configured algorithm
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating whether the hash uses the configured algorithm.
+    pub fn preferred_algo_matches(&self, phc: &str) -> bool {
+        self.hasher.is_supported_hash(phc)
+    }
+
     /// # Summary
     ///
     /// Verify a password.
     ///
+    /// The algorithm is derived from the stored hash, so that hashes that were created with a
+    /// different algorithm than the configured one can still be verified. The hashes are compared
+    /// in constant time. Secrets should always be compared through this function (or another
+    /// constant-time comparison), never by comparing strings with `==` or `!=`, as that leaks
+    /// timing information about how many leading bytes match.
+    ///
     /// # Arguments
     ///
     /// * `password` - The password to verify.
@@ -42,9 +306,226 @@ impl PasswordService {
     /// # Returns
     ///
     /// A boolean indicating whether the password is valid.
-    pub fn verify_password(password: &str, hash: &PasswordHash) -> bool {
-        Argon2::default()
-            .verify_password(&password.as_bytes(), &hash)
-            .is_ok()
+    pub fn verify_password(&self, password: &str, hash: &str) -> bool {
+        match self.hashers.iter().find(|h| h.is_supported_hash(hash)) {
+            Some(h) => h.verify_password(&self.apply_pepper(password), hash),
+            None => {
+                error!("Unsupported password hash format");
+                false
+            }
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a new password matches the current password or one of the previous passwords in the history.
+    /// Passwords can always be reused if the history size is zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The new plaintext password.
+    /// * `current_hash` - The hash of the current password.
+    /// * `history` - The hashes of the previous passwords, most recent first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if password_service.is_reused("password", &user.password, &user.password_history) {
+    ///     // Reject the password
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating whether the password was used before.
+    pub fn is_reused(&self, password: &str, current_hash: &str, history: &[String]) -> bool {
+        if self.history_size == 0 {
+            return false;
+        }
+
+        std::iter::once(current_hash)
+            .chain(history.iter().map(|h| h.as_str()))
+            .take(self.history_size)
+            .any(|h| self.verify_password(password, h))
+    }
+
+    /// # Summary
+    ///
+    /// Add the hash of the password that is being replaced to the front of the history and drop the hashes that
+    /// exceed the history size.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous_hash` - The hash of the password that is being replaced.
+    /// * `history` - The hashes of the previous passwords, most recent first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let history = password_service.push_history(user.password, user.password_history);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// The new history.
+    pub fn push_history(&self, previous_hash: String, history: Vec<String>) -> Vec<String> {
+        // The current password counts towards the history size as well
+        let size = self.history_size.saturating_sub(1);
+
+        std::iter::once(previous_hash)
+            .chain(history)
+            .take(size)
+            .collect()
+    }
+
+    /// # Summary
+    ///
+    /// Check whether a password has expired. Passwords never expire if the maximum age is zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `changed_at` - The time at which the password was last changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// if password_service.is_expired(user.password_changed_at()) {
+    ///     // Force the User to change their password
+    /// }
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A boolean indicating whether the password has expired.
+    pub fn is_expired(&self, changed_at: DateTime<Utc>) -> bool {
+        if self.max_age_days == 0 {
+            return false;
+        }
+
+        changed_at + Duration::days(self.max_age_days as i64) <= Utc::now()
+    }
+
+    /// # Summary
+    ///
+    /// Generate a random temporary password that is handed out once and must be changed on the next login.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let temporary_password = password_service.generate_temporary_password();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Zeroizing<String>` - The temporary password.
+    pub fn generate_temporary_password(&self) -> Zeroizing<String> {
+        // A v4 UUID contains 122 random bits from the operating system's random number generator
+        Zeroizing::new(Uuid::new_v4().simple().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn password_service(algorithm: PasswordHashAlgorithm) -> PasswordService {
+        password_service_with_pepper(algorithm, Some("pepper"))
+    }
+
+    fn password_service_with_pepper(
+        algorithm: PasswordHashAlgorithm,
+        pepper: Option<&str>,
+    ) -> PasswordService {
+        // The lowest costs keep the tests fast
+        PasswordService::new(PasswordConfig::new(
+            algorithm,
+            4,
+            pepper.map(String::from),
+            256,
+            0,
+            0,
+            8,
+            1,
+            1,
+            0,
+            false,
+        ))
+    }
+
+    #[test]
+    fn verify_password_accepts_the_hashed_password() {
+        for algorithm in [PasswordHashAlgorithm::Argon2, PasswordHashAlgorithm::Bcrypt] {
+            let password_service = password_service(algorithm);
+            let hash = password_service.hash_password("password").unwrap();
+
+            assert!(password_service.verify_password("password", &hash));
+        }
+    }
+
+    #[test]
+    fn verify_password_rejects_another_password() {
+        for algorithm in [PasswordHashAlgorithm::Argon2, PasswordHashAlgorithm::Bcrypt] {
+            let password_service = password_service(algorithm);
+            let hash = password_service.hash_password("password").unwrap();
+
+            assert!(!password_service.verify_password("Password", &hash));
+            assert!(!password_service.verify_password("", &hash));
+        }
+    }
+
+    #[test]
+    fn verify_password_compares_hashes_instead_of_strings() {
+        // Hashing the same password twice gives different PHC strings because of the salt, so a string comparison
+        // of the hashes would reject the password
+        let password_service = password_service(PasswordHashAlgorithm::Argon2);
+        let hash = password_service.hash_password("password").unwrap();
+
+        assert_ne!(password_service.hash_password("password").unwrap(), hash);
+        assert!(password_service.verify_password("password", &hash));
+    }
+
+    #[test]
+    fn verify_password_rejects_an_unsupported_hash() {
+        let password_service = password_service(PasswordHashAlgorithm::Argon2);
+
+        assert!(!password_service.verify_password("password", "password"));
+    }
+
+    #[test]
+    fn exceeds_max_new_length_rejects_passwords_that_bcrypt_would_truncate() {
+        let bcrypt = password_service_with_pepper(PasswordHashAlgorithm::Bcrypt, None);
+        let argon2 = password_service_with_pepper(PasswordHashAlgorithm::Argon2, None);
+
+        assert_eq!(bcrypt.max_new_length, 72);
+        assert!(!bcrypt.exceeds_max_new_length(&"a".repeat(72)));
+        assert!(bcrypt.exceeds_max_new_length(&"a".repeat(73)));
+        assert!(!bcrypt.exceeds_max_length(&"a".repeat(73)));
+        assert!(!argon2.exceeds_max_new_length(&"a".repeat(73)));
+        // A peppered password is hashed as an HMAC, which bcrypt never truncates
+        assert!(!password_service(PasswordHashAlgorithm::Bcrypt)
+            .exceeds_max_new_length(&"a".repeat(73)));
+    }
+
+    #[test]
+    fn verify_password_rejects_a_long_password_with_a_different_pepper() {
+        // Both the password and the pepper would be truncated if the pepper was appended
+        let password = "a".repeat(100);
+        let hash = password_service_with_pepper(PasswordHashAlgorithm::Bcrypt, Some("pepper"))
+            .hash_password(&password)
+            .unwrap();
+
+        assert!(
+            !password_service_with_pepper(PasswordHashAlgorithm::Bcrypt, Some("other"))
+                .verify_password(&password, &hash)
+        );
+    }
+
+    #[test]
+    fn verify_password_verifies_hashes_of_the_other_algorithm() {
+        let hash = password_service(PasswordHashAlgorithm::Bcrypt)
+            .hash_password("password")
+            .unwrap();
+
+        assert!(password_service(PasswordHashAlgorithm::Argon2).verify_password("password", &hash));
     }
 }
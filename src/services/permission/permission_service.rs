@@ -1,3 +1,4 @@
+use crate::configuration::name_config::NameConfig;
 use crate::repository::audit::audit_model::Action::{Create, Delete, Update};
 use crate::repository::audit::audit_model::ResourceType::Permission as PermissionResourceType;
 use crate::repository::audit::audit_model::{Audit, ResourceIdType};
@@ -8,11 +9,13 @@ use crate::services::audit::audit_service::AuditService;
 use crate::services::role::role_service::RoleService;
 use log::{error, info};
 use mongodb::bson::oid::ObjectId;
-use mongodb::Database;
+use mongodb::{Client, ClientSession, Database};
 
 #[derive(Clone)]
 pub struct PermissionService {
     pub permission_repository: PermissionRepository,
+    pub name_config: NameConfig,
+    pub transaction_client: Option<Client>,
 }
 
 impl PermissionService {
@@ -23,21 +26,53 @@ impl PermissionService {
     /// # Arguments
     ///
     /// * `permission_repository` - The PermissionRepository to be used by the PermissionService.
+    /// * `name_config` - The NameConfig that determines how Permission names are normalized and validated.
+    /// * `transaction_client` - The Client that is used to start transactions, or None if the deployment does not support transactions.
     ///
     /// # Example
     ///
     /// ```
     /// let permission_repository = PermissionRepository::new(String::from("permissions"));
-    /// let permission_service = PermissionService::new(permission_repository);
+    /// let permission_service = PermissionService::new(permission_repository, NameConfig::new(NameNormalization::None, 64), None);
     /// ```
     ///
     /// # Returns
     ///
     /// * `PermissionService` - The new PermissionService.
-    pub fn new(permission_repository: PermissionRepository) -> PermissionService {
+    pub fn new(
+        permission_repository: PermissionRepository,
+        name_config: NameConfig,
+        transaction_client: Option<Client>,
+    ) -> PermissionService {
         PermissionService {
             permission_repository,
+            name_config,
+            transaction_client,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Normalize a Permission name and check that it is not empty and not too long.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name to normalize.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The normalized name.
+    /// * `Error` - The Error that occurred.
+    fn normalize_name(&self, name: &str) -> Result<String, Error> {
+        let name = self.name_config.normalize(name);
+        if name.is_empty() {
+            return Err(Error::EmptyName);
         }
+        if name.chars().count() > self.name_config.max_length {
+            return Err(Error::NameTooLong(self.name_config.max_length));
+        }
+
+        Ok(name)
     }
 
     /// # Summary
@@ -70,31 +105,36 @@ impl PermissionService {
     /// * `Error` - The Error that occurred.
     pub async fn create(
         &self,
-        new_permission: Permission,
+        mut new_permission: Permission,
         user_id: Option<ObjectId>,
         db: &Database,
         audit: &AuditService,
     ) -> Result<Permission, Error> {
         info!("Creating Permission: {}", new_permission);
 
-        if user_id.is_some() {
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Create,
-                new_permission.id,
-                ResourceIdType::PermissionId,
-                PermissionResourceType,
-            );
-            match audit.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
+        new_permission.name = self.normalize_name(&new_permission.name)?;
+
+        let permission = self
+            .permission_repository
+            .create(new_permission, db)
+            .await?;
+
+        let new_audit = audit.new_audit(
+            user_id,
+            Create,
+            permission.id,
+            ResourceIdType::PermissionId,
+            PermissionResourceType,
+        );
+        match audit.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
             }
         }
 
-        self.permission_repository.create(new_permission, db).await
+        Ok(permission)
     }
 
     /// # Summary
@@ -138,9 +178,8 @@ impl PermissionService {
     /// # Arguments
     ///
     /// * `id_vec` - The Vector of IDs of the Permission entities.
-    /// * `user_id` - The ID of the User finding the Permission entities.
+    /// * `preserve_order` - Whether the Permission entities should be returned in the order of `id_vec`.
     /// * `db` - The Database to find the Permission entities in.
-    /// * `audit` - The AuditService to be used.
     ///
     /// # Example
     ///
@@ -149,7 +188,7 @@ impl PermissionService {
     /// let permission_service = PermissionService::new(permission_repository);
     /// let db = mongodb::Database::new();
     /// let id_vec = vec![String::from("id")];
-    /// let permissions = permission_service.find_by_id_vec(id_vec, &db);
+    /// let permissions = permission_service.find_by_id_vec(id_vec, true, &db);
     /// ```
     ///
     /// # Returns
@@ -159,10 +198,51 @@ impl PermissionService {
     pub async fn find_by_id_vec(
         &self,
         id_vec: Vec<String>,
+        preserve_order: bool,
         db: &Database,
     ) -> Result<Vec<Permission>, Error> {
         info!("Finding permissions by id_vec: {:?}", id_vec);
-        self.permission_repository.find_by_id_vec(id_vec, db).await
+        self.permission_repository
+            .find_by_id_vec(id_vec, preserve_order, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Find the Permission entities that are satisfied by a set of wildcard Permissions.
+    ///
+    /// # Arguments
+    ///
+    /// * `wildcards` - The wildcard Permission entities. Permissions that are not a wildcard are ignored.
+    /// * `db` - The Database to find the Permission entities in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let permission_repository = PermissionRepository::new(String::from("permissions"));
+    /// let permission_service = PermissionService::new(permission_repository);
+    /// let db = mongodb::Database::new();
+    /// let wildcards = vec![Permission::new(String::from("users:*"), None)];
+    /// let permissions = permission_service.find_by_wildcards(&wildcards, &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Permission>` - The Permission entities.
+    /// * `Error` - The Error that occurred.
+    pub async fn find_by_wildcards(
+        &self,
+        wildcards: &[Permission],
+        db: &Database,
+    ) -> Result<Vec<Permission>, Error> {
+        let prefixes: Vec<&str> = wildcards
+            .iter()
+            .filter_map(|w| w.wildcard_prefix())
+            .collect();
+        info!("Finding permissions by wildcard prefixes: {:?}", prefixes);
+        self.permission_repository
+            .find_by_name_prefixes(&prefixes, db)
+            .await
     }
 
     /// # Summary
@@ -223,7 +303,9 @@ impl PermissionService {
         db: &Database,
     ) -> Result<Option<Permission>, Error> {
         info!("Finding Permission by name: {}", name);
-        self.permission_repository.find_by_name(name, db).await
+        self.permission_repository
+            .find_by_name(&self.name_config.normalize(name), db)
+            .await
     }
 
     /// # Summary
@@ -255,31 +337,34 @@ impl PermissionService {
     /// * `Error` - The Error that occurred.
     pub async fn update(
         &self,
-        permission: Permission,
+        mut permission: Permission,
         user_id: Option<ObjectId>,
         db: &Database,
         audit: &AuditService,
     ) -> Result<Permission, Error> {
         info!("Updating Permission: {}", permission);
 
-        if user_id.is_some() {
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Update,
-                permission.id,
-                ResourceIdType::PermissionId,
-                PermissionResourceType,
-            );
-            match audit.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
+        permission.name = self.normalize_name(&permission.name)?;
+
+        permission.updated_by = user_id.map(|u| u.to_hex());
+        let permission = self.permission_repository.update(permission, db).await?;
+
+        let new_audit = audit.new_audit(
+            user_id,
+            Update,
+            permission.id,
+            ResourceIdType::PermissionId,
+            PermissionResourceType,
+        );
+        match audit.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
             }
         }
 
-        self.permission_repository.update(permission, db).await
+        Ok(permission)
     }
 
     /// # Summary
@@ -310,7 +395,7 @@ impl PermissionService {
     ///
     /// # Returns
     ///
-    /// * `()` - The operation was successful.
+    /// * `u64` - The number of Role entities the Permission entity was removed from.
     /// * `Error` - The Error that occurred.
     pub async fn delete(
         &self,
@@ -319,36 +404,136 @@ impl PermissionService {
         db: &Database,
         role_service: &RoleService,
         audit: &AuditService,
-    ) -> Result<(), Error> {
+    ) -> Result<u64, Error> {
         info!("Deleting Permission by ID: {}", id);
 
-        if user_id.is_some() {
-            let oid = match ObjectId::parse_str(id) {
-                Ok(oid) => oid,
-                Err(e) => {
-                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
-                }
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(e) => {
+                return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+            }
+        };
+
+        match self.permission_repository.find_by_id(id, db).await {
+            Ok(Some(p)) if p.is_protected() => return Err(Error::Protected(p.name)),
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+
+        let new_audit = audit.new_audit(
+            user_id,
+            Delete,
+            oid,
+            ResourceIdType::PermissionId,
+            PermissionResourceType,
+        );
+
+        // The Permission and the references of the Roles to it are deleted together, so that no Role keeps a dangling
+        // Permission
+        if let Some(client) = &self.transaction_client {
+            let mut session = match client.start_session(None).await {
+                Ok(s) => s,
+                Err(e) => return Err(Error::MongoDb(e)),
             };
+            if let Err(e) = session.start_transaction(None).await {
+                return Err(Error::MongoDb(e));
+            }
 
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Delete,
-                oid,
-                ResourceIdType::PermissionId,
-                PermissionResourceType,
-            );
-            match audit.create(new_audit, db).await {
-                Ok(_) => {}
+            let res = self
+                .delete_in_transaction(id, new_audit, db, role_service, audit, &mut session)
+                .await;
+            return match res {
+                Ok(removed_from) => match session.commit_transaction().await {
+                    Ok(_) => Ok(removed_from),
+                    Err(e) => Err(Error::MongoDb(e)),
+                },
                 Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
+                    if let Err(abort_error) = session.abort_transaction().await {
+                        error!("Failed to abort transaction: {}", abort_error);
+                    }
+                    Err(e)
                 }
+            };
+        }
+
+        let removed_from = self
+            .permission_repository
+            .delete(id, db, role_service)
+            .await?;
+
+        match audit.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
             }
         }
 
+        Ok(removed_from)
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Permission entity, the references of the Role entities to it and its Audit in the transaction of a
+    /// ClientSession.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the Permission entity to delete.
+    /// * `new_audit` - The Audit of the deletion.
+    /// * `db` - The Database to be used.
+    /// * `role_service` - The RoleService to be used.
+    /// * `audit` - The AuditService to be used.
+    /// * `session` - The ClientSession with an active transaction.
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The number of Role entities the Permission entity was removed from.
+    /// * `Error` - The Error that occurred.
+    async fn delete_in_transaction(
+        &self,
+        id: &str,
+        new_audit: Audit,
+        db: &Database,
+        role_service: &RoleService,
+        audit: &AuditService,
+        session: &mut ClientSession,
+    ) -> Result<u64, Error> {
         self.permission_repository
-            .delete(id, db, role_service)
+            .delete_with_session(id, db, session)
+            .await?;
+
+        let removed_from = match role_service
+            .delete_permission_from_all_roles_with_session(id, db, session)
             .await
+        {
+            Ok(count) => count,
+            Err(e) => return Err(Error::Role(e)),
+        };
+
+        if let Err(e) = audit.create_with_session(new_audit, db, session).await {
+            error!("Failed to create Audit: {}", e);
+            return Err(Error::Audit(e));
+        }
+
+        Ok(removed_from)
+    }
+
+    /// # Summary
+    ///
+    /// Count the Permission entities, optionally only the ones that match a text search.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to search for, or None to count all Permission entities.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of matching Permission entities.
+    pub async fn count(&self, text: Option<&str>, db: &Database) -> Result<u64, Error> {
+        info!("Counting Permissions");
+        self.permission_repository.count(text, db).await
     }
 
     /// # Summary
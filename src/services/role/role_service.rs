@@ -1,17 +1,23 @@
+use crate::configuration::name_config::NameConfig;
+use crate::configuration::role_template::RoleTemplate;
 use crate::repository::audit::audit_model::Action::{Create, Delete, Update};
 use crate::repository::audit::audit_model::{Audit, ResourceIdType, ResourceType};
 use crate::repository::audit::audit_repository::Error as AuditError;
 use crate::repository::role::role_model::Role;
 use crate::repository::role::role_repository::{Error, RoleRepository};
 use crate::services::audit::audit_service::AuditService;
+use crate::services::permission::permission_service::PermissionService;
 use crate::services::user::user_service::UserService;
-use log::{error, info};
+use log::{error, info, warn};
 use mongodb::bson::oid::ObjectId;
-use mongodb::Database;
+use mongodb::{Client, ClientSession, Database};
 
 #[derive(Clone)]
 pub struct RoleService {
     pub role_repository: RoleRepository,
+    pub name_config: NameConfig,
+    pub templates: Vec<RoleTemplate>,
+    pub transaction_client: Option<Client>,
 }
 
 impl RoleService {
@@ -22,19 +28,56 @@ impl RoleService {
     /// # Arguments
     ///
     /// * `role_repository` - The RoleRepository to be used by the RoleService.
+    /// * `name_config` - The NameConfig that determines how Role names are normalized and validated.
+    /// * `templates` - The RoleTemplates that can be used to create Roles.
+    /// * `transaction_client` - The Client that is used to start transactions, or None if the deployment does not support transactions.
     ///
     /// # Example
     ///
     /// ```
     /// let role_repository = RoleRepository::new(String::from("roles"));
-    /// let role_service = RoleService::new(role_repository);
+    /// let role_service = RoleService::new(role_repository, NameConfig::new(NameNormalization::None, 64), vec![], None);
     /// ```
     ///
     /// # Returns
     ///
     /// * `RoleService` - The new RoleService.
-    pub fn new(role_repository: RoleRepository) -> RoleService {
-        RoleService { role_repository }
+    pub fn new(
+        role_repository: RoleRepository,
+        name_config: NameConfig,
+        templates: Vec<RoleTemplate>,
+        transaction_client: Option<Client>,
+    ) -> RoleService {
+        RoleService {
+            role_repository,
+            name_config,
+            templates,
+            transaction_client,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Normalize a Role name and check that it is not empty and not too long.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name to normalize.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The normalized name.
+    /// * `Error` - The Error that occurred.
+    fn normalize_name(&self, name: &str) -> Result<String, Error> {
+        let name = self.name_config.normalize(name);
+        if name.is_empty() {
+            return Err(Error::EmptyName);
+        }
+        if name.chars().count() > self.name_config.max_length {
+            return Err(Error::NameTooLong(self.name_config.max_length));
+        }
+
+        Ok(name)
     }
 
     /// # Summary
@@ -67,31 +110,93 @@ impl RoleService {
     /// * `Error` - The Error that occurred.
     pub async fn create(
         &self,
-        role: Role,
+        mut role: Role,
         user_id: Option<ObjectId>,
         db: &Database,
         audit_service: &AuditService,
     ) -> Result<Role, Error> {
         info!("Creating Role: {}", role);
 
-        if user_id.is_some() {
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Create,
-                role.id,
-                ResourceIdType::RoleId,
-                ResourceType::Role,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
+        role.name = self.normalize_name(&role.name)?;
+
+        let role = self.role_repository.create(role, db).await?;
+
+        let new_audit = audit_service.new_audit(
+            user_id,
+            Create,
+            role.id,
+            ResourceIdType::RoleId,
+            ResourceType::Role,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
+            }
+        }
+
+        Ok(role)
+    }
+
+    /// # Summary
+    ///
+    /// Create a new Role from a RoleTemplate, granting it the Permissions of the RoleTemplate.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the RoleTemplate, which is also used as the name of the Role.
+    /// * `user_id` - The id of the User creating the Role.
+    /// * `db` - The Database to be used.
+    /// * `permission_service` - The PermissionService to be used to resolve the Permission names.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role = role_service.create_from_template("EDITOR", user_id, &db, &permission_service, &audit_service);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Role` - The created Role entity.
+    /// * `Error` - The Error that occurred.
+    pub async fn create_from_template(
+        &self,
+        name: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        permission_service: &PermissionService,
+        audit_service: &AuditService,
+    ) -> Result<Role, Error> {
+        info!("Creating Role from template: {}", name);
+
+        let template = match self.templates.iter().find(|t| t.name == name) {
+            Some(t) => t,
+            None => return Err(Error::TemplateNotFound(name.to_string())),
+        };
+
+        let mut permission_ids: Vec<ObjectId> = vec![];
+        for permission_name in &template.permissions {
+            match permission_service.find_by_name(permission_name, db).await {
+                Ok(Some(p)) => permission_ids.push(p.id),
+                Ok(None) => {
+                    return Err(Error::Permission(format!(
+                        "Permission not found: {}",
+                        permission_name
+                    )))
                 }
+                Err(e) => return Err(Error::Permission(e.to_string())),
             }
         }
 
-        self.role_repository.create(role, db).await
+        let role = Role::new(
+            template.name.clone(),
+            template.description.clone(),
+            Some(permission_ids),
+        );
+
+        self.create(role, user_id, db, audit_service).await
     }
 
     /// # Summary
@@ -166,9 +271,8 @@ impl RoleService {
     /// # Arguments
     ///
     /// * `id_vec` - The vector of ids of the Role entities.
-    /// * `user_id` - The id of the User finding the Role entities.
+    /// * `preserve_order` - Whether the Role entities should be returned in the order of `id_vec`.
     /// * `db` - The Database to be used.
-    /// * `audit_service` - The AuditService to be used.
     ///
     /// # Example
     ///
@@ -178,7 +282,7 @@ impl RoleService {
     /// let db = mongodb::Database::new();
     /// let id_vec = vec!["role_id"];
     ///
-    /// let roles = role_service.find_by_id_vec(id_vec, &db);
+    /// let roles = role_service.find_by_id_vec(id_vec, true, &db);
     /// ```
     ///
     /// # Returns
@@ -188,10 +292,13 @@ impl RoleService {
     pub async fn find_by_id_vec(
         &self,
         id_vec: Vec<String>,
+        preserve_order: bool,
         db: &Database,
     ) -> Result<Vec<Role>, Error> {
         info!("Finding roles by id vec: {:?}", id_vec);
-        self.role_repository.find_by_id_vec(id_vec, db).await
+        self.role_repository
+            .find_by_id_vec(id_vec, preserve_order, db)
+            .await
     }
 
     /// # Summary
@@ -221,7 +328,44 @@ impl RoleService {
     /// A Result with an Option of a Role instance or an Error.
     pub async fn find_by_name(&self, name: &str, db: &Database) -> Result<Option<Role>, Error> {
         info!("Finding Role by name: {}", name);
-        self.role_repository.find_by_name(name, db).await
+        self.role_repository
+            .find_by_name(&self.name_config.normalize(name), db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Find the Roles with the given names. Names that do not belong to a Role are skipped with a warning.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - The names of the Roles.
+    /// * `db` - A reference to a Database instance.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_repository = RoleRepository::new(String::from("roles"));
+    /// let role_service = RoleService::new(role_repository);
+    /// let db = mongodb::Database::new();
+    /// let names = vec![String::from("DEFAULT"), String::from("NEWSLETTER")];
+    ///
+    /// let roles = role_service.find_by_names(&names, &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// A Result with the Roles that were found, in the order of their names, or an Error.
+    pub async fn find_by_names(&self, names: &[String], db: &Database) -> Result<Vec<Role>, Error> {
+        let mut roles = vec![];
+        for name in names {
+            match self.find_by_name(name, db).await? {
+                Some(r) => roles.push(r),
+                None => warn!("The Role {} does not exist and is skipped", name),
+            }
+        }
+
+        Ok(roles)
     }
 
     /// # Summary
@@ -254,31 +398,34 @@ impl RoleService {
     /// * `Error` - The Error that occurred.
     pub async fn update(
         &self,
-        role: Role,
+        mut role: Role,
         user_id: Option<ObjectId>,
         db: &Database,
         audit_service: &AuditService,
     ) -> Result<Role, Error> {
         info!("Updating Role: {}", role);
 
-        if user_id.is_some() {
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Update,
-                role.id,
-                ResourceIdType::RoleId,
-                ResourceType::Role,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
+        role.name = self.normalize_name(&role.name)?;
+
+        role.updated_by = user_id.map(|u| u.to_hex());
+        let role = self.role_repository.update(role, db).await?;
+
+        let new_audit = audit_service.new_audit(
+            user_id,
+            Update,
+            role.id,
+            ResourceIdType::RoleId,
+            ResourceType::Role,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
             }
         }
 
-        self.role_repository.update(role, db).await
+        Ok(role)
     }
 
     /// # Summary
@@ -321,31 +468,111 @@ impl RoleService {
     ) -> Result<(), Error> {
         info!("Deleting Role by ID: {}", id);
 
-        if user_id.is_some() {
-            let oid = match ObjectId::parse_str(id) {
-                Ok(oid) => oid,
-                Err(e) => {
-                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
-                }
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(e) => {
+                return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+            }
+        };
+
+        match self.role_repository.find_by_id(id, db).await {
+            Ok(Some(r)) if r.is_protected() => return Err(Error::Protected(r.name)),
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+
+        let new_audit = audit_service.new_audit(
+            user_id,
+            Delete,
+            oid,
+            ResourceIdType::RoleId,
+            ResourceType::Role,
+        );
+
+        // The Role and the references of the Users to it are deleted together, so that no User keeps a dangling Role
+        if let Some(client) = &self.transaction_client {
+            let mut session = match client.start_session(None).await {
+                Ok(s) => s,
+                Err(e) => return Err(Error::MongoDb(e)),
             };
+            if let Err(e) = session.start_transaction(None).await {
+                return Err(Error::MongoDb(e));
+            }
 
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Delete,
-                oid,
-                ResourceIdType::RoleId,
-                ResourceType::Role,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
+            let res = self
+                .delete_in_transaction(id, new_audit, db, user_service, audit_service, &mut session)
+                .await;
+            return match res {
+                Ok(_) => match session.commit_transaction().await {
+                    Ok(_) => Ok(()),
+                    Err(e) => Err(Error::MongoDb(e)),
+                },
                 Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
+                    if let Err(abort_error) = session.abort_transaction().await {
+                        error!("Failed to abort transaction: {}", abort_error);
+                    }
+                    Err(e)
                 }
+            };
+        }
+
+        self.role_repository.delete(id, db, user_service).await?;
+
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
             }
         }
 
-        self.role_repository.delete(id, db, user_service).await
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Delete a Role entity, the references of the User entities to it and its Audit in the transaction of a
+    /// ClientSession.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the Role entity.
+    /// * `audit` - The Audit of the deletion.
+    /// * `db` - The Database to be used.
+    /// * `user_service` - The UserService to be used.
+    /// * `audit_service` - The AuditService to be used.
+    /// * `session` - The ClientSession with an active transaction.
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The operation was successful.
+    /// * `Error` - The Error that occurred.
+    async fn delete_in_transaction(
+        &self,
+        id: &str,
+        audit: Audit,
+        db: &Database,
+        user_service: &UserService,
+        audit_service: &AuditService,
+        session: &mut ClientSession,
+    ) -> Result<(), Error> {
+        self.role_repository
+            .delete_with_session(id, db, session)
+            .await?;
+
+        if let Err(e) = user_service
+            .delete_role_from_all_users_with_session(id, db, session)
+            .await
+        {
+            return Err(Error::User(e));
+        }
+
+        if let Err(e) = audit_service.create_with_session(audit, db, session).await {
+            error!("Failed to create Audit: {}", e);
+            return Err(Error::Audit(e));
+        }
+
+        Ok(())
     }
 
     /// # Summary
@@ -369,13 +596,13 @@ impl RoleService {
     ///
     /// # Returns
     ///
-    /// * `()` - The operation was successful.
+    /// * `u64` - The number of Role entities that were modified.
     /// * `Error` - The Error that occurred.
     pub async fn delete_permission_from_all_roles(
         &self,
         permission_id: &str,
         db: &Database,
-    ) -> Result<(), Error> {
+    ) -> Result<u64, Error> {
         info!(
             "Deleting permission {} from all Role entities",
             permission_id
@@ -385,6 +612,188 @@ impl RoleService {
             .await
     }
 
+    /// # Summary
+    ///
+    /// Delete a Permission entity from all Role entities as part of the transaction of a ClientSession.
+    ///
+    /// # Arguments
+    ///
+    /// * `permission_id` - The id of the Permission entity.
+    /// * `db` - The Database to be used.
+    /// * `session` - The ClientSession with an active transaction.
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The number of Role entities that were modified.
+    /// * `Error` - The Error that occurred.
+    pub async fn delete_permission_from_all_roles_with_session(
+        &self,
+        permission_id: &str,
+        db: &Database,
+        session: &mut ClientSession,
+    ) -> Result<u64, Error> {
+        info!(
+            "Deleting permission {} from all Role entities",
+            permission_id
+        );
+        self.role_repository
+            .delete_permission_from_all_roles_with_session(permission_id, db, session)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Add Permission entities to a Role entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the Role entity.
+    /// * `permissions` - The ids of the Permission entities to add.
+    /// * `user_id` - The id of the User updating the Role entity.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_repository = RoleRepository::new(String::from("roles"));
+    /// let role_service = RoleService::new(role_repository);
+    /// let db = mongodb::Database::new();
+    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
+    /// let user_id = Some(ObjectId::parse_str("user_id"));
+    ///
+    /// let res = role_service.add_permissions("role_id", vec![ObjectId::new()], user_id, &db, &audit_service).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Role` - The updated Role entity.
+    /// * `Error` - The Error that occurred.
+    pub async fn add_permissions(
+        &self,
+        id: &str,
+        permissions: Vec<ObjectId>,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &AuditService,
+    ) -> Result<Role, Error> {
+        info!("Adding Permissions {:?} to Role: {}", permissions, id);
+
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(e) => {
+                return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+            }
+        };
+
+        let role = self
+            .role_repository
+            .add_permissions(id, permissions, user_id.map(|u| u.to_hex()), db)
+            .await?;
+
+        let new_audit = audit_service.new_audit(
+            user_id,
+            Update,
+            oid,
+            ResourceIdType::RoleId,
+            ResourceType::Role,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
+            }
+        }
+
+        Ok(role)
+    }
+
+    /// # Summary
+    ///
+    /// Remove a Permission entity from a Role entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the Role entity.
+    /// * `permission_id` - The id of the Permission entity to remove.
+    /// * `user_id` - The id of the User updating the Role entity.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let role_repository = RoleRepository::new(String::from("roles"));
+    /// let role_service = RoleService::new(role_repository);
+    /// let db = mongodb::Database::new();
+    /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
+    /// let user_id = Some(ObjectId::parse_str("user_id"));
+    ///
+    /// let res = role_service.remove_permission("role_id", "permission_id", user_id, &db, &audit_service).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Role` - The updated Role entity.
+    /// * `Error` - The Error that occurred.
+    pub async fn remove_permission(
+        &self,
+        id: &str,
+        permission_id: &str,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &AuditService,
+    ) -> Result<Role, Error> {
+        info!("Removing Permission {} from Role: {}", permission_id, id);
+
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(e) => {
+                return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+            }
+        };
+
+        let role = self
+            .role_repository
+            .remove_permission(id, permission_id, user_id.map(|u| u.to_hex()), db)
+            .await?;
+
+        let new_audit = audit_service.new_audit(
+            user_id,
+            Update,
+            oid,
+            ResourceIdType::RoleId,
+            ResourceType::Role,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
+            }
+        }
+
+        Ok(role)
+    }
+
+    /// # Summary
+    ///
+    /// Count the Role entities, optionally only the ones that match a text search.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to search for, or None to count all Role entities.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of matching Role entities.
+    pub async fn count(&self, text: Option<&str>, db: &Database) -> Result<u64, Error> {
+        info!("Counting Roles");
+        self.role_repository.count(text, db).await
+    }
+
     /// # Summary
     ///
     /// Search for Role entities by text.
@@ -0,0 +1,217 @@
+use crate::repository::session::session_model::Session;
+use crate::repository::session::session_repository::{Error, SessionRepository};
+use log::info;
+use mongodb::bson::oid::ObjectId;
+use mongodb::Database;
+
+#[derive(Clone)]
+pub struct SessionService {
+    pub session_repository: SessionRepository,
+    pub max_sessions: usize,
+}
+
+impl SessionService {
+    /// # Summary
+    ///
+    /// Create a new SessionService.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_repository` - The SessionRepository.
+    /// * `max_sessions` - The maximum number of active Sessions per User, or zero for no limit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let session_repository = SessionRepository::new(String::from("sessions"));
+    /// let session_service = SessionService::new(session_repository, 5);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SessionService` - The new SessionService.
+    pub fn new(session_repository: SessionRepository, max_sessions: usize) -> SessionService {
+        SessionService {
+            session_repository,
+            max_sessions,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create a new Session. If the User exceeds the maximum number of active Sessions, the oldest Sessions are
+    /// removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The Session to create.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let session = Session::new(user.id, String::from("jti"), None, expires_at);
+    /// session_service.create(session, &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn create(&self, session: Session, db: &Database) -> Result<(), Error> {
+        info!("Creating Session for User: {}", session.user_id);
+        let user_id = session.user_id;
+        self.session_repository.create(session, db).await?;
+
+        if self.max_sessions == 0 {
+            return Ok(());
+        }
+
+        let sessions = self
+            .session_repository
+            .find_by_user_id(&user_id, db)
+            .await?;
+        if sessions.len() <= self.max_sessions {
+            return Ok(());
+        }
+
+        let evicted: Vec<ObjectId> = sessions
+            .iter()
+            .take(sessions.len() - self.max_sessions)
+            .map(|s| s.id)
+            .collect();
+
+        info!(
+            "Evicting {} Session(s) of User {} that exceed the limit",
+            evicted.len(),
+            user_id
+        );
+        self.session_repository
+            .delete_by_id_vec(evicted, db)
+            .await
+            .map(|_| ())
+    }
+
+    /// # Summary
+    ///
+    /// Find all active Sessions of a User, oldest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let sessions = session_service.find_by_user_id(&user.id, &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Session>, Error>` - The result of the operation.
+    pub async fn find_by_user_id(
+        &self,
+        user_id: &ObjectId,
+        db: &Database,
+    ) -> Result<Vec<Session>, Error> {
+        info!("Finding Sessions for User: {}", user_id);
+        self.session_repository.find_by_user_id(user_id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the Session of a token is still active. Tokens without an ID cannot be tracked and are always
+    /// considered to be active.
+    ///
+    /// # Arguments
+    ///
+    /// * `jti` - The optional ID of the token.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let active = session_service.is_active(claims.jti.as_deref(), &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, Error>` - The result of the operation.
+    pub async fn is_active(&self, jti: Option<&str>, db: &Database) -> Result<bool, Error> {
+        match jti {
+            Some(j) => self.session_repository.exists_by_jti(j, db).await,
+            None => Ok(true),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Revoke a Session of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the Session.
+    /// * `user_id` - The ID of the User that the Session belongs to.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// session_service.delete("id", &user.id, &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn delete(&self, id: &str, user_id: &ObjectId, db: &Database) -> Result<(), Error> {
+        info!("Revoking Session {} of User {}", id, user_id);
+        self.session_repository.delete(id, user_id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Revoke the Session of a token. Only one caller can revoke a Session, which makes it safe to rotate the tokens
+    /// of a Session when it was revoked.
+    ///
+    /// # Arguments
+    ///
+    /// * `jti` - The ID of the token.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let revoked = session_service.delete_by_jti("jti", &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool, Error>` - Whether the Session was active and is now revoked.
+    pub async fn delete_by_jti(&self, jti: &str, db: &Database) -> Result<bool, Error> {
+        info!("Revoking Session of token {}", jti);
+        self.session_repository.delete_by_jti(jti, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Revoke all Sessions of a User.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The ID of the User.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let revoked = session_service.delete_all(&user.id, &db).await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u64, Error>` - The number of revoked Sessions.
+    pub async fn delete_all(&self, user_id: &ObjectId, db: &Database) -> Result<u64, Error> {
+        info!("Revoking all Sessions of User {}", user_id);
+        self.session_repository.delete_by_user_id(user_id, db).await
+    }
+}
@@ -0,0 +1,2 @@
+pub mod opaque_token_service;
+pub mod token_service;
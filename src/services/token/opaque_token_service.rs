@@ -0,0 +1,159 @@
+use crate::configuration::jwt_config::JwtConfig;
+use crate::repository::token::token_model::OpaqueToken;
+use crate::repository::token::token_repository::TokenRepository;
+use crate::services::token::token_service::{Claims, Error, TokenService};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{TimeZone, Utc};
+use futures::future::BoxFuture;
+use log::error;
+use mongodb::Database;
+use ring::digest::{digest, SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+
+const TOKEN_LENGTH: usize = 32;
+
+pub struct OpaqueTokenService {
+    pub jwt_config: JwtConfig,
+    token_repository: TokenRepository,
+    database: Database,
+    random: SystemRandom,
+}
+
+impl OpaqueTokenService {
+    /// # Summary
+    ///
+    /// Create a new OpaqueTokenService that issues random tokens and stores their Claims in the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `jwt_config` - The configuration for the OpaqueTokenService.
+    /// * `token_repository` - The TokenRepository that stores the Claims of the issued tokens.
+    /// * `database` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let opaque_token_service = OpaqueTokenService::new(jwt_config, token_repository, db.clone());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `OpaqueTokenService` - The new OpaqueTokenService.
+    pub fn new(
+        jwt_config: JwtConfig,
+        token_repository: TokenRepository,
+        database: Database,
+    ) -> OpaqueTokenService {
+        OpaqueTokenService {
+            jwt_config,
+            token_repository,
+            database,
+            random: SystemRandom::new(),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Hash an opaque token, so that a leaked database does not contain usable tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The opaque token.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The hash of the opaque token.
+    fn hash(token: &str) -> String {
+        URL_SAFE_NO_PAD.encode(digest(&SHA256, token.as_bytes()))
+    }
+}
+
+impl TokenService for OpaqueTokenService {
+    /// # Summary
+    ///
+    /// Get the configuration that determines the lifetime and the registered claims of the issued opaque tokens.
+    ///
+    /// # Returns
+    ///
+    /// * `&JwtConfig` - The JwtConfig.
+    fn config(&self) -> &JwtConfig {
+        &self.jwt_config
+    }
+
+    /// # Summary
+    ///
+    /// Generate a random opaque token and store the hash of the token together with its Claims.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The Claims that the opaque token refers to.
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Option<(String, Claims)>>` - The opaque token and the Claims that it refers to.
+    fn encode(&self, claims: Claims) -> BoxFuture<'_, Option<(String, Claims)>> {
+        Box::pin(async move {
+            let mut bytes = [0u8; TOKEN_LENGTH];
+            if let Err(e) = self.random.fill(&mut bytes) {
+                error!("Error generating opaque token: {}", e);
+                return None;
+            }
+            let token = URL_SAFE_NO_PAD.encode(bytes);
+
+            let expires_at = Utc.timestamp_opt(claims.exp as i64, 0).single()?;
+            let opaque_token =
+                OpaqueToken::new(OpaqueTokenService::hash(&token), claims.clone(), expires_at);
+
+            if let Err(e) = self
+                .token_repository
+                .create(opaque_token, &self.database)
+                .await
+            {
+                error!("Error storing opaque token: {}", e);
+                return None;
+            }
+
+            Some((token, claims))
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Look up the Claims of an opaque token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The opaque token.
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<Claims, Error>>` - The result of the operation.
+    fn decode<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Result<Claims, Error>> {
+        Box::pin(async move {
+            let opaque_token = match self
+                .token_repository
+                .find_by_id(&OpaqueTokenService::hash(token), &self.database)
+                .await
+            {
+                Ok(Some(t)) => t,
+                Ok(None) => {
+                    error!("Error verifying opaque token: unknown token");
+                    return Err(Error::InvalidToken(String::from("Unknown token")));
+                }
+                Err(e) => {
+                    error!("Error verifying opaque token: {}", e);
+                    return Err(Error::InvalidToken(e.to_string()));
+                }
+            };
+
+            // MongoDB removes expired tokens periodically, so a token can outlive its expiration for a short while
+            if opaque_token.expires_at < Utc::now() {
+                error!("Error verifying opaque token: the token has expired");
+                return Err(Error::InvalidToken(String::from("Token has expired")));
+            }
+
+            Ok(opaque_token.claims)
+        })
+    }
+}
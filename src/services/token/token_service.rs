@@ -0,0 +1,443 @@
+use crate::configuration::jwt_config::JwtConfig;
+use futures::future::{ready, BoxFuture};
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+
+pub const PASSWORD_CHANGE_SCOPE: &str = "password_change";
+pub const REFRESH_SCOPE: &str = "refresh";
+const PASSWORD_CHANGE_TOKEN_EXPIRATION: i64 = 300;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub exp: usize,
+    pub iat: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<usize>,
+    pub sub: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+impl Claims {
+    /// # Summary
+    ///
+    /// Create a new Claims.
+    ///
+    /// # Arguments
+    ///
+    /// * `sub` - The subject of the Claims.
+    /// * `exp` - The expiration time of the Claims.
+    /// * `iat` - The issued at time of the Claims.
+    /// * `iss` - The optional issuer of the Claims.
+    /// * `aud` - The optional audience of the Claims.
+    /// * `tenant` - The optional ID of the tenant of the subject.
+    /// * `roles` - The optional Role names of the subject.
+    /// * `permissions` - The optional effective Permission names of the subject.
+    ///
+    /// A unique token ID is generated for the `jti` claim and the token is not valid before it was issued.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        sub: String,
+        exp: usize,
+        iat: usize,
+        iss: Option<String>,
+        aud: Option<String>,
+        tenant: Option<String>,
+        roles: Option<Vec<String>>,
+        permissions: Option<Vec<String>>,
+    ) -> Claims {
+        Claims {
+            sub,
+            exp,
+            iat,
+            nbf: Some(iat),
+            jti: Some(Uuid::new_v4().to_string()),
+            iss,
+            aud,
+            tenant,
+            roles,
+            permissions,
+            scope: None,
+        }
+    }
+}
+
+pub enum Error {
+    InvalidToken(String),
+}
+
+impl Display for Error {
+    /// # Summary
+    ///
+    /// Display the Error.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let error = Error::InvalidToken(String::from("message"));
+    /// println!("{}", error);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the operation.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidToken(message) => write!(f, "Invalid token: {}", message),
+        }
+    }
+}
+
+pub trait TokenService: Send + Sync {
+    /// # Summary
+    ///
+    /// Get the configuration that determines the lifetime and the registered claims of the issued tokens.
+    ///
+    /// # Returns
+    ///
+    /// * `&JwtConfig` - The configuration of the TokenService.
+    fn config(&self) -> &JwtConfig;
+
+    /// # Summary
+    ///
+    /// Sign Claims into a token of the format of this TokenService.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The Claims to sign.
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Option<(String, Claims)>>` - The token and the Claims that it contains.
+    fn encode(&self, claims: Claims) -> BoxFuture<'_, Option<(String, Claims)>>;
+
+    /// # Summary
+    ///
+    /// Verify the signature and the registered claims of a token of the format of this TokenService and decode its Claims.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token to decode.
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<Claims, Error>>` - The result of the operation.
+    fn decode<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Result<Claims, Error>>;
+
+    /// # Summary
+    ///
+    /// Check that a token was not issued in the future and that it is already valid, allowing for the configured
+    /// leeway. Tokens that were issued before the `nbf` claim was added do not have to contain it.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The Claims of the token.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// token_service.validate_time_claims(&claims)?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    fn validate_time_claims(&self, claims: &Claims) -> Result<(), Error> {
+        let now = chrono::Utc::now().timestamp() as u64;
+        let latest = now + self.config().leeway;
+
+        if claims.iat as u64 > latest {
+            error!("Error verifying token: the token was issued in the future");
+            return Err(Error::InvalidToken(String::from(
+                "Token was issued in the future",
+            )));
+        }
+
+        if claims.nbf.is_some_and(|nbf| nbf as u64 > latest) {
+            error!("Error verifying token: the token is not valid yet");
+            return Err(Error::InvalidToken(String::from("Token is not valid yet")));
+        }
+
+        Ok(())
+    }
+
+    /// # Summary
+    ///
+    /// Issue an access token.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The subject of the token.
+    /// * `tenant` - The ID of the tenant of the subject, if any.
+    /// * `roles` - The Role names to add as a claim, if enabled.
+    /// * `permissions` - The Permission names to add as a claim, if enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (token, claims) = token_service.issue_access("subject", None, None, None).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Option<(String, Claims)>>` - The token and the Claims that it contains.
+    fn issue_access(
+        &self,
+        subject: &str,
+        tenant: Option<&str>,
+        roles: Option<Vec<String>>,
+        permissions: Option<Vec<String>>,
+    ) -> BoxFuture<'_, Option<(String, Claims)>> {
+        let config = self.config();
+        let now = chrono::Utc::now();
+        let exp = now + chrono::Duration::seconds(config.jwt_expiration as i64);
+        let iat = now;
+
+        let mut roles = roles.filter(|_| config.roles_claim);
+        let mut permissions = permissions.filter(|_| config.permissions_claim);
+
+        // Keep the token small enough to fit in a header, resource servers have to look up the claims instead
+        let claims_size: usize = roles
+            .iter()
+            .chain(permissions.iter())
+            .flatten()
+            .map(|c| c.len())
+            .sum();
+        if claims_size > config.max_claims_size {
+            warn!(
+                "Omitting roles and permissions claims for {}: {} bytes exceeds the maximum of {} bytes",
+                subject, claims_size, config.max_claims_size
+            );
+            roles = None;
+            permissions = None;
+        }
+
+        let claims = Claims::new(
+            String::from(subject),
+            exp.timestamp() as usize,
+            iat.timestamp() as usize,
+            config.issuer.clone(),
+            config.audience.clone(),
+            tenant.map(String::from),
+            roles,
+            permissions,
+        );
+
+        self.encode(claims)
+    }
+
+    /// # Summary
+    ///
+    /// Issue a long-lived refresh token for the Session of an access token. Refresh tokens are restricted to their
+    /// scope, so they are never accepted as access tokens, and they do not contain the Role and Permission names of the
+    /// subject.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The subject of the token.
+    /// * `tenant` - The ID of the tenant of the subject, if any.
+    /// * `jti` - The ID of the access token, which identifies the Session.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let refresh = token_service.issue_refresh("subject", None, "jti").await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Option<(String, Claims)>>` - The token and the Claims that it contains, or None if refresh tokens
+    ///   are disabled.
+    fn issue_refresh(
+        &self,
+        subject: &str,
+        tenant: Option<&str>,
+        jti: &str,
+    ) -> BoxFuture<'_, Option<(String, Claims)>> {
+        let config = self.config();
+        if config.refresh_expiration == 0 {
+            return Box::pin(ready(None));
+        }
+
+        let now = chrono::Utc::now();
+        let exp = now + chrono::Duration::seconds(config.refresh_expiration as i64);
+
+        let mut claims = Claims::new(
+            String::from(subject),
+            exp.timestamp() as usize,
+            now.timestamp() as usize,
+            config.issuer.clone(),
+            config.audience.clone(),
+            tenant.map(String::from),
+            None,
+            None,
+        );
+        claims.jti = Some(String::from(jti));
+        claims.scope = Some(String::from(REFRESH_SCOPE));
+
+        self.encode(claims)
+    }
+
+    /// # Summary
+    ///
+    /// Issue a short-lived token that can only be used to change the password of the subject.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The subject of the token.
+    /// * `tenant` - The ID of the tenant of the subject, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (token, claims) = token_service.issue_password_change("subject", None).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Option<(String, Claims)>>` - The token and the Claims that it contains.
+    fn issue_password_change(
+        &self,
+        subject: &str,
+        tenant: Option<&str>,
+    ) -> BoxFuture<'_, Option<(String, Claims)>> {
+        let config = self.config();
+        let now = chrono::Utc::now();
+        let expiration = PASSWORD_CHANGE_TOKEN_EXPIRATION.min(config.jwt_expiration as i64);
+        let exp = now + chrono::Duration::seconds(expiration);
+
+        let mut claims = Claims::new(
+            String::from(subject),
+            exp.timestamp() as usize,
+            now.timestamp() as usize,
+            config.issuer.clone(),
+            config.audience.clone(),
+            tenant.map(String::from),
+            None,
+            None,
+        );
+        claims.scope = Some(String::from(PASSWORD_CHANGE_SCOPE));
+
+        self.encode(claims)
+    }
+
+    /// # Summary
+    ///
+    /// Verify an access token. Tokens that are restricted to a scope, such as password change tokens, are rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token to verify.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let claims = token_service.verify("token").await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<Claims, Error>>` - The result of the operation.
+    fn verify<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Result<Claims, Error>> {
+        Box::pin(async move {
+            let claims = self.decode(token).await?;
+            self.validate_time_claims(&claims)?;
+
+            if claims.scope.is_some() {
+                error!("Error verifying token: the token is restricted to a scope");
+                return Err(Error::InvalidToken(String::from(
+                    "Token is restricted to a scope",
+                )));
+            }
+
+            Ok(claims)
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Verify a token that was issued to change the password of the subject.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token to verify.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let claims = token_service.verify_password_change("token").await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<Claims, Error>>` - The result of the operation.
+    fn verify_password_change<'a>(
+        &'a self,
+        token: &'a str,
+    ) -> BoxFuture<'a, Result<Claims, Error>> {
+        Box::pin(async move {
+            let claims = self.decode(token).await?;
+            self.validate_time_claims(&claims)?;
+
+            if claims.scope.as_deref() != Some(PASSWORD_CHANGE_SCOPE) {
+                error!("Error verifying token: the token is not a password change token");
+                return Err(Error::InvalidToken(String::from(
+                    "Token is not a password change token",
+                )));
+            }
+
+            Ok(claims)
+        })
+    }
+
+    /// # Summary
+    ///
+    /// Verify a refresh token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token to verify.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let claims = token_service.verify_refresh("token").await;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `BoxFuture<Result<Claims, Error>>` - The result of the operation.
+    fn verify_refresh<'a>(&'a self, token: &'a str) -> BoxFuture<'a, Result<Claims, Error>> {
+        Box::pin(async move {
+            let claims = self.decode(token).await?;
+            self.validate_time_claims(&claims)?;
+
+            if claims.scope.as_deref() != Some(REFRESH_SCOPE) {
+                error!("Error verifying token: the token is not a refresh token");
+                return Err(Error::InvalidToken(String::from(
+                    "Token is not a refresh token",
+                )));
+            }
+
+            Ok(claims)
+        })
+    }
+}
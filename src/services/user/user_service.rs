@@ -1,16 +1,19 @@
-use crate::repository::audit::audit_model::Action::{Create, Delete, Update};
+use crate::repository::audit::audit_model::Action::{Create, Delete, Search, Update};
 use crate::repository::audit::audit_model::{Audit, ResourceIdType, ResourceType};
 use crate::repository::audit::audit_repository::Error as AuditError;
 use crate::repository::user::user_model::User;
-use crate::repository::user::user_repository::{Error, UserRepository};
+use crate::repository::user::user_repository::{Error, ImportOutcome, UserFilter, UserRepository};
 use crate::services::audit::audit_service::AuditService;
+use chrono::{DateTime, Utc};
 use log::{error, info};
 use mongodb::bson::oid::ObjectId;
-use mongodb::Database;
+use mongodb::bson::DateTime as BsonDateTime;
+use mongodb::{Client, ClientSession, Cursor, Database};
 
 #[derive(Clone)]
 pub struct UserService {
     pub user_repository: UserRepository,
+    pub transaction_client: Option<Client>,
 }
 
 impl UserService {
@@ -21,19 +24,23 @@ impl UserService {
     /// # Arguments
     ///
     /// * `user_repository` - The UserRepository to be used by the UserService.
+    /// * `transaction_client` - The Client that is used to start transactions, or None if the deployment does not support transactions.
     ///
     /// # Example
     ///
     /// ```
     /// let user_repository = UserRepository::new(String::from("users"));
-    /// let user_service = UserService::new(user_repository);
+    /// let user_service = UserService::new(user_repository, None);
     /// ```
     ///
     /// # Returns
     ///
     /// * `UserService` - The new UserService.
-    pub fn new(user_repository: UserRepository) -> UserService {
-        UserService { user_repository }
+    pub fn new(user_repository: UserRepository, transaction_client: Option<Client>) -> UserService {
+        UserService {
+            user_repository,
+            transaction_client,
+        }
     }
 
     /// # Summary
@@ -72,24 +79,88 @@ impl UserService {
     ) -> Result<User, Error> {
         info!("Creating User: {}", user);
 
-        if user_id.is_some() {
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Create,
-                user.id,
-                ResourceIdType::UserId,
-                ResourceType::User,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
+        let new_audit = audit_service.new_audit(
+            user_id,
+            Create,
+            user.id,
+            ResourceIdType::UserId,
+            ResourceType::User,
+        );
+
+        if let Some(client) = &self.transaction_client {
+            let mut session = match client.start_session(None).await {
+                Ok(s) => s,
+                Err(e) => return Err(Error::MongoDb(e)),
+            };
+            if let Err(e) = session.start_transaction(None).await {
+                return Err(Error::MongoDb(e));
+            }
+
+            let res = self
+                .create_in_transaction(user, new_audit, db, audit_service, &mut session)
+                .await;
+            return match res {
+                Ok(u) => match session.commit_transaction().await {
+                    Ok(_) => Ok(u),
+                    Err(e) => Err(Error::MongoDb(e)),
+                },
                 Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
+                    if let Err(abort_error) = session.abort_transaction().await {
+                        error!("Failed to abort transaction: {}", abort_error);
+                    }
+                    Err(e)
                 }
+            };
+        }
+
+        let user = self.user_repository.create(user, db).await?;
+
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
             }
         }
 
-        self.user_repository.create(user, db).await
+        Ok(user)
+    }
+
+    /// # Summary
+    ///
+    /// Create a User entity and its Audit in the transaction of a ClientSession.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User entity to be created.
+    /// * `audit` - The Audit of the creation.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    /// * `session` - The ClientSession with an active transaction.
+    ///
+    /// # Returns
+    ///
+    /// * `User` - The created User entity.
+    /// * `Error` - The Error that occurred.
+    async fn create_in_transaction(
+        &self,
+        user: User,
+        audit: Audit,
+        db: &Database,
+        audit_service: &AuditService,
+        session: &mut ClientSession,
+    ) -> Result<User, Error> {
+        let user = self
+            .user_repository
+            .create_with_session(user, db, session)
+            .await?;
+
+        if let Err(e) = audit_service.create_with_session(audit, db, session).await {
+            error!("Failed to create Audit: {}", e);
+            return Err(Error::Audit(e));
+        }
+
+        Ok(user)
     }
 
     /// # Summary
@@ -125,6 +196,151 @@ impl UserService {
         self.user_repository.find_all(limit, page, db).await
     }
 
+    /// # Summary
+    ///
+    /// Count the User entities that match a filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The UserFilter that the User entities must match.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = UserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let total = user_service.count(UserFilter::All, &db).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `u64` - The number of matching User entities.
+    /// * `Error` - The Error that occurred.
+    pub async fn count(&self, filter: UserFilter<'_>, db: &Database) -> Result<u64, Error> {
+        info!("Counting users");
+        self.user_repository.count(filter, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Find a page of User entities after the given cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `after` - The opaque cursor of the previous page, or None to start at the first page.
+    /// * `limit` - The maximum number of Users to return.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = UserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let (users, next_cursor) = user_service.find_after(None, Some(10), &db).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(Vec<User>, Option<String>), Error>` - The page of Users and the cursor of the next page, if any.
+    pub async fn find_after(
+        &self,
+        after: Option<&str>,
+        limit: Option<i64>,
+        db: &Database,
+    ) -> Result<(Vec<User>, Option<String>), Error> {
+        info!("Finding users after cursor: {:?}", after);
+        self.user_repository.find_after(after, limit, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Open a cursor over all User entities for an export.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = UserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let cursor = user_service.export(&db).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Cursor<User>` - The cursor over all User entities.
+    /// * `Error` - The Error that occurred.
+    pub async fn export(&self, db: &Database) -> Result<Cursor<User>, Error> {
+        info!("Exporting all users");
+        self.user_repository.export(db).await
+    }
+
+    /// # Summary
+    ///
+    /// Import a User entity while preserving its ID and password hashes.
+    ///
+    /// # Arguments
+    ///
+    /// * `user` - The User entity to be imported.
+    /// * `overwrite` - Whether an existing User with the same ID should be overwritten instead of skipped.
+    /// * `user_id` - The ID of the User entity that is importing the User.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = UserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let outcome = user_service.import(user, false, None, &db, &audit_service).await.unwrap();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `ImportOutcome` - Whether the User was created, overwritten or skipped.
+    /// * `Error` - The Error that occurred.
+    pub async fn import(
+        &self,
+        user: User,
+        overwrite: bool,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &AuditService,
+    ) -> Result<ImportOutcome, Error> {
+        info!("Importing User: {}", user);
+
+        let resource_id = user.id;
+        let outcome = self.user_repository.import(user, overwrite, db).await?;
+
+        // Whether the User is created or overwritten is only known after the import, so the Audit is created afterwards
+        let action = match outcome {
+            ImportOutcome::Created => Create,
+            ImportOutcome::Overwritten => Update,
+            ImportOutcome::Skipped => return Ok(outcome),
+        };
+
+        let new_audit = audit_service.new_audit(
+            user_id,
+            action,
+            resource_id,
+            ResourceIdType::UserId,
+            ResourceType::User,
+        );
+        if let Err(e) = audit_service.create(new_audit, db).await {
+            error!("Failed to create Audit: {}", e);
+            return Err(Error::Audit(e));
+        }
+
+        Ok(outcome)
+    }
+
     /// # Summary
     ///
     /// Find a User entity by ID.
@@ -153,6 +369,131 @@ impl UserService {
         self.user_repository.find_by_id(id, db).await
     }
 
+    /// # Summary
+    ///
+    /// Find all User entities whose ID is in a list of IDs, using a single query.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The IDs of the User entities.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let users = user_service.find_by_id_vec(vec![ObjectId::new()], &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<User>, Error>` - The User entities that were found, in no particular order.
+    pub async fn find_by_id_vec(
+        &self,
+        ids: Vec<ObjectId>,
+        db: &Database,
+    ) -> Result<Vec<User>, Error> {
+        info!("Finding Users by IDs: {:?}", ids);
+        self.user_repository.find_by_id_vec(ids, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Record a failed login of a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let failed_login_attempts = user_service.record_failed_login(&user.id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<u32, Error>` - The number of consecutive failed logins, including this one.
+    pub async fn record_failed_login(&self, id: &ObjectId, db: &Database) -> Result<u32, Error> {
+        info!("Recording failed login for User: {}", id);
+        self.user_repository.record_failed_login(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Lock a User entity until the given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity.
+    /// * `until` - The time at which the lock expires.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// user_service.lock(&user.id, until, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn lock(
+        &self,
+        id: &ObjectId,
+        until: BsonDateTime,
+        db: &Database,
+    ) -> Result<(), Error> {
+        info!("Locking User {} until {}", id, until);
+        self.user_repository.lock(id, until, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Reset the failed logins and the lock of a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// user_service.reset_failed_logins(&user.id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn reset_failed_logins(&self, id: &ObjectId, db: &Database) -> Result<(), Error> {
+        info!("Resetting failed logins for User: {}", id);
+        self.user_repository.reset_failed_logins(id, db).await
+    }
+
+    /// # Summary
+    ///
+    /// Record the time of a successful login of a User entity.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the User entity.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// user_service.record_login(&user.id, &db).await?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), Error>` - The result of the operation.
+    pub async fn record_login(&self, id: &ObjectId, db: &Database) -> Result<(), Error> {
+        info!("Recording login for User: {}", id);
+        self.user_repository.record_login(id, db).await
+    }
+
     /// # Summary
     ///
     /// Find a User entity by its username.
@@ -160,9 +501,8 @@ impl UserService {
     /// # Arguments
     ///
     /// * `username` - The username of the User entity.
-    /// * `user_id` - The ID of the User entity that is finding the User.
+    /// * `tenant_id` - The ID of the tenant of the User entity, which is ignored if tenancy is disabled.
     /// * `db` - The Database.
-    /// * `audit_service` - The AuditService.
     ///
     /// # Example
     ///
@@ -170,7 +510,7 @@ impl UserService {
     /// let user_repository = UserRepository::new(String::from("users"));
     /// let user_service = UserService::new(user_repository);
     /// let db = mongodb::Database::new();
-    /// let user = user_service.find_by_username("username", &db);
+    /// let user = user_service.find_by_username("username", None, &db);
     /// ```
     ///
     /// # Returns
@@ -179,10 +519,47 @@ impl UserService {
     pub async fn find_by_username(
         &self,
         username: &str,
+        tenant_id: Option<&str>,
         db: &Database,
     ) -> Result<Option<User>, Error> {
         info!("Finding User by username: {}", username);
-        self.user_repository.find_by_username(username, db).await
+        self.user_repository
+            .find_by_username(username, tenant_id, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Find a User entity by its username or its email.
+    ///
+    /// # Arguments
+    ///
+    /// * `identifier` - The username or email of the User entity.
+    /// * `tenant_id` - The ID of the tenant of the User entity, which is ignored if tenancy is disabled.
+    /// * `db` - The Database.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = UserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let user = user_service.find_by_username_or_email("username", None, &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Option<User>, Error>` - The result of the operation.
+    pub async fn find_by_username_or_email(
+        &self,
+        identifier: &str,
+        tenant_id: Option<&str>,
+        db: &Database,
+    ) -> Result<Option<User>, Error> {
+        info!("Finding User by username or email: {}", identifier);
+        self.user_repository
+            .find_by_username_or_email(identifier, tenant_id, db)
+            .await
     }
 
     /// # Summary
@@ -213,31 +590,32 @@ impl UserService {
     /// * `Error` - The Error that occurred.
     pub async fn update(
         &self,
-        user: User,
+        mut user: User,
         user_id: Option<ObjectId>,
         db: &Database,
         audit_service: &AuditService,
     ) -> Result<User, Error> {
         info!("Updating User: {}", user);
 
-        if user_id.is_some() {
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Update,
-                user.id,
-                ResourceIdType::UserId,
-                ResourceType::User,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
+        user.updated_by = user_id.map(|u| u.to_hex());
+        let user = self.user_repository.update(user, db).await?;
+
+        let new_audit = audit_service.new_audit(
+            user_id,
+            Update,
+            user.id,
+            ResourceIdType::UserId,
+            ResourceType::User,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
             }
         }
 
-        self.user_repository.update(user, db).await
+        Ok(user)
     }
 
     /// # Summary
@@ -248,6 +626,8 @@ impl UserService {
     ///
     /// * `id` - The ID of the User entity to be updated.
     /// * `password` - The new password of the User entity.
+    /// * `password_history` - The new password history of the User entity, or None to leave it untouched.
+    /// * `must_change_password` - Whether the User must change the password on the next login.
     /// * `user_id` - The ID of the User entity that is updating the User.
     /// * `db` - The Database to be used.
     /// * `audit_service` - The AuditService to be used.
@@ -260,48 +640,60 @@ impl UserService {
     /// let db = mongodb::Database::new();
     /// let audit_service = AuditService::new(AuditRepository::new(String::from("audits")));
     ///
-    /// let user = user_service.update_password("id", "password", ObjectId::parse_str("user_id").unwrap(), &db);
+    /// let user = user_service.update_password("id", "password", None, false, ObjectId::parse_str("user_id").unwrap(), &db);
     /// ```
     ///
     /// # Returns
     ///
     /// * `()` - The update operation was successful.
     /// * `Error` - The Error that occurred.
+    #[allow(clippy::too_many_arguments)]
     pub async fn update_password(
         &self,
         id: &str,
         password: &str,
+        password_history: Option<Vec<String>>,
+        must_change_password: bool,
         user_id: Option<ObjectId>,
         db: &Database,
         audit_service: &AuditService,
     ) -> Result<(), Error> {
         info!("Updating User password: {}", id);
 
-        if user_id.is_some() {
-            let oid = match ObjectId::parse_str(id) {
-                Ok(oid) => oid,
-                Err(e) => {
-                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
-                }
-            };
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(e) => {
+                return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+            }
+        };
 
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Update,
-                oid,
-                ResourceIdType::UserId,
-                ResourceType::User,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
+        self.user_repository
+            .update_password(
+                id,
+                password,
+                password_history,
+                must_change_password,
+                user_id.map(|u| u.to_hex()),
+                db,
+            )
+            .await?;
+
+        let new_audit = audit_service.new_audit(
+            user_id,
+            Update,
+            oid,
+            ResourceIdType::UserId,
+            ResourceType::User,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
             }
         }
 
-        self.user_repository.update_password(id, password, db).await
+        Ok(())
     }
 
     /// # Summary
@@ -339,31 +731,31 @@ impl UserService {
     ) -> Result<(), Error> {
         info!("Deleting User: {}", id);
 
-        if user_id.is_some() {
-            let oid = match ObjectId::parse_str(id) {
-                Ok(oid) => oid,
-                Err(e) => {
-                    return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
-                }
-            };
+        let oid = match ObjectId::parse_str(id) {
+            Ok(oid) => oid,
+            Err(e) => {
+                return Err(Error::Audit(AuditError::ObjectId(e.to_string())));
+            }
+        };
 
-            let new_audit = Audit::new(
-                user_id.unwrap(),
-                Delete,
-                oid,
-                ResourceIdType::UserId,
-                ResourceType::User,
-            );
-            match audit_service.create(new_audit, db).await {
-                Ok(_) => {}
-                Err(e) => {
-                    error!("Failed to create Audit: {}", e);
-                    return Err(Error::Audit(e));
-                }
+        self.user_repository.delete(id, db).await?;
+
+        let new_audit = audit_service.new_audit(
+            user_id,
+            Delete,
+            oid,
+            ResourceIdType::UserId,
+            ResourceType::User,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
             }
         }
 
-        self.user_repository.delete(id, db).await
+        Ok(())
     }
 
     /// # Summary
@@ -400,6 +792,32 @@ impl UserService {
             .await
     }
 
+    /// # Summary
+    ///
+    /// Delete a Role from all Users as part of the transaction of a ClientSession.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_id` - The ID of the Role entity to be deleted from all Users.
+    /// * `db` - The Database to be used.
+    /// * `session` - The ClientSession with an active transaction.
+    ///
+    /// # Returns
+    ///
+    /// * `()` - The delete operation was successful.
+    /// * `Error` - The Error that occurred.
+    pub async fn delete_role_from_all_users_with_session(
+        &self,
+        role_id: &str,
+        db: &Database,
+        session: &mut ClientSession,
+    ) -> Result<(), Error> {
+        info!("Deleting Role from all Users: {}", role_id);
+        self.user_repository
+            .delete_role_from_all_users_with_session(role_id, db, session)
+            .await
+    }
+
     /// # Summary
     ///
     /// Search for Users.
@@ -434,4 +852,141 @@ impl UserService {
         info!("Searching Users: {}", text);
         self.user_repository.search(text, limit, page, db).await
     }
+
+    /// # Summary
+    ///
+    /// Search for Users whose username, email, first name or last name contain the query.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The (partial) text to search for.
+    /// * `limit` - The maximum number of Users to return.
+    /// * `page` - The page of Users to return.
+    /// * `user_id` - The ID of the User entity that is searching for Users.
+    /// * `db` - The Database to be used.
+    /// * `audit_service` - The AuditService to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = UserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let users = user_service.search_partial("jan", Some(10), Some(1), Some(user_id), &db, &audit_service);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<User>` - The Users that match the query.
+    /// * `Error` - The Error that occurred.
+    pub async fn search_partial(
+        &self,
+        query: &str,
+        limit: Option<i64>,
+        page: Option<i64>,
+        user_id: Option<ObjectId>,
+        db: &Database,
+        audit_service: &AuditService,
+    ) -> Result<Vec<User>, Error> {
+        info!("Searching Users by partial match: {}", query);
+
+        let users = self
+            .user_repository
+            .search_partial(query, limit, page, db)
+            .await?;
+
+        // A search does not target a single User, so the Audit receives its own resource ID
+        let new_audit = audit_service.new_audit(
+            user_id,
+            Search,
+            ObjectId::new(),
+            ResourceIdType::UserSearch,
+            ResourceType::User,
+        );
+        match audit_service.create(new_audit, db).await {
+            Ok(_) => {}
+            Err(e) => {
+                error!("Failed to create Audit: {}", e);
+                return Err(Error::Audit(e));
+            }
+        }
+
+        Ok(users)
+    }
+
+    /// # Summary
+    ///
+    /// Find all Users that have at least one of the given Roles.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_ids` - The IDs of the Role entities.
+    /// * `limit` - The maximum number of Users to return.
+    /// * `page` - The page of Users to return.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = UserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let users = user_service.find_by_role(vec![String::from("role_id")], Some(10), Some(1), &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<User>` - The Users that have at least one of the given Roles.
+    /// * `Error` - The Error that occurred.
+    pub async fn find_by_role(
+        &self,
+        role_ids: Vec<String>,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<User>, Error> {
+        info!("Finding Users by Roles: {:?}", role_ids);
+        self.user_repository
+            .find_by_role(role_ids, limit, page, db)
+            .await
+    }
+
+    /// # Summary
+    ///
+    /// Find all Users that were created within a date range.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The inclusive start of the range, or None for an open start.
+    /// * `to` - The exclusive end of the range, or None for an open end.
+    /// * `limit` - The maximum number of Users to return.
+    /// * `page` - The page of Users to return.
+    /// * `db` - The Database to be used.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_repository = UserRepository::new(String::from("users"));
+    /// let user_service = UserService::new(user_repository);
+    /// let db = mongodb::Database::new();
+    /// let users = user_service.find_by_date_range(Some(Utc::now() - Duration::days(30)), None, Some(10), Some(1), &db);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<User>` - The Users that were created within the date range.
+    /// * `Error` - The Error that occurred.
+    pub async fn find_by_date_range(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: Option<i64>,
+        page: Option<i64>,
+        db: &Database,
+    ) -> Result<Vec<User>, Error> {
+        info!("Finding Users created between {:?} and {:?}", from, to);
+        self.user_repository
+            .find_by_date_range(from, to, limit, page, db)
+            .await
+    }
 }
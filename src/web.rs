@@ -1,3 +1,5 @@
 pub mod controller;
 pub mod dto;
 pub mod extractors;
+pub mod middleware;
+pub mod streaming;
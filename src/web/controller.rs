@@ -1,16 +1,31 @@
+use crate::configuration::server_config::ServerConfig;
+use crate::web::controller::admin::admin_controller;
 use crate::web::controller::authentication::authentication_controller;
+use crate::web::controller::authz::authz_controller;
+use crate::web::controller::debug::debug_controller;
 use crate::web::controller::health::health_controller;
+use crate::web::controller::invite::invite_controller;
 use crate::web::controller::permission::permission_controller;
 use crate::web::controller::role::role_controller;
+use crate::web::controller::session::session_controller;
 use crate::web::controller::user::user_controller;
+use crate::web::controller::version::version_controller;
+use crate::web::controller::well_known::well_known_controller;
 use actix_web::web;
 
+pub mod admin;
 pub mod audit;
 pub mod authentication;
+pub mod authz;
+pub mod debug;
 pub mod health;
+pub mod invite;
 pub mod permission;
 pub mod role;
+pub mod session;
 pub mod user;
+pub mod version;
+pub mod well_known;
 
 pub struct Controller {}
 
@@ -22,49 +37,93 @@ impl Controller {
     /// # Arguments
     ///
     /// * `cfg` - The web server configuration.
-    pub fn configure_routes(cfg: &mut web::ServiceConfig) {
-        cfg.service(
-            web::scope("/api/v1")
-                .service(
-                    web::scope("/permissions")
-                        .service(permission_controller::create_permission)
-                        .service(permission_controller::find_all_permissions)
-                        .service(permission_controller::find_by_id)
-                        .service(permission_controller::update_permission)
-                        .service(permission_controller::delete_permission),
-                )
-                .service(
-                    web::scope("/roles")
-                        .service(role_controller::create)
-                        .service(role_controller::find_all_roles)
-                        .service(role_controller::find_by_id)
-                        .service(role_controller::update)
-                        .service(role_controller::delete),
-                )
-                .service(
-                    web::scope("/users")
-                        .service(user_controller::create)
-                        .service(user_controller::find_all)
-                        .service(user_controller::find_by_id)
-                        .service(user_controller::update)
-                        .service(user_controller::update_password)
-                        .service(user_controller::admin_update_password)
-                        .service(user_controller::delete)
-                        .service(user_controller::delete_self),
-                )
-                .service(
-                    web::scope("/authentication")
-                        .service(authentication_controller::login)
-                        .service(authentication_controller::current_user)
-                        .service(authentication_controller::register),
-                )
-                .service(
-                    web::scope("/audits")
-                        .service(audit::audit_controller::find_all)
-                        .service(audit::audit_controller::find_by_id),
-                ),
-        );
+    /// * `server_config` - The ServerConfig that holds the route prefix.
+    pub fn configure_routes(cfg: &mut web::ServiceConfig, server_config: &ServerConfig) {
+        let health = web::scope("/health")
+            .service(health_controller::health)
+            .service(health_controller::live)
+            .service(health_controller::ready);
 
-        cfg.service(web::scope("/health").service(health_controller::health));
+        let mut api = web::scope(&server_config.route_prefix)
+            .service(
+                web::scope("/permissions")
+                    .service(permission_controller::create_permission)
+                    .service(permission_controller::find_all_permissions)
+                    .service(permission_controller::find_by_id)
+                    .service(permission_controller::update_permission)
+                    .service(permission_controller::delete_permission),
+            )
+            .service(
+                web::scope("/roles")
+                    .service(role_controller::create)
+                    .service(role_controller::create_from_template)
+                    .service(role_controller::find_all_roles)
+                    .service(role_controller::find_by_id)
+                    .service(role_controller::update)
+                    .service(role_controller::delete)
+                    .service(role_controller::add_permissions)
+                    .service(role_controller::remove_permission),
+            )
+            .service(
+                web::scope("/users")
+                    .service(user_controller::create)
+                    .service(user_controller::find_all)
+                    .service(user_controller::search_users)
+                    .service(session_controller::find_own_sessions)
+                    .service(session_controller::revoke_own_session)
+                    .service(session_controller::revoke_all_own_sessions)
+                    .service(user_controller::find_own_permissions)
+                    .service(user_controller::patch_self)
+                    .service(user_controller::confirm_email_change)
+                    .service(user_controller::find_by_id)
+                    .service(user_controller::update)
+                    .service(user_controller::update_password)
+                    .service(user_controller::admin_update_password)
+                    .service(user_controller::admin_reset_password)
+                    .service(user_controller::delete)
+                    .service(user_controller::delete_self),
+            )
+            .service(
+                web::scope("/authentication")
+                    .service(authentication_controller::login)
+                    .service(authentication_controller::refresh)
+                    .service(authentication_controller::current_user)
+                    .service(authentication_controller::introspect)
+                    .service(authentication_controller::change_password)
+                    .service(authentication_controller::register),
+            )
+            .service(web::scope("/authz").service(authz_controller::check))
+            .service(
+                web::scope("/audits")
+                    .service(audit::audit_controller::find_all)
+                    .service(audit::audit_controller::delete_before)
+                    .service(audit::audit_controller::stats)
+                    .service(audit::audit_controller::find_by_id),
+            )
+            .service(
+                web::scope("/invites")
+                    .service(invite_controller::create)
+                    .service(invite_controller::find_all),
+            )
+            .service(
+                web::scope("/admin")
+                    .service(admin_controller::export_users)
+                    .service(admin_controller::export_audits)
+                    .service(admin_controller::import_users),
+            );
+
+        if server_config.dev_mode {
+            api = api.service(web::scope("/debug").service(debug_controller::whoami));
+        }
+
+        if server_config.prefix_health {
+            api = api.service(health);
+        } else {
+            cfg.service(health);
+        }
+
+        cfg.service(web::scope("/.well-known").service(well_known_controller::jwks));
+        cfg.service(version_controller::version);
+        cfg.service(api);
     }
 }
@@ -0,0 +1,252 @@
+use crate::configuration::config::Config;
+use crate::configuration::server_config::ImportConflictPolicy;
+use crate::errors::bad_request::BadRequest;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::repository::user::user_model::User;
+use crate::repository::user::user_repository::ImportOutcome;
+use crate::web::controller::audit::audit_controller::parse_date;
+use crate::web::dto::audit::audit_dto::AuditDto;
+use crate::web::dto::audit::audit_stats_request::AuditStatsRequest;
+use crate::web::dto::user::user_export::UserExport;
+use crate::web::dto::user::user_import_response::UserImportResponse;
+use crate::web::extractors::authenticated_user::AuthenticatedUser;
+use crate::web::streaming::stream_cursor_as_ndjson;
+use actix_web::{get, post, web, HttpResponse};
+use actix_web_grants::protect;
+use futures::StreamExt;
+use log::error;
+use mongodb::bson::oid::ObjectId;
+
+/// The maximum length of a single line of a User import in bytes, so that a payload without newlines cannot exhaust
+/// the memory of the server.
+const MAX_IMPORT_LINE_LENGTH: usize = 64 * 1024;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/export/users",
+    responses(
+        (status = 200, description = "OK, one UserExport per line", body = UserExport, content_type = "application/x-ndjson"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Admin",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/export/users")]
+#[protect("CAN_EXPORT_USER")]
+pub async fn export_users(pool: web::Data<Config>) -> HttpResponse {
+    let cursor = match pool.services.user_service.export(&pool.database).await {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Error while exporting Users: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    stream_cursor_as_ndjson(cursor, UserExport::from)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/export/audits",
+    params(
+        ("from" = Option<String>, Query, description = "The inclusive start of the date range, in RFC 3339 format", nullable = true),
+        ("to" = Option<String>, Query, description = "The exclusive end of the date range, in RFC 3339 format", nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OK, one AuditDto per line", body = AuditDto, content_type = "application/x-ndjson"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Admin",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/export/audits")]
+#[protect("CAN_READ_AUDIT")]
+pub async fn export_audits(
+    request: web::Query<AuditStatsRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let request = request.into_inner();
+
+    let from = match parse_date(request.from.as_deref(), "from") {
+        Ok(d) => d,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+    let to = match parse_date(request.to.as_deref(), "to") {
+        Ok(d) => d,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    if let (Some(f), Some(t)) = (from, to) {
+        if f > t {
+            return HttpResponse::BadRequest().json(BadRequest::for_field(
+                "The start of the date range must not be after the end",
+                Some("from"),
+            ));
+        }
+    }
+
+    let cursor = match pool
+        .services
+        .audit_service
+        .export(from, to, &pool.database)
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Error while exporting Audits: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    stream_cursor_as_ndjson(cursor, AuditDto::from)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/import/users",
+    request_body(content = UserExport, description = "One UserExport per line", content_type = "application/x-ndjson"),
+    responses(
+        (status = 200, description = "OK", body = UserImportResponse),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+    ),
+    tag = "Admin",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/import/users")]
+#[protect("CAN_IMPORT_USER")]
+pub async fn import_users(
+    mut payload: web::Payload,
+    pool: web::Data<Config>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+) -> HttpResponse {
+    let mut response = UserImportResponse::default();
+    let mut buffer: Vec<u8> = vec![];
+    let mut line_number: usize = 0;
+
+    // The payload is processed line by line, so that large imports do not have to fit in memory
+    while let Some(chunk) = payload.next().await {
+        let chunk = match chunk {
+            Ok(c) => c,
+            Err(e) => {
+                error!("Error while reading the User import: {}", e);
+                return HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()));
+            }
+        };
+        buffer.extend_from_slice(&chunk);
+
+        while let Some(position) = buffer.iter().position(|b| *b == b'\n') {
+            line_number += 1;
+            if position > MAX_IMPORT_LINE_LENGTH {
+                return line_too_long(line_number);
+            }
+
+            let line: Vec<u8> = buffer.drain(..=position).collect();
+            import_line(&line, line_number, current_user.id, &pool, &mut response).await;
+        }
+
+        if buffer.len() > MAX_IMPORT_LINE_LENGTH {
+            return line_too_long(line_number + 1);
+        }
+    }
+
+    if !buffer.is_empty() {
+        line_number += 1;
+        import_line(&buffer, line_number, current_user.id, &pool, &mut response).await;
+    }
+
+    HttpResponse::Ok().json(response)
+}
+
+/// # Summary
+///
+/// Create the response of a User import that contains a line that is longer than `MAX_IMPORT_LINE_LENGTH`. The lines
+/// before it have already been imported.
+///
+/// # Arguments
+///
+/// * `line_number` - The number of the line that is too long.
+///
+/// # Returns
+///
+/// * `HttpResponse` - 400 Bad Request.
+fn line_too_long(line_number: usize) -> HttpResponse {
+    HttpResponse::BadRequest().json(BadRequest::new(&format!(
+        "Line {} is larger than the limit of {} bytes",
+        line_number, MAX_IMPORT_LINE_LENGTH
+    )))
+}
+
+/// # Summary
+///
+/// Import a single line of a User import and record the outcome.
+///
+/// # Arguments
+///
+/// * `line` - The line, containing a single UserExport.
+/// * `line_number` - The number of the line, used in error messages.
+/// * `user_id` - The ID of the User that is importing the Users.
+/// * `pool` - The Config.
+/// * `response` - The UserImportResponse to record the outcome in.
+async fn import_line(
+    line: &[u8],
+    line_number: usize,
+    user_id: ObjectId,
+    pool: &Config,
+    response: &mut UserImportResponse,
+) {
+    if line.iter().all(|b| b.is_ascii_whitespace()) {
+        return;
+    }
+
+    let user = match serde_json::from_slice::<UserExport>(line)
+        .map_err(|e| e.to_string())
+        .and_then(User::try_from)
+    {
+        Ok(u) => u,
+        Err(e) => {
+            response.errors.push(format!("Line {}: {}", line_number, e));
+            return;
+        }
+    };
+
+    let overwrite = matches!(
+        pool.server_config.import_conflict_policy,
+        ImportConflictPolicy::Overwrite
+    );
+
+    match pool
+        .services
+        .user_service
+        .import(
+            user,
+            overwrite,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(ImportOutcome::Created) => response.created += 1,
+        Ok(ImportOutcome::Overwritten) => response.overwritten += 1,
+        Ok(ImportOutcome::Skipped) => response.skipped += 1,
+        Err(e) => {
+            error!("Error while importing User on line {}: {}", line_number, e);
+            response.errors.push(format!("Line {}: {}", line_number, e));
+        }
+    }
+}
@@ -1,22 +1,45 @@
 use crate::configuration::config::Config;
+use crate::errors::bad_request::BadRequest;
 use crate::errors::internal_server_error::InternalServerError;
+use crate::repository::audit::audit_model::Audit;
+use crate::repository::audit::audit_repository::Error;
+use crate::repository::cursor::NEXT_CURSOR_HEADER;
+use crate::repository::user::user_model::User;
+use crate::repository::user::user_repository::Error as UserError;
+use crate::web::dto::audit::audit_actor_dto::AuditActorDto;
 use crate::web::dto::audit::audit_dto::AuditDto;
-use crate::web::dto::search::search_request::SearchRequest;
-use actix_web::{get, web, HttpResponse};
+use crate::web::dto::audit::audit_stats_dto::AuditStatsDto;
+use crate::web::dto::audit::audit_stats_request::AuditStatsRequest;
+use crate::web::dto::audit::delete_audits::{DeleteAuditsRequest, DeleteAuditsResponse};
+use crate::web::dto::common::page::{resolve_limit, Page};
+use crate::web::dto::search::audit_search_request::AuditSearchRequest;
+use crate::web::extractors::authenticated_user::AuthenticatedUser;
+use actix_web::{delete, get, web, HttpResponse};
 use actix_web_grants::protect;
-use log::error;
+use chrono::{DateTime, Utc};
+use log::{error, warn};
+use mongodb::bson::oid::ObjectId;
+use std::collections::HashMap;
 
 #[utoipa::path(
     get,
     path = "/api/v1/audits/",
     params(
         ("text" = Option<String>, Query, description = "The text to search for", nullable = true),
-        ("limit" = Option<i64>, Query, description = "The limit of audits to retrieve", nullable = true),
+        ("system" = Option<bool>, Query, description = "Only retrieve system audits if true, or only user audits if false", nullable = true),
+        ("limit" = Option<i64>, Query, description = "The limit of audits to retrieve, capped to MAX_PAGE_SIZE", nullable = true),
         ("page" = Option<i64>, Query, description = "The page", nullable = true),
+        ("after" = Option<String>, Query, description = "The cursor of the previous page, as returned in the X-Next-Cursor header", nullable = true),
+        ("resourceId" = Option<String>, Query, description = "Only retrieve the audits of the resource with this ID", nullable = true),
+        ("resourceIdType" = Option<String>, Query, description = "The type of the resource ID, such as userId, roleId or permissionId. Required if resourceId is set", nullable = true),
+        ("from" = Option<String>, Query, description = "The inclusive start of the date range, in RFC 3339 format. Requires resourceId", nullable = true),
+        ("to" = Option<String>, Query, description = "The exclusive end of the date range, in RFC 3339 format. Requires resourceId", nullable = true),
+        ("expandActor" = Option<bool>, Query, description = "Add a summary of the acting user to every user audit", nullable = true),
     ),
     responses(
-        (status = 200, description = "OK", body = Vec<AuditDto>),
+        (status = 200, description = "OK", body = crate::web::dto::common::page::AuditPage),
         (status = 204, description = "No Content"),
+        (status = 400, description = "Bad Request", body = crate::errors::bad_request::BadRequest),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Audits",
@@ -26,24 +49,44 @@ use log::error;
 )]
 #[get("/")]
 #[protect("CAN_READ_AUDIT")]
-pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>) -> HttpResponse {
+pub async fn find_all(
+    search: web::Query<AuditSearchRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
     let search = search.into_inner();
 
-    let mut limit = search.limit;
     let page = search.page;
 
-    let limit_clone = limit.unwrap_or(pool.server_config.max_limit);
-    if limit.is_none()
-        || (limit.is_some() && limit_clone > pool.server_config.max_limit || limit_clone < 1)
-    {
-        limit = Some(pool.server_config.max_limit);
+    let limit = match resolve_limit(search.limit, pool.server_config.max_page_size) {
+        Ok(l) => Some(l),
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    if search.after.is_some() && (search.text.is_some() || page.is_some()) {
+        return HttpResponse::BadRequest().json(BadRequest::for_field(
+            "The after cursor cannot be combined with the text or page parameters",
+            Some("after"),
+        ));
+    }
+
+    if search.resource_id.is_some() || search.resource_id_type.is_some() {
+        return find_by_resource(search, limit, &pool).await;
     }
 
-    let res = match search.text {
+    if search.from.is_some() || search.to.is_some() {
+        return HttpResponse::BadRequest().json(BadRequest::for_field(
+            "A date range can only be used together with the resourceId parameter",
+            Some(if search.from.is_some() { "from" } else { "to" }),
+        ));
+    }
+
+    let cursor_based = search.text.is_none() && page.is_none();
+    let mut next_cursor: Option<String> = None;
+    let res = match &search.text {
         Some(t) => match pool
             .services
             .audit_service
-            .search(&t, limit, page, &pool.database)
+            .search(t, limit, page, search.system, &pool.database)
             .await
         {
             Ok(d) => d,
@@ -53,10 +96,10 @@ pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>
                     .json(InternalServerError::new(&e.to_string()));
             }
         },
-        None => match pool
+        None if page.is_some() => match pool
             .services
             .audit_service
-            .find_all(limit, page, &pool.database)
+            .find_all(limit, page, search.system, &pool.database)
             .await
         {
             Ok(d) => d,
@@ -66,15 +109,254 @@ pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>
                     .json(InternalServerError::new(&e.to_string()));
             }
         },
+        None => match pool
+            .services
+            .audit_service
+            .find_after(
+                search.after.as_deref(),
+                limit,
+                search.system,
+                &pool.database,
+            )
+            .await
+        {
+            Ok((d, c)) => {
+                next_cursor = c;
+                d
+            }
+            Err(e) => {
+                error!("Error while finding audits after cursor: {}", e);
+                return match e {
+                    Error::InvalidCursor(_) => HttpResponse::BadRequest()
+                        .json(BadRequest::for_field(&e.to_string(), Some("after"))),
+                    _ => HttpResponse::InternalServerError()
+                        .json(InternalServerError::new(&e.to_string())),
+                };
+            }
+        },
     };
 
     if res.is_empty() {
         return HttpResponse::NoContent().finish();
     }
 
-    let dto_list = res.into_iter().map(|p| p.into()).collect::<Vec<AuditDto>>();
+    let total = match pool
+        .services
+        .audit_service
+        .count(search.text.as_deref(), search.system, &pool.database)
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Error while counting audits: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    let dto_list = match to_dto_list(res, search.expand_actor.unwrap_or(false), &pool).await {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error while finding the actors of audits: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+    let limit = limit.unwrap_or(pool.server_config.max_page_size);
+
+    let mut response = HttpResponse::Ok();
+    if let Some(c) = &next_cursor {
+        response.insert_header((NEXT_CURSOR_HEADER, c.as_str()));
+    }
+
+    if cursor_based {
+        response.json(Page::with_cursor(dto_list, total, limit, next_cursor))
+    } else {
+        response.json(Page::new(dto_list, total, limit, page))
+    }
+}
+
+/// # Summary
+///
+/// Find a page of the Audits of a single resource, optionally within a date range.
+///
+/// # Arguments
+///
+/// * `search` - The AuditSearchRequest that contains the resource ID and its type.
+/// * `limit` - The already validated limit of Audits to find.
+/// * `pool` - The Config.
+///
+/// # Returns
+///
+/// * `HttpResponse` - The page of Audits, or an error response.
+async fn find_by_resource(
+    search: AuditSearchRequest,
+    limit: Option<i64>,
+    pool: &Config,
+) -> HttpResponse {
+    let (resource_id, resource_id_type) = match (&search.resource_id, &search.resource_id_type) {
+        (Some(i), Some(t)) => (i, t),
+        (None, _) => {
+            return HttpResponse::BadRequest().json(BadRequest::for_field(
+                "The resourceIdType parameter requires the resourceId parameter",
+                Some("resourceId"),
+            ))
+        }
+        (_, None) => {
+            return HttpResponse::BadRequest().json(BadRequest::for_field(
+                "The resourceId parameter requires the resourceIdType parameter",
+                Some("resourceIdType"),
+            ))
+        }
+    };
+
+    if search.text.is_some() || search.after.is_some() {
+        return HttpResponse::BadRequest().json(BadRequest::for_field(
+            "The resourceId parameter cannot be combined with the text or after parameters",
+            Some("resourceId"),
+        ));
+    }
+
+    let from = match parse_date(search.from.as_deref(), "from") {
+        Ok(d) => d,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+    let to = match parse_date(search.to.as_deref(), "to") {
+        Ok(d) => d,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    if let (Some(f), Some(t)) = (from, to) {
+        if f > t {
+            return HttpResponse::BadRequest().json(BadRequest::for_field(
+                "The start of the date range must not be after the end",
+                Some("from"),
+            ));
+        }
+    }
+
+    let res = match pool
+        .services
+        .audit_service
+        .find_by_resource(
+            resource_id,
+            resource_id_type,
+            from,
+            to,
+            limit,
+            search.page,
+            &pool.database,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!(
+                "Error while finding audits of resource {}: {}",
+                resource_id, e
+            );
+            return match e {
+                Error::InvalidId(_) => HttpResponse::BadRequest()
+                    .json(BadRequest::for_field(&e.to_string(), Some("resourceId"))),
+                _ => HttpResponse::InternalServerError()
+                    .json(InternalServerError::new(&e.to_string())),
+            };
+        }
+    };
+
+    if res.is_empty() {
+        return HttpResponse::NoContent().finish();
+    }
+
+    let total = match pool
+        .services
+        .audit_service
+        .count_by_resource(resource_id, resource_id_type, from, to, &pool.database)
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!(
+                "Error while counting audits of resource {}: {}",
+                resource_id, e
+            );
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    let dto_list = match to_dto_list(res, search.expand_actor.unwrap_or(false), pool).await {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error while finding the actors of audits: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+    let limit = limit.unwrap_or(pool.server_config.max_page_size);
 
-    HttpResponse::Ok().json(dto_list)
+    HttpResponse::Ok().json(Page::new(dto_list, total, limit, search.page))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/audits/stats",
+    params(
+        ("from" = Option<String>, Query, description = "The inclusive start of the date range, in RFC 3339 format", nullable = true),
+        ("to" = Option<String>, Query, description = "The exclusive end of the date range, in RFC 3339 format", nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OK", body = AuditStatsDto),
+        (status = 400, description = "Bad Request", body = crate::errors::bad_request::BadRequest),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Audits",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/stats")]
+#[protect("CAN_READ_AUDIT")]
+pub async fn stats(
+    request: web::Query<AuditStatsRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let request = request.into_inner();
+
+    let from = match parse_date(request.from.as_deref(), "from") {
+        Ok(d) => d,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+    let to = match parse_date(request.to.as_deref(), "to") {
+        Ok(d) => d,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    if let (Some(f), Some(t)) = (from, to) {
+        if f > t {
+            return HttpResponse::BadRequest().json(BadRequest::for_field(
+                "The start of the date range must not be after the end",
+                Some("from"),
+            ));
+        }
+    }
+
+    match pool
+        .services
+        .audit_service
+        .stats(from, to, &pool.database)
+        .await
+    {
+        Ok(s) => HttpResponse::Ok().json(AuditStatsDto::new(
+            s,
+            from.map(|f| f.to_rfc3339()),
+            to.map(|t| t.to_rfc3339()),
+        )),
+        Err(e) => {
+            error!("Error while counting audits: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
 }
 
 #[utoipa::path(
@@ -115,3 +397,153 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
 
     HttpResponse::Ok().json(AuditDto::from(res))
 }
+
+/// # Summary
+///
+/// Convert Audits into AuditDtos, optionally adding a summary of the acting User of every User Audit. The actors are
+/// looked up with a single query, and actors that no longer exist are marked as deleted.
+///
+/// # Arguments
+///
+/// * `audits` - The Audits to convert.
+/// * `expand_actor` - Whether the acting Users should be added.
+/// * `pool` - The Config.
+///
+/// # Returns
+///
+/// * `Result<Vec<AuditDto>, UserError>` - The AuditDtos, or an error if the actors could not be found.
+async fn to_dto_list(
+    audits: Vec<Audit>,
+    expand_actor: bool,
+    pool: &Config,
+) -> Result<Vec<AuditDto>, UserError> {
+    if !expand_actor {
+        return Ok(audits.into_iter().map(|a| a.into()).collect());
+    }
+
+    // System audits are not initiated by a User, so their actor is never looked up
+    let mut actor_ids: Vec<ObjectId> = audits
+        .iter()
+        .filter(|a| !a.system)
+        .map(|a| a.user_id)
+        .collect();
+    actor_ids.sort();
+    actor_ids.dedup();
+
+    let actors: HashMap<ObjectId, User> = pool
+        .services
+        .user_service
+        .find_by_id_vec(actor_ids, &pool.database)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u))
+        .collect();
+
+    Ok(audits
+        .into_iter()
+        .map(|a| {
+            let actor = match a.system {
+                true => None,
+                false => Some(match actors.get(&a.user_id) {
+                    Some(u) => AuditActorDto::from(u),
+                    None => AuditActorDto::deleted(&a.user_id),
+                }),
+            };
+            let mut dto = AuditDto::from(a);
+            dto.actor = actor;
+            dto
+        })
+        .collect())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/audits/",
+    params(
+        ("before" = String, Query, description = "Delete the audits that were created before this date, in RFC 3339 format"),
+        ("confirm" = bool, Query, description = "Must be true to confirm that the audits should be deleted"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = DeleteAuditsResponse),
+        (status = 400, description = "Bad Request", body = crate::errors::bad_request::BadRequest),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Audits",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/")]
+#[protect("CAN_DELETE_AUDIT")]
+pub async fn delete_before(
+    request: web::Query<DeleteAuditsRequest>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let request = request.into_inner();
+
+    let before = match parse_date(request.before.as_deref(), "before") {
+        Ok(Some(d)) => d,
+        Ok(None) => {
+            return HttpResponse::BadRequest().json(BadRequest::for_field(
+                "The before parameter is required",
+                Some("before"),
+            ))
+        }
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    // Deleting audits cannot be undone, so the request has to be confirmed explicitly
+    if request.confirm != Some(true) {
+        return HttpResponse::BadRequest().json(BadRequest::for_field(
+            "The confirm parameter must be true to delete audits",
+            Some("confirm"),
+        ));
+    }
+
+    match pool
+        .services
+        .audit_service
+        .delete_before(before, current_user.id, &pool.database)
+        .await
+    {
+        Ok(n) => {
+            warn!(
+                "User {} deleted {} audits created before {}",
+                current_user.id.to_hex(),
+                n,
+                before
+            );
+            HttpResponse::Ok().json(DeleteAuditsResponse::new(n, before.to_rfc3339()))
+        }
+        Err(e) => {
+            error!("Error while deleting audits: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
+}
+
+/// # Summary
+///
+/// Parse an optional RFC 3339 date query parameter.
+///
+/// # Arguments
+///
+/// * `value` - The value of the query parameter, if any.
+/// * `field` - The name of the query parameter.
+///
+/// # Returns
+///
+/// * `Result<Option<DateTime<Utc>>, BadRequest>` - The parsed date, or a BadRequest if it is invalid.
+pub fn parse_date(value: Option<&str>, field: &str) -> Result<Option<DateTime<Utc>>, BadRequest> {
+    match value {
+        Some(v) => match DateTime::parse_from_rfc3339(v.trim()) {
+            Ok(d) => Ok(Some(d.with_timezone(&Utc))),
+            Err(_) => Err(BadRequest::for_field(
+                &format!("Invalid date: {}", v),
+                Some(field),
+            )),
+        },
+        None => Ok(None),
+    }
+}
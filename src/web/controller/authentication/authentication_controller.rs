@@ -1,20 +1,54 @@
+use crate::components::localizer::Message;
 use crate::configuration::config::Config;
+use crate::configuration::cookie_config::CookieConfig;
+use crate::configuration::server_config::RegistrationMode;
 use crate::errors::bad_request::BadRequest;
+use crate::errors::conflict::Conflict;
+use crate::errors::forbidden::Forbidden;
 use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::service_unavailable::ServiceUnavailable;
+use crate::repository::audit::audit_model::LoginOutcome;
+use crate::repository::invite::invite_model::Invite;
+use crate::repository::permission::permission_model::Permission;
+use crate::repository::role::role_model::Role;
+use crate::repository::role::role_repository::Error as RoleError;
+use crate::repository::session::session_model::Session;
 use crate::repository::user::user_model::User;
 use crate::repository::user::user_repository::Error;
-use crate::services::password::password_service::PasswordService;
+use crate::services::event::event_publisher::PasswordChangeMethod;
+use crate::services::idempotency::idempotency_service::{
+    IdempotencyService, IDEMPOTENCY_KEY_HEADER,
+};
+use crate::services::idempotency::idempotency_store::{IdempotencyState, StoredResponse};
 use crate::web::controller::user::user_controller::ConvertError;
+use crate::web::dto::authentication::account_locked_response::AccountLockedResponse;
+use crate::web::dto::authentication::change_password_request::ChangePasswordRequest;
+use crate::web::dto::authentication::expand_request::ExpandRequest;
+use crate::web::dto::authentication::include_request::IncludeRequest;
+use crate::web::dto::authentication::introspect_response::IntrospectResponse;
 use crate::web::dto::authentication::login_request::LoginRequest;
 use crate::web::dto::authentication::login_response::LoginResponse;
+use crate::web::dto::authentication::password_change_required_response::PasswordChangeRequiredResponse;
+use crate::web::dto::authentication::refresh_request::RefreshRequest;
 use crate::web::dto::authentication::register_request::RegisterRequest;
 use crate::web::dto::permission::permission_dto::SimplePermissionDto;
 use crate::web::dto::role::role_dto::SimpleRoleDto;
-use crate::web::dto::user::user_dto::SimpleUserDto;
+use crate::web::dto::user::user_dto::{DetailedUserDto, SimpleUserDto};
+use crate::web::dto::user::view_request::ViewRequest;
+use crate::web::extractors::authenticated_user::AuthenticatedUser;
+use crate::web::extractors::bearer_token::BearerToken;
+use crate::web::extractors::client_ip::ClientIp;
+use crate::web::extractors::json_body::JsonBody;
+use crate::web::extractors::locale::Locale;
+use actix_web::cookie::time::Duration;
+use actix_web::cookie::Cookie;
+use actix_web::http::header::{ContentType, RETRY_AFTER, USER_AGENT};
+use actix_web::http::StatusCode;
 use actix_web::{get, post, web, HttpRequest, HttpResponse};
-use argon2::PasswordHash;
-use log::error;
+use chrono::{TimeZone, Utc};
+use log::{error, warn};
 use mongodb::bson::oid::ObjectId;
+use std::collections::HashMap;
 
 /// # Summary
 ///
@@ -24,12 +58,13 @@ use mongodb::bson::oid::ObjectId;
 ///
 /// * `user` - A User
 /// * `pool` - The database connection pool
-/// * `audit_service` - The AuditService
+/// * `expand_roles` - Whether the Role entities of the User should be included
+/// * `expand_permissions` - Whether the Permission entities of the included Role entities should be included
 ///
 /// # Example
 ///
 /// ```
-/// let user_dto = convert_user_to_simple_dto(user, &pool, &audit_service).await;
+/// let user_dto = convert_user_to_simple_dto(user, &pool, true, true).await;
 /// ```
 ///
 /// # Returns
@@ -38,185 +73,1393 @@ use mongodb::bson::oid::ObjectId;
 pub async fn convert_user_to_simple_dto(
     user: User,
     pool: &Config,
+    expand_roles: bool,
+    expand_permissions: bool,
 ) -> Result<SimpleUserDto, ConvertError> {
     let mut user_dto = SimpleUserDto::from(user.clone());
 
+    if !expand_roles && !expand_permissions {
+        return Ok(user_dto);
+    }
+
     if user.roles.is_some() {
         let mut role_vec: Vec<String> = vec![];
         for r in user.roles.unwrap() {
             role_vec.push(r.to_hex());
         }
 
-        let roles = match pool
-            .services
-            .role_service
-            .find_by_id_vec(role_vec, &pool.database)
-            .await
-        {
-            Ok(d) => d,
-            Err(e) => {
-                return Err(ConvertError::RoleError(e));
+        let roles = match pool
+            .services
+            .role_service
+            .find_by_id_vec(role_vec, true, &pool.database)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => {
+                return Err(ConvertError::RoleError(e));
+            }
+        };
+
+        if !roles.is_empty() {
+            let mut role_dto_list: Vec<SimpleRoleDto> = vec![];
+
+            let permission_map = if expand_permissions {
+                find_permissions_of_roles(&roles, pool).await?
+            } else {
+                HashMap::new()
+            };
+
+            for r in &roles {
+                if expand_permissions {
+                    role_dto_list.push(convert_role_to_simple_dto(r, &permission_map));
+                } else {
+                    role_dto_list.push(SimpleRoleDto::from(r));
+                }
+            }
+
+            user_dto.roles = Some(role_dto_list);
+        }
+    }
+
+    Ok(user_dto)
+}
+
+/// # Summary
+///
+/// Find the Permission entities of a slice of Role entities at once, instead of querying them per Role
+///
+/// # Arguments
+///
+/// * `roles` - The Role entities
+/// * `pool` - The database connection pool
+///
+/// # Example
+///
+/// ```
+/// let permission_map = find_permissions_of_roles(&roles, &pool).await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<HashMap<ObjectId, Permission>, ConvertError>` - The Permission entities by their ID, or the ConvertError
+///   that occurred
+pub async fn find_permissions_of_roles(
+    roles: &[Role],
+    pool: &Config,
+) -> Result<HashMap<ObjectId, Permission>, ConvertError> {
+    let mut p_id_vec: Vec<String> = vec![];
+    for r in roles {
+        if let Some(permissions) = &r.permissions {
+            for p in permissions {
+                let id = p.to_hex();
+                if !p_id_vec.contains(&id) {
+                    p_id_vec.push(id);
+                }
+            }
+        }
+    }
+
+    let mut permission_map: HashMap<ObjectId, Permission> = HashMap::new();
+    if p_id_vec.is_empty() {
+        return Ok(permission_map);
+    }
+
+    let permissions = match pool
+        .services
+        .permission_service
+        .find_by_id_vec(p_id_vec, true, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => return Err(ConvertError::PermissionError(e)),
+    };
+
+    for p in permissions {
+        permission_map.insert(p.id, p);
+    }
+
+    Ok(permission_map)
+}
+
+/// # Summary
+///
+/// Convert a Role into a SimpleRoleDto that contains its Permission entities, in the order of the Role
+///
+/// # Arguments
+///
+/// * `role` - A reference to the Role
+/// * `permission_map` - The Permission entities by their ID, as returned by `find_permissions_of_roles`
+///
+/// # Example
+///
+/// ```
+/// let permission_map = find_permissions_of_roles(&roles, &pool).await?;
+/// let role_dto = convert_role_to_simple_dto(&roles[0], &permission_map);
+/// ```
+///
+/// # Returns
+///
+/// * `SimpleRoleDto` - The SimpleRoleDto, without Permissions if none of them could be found
+pub fn convert_role_to_simple_dto(
+    role: &Role,
+    permission_map: &HashMap<ObjectId, Permission>,
+) -> SimpleRoleDto {
+    let mut role_dto = SimpleRoleDto::from(role);
+    if let Some(permissions) = &role.permissions {
+        let permission_dto_list: Vec<SimplePermissionDto> = permissions
+            .iter()
+            .filter_map(|p| permission_map.get(p))
+            .map(SimplePermissionDto::from)
+            .collect();
+
+        if !permission_dto_list.is_empty() {
+            role_dto.permissions = Some(permission_dto_list)
+        }
+    }
+
+    role_dto
+}
+
+/// # Summary
+///
+/// Find the Role names and the effective Permission names of a User
+///
+/// # Arguments
+///
+/// * `user` - A reference to the User
+/// * `pool` - The database connection pool
+///
+/// # Example
+///
+/// ```
+/// let (roles, permissions) = find_role_and_permission_names(&user, &pool).await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<(Vec<String>, Vec<String>), ConvertError>` - The Role names and Permission names or the ConvertError that occurred
+pub async fn find_role_and_permission_names(
+    user: &User,
+    pool: &Config,
+) -> Result<(Vec<String>, Vec<String>), ConvertError> {
+    let mut role_names: Vec<String> = vec![];
+    let mut permission_names: Vec<String> = vec![];
+
+    let role_vec: Vec<String> = match &user.roles {
+        Some(r) => r.iter().map(|r| r.to_hex()).collect(),
+        None => return Ok((role_names, permission_names)),
+    };
+
+    let roles = match pool
+        .services
+        .role_service
+        .find_by_id_vec(role_vec, true, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => return Err(ConvertError::RoleError(e)),
+    };
+
+    let mut p_id_vec: Vec<String> = vec![];
+    for r in roles {
+        role_names.push(r.name);
+        if let Some(p) = r.permissions {
+            p_id_vec.extend(p.iter().map(|p| p.to_hex()));
+        }
+    }
+
+    p_id_vec.sort();
+    p_id_vec.dedup();
+
+    let permissions = match pool
+        .services
+        .permission_service
+        .find_by_id_vec(p_id_vec, true, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => return Err(ConvertError::PermissionError(e)),
+    };
+
+    for p in permissions {
+        permission_names.push(p.name);
+    }
+
+    Ok((role_names, permission_names))
+}
+
+/// # Summary
+///
+/// Check whether a User has a Permission, either directly or through a wildcard Permission.
+///
+/// # Arguments
+///
+/// * `user` - A reference to the User
+/// * `required` - The name of the required Permission
+/// * `pool` - The database connection pool
+///
+/// # Example
+///
+/// ```
+/// let allowed = has_permission(&user, "CAN_READ_USER_DETAILS", &pool).await?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<bool, ConvertError>` - Whether the User has the Permission or the ConvertError that occurred
+pub async fn has_permission(
+    user: &User,
+    required: &str,
+    pool: &Config,
+) -> Result<bool, ConvertError> {
+    let (_, permission_names) = find_role_and_permission_names(user, pool).await?;
+
+    Ok(permission_names
+        .into_iter()
+        .any(|n| Permission::new(n, None).matches(required)))
+}
+
+/// # Summary
+///
+/// Record a failed login of a User, and lock the User if it exceeded the maximum number of failed logins.
+///
+/// # Arguments
+///
+/// * `user` - The User whose login failed.
+/// * `pool` - The database connection pool
+async fn record_failed_login(user: &User, pool: &Config) {
+    let failed_login_attempts = match pool
+        .services
+        .user_service
+        .record_failed_login(&user.id, &pool.database)
+        .await
+    {
+        Ok(n) => n,
+        Err(e) => {
+            error!("Failed to record failed login: {}", e);
+            return;
+        }
+    };
+
+    if let Some(until) = pool
+        .services
+        .login_defense_service
+        .lock_until(failed_login_attempts)
+    {
+        if let Err(e) = pool
+            .services
+            .user_service
+            .lock(&user.id, until, &pool.database)
+            .await
+        {
+            error!("Failed to lock User: {}", e);
+        }
+    }
+}
+
+/// # Summary
+///
+/// Create the response to a request of a User that is locked because of too many failed login attempts.
+///
+/// # Arguments
+///
+/// * `user` - The locked User.
+/// * `pool` - The database connection pool
+///
+/// # Returns
+///
+/// * `HttpResponse` - The 403 Forbidden response that discloses when the lock ends if configured to do so, otherwise the
+///   same 400 Bad Request as a wrong password or an unknown User.
+fn locked_response(user: &User, pool: &Config) -> HttpResponse {
+    // Without disclosure, a locked account cannot be told apart from wrong credentials or an account that does not exist
+    if !pool
+        .services
+        .login_defense_service
+        .login_defense_config
+        .disclose_lockout
+    {
+        return HttpResponse::BadRequest().finish();
+    }
+
+    if let Some(l) = user.locked_until {
+        let locked_until = l.to_chrono();
+        // Round up, so that a client that honours the header does not retry while still locked
+        let retry_after = (locked_until - Utc::now()).num_milliseconds().max(0) as u64;
+        let retry_after = retry_after.div_ceil(1000);
+
+        return HttpResponse::Forbidden()
+            .insert_header((RETRY_AFTER, retry_after.to_string()))
+            .json(AccountLockedResponse::new(locked_until));
+    }
+
+    HttpResponse::Forbidden().json(Forbidden::new(
+        "account_locked",
+        "The account is locked because of too many failed login attempts",
+    ))
+}
+
+/// # Summary
+///
+/// Create the session cookie that holds a JWT.
+///
+/// # Arguments
+///
+/// * `token` - The JWT.
+/// * `max_age` - The amount of seconds until the JWT expires.
+/// * `cookie_config` - The CookieConfig.
+///
+/// # Returns
+///
+/// * `Cookie<'static>` - The session cookie.
+fn session_cookie(token: &str, max_age: i64, cookie_config: &CookieConfig) -> Cookie<'static> {
+    Cookie::build(cookie_config.name.clone(), String::from(token))
+        .path("/")
+        .secure(cookie_config.secure)
+        .http_only(cookie_config.http_only)
+        .same_site(cookie_config.same_site)
+        .max_age(Duration::seconds(max_age))
+        .finish()
+}
+
+/// # Summary
+///
+/// Record the outcome of a login attempt in an Audit. A failure to create the Audit does not fail the login.
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the User that attempted to log in, or None if no User was found.
+/// * `outcome` - The outcome of the login attempt.
+/// * `pool` - The database connection pool
+async fn audit_login(user_id: Option<ObjectId>, outcome: LoginOutcome, pool: &Config) {
+    let audit_service = &pool.services.audit_service;
+    let audit = audit_service.new_login_audit(user_id, outcome);
+    if let Err(e) = audit_service.create(audit, &pool.database).await {
+        error!("Failed to create Audit: {}", e);
+    }
+}
+
+/// # Summary
+///
+/// Start a Session for a User by issuing an access token and, if enabled, a refresh token. The Session lasts as long
+/// as the refresh token, or as long as the access token if no refresh token is issued.
+///
+/// # Arguments
+///
+/// * `user` - The User that the Session belongs to.
+/// * `user_agent` - The optional user agent of the client.
+/// * `pool` - The database connection pool
+///
+/// # Returns
+///
+/// * `Result<(String, Option<String>, i64), HttpResponse>` - The access token, the optional refresh token and the
+///   amount of seconds until the access token expires, or the error response.
+async fn start_session(
+    user: &User,
+    user_agent: Option<String>,
+    pool: &Config,
+) -> Result<(String, Option<String>, i64), HttpResponse> {
+    let jwt_config = pool.token_service.config();
+    let (roles, permissions) = if jwt_config.roles_claim || jwt_config.permissions_claim {
+        match find_role_and_permission_names(user, pool).await {
+            Ok((r, p)) => (Some(r), Some(p)),
+            Err(e) => {
+                error!("Failed to find Role and Permission names: {}", e);
+                return Err(HttpResponse::InternalServerError()
+                    .json(InternalServerError::new(&e.to_string())));
+            }
+        }
+    } else {
+        (None, None)
+    };
+
+    let (token, claims) = match pool
+        .token_service
+        .issue_access(
+            &user.id.to_hex(),
+            user.tenant_id.as_deref(),
+            roles,
+            permissions,
+        )
+        .await
+    {
+        Some(t) => t,
+        None => {
+            return Err(HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to generate token")))
+        }
+    };
+
+    let jti = claims.jti.unwrap_or_default();
+    let refresh_token = pool
+        .token_service
+        .issue_refresh(&user.id.to_hex(), user.tenant_id.as_deref(), &jti)
+        .await;
+
+    let max_age = (claims.exp as i64 - Utc::now().timestamp()).max(0);
+    let exp = refresh_token.as_ref().map_or(claims.exp, |(_, c)| c.exp);
+    let expires_at = Utc
+        .timestamp_opt(exp as i64, 0)
+        .single()
+        .unwrap_or_else(Utc::now);
+    let session = Session::new(user.id, jti, user_agent, expires_at);
+
+    if let Err(e) = pool
+        .services
+        .session_service
+        .create(session, &pool.database)
+        .await
+    {
+        error!("Failed to create Session: {}", e);
+        return Err(
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        );
+    }
+
+    Ok((token, refresh_token.map(|(t, _)| t), max_age))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authentication/login/",
+    request_body = LoginRequest,
+    params(
+        ("include" = Option<String>, Query, description = "Use user to include the User that logged in, including its roles and permissions", nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OK", body = LoginResponse),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden", body = Forbidden),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[post("/login/")]
+pub async fn login(
+    req: HttpRequest,
+    login_request: JsonBody<LoginRequest>,
+    include: web::Query<IncludeRequest>,
+    Locale(locale): Locale,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let login_request = login_request.into_inner();
+
+    if login_request.username.is_empty() {
+        return HttpResponse::BadRequest().json(BadRequest::for_field(
+            &pool
+                .localizer
+                .localize(Message::UsernameRequired, locale, &[]),
+            Some("username"),
+        ));
+    }
+    if login_request.password.is_empty() {
+        return HttpResponse::BadRequest().json(BadRequest::for_field(
+            &pool
+                .localizer
+                .localize(Message::PasswordRequired, locale, &[]),
+            Some("password"),
+        ));
+    }
+
+    // A session cookie would expose the JWT to anyone who can observe the connection
+    let cookie_config = &pool.cookie_config;
+    if cookie_config.enabled
+        && !cookie_config.allow_insecure
+        && req.connection_info().scheme() != "https"
+    {
+        return HttpResponse::BadRequest().json(BadRequest::new(
+            "Session cookies can only be set over HTTPS",
+        ));
+    }
+
+    if pool
+        .services
+        .password_service
+        .exceeds_max_length(login_request.password.expose_secret())
+    {
+        return HttpResponse::BadRequest().json(BadRequest::new(&pool.localizer.localize(
+            Message::PasswordTooLong,
+            locale,
+            &[&pool.services.password_service.max_length.to_string()],
+        )));
+    }
+
+    let user = match pool
+        .services
+        .user_service
+        .find_by_username_or_email(
+            &login_request.username,
+            login_request.tenant_id.as_deref(),
+            &pool.database,
+        )
+        .await
+    {
+        Ok(u) => match u {
+            Some(user) => user,
+            None => {
+                audit_login(None, LoginOutcome::UserNotFound, &pool).await;
+                return HttpResponse::BadRequest().finish();
+            }
+        },
+        Err(e) => {
+            error!("Failed to find user by username or email: {}", e);
+            return HttpResponse::BadRequest().finish();
+        }
+    };
+
+    // Only the Audit tells a locked account apart from an unknown User, unless the lockout is disclosed
+    let login_defense_service = &pool.services.login_defense_service;
+    if login_defense_service.is_locked(&user) {
+        audit_login(Some(user.id), LoginOutcome::Locked, &pool).await;
+        return locked_response(&user, &pool);
+    }
+
+    if let Some(d) = login_defense_service.delay(&user) {
+        actix_web::rt::time::sleep(d).await;
+    }
+
+    // Never compare the PHC strings directly, verify_password performs a constant-time comparison
+    if !pool
+        .services
+        .password_service
+        .verify_password(login_request.password.expose_secret(), &user.password)
+    {
+        if login_defense_service.is_enabled() {
+            record_failed_login(&user, &pool).await;
+        }
+        audit_login(Some(user.id), LoginOutcome::BadPassword, &pool).await;
+        return HttpResponse::BadRequest().finish();
+    }
+
+    // Only reveal that the account is disabled to someone who knows the password, and only if configured to do so
+    if !user.enabled {
+        audit_login(Some(user.id), LoginOutcome::Disabled, &pool).await;
+        if pool.server_config.disclose_disabled_accounts {
+            return HttpResponse::Forbidden()
+                .json(Forbidden::new("account_disabled", "Account disabled"));
+        }
+
+        return HttpResponse::BadRequest().finish();
+    }
+
+    if user.failed_login_attempts > 0 || user.locked_until.is_some() {
+        if let Err(e) = pool
+            .services
+            .user_service
+            .reset_failed_logins(&user.id, &pool.database)
+            .await
+        {
+            error!("Failed to reset failed logins: {}", e);
+        }
+    }
+
+    // No token is issued until the expired password has been changed
+    if pool
+        .services
+        .password_service
+        .is_expired(user.password_changed_at())
+    {
+        audit_login(Some(user.id), LoginOutcome::PasswordExpired, &pool).await;
+        return HttpResponse::Forbidden().json(Forbidden::new(
+            "password_expired",
+            "The password has expired and must be changed",
+        ));
+    }
+
+    // Only a token that is restricted to changing the password is issued until the temporary password is replaced
+    if user.must_change_password {
+        audit_login(Some(user.id), LoginOutcome::PasswordChangeRequired, &pool).await;
+        return match pool
+            .token_service
+            .issue_password_change(&user.id.to_hex(), user.tenant_id.as_deref())
+            .await
+        {
+            Some((token, _)) => {
+                HttpResponse::Forbidden().json(PasswordChangeRequiredResponse::new(token))
+            }
+            None => HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to generate token")),
+        };
+    }
+
+    // Gradually migrate hashes that were created using another algorithm, now that the plaintext password is known
+    if !pool
+        .services
+        .password_service
+        .preferred_algo_matches(&user.password)
+    {
+        match pool
+            .services
+            .password_service
+            .hash_password(login_request.password.expose_secret())
+        {
+            Ok(h) => {
+                if let Err(e) = pool
+                    .services
+                    .user_service
+                    .update_password(
+                        &user.id.to_hex(),
+                        &h,
+                        None,
+                        false,
+                        Some(user.id),
+                        &pool.database,
+                        &pool.services.audit_service,
+                    )
+                    .await
+                {
+                    error!("Failed to migrate password hash: {}", e);
+                }
+            }
+            Err(e) => error!("Failed to migrate password hash: {}", e),
+        };
+    }
+
+    let user_agent = req
+        .headers()
+        .get(USER_AGENT)
+        .and_then(|h| h.to_str().ok())
+        .map(String::from);
+    let (token, refresh_token, max_age) = match start_session(&user, user_agent, &pool).await {
+        Ok(t) => t,
+        Err(r) => return r,
+    };
+
+    if let Err(e) = pool
+        .services
+        .user_service
+        .record_login(&user.id, &pool.database)
+        .await
+    {
+        error!("Failed to record login: {}", e);
+    }
+
+    audit_login(Some(user.id), LoginOutcome::Ok, &pool).await;
+
+    let mut response = HttpResponse::Ok();
+    if cookie_config.enabled {
+        response.cookie(session_cookie(&token, max_age, cookie_config));
+    }
+
+    if !include.includes("user") {
+        return response.json(LoginResponse::new(token, refresh_token, None));
+    }
+
+    match convert_user_to_simple_dto(user, &pool, true, true).await {
+        Ok(u) => response.json(LoginResponse::new(token, refresh_token, Some(u))),
+        Err(e) => {
+            error!("Failed to convert User to SimpleUserDto: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authentication/refresh/",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, description = "OK", body = LoginResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+)]
+#[post("/refresh/")]
+pub async fn refresh(
+    req: HttpRequest,
+    refresh_request: JsonBody<RefreshRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let claims = match pool
+        .token_service
+        .verify_refresh(&refresh_request.refresh_token)
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to verify refresh token: {}", e);
+            return HttpResponse::Unauthorized().finish();
+        }
+    };
+
+    let jti = match claims.jti {
+        Some(j) => j,
+        None => return HttpResponse::Unauthorized().finish(),
+    };
+
+    // Revoking the Session first rotates the refresh token, so that it can only be used once
+    match pool
+        .services
+        .session_service
+        .delete_by_jti(&jti, &pool.database)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => return HttpResponse::Unauthorized().finish(),
+        Err(e) => {
+            error!("Failed to revoke Session: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    }
+
+    let user = match pool
+        .services
+        .user_service
+        .find_by_id(&claims.sub, &pool.database)
+        .await
+    {
+        Ok(Some(u)) => u,
+        Ok(None) => return HttpResponse::Unauthorized().finish(),
+        Err(e) => {
+            error!("Failed to find user by ID: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    // The User has to log in again to learn why the Session can no longer be refreshed
+    if !user.enabled
+        || user.must_change_password
+        || pool.services.login_defense_service.is_locked(&user)
+        || pool
+            .services
+            .password_service
+            .is_expired(user.password_changed_at())
+    {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    let user_agent = req
+        .headers()
+        .get(USER_AGENT)
+        .and_then(|h| h.to_str().ok())
+        .map(String::from);
+    let (token, refresh_token, max_age) = match start_session(&user, user_agent, &pool).await {
+        Ok(t) => t,
+        Err(r) => return r,
+    };
+
+    let mut response = HttpResponse::Ok();
+    if pool.cookie_config.enabled {
+        response.cookie(session_cookie(&token, max_age, &pool.cookie_config));
+    }
+
+    response.json(LoginResponse::new(token, refresh_token, None))
+}
+
+/// # Summary
+///
+/// Create a User from a RegisterRequest.
+///
+/// # Arguments
+///
+/// * `register_request` - The RegisterRequest
+/// * `locale` - The locale of the validation messages
+/// * `pool` - The database connection pool
+///
+/// # Returns
+///
+/// * `StoredResponse` - The response of the registration, which can be replayed for the same idempotency key
+async fn register_user(
+    register_request: RegisterRequest,
+    locale: &str,
+    pool: &Config,
+) -> StoredResponse {
+    if register_request.username.is_empty() {
+        return StoredResponse::json(
+            400,
+            &BadRequest::new(&pool.localizer.localize(Message::EmptyUsername, locale, &[])),
+        );
+    }
+
+    if register_request.password.is_empty() {
+        return StoredResponse::json(
+            400,
+            &BadRequest::new(&pool.localizer.localize(Message::EmptyPassword, locale, &[])),
+        );
+    }
+
+    if !pool
+        .services
+        .email_service
+        .is_domain_allowed(register_request.email.as_deref())
+    {
+        return StoredResponse::json(
+            400,
+            &BadRequest::new(
+                &pool
+                    .localizer
+                    .localize(Message::EmailDomainNotAllowed, locale, &[]),
+            ),
+        );
+    }
+
+    if pool
+        .services
+        .password_service
+        .exceeds_max_new_length(register_request.password.expose_secret())
+    {
+        return StoredResponse::json(
+            400,
+            &BadRequest::new(&pool.localizer.localize(
+                Message::PasswordTooLong,
+                locale,
+                &[&pool.services.password_service.max_new_length.to_string()],
+            )),
+        );
+    }
+
+    let default_roles: Option<Vec<ObjectId>> = match pool
+        .services
+        .role_service
+        .find_by_names(&pool.server_config.default_roles, &pool.database)
+        .await
+    {
+        Ok(roles) if !roles.is_empty() => Some(roles.into_iter().map(|r| r.id).collect()),
+        // The default roles were removed after startup, which is a configuration problem that a retry will not solve
+        Ok(_)
+            if pool.server_config.require_default_role
+                && !pool.server_config.default_roles.is_empty() =>
+        {
+            error!("None of the default roles exist");
+            return StoredResponse::json(
+                500,
+                &InternalServerError::new("None of the default roles exist"),
+            );
+        }
+        Ok(_) => None,
+        Err(e) => {
+            error!("Failed to find default role: {}", e);
+            return StoredResponse::json(
+                503,
+                &ServiceUnavailable::new("Unable to register at this time, please try again later"),
+            );
+        }
+    };
+
+    let password_hash = match pool
+        .services
+        .password_service
+        .hash_password(register_request.password.expose_secret())
+    {
+        Ok(e) => e.to_string(),
+        Err(e) => {
+            error!("Failed to hash password: {}", e);
+            return StoredResponse::json(500, &InternalServerError::new("Failed to hash password"));
+        }
+    };
+
+    // An invite token is used up front, so that concurrent registrations cannot use the same Invite twice. The guard
+    // releases the Invite if the registration fails or this future is dropped before the User is created
+    let invite_guard = match &register_request.invite_token {
+        Some(t) if !t.is_empty() => {
+            match pool
+                .services
+                .invite_service
+                .consume(t.expose_secret(), &pool.database)
+                .await
+            {
+                Ok(Some(i)) => Some(i),
+                Ok(None) => {
+                    return StoredResponse::json(
+                        400,
+                        &BadRequest::for_field(
+                            &pool
+                                .localizer
+                                .localize(Message::InviteTokenInvalid, locale, &[]),
+                            Some("inviteToken"),
+                        ),
+                    );
+                }
+                Err(e) => {
+                    error!("Failed to use invite: {}", e);
+                    return StoredResponse::json(
+                        503,
+                        &ServiceUnavailable::new(
+                            "Unable to register at this time, please try again later",
+                        ),
+                    );
+                }
             }
-        };
-
-        if !roles.is_empty() {
-            let mut role_dto_list: Vec<SimpleRoleDto> = vec![];
+        }
+        _ if pool.server_config.registration_mode == RegistrationMode::Invite => {
+            return StoredResponse::json(
+                400,
+                &BadRequest::for_field(
+                    &pool
+                        .localizer
+                        .localize(Message::InviteTokenRequired, locale, &[]),
+                    Some("inviteToken"),
+                ),
+            );
+        }
+        _ => None,
+    };
 
-            for r in &roles {
-                let mut role_dto = SimpleRoleDto::from(r);
-                if r.permissions.is_some() {
-                    let mut permission_dto_list: Vec<SimplePermissionDto> = vec![];
+    let mut roles = default_roles.unwrap_or_default();
+    if let Some(i) = invite_guard.as_ref().map(|g| &g.invite) {
+        let bound_email = i.email.as_deref();
+        let email = register_request
+            .email
+            .as_deref()
+            .map(|e| e.trim().to_lowercase());
+        if bound_email.is_some() && bound_email != email.as_deref() {
+            if let Some(g) = invite_guard {
+                g.release().await;
+            }
+            return StoredResponse::json(
+                400,
+                &BadRequest::for_field(
+                    &pool
+                        .localizer
+                        .localize(Message::InviteEmailMismatch, locale, &[]),
+                    Some("email"),
+                ),
+            );
+        }
 
-                    let mut p_id_vec: Vec<String> = vec![];
-                    for p in r.permissions.clone().unwrap() {
-                        p_id_vec.push(p.to_hex());
+        match find_invite_roles(i, pool).await {
+            Ok(r) => {
+                for role in r {
+                    if !roles.contains(&role) {
+                        roles.push(role);
                     }
+                }
+            }
+            Err(e) => {
+                error!("Failed to find the roles of invite {}: {}", i.id, e);
+                if let Some(g) = invite_guard {
+                    g.release().await;
+                }
+                return StoredResponse::json(
+                    503,
+                    &ServiceUnavailable::new(
+                        "Unable to register at this time, please try again later",
+                    ),
+                );
+            }
+        }
+    }
 
-                    let permissions = match pool
-                        .services
-                        .permission_service
-                        .find_by_id_vec(p_id_vec, &pool.database)
-                        .await
-                    {
-                        Ok(d) => d,
-                        Err(e) => return Err(ConvertError::PermissionError(e)),
-                    };
-
-                    if !permissions.is_empty() {
-                        for p in permissions {
-                            permission_dto_list.push(SimplePermissionDto::from(p));
-                        }
-                    }
+    // The plaintext password is zeroed when the RegisterRequest is dropped here
+    let mut user = User::from(register_request);
+    user.password = password_hash;
+    user.roles = if roles.is_empty() { None } else { Some(roles) };
+    if !pool.server_config.tenancy {
+        user.tenant_id = None;
+    }
 
-                    if !permission_dto_list.is_empty() {
-                        role_dto.permissions = Some(permission_dto_list)
-                    }
-                }
+    let user_id = user.id.clone();
 
-                role_dto_list.push(role_dto);
+    match pool
+        .services
+        .user_service
+        .create(
+            user,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => {
+            if let Some(g) = invite_guard {
+                g.complete(&user_id.to_hex()).await;
+            }
+            StoredResponse::empty(200)
+        }
+        Err(e) => {
+            error!("Error creating User: {}", e);
+            if let Some(g) = invite_guard {
+                g.release().await;
+            }
+            match e {
+                Error::UsernameAlreadyTaken | Error::EmailAlreadyTaken => {
+                    StoredResponse::json(409, &Conflict::new(&e.to_string(), e.field()))
+                }
+                Error::InvalidEmail(_) => {
+                    StoredResponse::json(400, &BadRequest::for_field(&e.to_string(), e.field()))
+                }
+                _ => StoredResponse::json(500, &InternalServerError::new(&e.to_string())),
             }
-
-            user_dto.roles = Some(role_dto_list);
         }
     }
+}
 
-    Ok(user_dto)
+/// # Summary
+///
+/// Find the IDs of the Roles that an Invite assigns. Roles that were deleted since the Invite was created are skipped, so
+/// that the User never refers to a Role that does not exist.
+///
+/// # Arguments
+///
+/// * `invite` - A reference to the Invite
+/// * `pool` - The database connection pool
+///
+/// # Returns
+///
+/// * `Result<Vec<ObjectId>, RoleError>` - The IDs of the existing Roles, or the RoleError that occurred
+async fn find_invite_roles(invite: &Invite, pool: &Config) -> Result<Vec<ObjectId>, RoleError> {
+    let role_ids: Vec<String> = match &invite.roles {
+        Some(r) => r.iter().map(|id| id.to_hex()).collect(),
+        None => return Ok(vec![]),
+    };
+
+    let roles = pool
+        .services
+        .role_service
+        .find_by_id_vec(role_ids.clone(), true, &pool.database)
+        .await?;
+    if roles.len() < role_ids.len() {
+        warn!("Some roles of invite {} no longer exist", invite.id);
+    }
+
+    Ok(roles.into_iter().map(|r| r.id).collect())
+}
+
+/// # Summary
+///
+/// Convert a StoredResponse into an HttpResponse
+///
+/// # Arguments
+///
+/// * `response` - The StoredResponse
+///
+/// # Returns
+///
+/// * `HttpResponse` - The HttpResponse
+fn to_http_response(response: StoredResponse) -> HttpResponse {
+    let status = StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    match response.body {
+        Some(b) => HttpResponse::build(status)
+            .content_type(ContentType::json())
+            .body(b),
+        None => HttpResponse::build(status).finish(),
+    }
 }
 
 #[utoipa::path(
     post,
-    path = "/api/v1/authentication/login/",
-    request_body = LoginRequest,
+    path = "/api/v1/authentication/register/",
+    request_body = RegisterRequest,
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "An optional key that causes retries of the same request to return the original response"),
+    ),
     responses(
-        (status = 200, description = "OK", body = LoginResponse),
+        (status = 200, description = "OK"),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Conflict, if the username or email address is already taken", body = Conflict),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
+        (status = 503, description = "Service Unavailable", body = crate::errors::service_unavailable::ServiceUnavailable),
     ),
     tag = "Authentication",
 )]
-#[post("/login/")]
-pub async fn login(
-    login_request: web::Json<LoginRequest>,
+#[post("/register/")]
+pub async fn register(
+    req: HttpRequest,
+    register_request: JsonBody<RegisterRequest>,
+    Locale(locale): Locale,
     pool: web::Data<Config>,
 ) -> HttpResponse {
-    let login_request = login_request.into_inner();
+    if !pool.server_config.allow_self_registration {
+        return HttpResponse::Forbidden().finish();
+    }
 
-    if login_request.username.is_empty() {
-        return HttpResponse::BadRequest().json("Username is required");
+    let register_request = register_request.into_inner();
+    let idempotency_key = match req.headers().get(IDEMPOTENCY_KEY_HEADER) {
+        Some(h) => match h.to_str() {
+            Ok(k) if IdempotencyService::is_valid_key(k) => {
+                let email = register_request
+                    .email
+                    .as_deref()
+                    .map(|e| e.trim().to_lowercase())
+                    .unwrap_or_default();
+                Some(IdempotencyService::scoped_key(
+                    "register",
+                    k,
+                    &[
+                        &register_request.username,
+                        &email,
+                        register_request.tenant_id.as_deref().unwrap_or_default(),
+                    ],
+                ))
+            }
+            _ => {
+                return HttpResponse::BadRequest()
+                    .json(BadRequest::new("Invalid Idempotency-Key header"));
+            }
+        },
+        None => None,
+    };
+
+    // The guard releases the key if this future is dropped, for example because the client disconnected
+    let mut idempotency_guard = None;
+    if let Some(key) = &idempotency_key {
+        match pool.services.idempotency_service.begin(key) {
+            (IdempotencyState::New, g) => idempotency_guard = g,
+            (IdempotencyState::InProgress, _) => return HttpResponse::Conflict().finish(),
+            (IdempotencyState::Completed(r), _) => return to_http_response(r),
+        }
     }
-    if login_request.password.is_empty() {
-        return HttpResponse::BadRequest().json("Password is required");
+
+    let response = register_user(register_request, locale, &pool).await;
+
+    // Unexpected failures are not stored, so that the client can retry them
+    if let Some(g) = idempotency_guard {
+        if response.status < 500 {
+            g.complete(response.clone());
+        }
     }
 
-    let user = match pool
-        .services
-        .user_service
-        .find_by_username(&login_request.username, &pool.database)
-        .await
+    to_http_response(response)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/authentication/current/",
+    params(
+        ("expand" = Option<String>, Query, description = "A comma-separated list of relations to include, either roles or permissions", nullable = true),
+        ("view" = Option<String>, Query, description = "Use detailed to include the account state of the User", nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OK, or a DetailedUserDto if the detailed view is requested", body = SimpleUserDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found", body = crate::errors::not_found::NotFound),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/current/")]
+pub async fn current_user(
+    AuthenticatedUser(user): AuthenticatedUser,
+    expand: web::Query<ExpandRequest>,
+    view: web::Query<ViewRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let expand = expand.into_inner();
+
+    // Users can always see the account state of their own record
+    let detailed = view.is_detailed().then(|| user.clone());
+    match convert_user_to_simple_dto(
+        user,
+        &pool,
+        expand.expands("roles"),
+        expand.expands("permissions"),
+    )
+    .await
     {
-        Ok(u) => match u {
-            Some(user) => user,
-            None => {
-                return HttpResponse::BadRequest().finish();
-            }
+        Ok(u) => match detailed {
+            Some(d) => HttpResponse::Ok().json(DetailedUserDto::new(u, &d)),
+            None => HttpResponse::Ok().json(u),
         },
         Err(e) => {
-            error!("Failed to find user by email: {}", e);
-            return HttpResponse::BadRequest().finish();
+            error!("Failed to convert User to SimpleUserDto: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
         }
-    };
+    }
+}
 
-    let parsed_hash = match PasswordHash::new(&user.password) {
-        Ok(h) => h,
+#[utoipa::path(
+    get,
+    path = "/api/v1/authentication/introspect/",
+    responses(
+        (status = 200, description = "OK", body = IntrospectResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authentication",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/introspect/")]
+pub async fn introspect(BearerToken(token): BearerToken, pool: web::Data<Config>) -> HttpResponse {
+    let claims = match pool.token_service.verify(&token).await {
+        Ok(c) => c,
         Err(e) => {
-            error!("Failed to parse password hash: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new("Failed to parse password hash"));
+            error!("Failed to introspect token: {}", e);
+            return HttpResponse::Unauthorized().finish();
         }
     };
 
-    if !PasswordService::verify_password(&login_request.password, &parsed_hash) {
-        return HttpResponse::BadRequest().finish();
-    }
-
     match pool
         .services
-        .jwt_service
-        .generate_jwt_token(&user.id.to_hex())
+        .session_service
+        .is_active(claims.jti.as_deref(), &pool.database)
+        .await
     {
-        Some(t) => HttpResponse::Ok().json(LoginResponse::new(t)),
-        None => HttpResponse::InternalServerError()
-            .json(InternalServerError::new("Failed to generate JWT token")),
+        Ok(true) => HttpResponse::Ok().json(IntrospectResponse::from(claims)),
+        Ok(false) => HttpResponse::Unauthorized().finish(),
+        Err(e) => {
+            error!("Failed to find Session: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
     }
 }
 
 #[utoipa::path(
     post,
-    path = "/api/v1/authentication/register/",
-    request_body = RegisterRequest,
+    path = "/api/v1/authentication/change-password/",
+    request_body = ChangePasswordRequest,
     responses(
         (status = 200, description = "OK"),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Authentication",
 )]
-#[post("/register/")]
-pub async fn register(
-    register_request: web::Json<RegisterRequest>,
+#[post("/change-password/")]
+pub async fn change_password(
+    change_password_request: JsonBody<ChangePasswordRequest>,
+    token: Option<BearerToken>,
     pool: web::Data<Config>,
+    ClientIp(ip): ClientIp,
+    Locale(locale): Locale,
 ) -> HttpResponse {
-    let register_request = register_request.into_inner();
+    let change_password_request = change_password_request.into_inner();
+    let old_password = change_password_request.old_password.expose_secret();
+    let new_password = change_password_request.new_password.expose_secret();
 
-    if register_request.username.is_empty() {
-        return HttpResponse::BadRequest().json(BadRequest::new("Empty usernames are not allowed"));
+    if token.is_none() && change_password_request.username.is_empty() {
+        return HttpResponse::BadRequest().json(BadRequest::new(&pool.localizer.localize(
+            Message::EmptyUsername,
+            locale,
+            &[],
+        )));
     }
 
-    if register_request.password.is_empty() {
-        return HttpResponse::BadRequest().json(BadRequest::new("Empty passwords are not allowed"));
+    if old_password.is_empty() || new_password.is_empty() {
+        return HttpResponse::BadRequest().json(BadRequest::new(&pool.localizer.localize(
+            Message::EmptyPassword,
+            locale,
+            &[],
+        )));
     }
 
-    let default_roles: Option<Vec<ObjectId>> = match pool
+    if pool
         .services
-        .role_service
-        .find_by_name("DEFAULT", &pool.database)
-        .await
+        .password_service
+        .exceeds_max_length(old_password)
     {
-        Ok(r) => match r {
-            Some(role) => Some(vec![role.id]),
-            None => None,
-        },
-        Err(e) => {
-            error!("Failed to find default role: {}", e);
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new(&e.to_string()));
+        return HttpResponse::BadRequest().json(BadRequest::new(&pool.localizer.localize(
+            Message::PasswordTooLong,
+            locale,
+            &[&pool.services.password_service.max_length.to_string()],
+        )));
+    }
+
+    if pool
+        .services
+        .password_service
+        .exceeds_max_new_length(new_password)
+    {
+        return HttpResponse::BadRequest().json(BadRequest::new(&pool.localizer.localize(
+            Message::PasswordTooLong,
+            locale,
+            &[&pool.services.password_service.max_new_length.to_string()],
+        )));
+    }
+
+    // A password change token that was issued at login identifies the User instead of the username
+    let user = match token {
+        Some(BearerToken(t)) => {
+            let claims = match pool.token_service.verify_password_change(&t).await {
+                Ok(c) => c,
+                Err(e) => {
+                    error!("Failed to verify password change token: {}", e);
+                    return HttpResponse::Unauthorized().finish();
+                }
+            };
+
+            match pool
+                .services
+                .user_service
+                .find_by_id(&claims.sub, &pool.database)
+                .await
+            {
+                // The token is spent once the password has been changed
+                Ok(Some(u)) if u.must_change_password => u,
+                Ok(_) => return HttpResponse::Unauthorized().finish(),
+                Err(e) => {
+                    error!("Failed to find user by ID: {}", e);
+                    return HttpResponse::InternalServerError()
+                        .json(InternalServerError::new(&e.to_string()));
+                }
+            }
         }
+        None => match pool
+            .services
+            .user_service
+            .find_by_username_or_email(
+                &change_password_request.username,
+                change_password_request.tenant_id.as_deref(),
+                &pool.database,
+            )
+            .await
+        {
+            Ok(Some(u)) => u,
+            Ok(None) => return HttpResponse::BadRequest().finish(),
+            Err(e) => {
+                error!("Failed to find user by username or email: {}", e);
+                return HttpResponse::BadRequest().finish();
+            }
+        },
     };
 
-    let mut user = User::from(register_request);
+    // The old password is as good as a login, so it is subject to the same lockout and throttling
+    let login_defense_service = &pool.services.login_defense_service;
+    if login_defense_service.is_locked(&user) {
+        return locked_response(&user, &pool);
+    }
 
-    let password_hash = match PasswordService::hash_password(user.password) {
-        Ok(e) => e.to_string(),
+    if let Some(d) = login_defense_service.delay(&user) {
+        actix_web::rt::time::sleep(d).await;
+    }
+
+    // Never compare the PHC strings directly, verify_password performs a constant-time comparison
+    if !pool
+        .services
+        .password_service
+        .verify_password(old_password, &user.password)
+    {
+        if login_defense_service.is_enabled() {
+            record_failed_login(&user, &pool).await;
+        }
+        return HttpResponse::BadRequest().finish();
+    }
+
+    // Only reveal that the account is disabled to someone who knows the password, and only if configured to do so
+    if !user.enabled {
+        if pool.server_config.disclose_disabled_accounts {
+            return HttpResponse::Forbidden()
+                .json(Forbidden::new("account_disabled", "Account disabled"));
+        }
+
+        return HttpResponse::BadRequest().finish();
+    }
+
+    if pool.services.password_service.is_reused(
+        new_password,
+        &user.password,
+        &user.password_history,
+    ) {
+        return HttpResponse::BadRequest().json(BadRequest::new(&pool.localizer.localize(
+            Message::PasswordReused,
+            locale,
+            &[&pool.services.password_service.history_size.to_string()],
+        )));
+    }
+
+    let password_hash = match pool.services.password_service.hash_password(new_password) {
+        Ok(h) => h,
         Err(e) => {
             error!("Failed to hash password: {}", e);
             return HttpResponse::InternalServerError()
@@ -224,94 +1467,48 @@ pub async fn register(
         }
     };
 
-    user.password = password_hash;
-    user.roles = default_roles;
-
-    let user_id = user.id.clone();
+    let user_id = user.id;
+    let password_history = pool
+        .services
+        .password_service
+        .push_history(user.password, user.password_history);
 
-    match pool
+    if let Err(e) = pool
         .services
         .user_service
-        .create(
-            user,
+        .update_password(
+            &user_id.to_hex(),
+            &password_hash,
+            Some(password_history),
+            false,
             Some(user_id),
             &pool.database,
             &pool.services.audit_service,
         )
         .await
     {
-        Ok(_) => HttpResponse::Ok().finish(),
-        Err(e) => {
-            error!("Error creating User: {}", e);
-            match e {
-                Error::UsernameAlreadyTaken | Error::EmailAlreadyTaken | Error::InvalidEmail(_) => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
-                }
-                _ => HttpResponse::InternalServerError()
-                    .json(InternalServerError::new(&e.to_string())),
-            }
-        }
+        error!("Failed to update password: {}", e);
+        return HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()));
     }
-}
-
-#[utoipa::path(
-    get,
-    path = "/api/v1/authentication/current/",
-    responses(
-        (status = 200, description = "OK", body = SimpleUserDto),
-        (status = 400, description = "Bad Request", body = BadRequest),
-        (status = 500, description = "Internal Server Error", body = InternalServerError),
-    ),
-    tag = "Authentication",
-    security(
-        ("Token" = [])
-    )
-)]
-#[get("/current/")]
-pub async fn current_user(req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
-    if let Some(auth_header) = req.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                let username = match pool.services.jwt_service.verify_jwt_token(token) {
-                    Ok(user) => user,
-                    Err(e) => {
-                        error!("Failed to verify JWT token: {}", e);
-                        return HttpResponse::Forbidden().finish();
-                    }
-                };
 
-                let user = match pool
-                    .services
-                    .user_service
-                    .find_by_id(&username, &pool.database)
-                    .await
-                {
-                    Ok(u) => match u {
-                        Some(user) => user,
-                        None => {
-                            return HttpResponse::Forbidden().finish();
-                        }
-                    },
-                    Err(e) => {
-                        error!("Failed to find user by ID: {}", e);
-                        return HttpResponse::Forbidden().finish();
-                    }
-                };
-
-                if !user.enabled {
-                    return HttpResponse::Forbidden().finish();
-                }
+    pool.services.event_service.password_changed(
+        &user_id,
+        user.email,
+        &user_id,
+        PasswordChangeMethod::SelfService,
+        ip,
+    );
 
-                return match convert_user_to_simple_dto(user, &pool).await {
-                    Ok(u) => HttpResponse::Ok().json(u),
-                    Err(e) => {
-                        error!("Failed to convert User to SimpleUserDto: {}", e);
-                        HttpResponse::Forbidden().finish()
-                    }
-                };
-            }
+    match pool
+        .services
+        .session_service
+        .delete_all(&user_id, &pool.database)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            error!("Failed to revoke Sessions after a password change: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
         }
     }
-
-    HttpResponse::Forbidden().finish()
 }
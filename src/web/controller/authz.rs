@@ -0,0 +1 @@
+pub mod authz_controller;
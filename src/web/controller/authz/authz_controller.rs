@@ -0,0 +1,72 @@
+use crate::configuration::config::Config;
+use crate::errors::bad_request::BadRequest;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::repository::permission::permission_model::Permission;
+use crate::web::controller::authentication::authentication_controller::find_role_and_permission_names;
+use crate::web::dto::authz::check_permissions::{
+    CheckPermissionsRequest, CheckPermissionsResponse,
+};
+use crate::web::extractors::authenticated_user::AuthenticatedUser;
+use crate::web::extractors::json_body::JsonBody;
+use actix_web::{post, web, HttpResponse};
+use log::error;
+use std::collections::BTreeMap;
+
+const MAX_CHECKED_PERMISSIONS: usize = 100;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/authz/check",
+    request_body = CheckPermissionsRequest,
+    responses(
+        (status = 200, description = "OK", body = CheckPermissionsResponse),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Authorization",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/check")]
+pub async fn check(
+    AuthenticatedUser(user): AuthenticatedUser,
+    check_request: JsonBody<CheckPermissionsRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let check_request = check_request.into_inner();
+
+    if check_request.permissions.len() > MAX_CHECKED_PERMISSIONS {
+        return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "No more than {} permissions can be checked at once",
+            MAX_CHECKED_PERMISSIONS
+        )));
+    }
+
+    let (_, permission_names) = match find_role_and_permission_names(&user, &pool).await {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error finding the Permissions of User {}: {}", user.id, e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    // Wildcards are matched the same way as by the authorization checks, without expanding them first
+    let granted: Vec<Permission> = permission_names
+        .into_iter()
+        .map(|n| Permission::new(n, None))
+        .collect();
+
+    let permissions: BTreeMap<String, bool> = check_request
+        .permissions
+        .into_iter()
+        .map(|required| {
+            let is_granted = granted.iter().any(|p| p.matches(&required));
+            (required, is_granted)
+        })
+        .collect();
+
+    HttpResponse::Ok().json(CheckPermissionsResponse::new(permissions))
+}
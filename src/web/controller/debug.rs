@@ -0,0 +1 @@
+pub mod debug_controller;
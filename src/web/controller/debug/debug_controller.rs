@@ -0,0 +1,53 @@
+use crate::configuration::config::Config;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::web::controller::authentication::authentication_controller::find_role_and_permission_names;
+use crate::web::dto::debug::whoami_response::WhoamiResponse;
+use crate::web::dto::user::user_dto::SimpleUserDto;
+use crate::web::extractors::authenticated_user::AuthenticatedUser;
+use crate::web::extractors::bearer_token::BearerToken;
+use actix_web::{get, web, HttpResponse};
+use log::{error, warn};
+
+/// # Summary
+///
+/// Dump the decoded token claims, the User, and the Role and Permission names of the current User, to troubleshoot
+/// authorization problems. This endpoint is only mounted in dev mode.
+///
+/// # Arguments
+///
+/// * `token` - The BearerToken of the request.
+/// * `user` - The AuthenticatedUser.
+/// * `pool` - The database connection pool.
+///
+/// # Returns
+///
+/// * `HttpResponse` - The WhoamiResponse, or 401 Unauthorized if the token is invalid.
+#[get("/whoami")]
+pub async fn whoami(
+    BearerToken(token): BearerToken,
+    AuthenticatedUser(user): AuthenticatedUser,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let claims = match pool.token_service.verify(&token).await {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Failed to decode token: {}", e);
+            return HttpResponse::Unauthorized().finish();
+        }
+    };
+
+    warn!("Dumping the token claims of User {}", user.id.to_hex());
+
+    match find_role_and_permission_names(&user, &pool).await {
+        Ok((roles, permissions)) => HttpResponse::Ok().json(WhoamiResponse::new(
+            claims,
+            SimpleUserDto::from(&user),
+            roles,
+            permissions,
+        )),
+        Err(e) => {
+            error!("Failed to find the Roles and Permissions of User: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
+}
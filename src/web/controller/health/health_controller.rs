@@ -1,10 +1,19 @@
-use actix_web::{get, HttpResponse};
+use crate::configuration::config::Config;
+use actix_web::{get, web, HttpResponse};
+use log::error;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use utoipa::ToSchema;
 
+const READINESS_TIMEOUT: Duration = Duration::from_secs(2);
+
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct HealthResponse {
     pub status: String,
+    #[serde(rename = "selfRegistration")]
+    pub self_registration: bool,
+    #[serde(rename = "passwordHashTime", skip_serializing_if = "Option::is_none")]
+    pub password_hash_time: Option<u64>,
 }
 
 impl HealthResponse {
@@ -15,13 +24,57 @@ impl HealthResponse {
     /// # Arguments
     ///
     /// * `status` - The status of the application.
+    /// * `self_registration` - Whether Users are allowed to register themselves.
+    /// * `password_hash_time` - The amount of milliseconds hashing a password took at startup, if it was measured.
     ///
     /// # Returns
     ///
     /// * `HealthResponse` - The new HealthResponse.
-    pub fn new(status: &str) -> Self {
+    pub fn new(status: &str, self_registration: bool, password_hash_time: Option<u64>) -> Self {
+        Self {
+            status: status.to_string(),
+            self_registration,
+            password_hash_time,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct LivenessResponse {
+    pub status: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ReadinessResponse {
+    pub status: String,
+    pub database: String,
+    #[serde(rename = "schemaVersion", skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<i32>,
+}
+
+impl ReadinessResponse {
+    /// # Summary
+    ///
+    /// Create a new ReadinessResponse.
+    ///
+    /// # Arguments
+    ///
+    /// * `database_up` - Whether the database can be reached.
+    /// * `schema_version` - The version of the most recent migration that was applied, if known.
+    ///
+    /// # Returns
+    ///
+    /// * `ReadinessResponse` - The new ReadinessResponse.
+    pub fn new(database_up: bool, schema_version: Option<i32>) -> Self {
+        let status = match database_up {
+            true => "UP",
+            false => "DOWN",
+        };
+
         Self {
             status: status.to_string(),
+            database: status.to_string(),
+            schema_version,
         }
     }
 }
@@ -35,6 +88,58 @@ impl HealthResponse {
     tag = "Health",
 )]
 #[get("/")]
-pub async fn health() -> HttpResponse {
-    HttpResponse::Ok().json(HealthResponse::new("UP"))
+pub async fn health(pool: web::Data<Config>) -> HttpResponse {
+    HttpResponse::Ok().json(HealthResponse::new(
+        "UP",
+        pool.server_config.allow_self_registration,
+        pool.services.password_service.hash_time,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    responses(
+        (status = 200, description = "OK", body = LivenessResponse),
+    ),
+    tag = "Health",
+)]
+#[get("/live")]
+pub async fn live() -> HttpResponse {
+    HttpResponse::Ok().json(LivenessResponse {
+        status: String::from("UP"),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    responses(
+        (status = 200, description = "OK", body = ReadinessResponse),
+        (status = 503, description = "Service Unavailable", body = ReadinessResponse),
+    ),
+    tag = "Health",
+)]
+#[get("/ready")]
+pub async fn ready(pool: web::Data<Config>) -> HttpResponse {
+    // The server only starts listening once the migrations, indexes and default entities have been applied,
+    // so the database is the only dependency that can still be unavailable here
+    if !pool.ping_database(READINESS_TIMEOUT).await {
+        return HttpResponse::ServiceUnavailable().json(ReadinessResponse::new(false, None));
+    }
+
+    let schema_version = match pool
+        .services
+        .migration_service
+        .current_version(&pool.database)
+        .await
+    {
+        Ok(v) => v,
+        Err(e) => {
+            error!("Failed to find the schema version: {}", e);
+            None
+        }
+    };
+
+    HttpResponse::Ok().json(ReadinessResponse::new(true, schema_version))
 }
@@ -0,0 +1,156 @@
+use crate::configuration::config::Config;
+use crate::errors::bad_request::BadRequest;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::web::controller::user::user_controller::validate_roles;
+use crate::web::dto::common::page::{resolve_limit, Page};
+use crate::web::dto::invite::create_invite::CreateInvite;
+use crate::web::dto::invite::invite_dto::InviteDto;
+use crate::web::dto::search::search_request::SearchRequest;
+use crate::web::extractors::authenticated_user::AuthenticatedUser;
+use crate::web::extractors::json_body::JsonBody;
+use actix_web::{get, post, web, HttpResponse};
+use actix_web_grants::protect;
+use log::error;
+use mongodb::bson::oid::ObjectId;
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invites/",
+    request_body = CreateInvite,
+    responses(
+        (status = 200, description = "OK, the only response that contains the invite token", body = InviteDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Invites",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/")]
+#[protect("CAN_CREATE_INVITE")]
+pub async fn create(
+    invite_dto: JsonBody<CreateInvite>,
+    pool: web::Data<Config>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+) -> HttpResponse {
+    let invite_dto = invite_dto.into_inner();
+
+    let email = match invite_dto.email.map(|e| e.trim().to_lowercase()) {
+        Some(e) if e.is_empty() => None,
+        Some(e) => {
+            if !pool
+                .services
+                .user_service
+                .user_repository
+                .email_regex
+                .is_match(&e)
+            {
+                return HttpResponse::BadRequest().json(BadRequest::for_field(
+                    &format!("Invalid email address: {}", e),
+                    Some("email"),
+                ));
+            }
+            Some(e)
+        }
+        None => None,
+    };
+
+    match validate_roles(&invite_dto.roles, &pool).await {
+        Ok(unknown_roles) if !unknown_roles.is_empty() => {
+            return HttpResponse::BadRequest().json(BadRequest::for_field(
+                &format!("Unknown roles: {}", unknown_roles.join(", ")),
+                Some("roles"),
+            ));
+        }
+        Ok(_) => (),
+        Err(e) => {
+            error!("Error validating roles: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    // All roles were validated, so every ID can be parsed
+    let roles: Option<Vec<ObjectId>> = invite_dto
+        .roles
+        .map(|r| {
+            r.iter()
+                .filter_map(|id| ObjectId::parse_str(id).ok())
+                .collect()
+        })
+        .filter(|r: &Vec<ObjectId>| !r.is_empty());
+
+    match pool
+        .services
+        .invite_service
+        .create(email, roles, current_user.id.to_hex(), &pool.database)
+        .await
+    {
+        Ok((token, invite)) => HttpResponse::Ok().json(InviteDto::with_token(invite, token)),
+        Err(e) => {
+            error!("Error creating Invite: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/invites/",
+    params(
+        ("limit" = Option<i64>, Query, description = "The limit of invites to retrieve, capped to MAX_PAGE_SIZE", nullable = true),
+        ("page" = Option<i64>, Query, description = "The page", nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OK", body = crate::web::dto::common::page::InvitePage),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Invites",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/")]
+#[protect("CAN_READ_INVITE")]
+pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>) -> HttpResponse {
+    let search = search.into_inner();
+    let page = search.page;
+
+    let limit = match resolve_limit(search.limit, pool.server_config.max_page_size) {
+        Ok(l) => l,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    let invites = match pool
+        .services
+        .invite_service
+        .find_all(Some(limit), page, &pool.database)
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error finding Invites: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    let total = match pool.services.invite_service.count(&pool.database).await {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Error counting Invites: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    let invite_dto_list: Vec<InviteDto> = invites.into_iter().map(InviteDto::from).collect();
+
+    HttpResponse::Ok().json(Page::new(invite_dto_list, total, limit, page))
+}
@@ -1,16 +1,21 @@
 use crate::configuration::config::Config;
 use crate::errors::bad_request::BadRequest;
+use crate::errors::conflict::Conflict;
 use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::not_found::NotFound;
 use crate::repository::permission::permission_model::Permission;
 use crate::repository::permission::permission_repository::Error;
+use crate::web::dto::common::page::{resolve_limit, Page};
 use crate::web::dto::permission::create_permission::CreatePermission;
+use crate::web::dto::permission::delete_permission_response::DeletePermissionResponse;
 use crate::web::dto::permission::permission_dto::PermissionDto;
 use crate::web::dto::permission::update_permission::UpdatePermission;
 use crate::web::dto::search::search_request::SearchRequest;
-use crate::web::extractors::user_id_extractor;
-use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse};
+use crate::web::extractors::authenticated_user::AuthenticatedUser;
+use crate::web::extractors::json_body::JsonBody;
+use actix_web::{delete, get, post, put, web, HttpResponse};
 use actix_web_grants::protect;
-use log::error;
+use log::{error, info};
 
 #[utoipa::path(
     post,
@@ -19,6 +24,9 @@ use log::error;
     responses(
         (status = 200, description = "OK", body = PermissionDto),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Conflict", body = Conflict),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Permissions",
@@ -30,8 +38,8 @@ use log::error;
 #[protect("CAN_CREATE_PERMISSION")]
 pub async fn create_permission(
     pool: web::Data<Config>,
-    info: web::Json<CreatePermission>,
-    req: HttpRequest,
+    info: JsonBody<CreatePermission>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
 ) -> HttpResponse {
     if info.name.is_empty() {
         return HttpResponse::BadRequest().json(BadRequest::new("Empty name"));
@@ -39,14 +47,7 @@ pub async fn create_permission(
 
     let new_permission = Permission::from(info.into_inner());
 
-    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
-        Some(e) => e,
-        None => {
-            error!("Failed to get User ID from token");
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new("Failed to get User ID from token"));
-        }
-    };
+    let user_id = current_user.id;
 
     let res = match pool
         .services
@@ -63,8 +64,10 @@ pub async fn create_permission(
         Err(e) => {
             error!("Error while creating Permission: {}", e);
             return match e {
+                Error::EmptyName | Error::NameTooLong(_) => HttpResponse::BadRequest()
+                    .json(BadRequest::for_field(&e.to_string(), e.field())),
                 Error::NameAlreadyTaken => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
+                    HttpResponse::Conflict().json(Conflict::new(&e.to_string(), e.field()))
                 }
                 _ => HttpResponse::InternalServerError()
                     .json(InternalServerError::new(&e.to_string())),
@@ -80,12 +83,13 @@ pub async fn create_permission(
     path = "/api/v1/permissions/",
     params(
         ("text" = Option<String>, Query, description = "The text to search for", nullable = true),
-        ("limit" = Option<i64>, Query, description = "The limit of permissions to retrieve", nullable = true),
+        ("limit" = Option<i64>, Query, description = "The limit of permissions to retrieve, capped to MAX_PAGE_SIZE", nullable = true),
         ("page" = Option<i64>, Query, description = "The page", nullable = true),
     ),
     responses(
-        (status = 200, description = "OK", body = Vec<PermissionDto>),
+        (status = 200, description = "OK", body = crate::web::dto::common::page::PermissionPage),
         (status = 204, description = "No Content"),
+        (status = 400, description = "Bad Request", body = BadRequest),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Permissions",
@@ -101,22 +105,19 @@ pub async fn find_all_permissions(
 ) -> HttpResponse {
     let search = search.into_inner();
 
-    let mut limit = search.limit;
     let page = search.page;
 
-    let limit_clone = limit.unwrap_or(pool.server_config.max_limit);
-    if limit.is_none()
-        || (limit.is_some() && limit_clone > pool.server_config.max_limit || limit_clone < 1)
-    {
-        limit = Some(pool.server_config.max_limit);
-    }
+    let limit = match resolve_limit(search.limit, pool.server_config.max_page_size) {
+        Ok(l) => Some(l),
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
 
-    let res = match search.text {
+    let res = match &search.text {
         Some(t) => {
             match pool
                 .services
                 .permission_service
-                .search(&t, limit, page, &pool.database)
+                .search(t, limit, page, &pool.database)
                 .await
             {
                 Ok(d) => d,
@@ -148,9 +149,24 @@ pub async fn find_all_permissions(
         return HttpResponse::NoContent().finish();
     }
 
+    let total = match pool
+        .services
+        .permission_service
+        .count(search.text.as_deref(), &pool.database)
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Error while counting permissions: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
     let dto_list = res.iter().map(|p| p.into()).collect::<Vec<PermissionDto>>();
+    let limit = limit.unwrap_or(pool.server_config.max_page_size);
 
-    HttpResponse::Ok().json(dto_list)
+    HttpResponse::Ok().json(Page::new(dto_list, total, limit, page))
 }
 
 #[utoipa::path(
@@ -161,7 +177,7 @@ pub async fn find_all_permissions(
     ),
     responses(
         (status = 200, description = "OK", body = PermissionDto),
-        (status = 404, description = "Not Found"),
+        (status = 404, description = "Not Found", body = NotFound),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Permissions",
@@ -180,7 +196,7 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
     {
         Ok(d) => match d {
             Some(d) => d,
-            None => return HttpResponse::NotFound().finish(),
+            None => return HttpResponse::NotFound().json(NotFound::new("Permission", &path)),
         },
         Err(e) => {
             error!("Error while finding Permission with ID {}: {}", path, e);
@@ -202,7 +218,10 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
     responses(
         (status = 200, description = "OK", body = PermissionDto),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
         (status = 404, description = "Not Found"),
+        (status = 409, description = "Conflict", body = Conflict),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Permissions",
@@ -214,22 +233,15 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
 #[protect("CAN_UPDATE_PERMISSION")]
 pub async fn update_permission(
     path: web::Path<String>,
-    update: web::Json<UpdatePermission>,
+    update: JsonBody<UpdatePermission>,
     pool: web::Data<Config>,
-    req: HttpRequest,
+    AuthenticatedUser(current_user): AuthenticatedUser,
 ) -> HttpResponse {
     if update.name.is_empty() {
         return HttpResponse::BadRequest().json(BadRequest::new("Empty name"));
     }
 
-    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
-        Some(e) => e,
-        None => {
-            error!("Failed to get User ID from token");
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new("Failed to get User ID from token"));
-        }
-    };
+    let user_id = current_user.id;
 
     let res = pool
         .services
@@ -271,8 +283,10 @@ pub async fn update_permission(
         Err(e) => {
             error!("Error while updating Permission with ID {}: {}", path, e);
             match e {
+                Error::EmptyName | Error::NameTooLong(_) => HttpResponse::BadRequest()
+                    .json(BadRequest::for_field(&e.to_string(), e.field())),
                 Error::NameAlreadyTaken => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
+                    HttpResponse::Conflict().json(Conflict::new(&e.to_string(), e.field()))
                 }
                 _ => HttpResponse::InternalServerError()
                     .json(InternalServerError::new(&e.to_string())),
@@ -288,8 +302,11 @@ pub async fn update_permission(
         ("id" = String, Path, description = "The ID of the Permission"),
     ),
     responses(
-        (status = 200, description = "OK"),
+        (status = 200, description = "OK, with the number of Roles the Permission was removed from", body = DeletePermissionResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
         (status = 404, description = "Not Found"),
+        (status = 409, description = "Conflict", body = Conflict),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Permissions",
@@ -302,16 +319,9 @@ pub async fn update_permission(
 pub async fn delete_permission(
     path: web::Path<String>,
     pool: web::Data<Config>,
-    req: HttpRequest,
+    AuthenticatedUser(current_user): AuthenticatedUser,
 ) -> HttpResponse {
-    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
-        Some(e) => e,
-        None => {
-            error!("Failed to get User ID from token");
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new("Failed to get User ID from token"));
-        }
-    };
+    let user_id = current_user.id;
     let res = pool
         .services
         .permission_service
@@ -324,9 +334,15 @@ pub async fn delete_permission(
         )
         .await;
     match res {
-        Ok(_) => HttpResponse::Ok().finish(),
+        Ok(count) => {
+            info!("Removed Permission with ID {} from {} Role(s)", path, count);
+            HttpResponse::Ok().json(DeletePermissionResponse::new(count))
+        }
         Err(e) => match e {
             Error::PermissionNotFound(_) => HttpResponse::NotFound().finish(),
+            Error::Protected(_) => {
+                HttpResponse::Conflict().json(Conflict::new(&e.to_string(), None))
+            }
             _ => {
                 error!("Error while deleting Permission with ID {}: {}", path, e);
                 HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
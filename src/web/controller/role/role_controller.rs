@@ -1,16 +1,24 @@
 use crate::configuration::config::Config;
 use crate::errors::bad_request::BadRequest;
+use crate::errors::conflict::Conflict;
 use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::not_found::NotFound;
 use crate::repository::permission::permission_repository::Error as PermissionError;
 use crate::repository::role::role_model::Role;
 use crate::repository::role::role_repository::Error;
+use crate::web::controller::authentication::authentication_controller::{
+    convert_role_to_simple_dto, find_permissions_of_roles,
+};
+use crate::web::dto::authentication::expand_request::ExpandRequest;
+use crate::web::dto::common::page::{resolve_limit, Page};
 use crate::web::dto::permission::permission_dto::PermissionDto;
 use crate::web::dto::role::create_role::CreateRole;
-use crate::web::dto::role::role_dto::RoleDto;
+use crate::web::dto::role::role_dto::{DetailedRoleDto, RoleDto};
 use crate::web::dto::role::update_role::UpdateRole;
 use crate::web::dto::search::search_request::SearchRequest;
-use crate::web::extractors::user_id_extractor;
-use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse};
+use crate::web::extractors::authenticated_user::AuthenticatedUser;
+use crate::web::extractors::json_body::JsonBody;
+use actix_web::{delete, get, post, put, web, HttpResponse};
 use actix_web_grants::protect;
 use log::error;
 use mongodb::bson::oid::ObjectId;
@@ -86,7 +94,7 @@ pub async fn find_permission_dto_from_permissions(
     let permissions = match config
         .services
         .permission_service
-        .find_by_id_vec(permissions, &config.database)
+        .find_by_id_vec(permissions, false, &config.database)
         .await
     {
         Ok(d) => d,
@@ -163,6 +171,9 @@ pub async fn validate_permissions(
     responses(
         (status = 200, description = "OK", body = RoleDto),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Conflict", body = Conflict),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Roles",
@@ -173,22 +184,15 @@ pub async fn validate_permissions(
 #[post("/")]
 #[protect("CAN_CREATE_ROLE")]
 pub async fn create(
-    role_dto: web::Json<CreateRole>,
+    role_dto: JsonBody<CreateRole>,
     pool: web::Data<Config>,
-    req: HttpRequest,
+    AuthenticatedUser(current_user): AuthenticatedUser,
 ) -> HttpResponse {
     if role_dto.name.is_empty() {
         return HttpResponse::BadRequest().json(BadRequest::new("Empty name"));
     }
 
-    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
-        Some(e) => e,
-        None => {
-            error!("Failed to get User ID from token");
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new("Failed to get User ID from token"));
-        }
-    };
+    let user_id = current_user.id;
 
     let role_dto = role_dto.into_inner();
     if role_dto.permissions.is_some() {
@@ -225,8 +229,74 @@ pub async fn create(
         Err(e) => {
             error!("Error creating Role: {}", e);
             return match e {
+                Error::EmptyName | Error::NameTooLong(_) => HttpResponse::BadRequest()
+                    .json(BadRequest::for_field(&e.to_string(), e.field())),
                 Error::NameAlreadyTaken => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
+                    HttpResponse::Conflict().json(Conflict::new(&e.to_string(), e.field()))
+                }
+                _ => HttpResponse::InternalServerError()
+                    .json(InternalServerError::new(&e.to_string())),
+            };
+        }
+    };
+
+    match get_role_dto_from_role(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting Role to RoleDto: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/roles/templates/{name}/",
+    params(
+        ("name" = String, Path, description = "The name of the Role template"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = RoleDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 409, description = "Conflict", body = Conflict),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Roles",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/templates/{name}/")]
+#[protect("CAN_CREATE_ROLE")]
+pub async fn create_from_template(
+    path: web::Path<String>,
+    pool: web::Data<Config>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+) -> HttpResponse {
+    let res = match pool
+        .services
+        .role_service
+        .create_from_template(
+            &path,
+            Some(current_user.id),
+            &pool.database,
+            &pool.services.permission_service,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error creating Role from template: {}", e);
+            return match e {
+                Error::TemplateNotFound(_) => HttpResponse::NotFound().finish(),
+                Error::NameTooLong(_) | Error::Permission(_) => HttpResponse::BadRequest()
+                    .json(BadRequest::for_field(&e.to_string(), e.field())),
+                Error::NameAlreadyTaken => {
+                    HttpResponse::Conflict().json(Conflict::new(&e.to_string(), e.field()))
                 }
                 _ => HttpResponse::InternalServerError()
                     .json(InternalServerError::new(&e.to_string())),
@@ -248,12 +318,13 @@ pub async fn create(
     path = "/api/v1/roles/",
     params(
         ("text" = Option<String>, Query, description = "The text to search for", nullable = true),
-        ("limit" = Option<i64>, Query, description = "The limit of roles to retrieve", nullable = true),
+        ("limit" = Option<i64>, Query, description = "The limit of roles to retrieve, capped to MAX_PAGE_SIZE", nullable = true),
         ("page" = Option<i64>, Query, description = "The page", nullable = true),
     ),
     responses(
-        (status = 200, description = "OK", body = Vec<RoleDto>),
+        (status = 200, description = "OK", body = crate::web::dto::common::page::RolePage),
         (status = 204, description = "No Content"),
+        (status = 400, description = "Bad Request", body = BadRequest),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Roles",
@@ -269,21 +340,18 @@ pub async fn find_all_roles(
 ) -> HttpResponse {
     let search = search.into_inner();
 
-    let mut limit = search.limit;
     let page = search.page;
 
-    let limit_clone = limit.unwrap_or(pool.server_config.max_limit);
-    if limit.is_none()
-        || (limit.is_some() && limit_clone > pool.server_config.max_limit || limit_clone < 1)
-    {
-        limit = Some(pool.server_config.max_limit);
-    }
+    let limit = match resolve_limit(search.limit, pool.server_config.max_page_size) {
+        Ok(l) => Some(l),
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
 
-    let res = match search.text {
+    let res = match &search.text {
         Some(t) => match pool
             .services
             .role_service
-            .search(&t, limit, page, &pool.database)
+            .search(t, limit, page, &pool.database)
             .await
         {
             Ok(d) => d,
@@ -312,6 +380,20 @@ pub async fn find_all_roles(
         return HttpResponse::NoContent().finish();
     }
 
+    let total = match pool
+        .services
+        .role_service
+        .count(search.text.as_deref(), &pool.database)
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Error while counting Roles: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
     let mut role_dto_list: Vec<RoleDto> = vec![];
     for r in res {
         let role_dto = match get_role_dto_from_role(r, &pool).await {
@@ -326,7 +408,9 @@ pub async fn find_all_roles(
         role_dto_list.push(role_dto);
     }
 
-    HttpResponse::Ok().json(role_dto_list)
+    let limit = limit.unwrap_or(pool.server_config.max_page_size);
+
+    HttpResponse::Ok().json(Page::new(role_dto_list, total, limit, page))
 }
 
 #[utoipa::path(
@@ -334,10 +418,11 @@ pub async fn find_all_roles(
     path = "/api/v1/roles/{id}",
     params(
         ("id" = String, Path, description = "The ID of the Role"),
+        ("expand" = Option<String>, Query, description = "Set to permissions to return a DetailedRoleDto with the Permissions of the Role", nullable = true),
     ),
     responses(
-        (status = 200, description = "OK", body = RoleDto),
-        (status = 404, description = "Not Found"),
+        (status = 200, description = "OK, a DetailedRoleDto if the permissions are expanded", body = RoleDto),
+        (status = 404, description = "Not Found", body = NotFound),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Roles",
@@ -347,7 +432,11 @@ pub async fn find_all_roles(
 )]
 #[get("/{id}")]
 #[protect("CAN_READ_ROLE")]
-pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> HttpResponse {
+pub async fn find_by_id(
+    path: web::Path<String>,
+    expand: web::Query<ExpandRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
     let res = match pool
         .services
         .role_service
@@ -356,7 +445,7 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
     {
         Ok(d) => match d {
             Some(d) => d,
-            None => return HttpResponse::NotFound().finish(),
+            None => return HttpResponse::NotFound().json(NotFound::new("Role", &path)),
         },
         Err(e) => {
             error!("Error finding Role by ID {}: {}", path, e);
@@ -365,6 +454,19 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
         }
     };
 
+    if expand.expands("permissions") {
+        return match find_permissions_of_roles(std::slice::from_ref(&res), &pool).await {
+            Ok(permission_map) => HttpResponse::Ok().json(DetailedRoleDto::new(
+                convert_role_to_simple_dto(&res, &permission_map),
+                &res,
+            )),
+            Err(e) => {
+                error!("Error finding the Permissions of Role {}: {}", path, e);
+                HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+            }
+        };
+    }
+
     match get_role_dto_from_role(res, &pool).await {
         Ok(dto) => HttpResponse::Ok().json(dto),
         Err(e) => {
@@ -384,7 +486,10 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
     responses(
         (status = 200, description = "OK", body = RoleDto),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
         (status = 404, description = "Not Found"),
+        (status = 409, description = "Conflict", body = Conflict),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Roles",
@@ -396,9 +501,9 @@ pub async fn find_by_id(path: web::Path<String>, pool: web::Data<Config>) -> Htt
 #[protect("CAN_UPDATE_ROLE")]
 pub async fn update(
     path: web::Path<String>,
-    update: web::Json<UpdateRole>,
+    update: JsonBody<UpdateRole>,
     pool: web::Data<Config>,
-    req: HttpRequest,
+    AuthenticatedUser(current_user): AuthenticatedUser,
 ) -> HttpResponse {
     let update = update.into_inner();
 
@@ -406,14 +511,7 @@ pub async fn update(
         return HttpResponse::BadRequest().json(BadRequest::new("Empty name"));
     }
 
-    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
-        Some(e) => e,
-        None => {
-            error!("Failed to get User ID from token");
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new("Failed to get User ID from token"));
-        }
-    };
+    let user_id = current_user.id;
 
     let mut role = match pool
         .services
@@ -470,6 +568,10 @@ pub async fn update(
     role.name = update.name;
     role.description = update.description;
     role.permissions = new_permissions;
+    // The update is rejected if the Role was modified after the client read it
+    if let Some(version) = update.version {
+        role.version = version;
+    }
 
     let res = match pool
         .services
@@ -486,8 +588,13 @@ pub async fn update(
         Err(e) => {
             error!("Error updating Role: {}", e);
             return match e {
+                Error::EmptyName | Error::NameTooLong(_) => HttpResponse::BadRequest()
+                    .json(BadRequest::for_field(&e.to_string(), e.field())),
                 Error::NameAlreadyTaken => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
+                    HttpResponse::Conflict().json(Conflict::new(&e.to_string(), e.field()))
+                }
+                Error::Conflict(_) => {
+                    HttpResponse::Conflict().json(Conflict::new(&e.to_string(), None))
                 }
                 _ => HttpResponse::InternalServerError()
                     .json(InternalServerError::new(&e.to_string())),
@@ -512,7 +619,10 @@ pub async fn update(
     ),
     responses(
         (status = 200, description = "OK"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
         (status = 404, description = "Not Found"),
+        (status = 409, description = "Conflict", body = Conflict),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Roles",
@@ -525,16 +635,9 @@ pub async fn update(
 pub async fn delete(
     path: web::Path<String>,
     pool: web::Data<Config>,
-    req: HttpRequest,
+    AuthenticatedUser(current_user): AuthenticatedUser,
 ) -> HttpResponse {
-    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
-        Some(e) => e,
-        None => {
-            error!("Failed to get User ID from token");
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new("Failed to get User ID from token"));
-        }
-    };
+    let user_id = current_user.id;
 
     match pool
         .services
@@ -551,6 +654,9 @@ pub async fn delete(
         Ok(_) => HttpResponse::Ok().finish(),
         Err(e) => match e {
             Error::RoleNotFound(_) => HttpResponse::NotFound().finish(),
+            Error::Protected(_) => {
+                HttpResponse::Conflict().json(Conflict::new(&e.to_string(), None))
+            }
             _ => {
                 error!("Error deleting Role: {}", e);
                 HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
@@ -558,3 +664,187 @@ pub async fn delete(
         },
     }
 }
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/roles/{id}/permissions",
+    request_body = Vec<String>,
+    params(
+        ("id" = String, Path, description = "The ID of the Role"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = RoleDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Roles",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/{id}/permissions")]
+#[protect("CAN_UPDATE_ROLE")]
+pub async fn add_permissions(
+    path: web::Path<String>,
+    permissions: JsonBody<Vec<String>>,
+    pool: web::Data<Config>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+) -> HttpResponse {
+    let mut permissions = permissions.into_inner();
+    permissions.sort();
+    permissions.dedup();
+
+    if permissions.is_empty() {
+        return HttpResponse::BadRequest().json(BadRequest::new("Empty permissions"));
+    }
+
+    let user_id = current_user.id;
+
+    let mut invalid: Vec<String> = vec![];
+    let mut oid_vec: Vec<ObjectId> = vec![];
+    for p in permissions {
+        match ObjectId::parse_str(&p) {
+            Ok(d) => oid_vec.push(d),
+            Err(_) => invalid.push(p),
+        }
+    }
+
+    let existing = match pool
+        .services
+        .permission_service
+        .find_by_id_vec(
+            oid_vec.iter().map(|p| p.to_hex()).collect(),
+            true,
+            &pool.database,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error validating permissions: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    for oid in &oid_vec {
+        if !existing.iter().any(|p| p.id == *oid) {
+            invalid.push(oid.to_hex());
+        }
+    }
+
+    if !invalid.is_empty() {
+        return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "Permissions not found: {}",
+            invalid.join(", ")
+        )));
+    }
+
+    let res = match pool
+        .services
+        .role_service
+        .add_permissions(
+            &path,
+            oid_vec,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            return match e {
+                Error::RoleNotFound(_) | Error::InvalidId(_) => HttpResponse::NotFound().finish(),
+                _ => {
+                    error!("Error adding Permissions to Role: {}", e);
+                    HttpResponse::InternalServerError()
+                        .json(InternalServerError::new(&e.to_string()))
+                }
+            };
+        }
+    };
+
+    match get_role_dto_from_role(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting Role to RoleDto: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/roles/{id}/permissions/{permission_id}",
+    params(
+        ("id" = String, Path, description = "The ID of the Role"),
+        ("permission_id" = String, Path, description = "The ID of the Permission"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = RoleDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Roles",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/{id}/permissions/{permission_id}")]
+#[protect("CAN_UPDATE_ROLE")]
+pub async fn remove_permission(
+    path: web::Path<(String, String)>,
+    pool: web::Data<Config>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+) -> HttpResponse {
+    let (id, permission_id) = path.into_inner();
+
+    if ObjectId::parse_str(&permission_id).is_err() {
+        return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "Invalid Permission ID: {}",
+            permission_id
+        )));
+    }
+
+    let user_id = current_user.id;
+
+    let res = match pool
+        .services
+        .role_service
+        .remove_permission(
+            &id,
+            &permission_id,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            return match e {
+                Error::RoleNotFound(_) | Error::InvalidId(_) => HttpResponse::NotFound().finish(),
+                _ => {
+                    error!("Error removing Permission from Role: {}", e);
+                    HttpResponse::InternalServerError()
+                        .json(InternalServerError::new(&e.to_string()))
+                }
+            };
+        }
+    };
+
+    match get_role_dto_from_role(res, &pool).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting Role to RoleDto: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::configuration::config::Config;
+use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::not_found::NotFound;
+use crate::repository::session::session_repository::Error;
+use crate::web::dto::common::page::Page;
+use crate::web::dto::session::session_dto::SessionDto;
+use crate::web::extractors::authenticated_user::AuthenticatedUser;
+use actix_web::{delete, get, post, web, HttpResponse};
+use log::error;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/sessions",
+    responses(
+        (status = 200, description = "OK", body = crate::web::dto::common::page::SessionPage),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/me/sessions")]
+pub async fn find_own_sessions(
+    AuthenticatedUser(user): AuthenticatedUser,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    match pool
+        .services
+        .session_service
+        .find_by_user_id(&user.id, &pool.database)
+        .await
+    {
+        Ok(d) => {
+            // Sessions are not paginated, so the page always contains every active Session
+            let items: Vec<SessionDto> = d.into_iter().map(SessionDto::from).collect();
+            let total = items.len() as u64;
+            let limit = i64::try_from(items.len()).unwrap_or(i64::MAX);
+
+            HttpResponse::Ok().json(Page::new(items, total, limit, None))
+        }
+        Err(e) => {
+            error!("Error finding Sessions of User {}: {}", user.id, e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/sessions/{id}",
+    params(
+        ("id" = String, Path, description = "The ID of the Session"),
+    ),
+    responses(
+        (status = 200, description = "OK"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found", body = NotFound),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[delete("/me/sessions/{id}")]
+pub async fn revoke_own_session(
+    AuthenticatedUser(user): AuthenticatedUser,
+    path: web::Path<String>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    match pool
+        .services
+        .session_service
+        .delete(&path, &user.id, &pool.database)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => match e {
+            Error::SessionNotFound(_) | Error::InvalidId(_) => {
+                HttpResponse::NotFound().json(NotFound::new("Session", &path))
+            }
+            _ => {
+                error!("Error revoking Session {}: {}", path, e);
+                HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+            }
+        },
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/sessions/revoke-all",
+    responses(
+        (status = 200, description = "OK"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/me/sessions/revoke-all")]
+pub async fn revoke_all_own_sessions(
+    AuthenticatedUser(user): AuthenticatedUser,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    match pool
+        .services
+        .session_service
+        .delete_all(&user.id, &pool.database)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            error!("Error revoking Sessions of User {}: {}", user.id, e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
+}
@@ -1,25 +1,46 @@
 use crate::configuration::config::Config;
 use crate::errors::bad_request::BadRequest;
+use crate::errors::forbidden::Forbidden;
 use crate::errors::internal_server_error::InternalServerError;
+use crate::errors::not_found::NotFound;
+use crate::repository::cursor::NEXT_CURSOR_HEADER;
+use crate::repository::permission::permission_model::Permission;
 use crate::repository::permission::permission_repository::Error as PermissionError;
 use crate::repository::role::role_repository::Error as RoleError;
 use crate::repository::user::user_model::User;
-use crate::repository::user::user_repository::Error;
-use crate::services::password::password_service::PasswordService;
+use crate::repository::user::user_repository::{Error, UserFilter};
+use crate::services::event::event_publisher::PasswordChangeMethod;
+use crate::web::controller::audit::audit_controller::parse_date;
+use crate::web::controller::authentication::authentication_controller::{
+    convert_user_to_simple_dto, find_role_and_permission_names, has_permission,
+};
 use crate::web::controller::role::role_controller::get_role_dto_from_role;
+use crate::web::dto::common::page::{resolve_limit, Page};
 use crate::web::dto::role::role_dto::RoleDto;
-use crate::web::dto::search::search_request::SearchRequest;
+use crate::web::dto::search::query_search_request::QuerySearchRequest;
+use crate::web::dto::search::user_search_request::UserSearchRequest;
 use crate::web::dto::user::create_user::CreateUser;
-use crate::web::dto::user::update_password::{AdminUpdatePassword, UpdatePassword};
-use crate::web::dto::user::update_user::{UpdateOwnUser, UpdateUser};
-use crate::web::dto::user::user_dto::UserDto;
-use crate::web::extractors::user_id_extractor;
-use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse};
+use crate::web::dto::user::update_password::{
+    AdminResetPassword, AdminResetPasswordResponse, AdminUpdatePassword, UpdatePassword,
+};
+use crate::web::dto::user::update_user::{
+    ConfirmEmailChange, PatchOwnUser, UpdateOwnUser, UpdateUser,
+};
+use crate::web::dto::user::user_dto::{DetailedUserDto, UserDto};
+use crate::web::dto::user::view_request::ViewRequest;
+use crate::web::extractors::authenticated_user::AuthenticatedUser;
+use crate::web::extractors::client_ip::ClientIp;
+use crate::web::extractors::json_body::JsonBody;
+use actix_web::http::header::{CacheControl, CacheDirective};
+use actix_web::{delete, get, patch, post, put, web, HttpResponse};
 use actix_web_grants::protect;
-use argon2::PasswordHash;
 use log::error;
 use mongodb::bson::oid::ObjectId;
+use std::collections::BTreeSet;
 use std::fmt::{Display, Formatter};
+use zeroize::Zeroizing;
+
+const OWN_PERMISSIONS_MAX_AGE: u32 = 60;
 
 pub enum ConvertError {
     RoleError(RoleError),
@@ -48,7 +69,7 @@ impl Display for ConvertError {
 
 /// # Summary
 ///
-/// Validate whether the roles exist
+/// Find the role IDs that do not refer to an existing Role. All roles are looked up in a single query.
 ///
 /// # Arguments
 ///
@@ -58,40 +79,49 @@ impl Display for ConvertError {
 /// # Example
 ///
 /// ```
-/// let roles = vec!["role1".to_string(), "role2".to_string()];
-/// let res = validate_roles(&roles, &pool);
+/// let roles = Some(vec!["role1".to_string(), "role2".to_string()]);
+/// let unknown_roles = validate_roles(&roles, &pool).await?;
 /// ```
 ///
 /// # Returns
 ///
-/// * `Result<(), RoleError>` - The result containing the () or the RoleError that occurred
-async fn validate_roles(roles: &Option<Vec<String>>, pool: &Config) -> Result<(), RoleError> {
-    if roles.is_none() {
-        return Ok(());
-    }
+/// * `Result<Vec<String>, RoleError>` - The unknown role IDs, which is empty if all roles exist, or the RoleError that
+///   occurred
+pub async fn validate_roles(
+    roles: &Option<Vec<String>>,
+    pool: &Config,
+) -> Result<Vec<String>, RoleError> {
+    let roles = match roles {
+        Some(r) => r,
+        None => return Ok(vec![]),
+    };
 
-    let roles = roles.clone().unwrap();
+    // IDs that are not valid ObjectIds cannot refer to a Role, so they are reported instead of failing the lookup
+    let valid_ids: Vec<String> = roles
+        .iter()
+        .filter(|r| ObjectId::parse_str(r).is_ok())
+        .cloned()
+        .collect();
 
-    for role in roles {
-        let res = pool
-            .services
-            .role_service
-            .find_by_id(&role, &pool.database)
-            .await;
+    let found = pool
+        .services
+        .role_service
+        .find_by_id_vec(valid_ids, false, &pool.database)
+        .await?;
 
-        match res {
-            Ok(d) => {
-                if d.is_none() {
-                    return Err(RoleError::RoleNotFound(role));
-                }
-            }
-            Err(e) => {
-                return Err(e);
-            }
+    let mut unknown_roles: Vec<String> = vec![];
+    for role in roles {
+        let exists = match ObjectId::parse_str(role) {
+            Ok(oid) => found.iter().any(|r| r.id == oid),
+            Err(_) => false,
         };
+
+        if !exists && !unknown_roles.contains(role) {
+            unknown_roles.push(role.clone());
+        }
     }
 
-    Ok(())
+    Ok(unknown_roles)
 }
 
 /// # Summary
@@ -125,7 +155,7 @@ async fn convert_user_to_dto(user: User, pool: &Config) -> Result<UserDto, Conve
         let roles = match pool
             .services
             .role_service
-            .find_by_id_vec(role_vec, &pool.database)
+            .find_by_id_vec(role_vec, true, &pool.database)
             .await
         {
             Ok(d) => d,
@@ -155,6 +185,127 @@ async fn convert_user_to_dto(user: User, pool: &Config) -> Result<UserDto, Conve
     Ok(user_dto)
 }
 
+/// # Summary
+///
+/// Change the email address of a User that is updating itself. The email address is trimmed and an empty email address
+/// is removed. If email changes have to be verified, the new email address is stored as pending until it is confirmed
+/// using the generated token.
+///
+/// # Arguments
+///
+/// * `user` - The User that is updating itself
+/// * `email` - The new email address, or None to remove the email address
+/// * `pool` - The actix-web shared data
+///
+/// # Example
+///
+/// ```
+/// let token = change_own_email(&mut user, Some(String::from("new@codedead.com")), &pool)?;
+/// ```
+///
+/// # Returns
+///
+/// * `Result<Option<String>, &'static str>` - The token that confirms the new email address, if it is pending until it
+///   is confirmed, or the error message if the domain of the email address is not allowed
+fn change_own_email(
+    user: &mut User,
+    email: Option<String>,
+    pool: &Config,
+) -> Result<Option<String>, &'static str> {
+    let email = email
+        .map(|e| String::from(e.trim()))
+        .filter(|e| !e.is_empty());
+
+    if !pool
+        .services
+        .email_service
+        .is_domain_allowed(email.as_deref())
+    {
+        return Err("Email addresses from this domain are not allowed");
+    }
+
+    // Removing the email address or keeping the current one does not need to be confirmed
+    let email = match email {
+        Some(e)
+            if pool.services.email_service.verify_changes && user.email.as_ref() != Some(&e) =>
+        {
+            e
+        }
+        e => {
+            user.email = e;
+            user.pending_email = None;
+            user.email_change_token = None;
+            user.email_change_expires_at = None;
+            return Ok(None);
+        }
+    };
+
+    let (token, token_hash, expires_at) = pool.services.email_service.generate_change_token();
+    user.pending_email = Some(email);
+    user.email_change_token = Some(token_hash);
+    user.email_change_expires_at = Some(expires_at);
+    Ok(Some(token))
+}
+
+/// # Summary
+///
+/// Publish the confirmation token of the pending email address of a User.
+///
+/// # Arguments
+///
+/// * `user` - The User that has a pending email address
+/// * `token` - The token that confirms the pending email address
+/// * `pool` - The actix-web shared data
+///
+/// # Example
+///
+/// ```
+/// publish_email_change(&user, token, &pool);
+/// ```
+fn publish_email_change(user: &User, token: String, pool: &Config) {
+    if let (Some(email), Some(expires_at)) = (&user.pending_email, user.email_change_expires_at) {
+        pool.services.event_service.email_change_requested(
+            &user.id,
+            email.clone(),
+            token,
+            expires_at,
+        );
+    }
+}
+
+/// # Summary
+///
+/// Revoke all Sessions of a User after their password was changed
+///
+/// # Arguments
+///
+/// * `user_id` - The ID of the User whose password was changed
+/// * `pool` - The actix-web shared data
+///
+/// # Example
+///
+/// ```
+/// let res = revoke_sessions(&user.id, &pool).await;
+/// ```
+///
+/// # Returns
+///
+/// * `HttpResponse` - 200 OK if the Sessions were revoked
+async fn revoke_sessions(user_id: &ObjectId, pool: &Config) -> HttpResponse {
+    match pool
+        .services
+        .session_service
+        .delete_all(user_id, &pool.database)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => {
+            error!("Error revoking Sessions after a password change: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/api/v1/users/",
@@ -162,6 +313,8 @@ async fn convert_user_to_dto(user: User, pool: &Config) -> Result<UserDto, Conve
     responses(
         (status = 200, description = "OK", body = UserDto),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Users",
@@ -172,9 +325,9 @@ async fn convert_user_to_dto(user: User, pool: &Config) -> Result<UserDto, Conve
 #[post("/")]
 #[protect("CAN_CREATE_USER")]
 pub async fn create(
-    user_dto: web::Json<CreateUser>,
+    user_dto: JsonBody<CreateUser>,
     pool: web::Data<Config>,
-    req: HttpRequest,
+    AuthenticatedUser(current_user): AuthenticatedUser,
 ) -> HttpResponse {
     if user_dto.username.is_empty() {
         return HttpResponse::BadRequest().json(BadRequest::new("Empty usernames are not allowed"));
@@ -184,37 +337,39 @@ pub async fn create(
         return HttpResponse::BadRequest().json(BadRequest::new("Empty passwords are not allowed"));
     }
 
-    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
-        Some(e) => e,
-        None => {
-            error!("Failed to get User ID from token");
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new("Failed to get User ID from token"));
-        }
-    };
+    if pool
+        .services
+        .password_service
+        .exceeds_max_new_length(&user_dto.password)
+    {
+        return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "Passwords may not be longer than {} bytes",
+            pool.services.password_service.max_new_length
+        )));
+    }
+
+    let user_id = current_user.id;
 
     let user_dto = user_dto.into_inner();
 
-    if user_dto.roles.is_some() {
-        match validate_roles(&user_dto.roles, &pool).await {
-            Ok(_) => (),
-            Err(e) => {
-                return match e {
-                    RoleError::RoleNotFound(r) => HttpResponse::BadRequest()
-                        .json(BadRequest::new(&format!("Role {} not found", r))),
-                    _ => {
-                        error!("Error validating roles: {}", e);
-                        HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()))
-                    }
-                };
-            }
-        };
-    }
+    match validate_roles(&user_dto.roles, &pool).await {
+        Ok(unknown_roles) if !unknown_roles.is_empty() => {
+            return HttpResponse::BadRequest().json(BadRequest::for_field(
+                &format!("Unknown roles: {}", unknown_roles.join(", ")),
+                Some("roles"),
+            ));
+        }
+        Ok(_) => (),
+        Err(e) => {
+            error!("Error validating roles: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
 
     let mut user = User::from(user_dto);
 
-    let password_hash = match PasswordService::hash_password(user.password) {
+    let password_hash = match pool.services.password_service.hash_password(&user.password) {
         Ok(e) => e.to_string(),
         Err(e) => {
             error!("Failed to hash password: {}", e);
@@ -224,6 +379,9 @@ pub async fn create(
     };
 
     user.password = password_hash;
+    if !pool.server_config.tenancy {
+        user.tenant_id = None;
+    }
 
     let res = match pool
         .services
@@ -241,7 +399,8 @@ pub async fn create(
             error!("Error creating User: {}", e);
             return match e {
                 Error::UsernameAlreadyTaken | Error::EmailAlreadyTaken | Error::InvalidEmail(_) => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
+                    HttpResponse::BadRequest()
+                        .json(BadRequest::for_field(&e.to_string(), e.field()))
                 }
                 _ => HttpResponse::InternalServerError()
                     .json(InternalServerError::new(&e.to_string())),
@@ -263,12 +422,17 @@ pub async fn create(
     path = "/api/v1/users/",
     params(
         ("text" = Option<String>, Query, description = "The text to search for", nullable = true),
-        ("limit" = Option<i64>, Query, description = "The limit of users to retrieve", nullable = true),
+        ("role" = Option<String>, Query, description = "A comma-separated list of Role IDs to filter by", nullable = true),
+        ("limit" = Option<i64>, Query, description = "The limit of users to retrieve, capped to MAX_PAGE_SIZE", nullable = true),
         ("page" = Option<i64>, Query, description = "The page", nullable = true),
+        ("after" = Option<String>, Query, description = "The cursor of the previous page, as returned in the X-Next-Cursor header", nullable = true),
+        ("createdAfter" = Option<String>, Query, description = "Only include Users that were created at or after this RFC 3339 date", nullable = true),
+        ("createdBefore" = Option<String>, Query, description = "Only include Users that were created before this RFC 3339 date", nullable = true),
     ),
     responses(
-        (status = 200, description = "OK", body = Vec<UserDto>),
+        (status = 200, description = "OK", body = crate::web::dto::common::page::UserPage),
         (status = 204, description = "No Content"),
+        (status = 400, description = "Bad Request", body = BadRequest),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Users",
@@ -278,24 +442,88 @@ pub async fn create(
 )]
 #[get("/")]
 #[protect("CAN_READ_USER")]
-pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>) -> HttpResponse {
+pub async fn find_all(
+    search: web::Query<UserSearchRequest>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
     let search = search.into_inner();
 
-    let mut limit = search.limit;
     let page = search.page;
 
-    let limit_clone = limit.unwrap_or(pool.server_config.max_limit);
-    if limit.is_none()
-        || (limit.is_some() && limit_clone > pool.server_config.max_limit || limit_clone < 1)
+    let limit = match resolve_limit(search.limit, pool.server_config.max_page_size) {
+        Ok(l) => Some(l),
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    if search.after.is_some() && (search.text.is_some() || search.role.is_some() || page.is_some())
     {
-        limit = Some(pool.server_config.max_limit);
+        return HttpResponse::BadRequest().json(BadRequest::for_field(
+            "The after cursor cannot be combined with the text, role or page parameters",
+            Some("after"),
+        ));
+    }
+
+    if search.text.is_some() && search.role.is_some() {
+        return HttpResponse::BadRequest().json(BadRequest::for_field(
+            "The text parameter cannot be combined with the role parameter",
+            Some("role"),
+        ));
+    }
+
+    let created_after = match parse_date(search.created_after.as_deref(), "createdAfter") {
+        Ok(d) => d,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+    let created_before = match parse_date(search.created_before.as_deref(), "createdBefore") {
+        Ok(d) => d,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+    let date_range = created_after.is_some() || created_before.is_some();
+
+    if date_range && (search.text.is_some() || search.role.is_some() || search.after.is_some()) {
+        return HttpResponse::BadRequest().json(BadRequest::for_field(
+            "The createdAfter and createdBefore parameters cannot be combined with the text, role or after parameters",
+            Some(if created_after.is_some() {
+                "createdAfter"
+            } else {
+                "createdBefore"
+            }),
+        ));
     }
 
-    let res = match search.text {
-        Some(t) => match pool
+    let role_ids: Option<Vec<String>> = search.role.as_ref().map(|r| {
+        r.split(',')
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .collect()
+    });
+
+    let cursor_based = role_ids.is_none() && search.text.is_none() && page.is_none() && !date_range;
+    let mut next_cursor: Option<String> = None;
+    let res = match (&role_ids, &search.text) {
+        (Some(r), _) => {
+            match pool
+                .services
+                .user_service
+                .find_by_role(r.clone(), limit, page, &pool.database)
+                .await
+            {
+                Ok(d) => d,
+                Err(e) => {
+                    error!("Error while finding Users by Role: {}", e);
+                    return match e {
+                        Error::InvalidId(_) | Error::EmptyId => HttpResponse::BadRequest()
+                            .json(BadRequest::for_field(&e.to_string(), e.field())),
+                        _ => HttpResponse::InternalServerError()
+                            .json(InternalServerError::new(&e.to_string())),
+                    };
+                }
+            }
+        }
+        (None, Some(t)) => match pool
             .services
             .user_service
-            .search(&t, limit, page, &pool.database)
+            .search(t, limit, page, &pool.database)
             .await
         {
             Ok(d) => d,
@@ -305,7 +533,20 @@ pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>
                     .json(InternalServerError::new(&e.to_string()));
             }
         },
-        None => match pool
+        (None, None) if date_range => match pool
+            .services
+            .user_service
+            .find_by_date_range(created_after, created_before, limit, page, &pool.database)
+            .await
+        {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Error while finding Users by date range: {}", e);
+                return HttpResponse::InternalServerError()
+                    .json(InternalServerError::new(&e.to_string()));
+            }
+        },
+        (None, None) if page.is_some() => match pool
             .services
             .user_service
             .find_all(limit, page, &pool.database)
@@ -318,6 +559,142 @@ pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>
                     .json(InternalServerError::new(&e.to_string()));
             }
         },
+        (None, None) => match pool
+            .services
+            .user_service
+            .find_after(search.after.as_deref(), limit, &pool.database)
+            .await
+        {
+            Ok((d, c)) => {
+                next_cursor = c;
+                d
+            }
+            Err(e) => {
+                error!("Error while finding Users after cursor: {}", e);
+                return match e {
+                    Error::InvalidCursor(_) => HttpResponse::BadRequest()
+                        .json(BadRequest::for_field(&e.to_string(), e.field())),
+                    _ => HttpResponse::InternalServerError()
+                        .json(InternalServerError::new(&e.to_string())),
+                };
+            }
+        },
+    };
+
+    if res.is_empty() {
+        return HttpResponse::NoContent().finish();
+    }
+
+    let mut user_dto_list: Vec<UserDto> = vec![];
+    for u in res {
+        let user_dto = match convert_user_to_dto(u, &pool).await {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Error converting User to UserDto: {}", e);
+                return HttpResponse::InternalServerError()
+                    .json(InternalServerError::new(&e.to_string()));
+            }
+        };
+
+        user_dto_list.push(user_dto);
+    }
+
+    let filter = match (role_ids, &search.text) {
+        (Some(r), _) => UserFilter::Role(r),
+        (None, Some(t)) => UserFilter::Text(t),
+        (None, None) if date_range => UserFilter::CreatedBetween(created_after, created_before),
+        (None, None) => UserFilter::All,
+    };
+
+    let total = match pool
+        .services
+        .user_service
+        .count(filter, &pool.database)
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Error while counting Users: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    let limit = limit.unwrap_or(pool.server_config.max_page_size);
+
+    let mut response = HttpResponse::Ok();
+    if let Some(c) = &next_cursor {
+        response.insert_header((NEXT_CURSOR_HEADER, c.as_str()));
+    }
+
+    if cursor_based {
+        response.json(Page::with_cursor(user_dto_list, total, limit, next_cursor))
+    } else {
+        response.json(Page::new(user_dto_list, total, limit, page))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/search",
+    params(
+        ("q" = String, Query, description = "The (partial) username, email, first name or last name to search for"),
+        ("limit" = Option<i64>, Query, description = "The limit of users to retrieve, capped to MAX_PAGE_SIZE", nullable = true),
+        ("page" = Option<i64>, Query, description = "The page", nullable = true),
+    ),
+    responses(
+        (status = 200, description = "OK", body = crate::web::dto::common::page::UserPage),
+        (status = 204, description = "No Content"),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/search")]
+#[protect("CAN_READ_USER")]
+pub async fn search_users(
+    search: web::Query<QuerySearchRequest>,
+    pool: web::Data<Config>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+) -> HttpResponse {
+    let search = search.into_inner();
+
+    let query = match search.q {
+        Some(q) if !q.trim().is_empty() => q,
+        _ => return HttpResponse::BadRequest().json(BadRequest::new("Empty query")),
+    };
+
+    let page = search.page;
+
+    let limit = match resolve_limit(search.limit, pool.server_config.max_page_size) {
+        Ok(l) => Some(l),
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+
+    let res = match pool
+        .services
+        .user_service
+        .search_partial(
+            &query,
+            limit,
+            page,
+            Some(current_user.id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error while searching for Users: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
     };
 
     if res.is_empty() {
@@ -338,7 +715,23 @@ pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>
         user_dto_list.push(user_dto);
     }
 
-    HttpResponse::Ok().json(user_dto_list)
+    let total = match pool
+        .services
+        .user_service
+        .count(UserFilter::Partial(&query), &pool.database)
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Error while counting Users: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    let limit = limit.unwrap_or(pool.server_config.max_page_size);
+
+    HttpResponse::Ok().json(Page::new(user_dto_list, total, limit, page))
 }
 
 #[utoipa::path(
@@ -346,10 +739,12 @@ pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>
     path = "/api/v1/users/{id}",
     params(
         ("id" = String, Path, description = "The ID of the User"),
+        ("view" = Option<String>, Query, description = "Use detailed to include the account state of the User, which requires the CAN_READ_USER_DETAILS permission", nullable = true),
     ),
     responses(
-        (status = 200, description = "OK", body = UserDto),
-        (status = 404, description = "Not Found"),
+        (status = 200, description = "OK, or a DetailedUserDto if the detailed view is requested", body = UserDto),
+        (status = 403, description = "Forbidden", body = Forbidden),
+        (status = 404, description = "Not Found", body = NotFound),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Users",
@@ -359,22 +754,41 @@ pub async fn find_all(search: web::Query<SearchRequest>, pool: web::Data<Config>
 )]
 #[get("/{id}")]
 #[protect("CAN_READ_USER")]
-pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Config>) -> HttpResponse {
+pub async fn find_by_id(
+    id: web::Path<String>,
+    view: web::Query<ViewRequest>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    pool: web::Data<Config>,
+) -> HttpResponse {
     let id = id.into_inner();
 
+    if view.is_detailed() {
+        match has_permission(&current_user, "CAN_READ_USER_DETAILS", &pool).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return HttpResponse::Forbidden().json(Forbidden::new(
+                    "missing_permission",
+                    "The CAN_READ_USER_DETAILS permission is required for the detailed view",
+                ))
+            }
+            Err(e) => {
+                error!("Error checking the permissions of the current User: {}", e);
+                return HttpResponse::InternalServerError()
+                    .json(InternalServerError::new(&e.to_string()));
+            }
+        }
+    }
+
     let user = match pool
         .services
         .user_service
         .find_by_id(&id, &pool.database)
         .await
     {
-        Ok(d) => {
-            if d.is_some() {
-                d.unwrap()
-            } else {
-                return HttpResponse::NotFound().finish();
-            }
-        }
+        Ok(d) => match d {
+            Some(d) => d,
+            None => return HttpResponse::NotFound().json(NotFound::new("User", &id)),
+        },
         Err(e) => {
             error!("Error finding User by ID {}: {}", id, e);
             return HttpResponse::InternalServerError()
@@ -382,6 +796,16 @@ pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Config>) -> HttpR
         }
     };
 
+    if view.is_detailed() {
+        return match convert_user_to_simple_dto(user.clone(), &pool, true, false).await {
+            Ok(dto) => HttpResponse::Ok().json(DetailedUserDto::new(dto, &user)),
+            Err(e) => {
+                error!("Error converting User to DetailedUserDto: {}", e);
+                HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+            }
+        };
+    }
+
     match convert_user_to_dto(user, &pool).await {
         Ok(dto) => HttpResponse::Ok().json(dto),
         Err(e) => {
@@ -401,7 +825,10 @@ pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Config>) -> HttpR
     responses(
         (status = 200, description = "OK", body = UserDto),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
         (status = 404, description = "Not Found"),
+        (status = 409, description = "Conflict"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
     tag = "Users",
@@ -413,20 +840,13 @@ pub async fn find_by_id(id: web::Path<String>, pool: web::Data<Config>) -> HttpR
 #[protect("CAN_UPDATE_USER")]
 pub async fn update(
     id: web::Path<String>,
-    user_dto: web::Json<UpdateUser>,
+    user_dto: JsonBody<UpdateUser>,
     pool: web::Data<Config>,
-    req: HttpRequest,
+    AuthenticatedUser(current_user): AuthenticatedUser,
 ) -> HttpResponse {
     let id = id.into_inner();
 
-    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
-        Some(e) => e,
-        None => {
-            error!("Failed to get User ID from token");
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new("Failed to get User ID from token"));
-        }
-    };
+    let user_id = current_user.id;
 
     let mut user = match pool
         .services
@@ -454,22 +874,20 @@ pub async fn update(
 
     let user_dto = user_dto.into_inner();
 
-    if user_dto.roles.is_some() {
-        match validate_roles(&user_dto.roles, &pool).await {
-            Ok(_) => (),
-            Err(e) => {
-                return match e {
-                    RoleError::RoleNotFound(r) => HttpResponse::BadRequest()
-                        .json(BadRequest::new(&format!("Role {} not found", r))),
-                    _ => {
-                        error!("Error validating roles: {}", e);
-                        HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()))
-                    }
-                };
-            }
-        };
-    }
+    match validate_roles(&user_dto.roles, &pool).await {
+        Ok(unknown_roles) if !unknown_roles.is_empty() => {
+            return HttpResponse::BadRequest().json(BadRequest::for_field(
+                &format!("Unknown roles: {}", unknown_roles.join(", ")),
+                Some("roles"),
+            ));
+        }
+        Ok(_) => (),
+        Err(e) => {
+            error!("Error validating roles: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
 
     let role_oid_vec = match user_dto.roles {
         Some(e) => {
@@ -495,6 +913,10 @@ pub async fn update(
     user.last_name = user_dto.last_name;
     user.roles = role_oid_vec;
     user.enabled = user_dto.enabled;
+    // The update is rejected if the User was modified after the client read it
+    if let Some(version) = user_dto.version {
+        user.version = version;
+    }
 
     let res = match pool
         .services
@@ -512,8 +934,10 @@ pub async fn update(
             error!("Error updating User: {}", e);
             return match e {
                 Error::UsernameAlreadyTaken | Error::EmailAlreadyTaken | Error::InvalidEmail(_) => {
-                    HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
+                    HttpResponse::BadRequest()
+                        .json(BadRequest::for_field(&e.to_string(), e.field()))
                 }
+                Error::Conflict(_) => HttpResponse::Conflict().finish(),
                 _ => HttpResponse::InternalServerError()
                     .json(InternalServerError::new(&e.to_string())),
             };
@@ -539,6 +963,8 @@ pub async fn update(
     responses(
         (status = 200, description = "OK", body = SimpleUserDto),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
         (status = 404, description = "Not Found"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
@@ -550,93 +976,282 @@ pub async fn update(
 #[put("/{id}/self/")]
 #[protect("CAN_UPDATE_SELF")]
 pub async fn update_self(
-    req: HttpRequest,
-    user_dto: web::Json<UpdateOwnUser>,
+    AuthenticatedUser(mut user): AuthenticatedUser,
+    user_dto: JsonBody<UpdateOwnUser>,
     pool: web::Data<Config>,
 ) -> HttpResponse {
-    if let Some(auth_header) = req.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                let user_id = match ObjectId::parse_str(token) {
-                    Ok(oid) => oid,
-                    Err(e) => {
-                        error!("Error parsing user ID {}: {}", token, e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()));
-                    }
-                };
+    let user_id = user.id;
 
-                let mut user = match pool
-                    .services
-                    .user_service
-                    .find_by_username(token, &pool.database)
-                    .await
-                {
-                    Ok(d) => {
-                        if d.is_some() {
-                            d.unwrap()
-                        } else {
-                            return HttpResponse::NotFound().finish();
-                        }
-                    }
-                    Err(e) => {
-                        error!("Error finding User by email {}: {}", token, e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()));
-                    }
-                };
+    if user_dto.username.is_empty() {
+        return HttpResponse::BadRequest().json(BadRequest::new("Empty usernames are not allowed"));
+    }
 
-                if user_dto.username.is_empty() {
-                    return HttpResponse::BadRequest()
-                        .json(BadRequest::new("Empty usernames are not allowed"));
+    let user_dto = user_dto.into_inner();
+
+    user.username = user_dto.username;
+    let email_change_token = match change_own_email(&mut user, user_dto.email, &pool) {
+        Ok(t) => t,
+        Err(e) => return HttpResponse::BadRequest().json(BadRequest::for_field(e, Some("email"))),
+    };
+    user.first_name = user_dto.first_name;
+    user.last_name = user_dto.last_name;
+
+    let res = match pool
+        .services
+        .user_service
+        .update(
+            user,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => {
+            if let Some(token) = email_change_token {
+                publish_email_change(&d, token, &pool);
+            }
+            d
+        }
+        Err(e) => {
+            error!("Error updating User: {}", e);
+            return match e {
+                Error::UsernameAlreadyTaken | Error::EmailAlreadyTaken | Error::InvalidEmail(_) => {
+                    HttpResponse::BadRequest()
+                        .json(BadRequest::for_field(&e.to_string(), e.field()))
                 }
+                Error::Conflict(_) => HttpResponse::Conflict().finish(),
+                _ => HttpResponse::InternalServerError()
+                    .json(InternalServerError::new(&e.to_string())),
+            };
+        }
+    };
 
-                let user_dto = user_dto.into_inner();
-
-                user.username = user_dto.username;
-                user.email = user_dto.email;
-                user.first_name = user_dto.first_name;
-                user.last_name = user_dto.last_name;
-
-                let res = match pool
-                    .services
-                    .user_service
-                    .update(
-                        user,
-                        Some(user_id),
-                        &pool.database,
-                        &pool.services.audit_service,
-                    )
-                    .await
-                {
-                    Ok(d) => d,
-                    Err(e) => {
-                        error!("Error updating User: {}", e);
-                        return match e {
-                            Error::UsernameAlreadyTaken
-                            | Error::EmailAlreadyTaken
-                            | Error::InvalidEmail(_) => {
-                                HttpResponse::BadRequest().json(BadRequest::new(&e.to_string()))
-                            }
-                            _ => HttpResponse::InternalServerError()
-                                .json(InternalServerError::new(&e.to_string())),
-                        };
-                    }
-                };
+    match crate::web::controller::authentication::authentication_controller::convert_user_to_simple_dto(res, &pool, true, true).await {
+        Ok(dto) => {
+            HttpResponse::Ok().json(dto)
+        },
+        Err(e) => {
+            error!("Error converting User to UserDto: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
+}
 
-                return match crate::web::controller::authentication::authentication_controller::convert_user_to_simple_dto(res, &pool).await {
-                    Ok(dto) => {
-                        HttpResponse::Ok().json(dto)
-                    },
-                    Err(e) => {
-                        error!("Error converting User to UserDto: {}", e);
-                        HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
-                    }
-                };
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/permissions",
+    responses(
+        (status = 200, description = "OK", body = Vec<String>),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[get("/me/permissions")]
+pub async fn find_own_permissions(
+    AuthenticatedUser(user): AuthenticatedUser,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let (_, permission_names) = match find_role_and_permission_names(&user, &pool).await {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error finding the Permissions of User {}: {}", user.id, e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    let wildcards: Vec<Permission> = permission_names
+        .iter()
+        .map(|n| Permission::new(n.clone(), None))
+        .filter(|p| p.is_wildcard())
+        .collect();
+    let mut effective: BTreeSet<String> = permission_names.into_iter().collect();
+
+    // Wildcards are expanded into every Permission name that they match, just like the authorization checks do
+    if !wildcards.is_empty() {
+        match pool
+            .services
+            .permission_service
+            .find_by_wildcards(&wildcards, &pool.database)
+            .await
+        {
+            Ok(matched) => effective.extend(matched.into_iter().map(|p| p.name)),
+            Err(e) => {
+                error!("Error expanding wildcard Permissions: {}", e);
+                return HttpResponse::InternalServerError()
+                    .json(InternalServerError::new(&e.to_string()));
+            }
+        }
+    }
+
+    // Permissions rarely change, but they belong to a single User and must be revalidated using the ETag afterwards
+    HttpResponse::Ok()
+        .insert_header(CacheControl(vec![
+            CacheDirective::Private,
+            CacheDirective::MaxAge(OWN_PERMISSIONS_MAX_AGE),
+        ]))
+        .json(effective)
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/users/me",
+    request_body = PatchOwnUser,
+    responses(
+        (status = 200, description = "OK", body = SimpleUserDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Conflict"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[patch("/me")]
+#[protect("CAN_UPDATE_SELF")]
+pub async fn patch_self(
+    AuthenticatedUser(mut user): AuthenticatedUser,
+    user_dto: JsonBody<PatchOwnUser>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let user_id = user.id;
+    let user_dto = user_dto.into_inner();
+    let mut email_change_token = None;
+
+    // Fields that are omitted are left untouched, empty fields are cleared
+    if let Some(email) = user_dto.email {
+        email_change_token = match change_own_email(&mut user, Some(email), &pool) {
+            Ok(t) => t,
+            Err(e) => {
+                return HttpResponse::BadRequest().json(BadRequest::for_field(e, Some("email")))
             }
+        };
+    }
+    if let Some(first_name) = user_dto.first_name {
+        user.first_name = Some(first_name).filter(|n| !n.trim().is_empty());
+    }
+    if let Some(last_name) = user_dto.last_name {
+        user.last_name = Some(last_name).filter(|n| !n.trim().is_empty());
+    }
+
+    let res = match pool
+        .services
+        .user_service
+        .update(
+            user,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => {
+            if let Some(token) = email_change_token {
+                publish_email_change(&d, token, &pool);
+            }
+            d
+        }
+        Err(e) => {
+            error!("Error updating User: {}", e);
+            return match e {
+                Error::EmailAlreadyTaken | Error::InvalidEmail(_) => HttpResponse::BadRequest()
+                    .json(BadRequest::for_field(&e.to_string(), e.field())),
+                Error::Conflict(_) => HttpResponse::Conflict().finish(),
+                _ => HttpResponse::InternalServerError()
+                    .json(InternalServerError::new(&e.to_string())),
+            };
+        }
+    };
+
+    match crate::web::controller::authentication::authentication_controller::convert_user_to_simple_dto(res, &pool, true, true).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting User to UserDto: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/email/confirm",
+    request_body = ConfirmEmailChange,
+    responses(
+        (status = 200, description = "OK", body = SimpleUserDto),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 409, description = "Conflict"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/me/email/confirm")]
+#[protect("CAN_UPDATE_SELF")]
+pub async fn confirm_email_change(
+    AuthenticatedUser(mut user): AuthenticatedUser,
+    confirm_email_change: JsonBody<ConfirmEmailChange>,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    let user_id = user.id;
+
+    if !pool
+        .services
+        .email_service
+        .is_change_token_valid(&user, &confirm_email_change.token)
+    {
+        return HttpResponse::BadRequest().json(BadRequest::for_field(
+            "Invalid or expired email change token",
+            Some("token"),
+        ));
+    }
+
+    user.email = user.pending_email.take();
+    user.email_change_token = None;
+    user.email_change_expires_at = None;
+
+    let res = match pool
+        .services
+        .user_service
+        .update(
+            user,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(d) => d,
+        Err(e) => {
+            error!("Error confirming email change: {}", e);
+            return match e {
+                Error::EmailAlreadyTaken | Error::InvalidEmail(_) => HttpResponse::BadRequest()
+                    .json(BadRequest::for_field(&e.to_string(), e.field())),
+                Error::Conflict(_) => HttpResponse::Conflict().finish(),
+                _ => HttpResponse::InternalServerError()
+                    .json(InternalServerError::new(&e.to_string())),
+            };
+        }
+    };
+
+    match crate::web::controller::authentication::authentication_controller::convert_user_to_simple_dto(res, &pool, true, true).await {
+        Ok(dto) => HttpResponse::Ok().json(dto),
+        Err(e) => {
+            error!("Error converting User to UserDto: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
         }
     }
-    HttpResponse::BadRequest().finish()
 }
 
 #[utoipa::path(
@@ -652,6 +1267,8 @@ pub async fn update_self(
     responses(
         (status = 200, description = "OK"),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
         (status = 404, description = "Not Found"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
@@ -663,101 +1280,113 @@ pub async fn update_self(
 #[put("/{id}/self/password/")]
 #[protect("CAN_UPDATE_SELF")]
 pub async fn update_password(
-    req: HttpRequest,
-    update_password: web::Json<UpdatePassword>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    update_password: JsonBody<UpdatePassword>,
     pool: web::Data<Config>,
+    ClientIp(ip): ClientIp,
 ) -> HttpResponse {
-    if let Some(auth_header) = req.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                let user_oid = match ObjectId::parse_str(token) {
-                    Ok(oid) => oid,
-                    Err(e) => {
-                        error!("Error parsing user ID {}: {}", token, e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()));
-                    }
-                };
+    let update_password = update_password.into_inner();
 
-                let user = match pool
-                    .services
-                    .user_service
-                    .find_by_username(token, &pool.database)
-                    .await
-                {
-                    Ok(d) => {
-                        if d.is_some() {
-                            d.unwrap()
-                        } else {
-                            return HttpResponse::NotFound().finish();
-                        }
-                    }
-                    Err(e) => {
-                        error!("Error finding User by email {}: {}", token, e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()));
-                    }
-                };
+    if update_password.old_password.is_empty() {
+        return HttpResponse::BadRequest()
+            .json(BadRequest::new("Empty old passwords are not allowed"));
+    }
 
-                let update_password = update_password.into_inner();
+    if update_password.new_password.is_empty() {
+        return HttpResponse::BadRequest()
+            .json(BadRequest::new("Empty new passwords are not allowed"));
+    }
 
-                if update_password.old_password.is_empty() {
-                    return HttpResponse::BadRequest()
-                        .json(BadRequest::new("Empty old passwords are not allowed"));
-                }
+    // The old password is verified by hashing it as well, so it is bounded in the same way
+    if pool
+        .services
+        .password_service
+        .exceeds_max_length(&update_password.old_password)
+    {
+        return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "Passwords may not be longer than {} bytes",
+            pool.services.password_service.max_length
+        )));
+    }
 
-                if update_password.new_password.is_empty() {
-                    return HttpResponse::BadRequest()
-                        .json(BadRequest::new("Empty new passwords are not allowed"));
-                }
+    if pool
+        .services
+        .password_service
+        .exceeds_max_new_length(&update_password.new_password)
+    {
+        return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "Passwords may not be longer than {} bytes",
+            pool.services.password_service.max_new_length
+        )));
+    }
 
-                let parsed_hash = match PasswordHash::new(&user.password) {
-                    Ok(h) => h,
-                    Err(e) => {
-                        error!("Failed to parse password hash: {}", e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new("Failed to parse password hash"));
-                    }
-                };
+    // Never compare the PHC strings directly, verify_password performs a constant-time comparison
+    if !pool
+        .services
+        .password_service
+        .verify_password(&update_password.old_password, &user.password)
+    {
+        return HttpResponse::BadRequest().finish();
+    }
 
-                if !PasswordService::verify_password(&update_password.old_password, &parsed_hash) {
-                    return HttpResponse::BadRequest().finish();
-                }
+    if pool.services.password_service.is_reused(
+        &update_password.new_password,
+        &user.password,
+        &user.password_history,
+    ) {
+        return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "Passwords may not match any of the last {} passwords",
+            pool.services.password_service.history_size
+        )));
+    }
 
-                let new_password_hash =
-                    match PasswordService::hash_password(update_password.new_password) {
-                        Ok(e) => e.to_string(),
-                        Err(e) => {
-                            error!("Error hashing password: {}", e);
-                            return HttpResponse::InternalServerError()
-                                .json(InternalServerError::new("Failed to hash password"));
-                        }
-                    };
+    let new_password_hash = match pool
+        .services
+        .password_service
+        .hash_password(&update_password.new_password)
+    {
+        Ok(e) => e.to_string(),
+        Err(e) => {
+            error!("Error hashing password: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to hash password"));
+        }
+    };
 
-                return match pool
-                    .services
-                    .user_service
-                    .update_password(
-                        &user.id.to_hex(),
-                        &new_password_hash,
-                        Some(user_oid),
-                        &pool.database,
-                        &pool.services.audit_service,
-                    )
-                    .await
-                {
-                    Ok(_) => HttpResponse::Ok().finish(),
-                    Err(e) => {
-                        error!("Error updating password: {}", e);
-                        HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()))
-                    }
-                };
-            }
+    let password_history = pool
+        .services
+        .password_service
+        .push_history(user.password, user.password_history);
+
+    match pool
+        .services
+        .user_service
+        .update_password(
+            &user.id.to_hex(),
+            &new_password_hash,
+            Some(password_history),
+            false,
+            Some(user.id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => {
+            pool.services.event_service.password_changed(
+                &user.id,
+                user.email,
+                &user.id,
+                PasswordChangeMethod::SelfService,
+                ip,
+            );
+            revoke_sessions(&user.id, &pool).await
+        }
+        Err(e) => {
+            error!("Error updating password: {}", e);
+            HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
         }
     }
-
-    HttpResponse::BadRequest().finish()
 }
 
 #[utoipa::path(
@@ -770,6 +1399,8 @@ pub async fn update_password(
     responses(
         (status = 200, description = "OK"),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
         (status = 404, description = "Not Found"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
@@ -782,21 +1413,15 @@ pub async fn update_password(
 #[protect("CAN_UPDATE_USER")]
 pub async fn admin_update_password(
     id: web::Path<String>,
-    admin_update_password: web::Json<AdminUpdatePassword>,
+    admin_update_password: JsonBody<AdminUpdatePassword>,
     pool: web::Data<Config>,
-    req: HttpRequest,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
 ) -> HttpResponse {
     let id = id.into_inner();
     let admin_update_password = admin_update_password.into_inner();
 
-    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
-        Some(e) => e,
-        None => {
-            error!("Failed to get User ID from token");
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new("Failed to get User ID from token"));
-        }
-    };
+    let user_id = current_user.id;
 
     let user = match pool
         .services
@@ -822,7 +1447,33 @@ pub async fn admin_update_password(
         return HttpResponse::BadRequest().json(BadRequest::new("Empty passwords are not allowed"));
     }
 
-    let password_hash = match PasswordService::hash_password(admin_update_password.password) {
+    if pool
+        .services
+        .password_service
+        .exceeds_max_new_length(&admin_update_password.password)
+    {
+        return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "Passwords may not be longer than {} bytes",
+            pool.services.password_service.max_new_length
+        )));
+    }
+
+    if pool.services.password_service.is_reused(
+        &admin_update_password.password,
+        &user.password,
+        &user.password_history,
+    ) {
+        return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "Passwords may not match any of the last {} passwords",
+            pool.services.password_service.history_size
+        )));
+    }
+
+    let password_hash = match pool
+        .services
+        .password_service
+        .hash_password(&admin_update_password.password)
+    {
         Ok(e) => e.to_string(),
         Err(e) => {
             error!("Error hashing password: {}", e);
@@ -831,19 +1482,35 @@ pub async fn admin_update_password(
         }
     };
 
+    let password_history = pool
+        .services
+        .password_service
+        .push_history(user.password, user.password_history);
+
     match pool
         .services
         .user_service
         .update_password(
             &user.id.to_hex(),
             &password_hash,
+            Some(password_history),
+            false,
             Some(user_id),
             &pool.database,
             &pool.services.audit_service,
         )
         .await
     {
-        Ok(_) => HttpResponse::Ok().finish(),
+        Ok(_) => {
+            pool.services.event_service.password_changed(
+                &user.id,
+                user.email,
+                &user_id,
+                PasswordChangeMethod::Admin,
+                ip,
+            );
+            revoke_sessions(&user.id, &pool).await
+        }
         Err(e) => {
             error!("Error updating password: {}", e);
             HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
@@ -851,6 +1518,144 @@ pub async fn admin_update_password(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{id}/reset-password/",
+    request_body = AdminResetPassword,
+    params(
+        ("id" = String, Path, description = "The ID of the User"),
+    ),
+    responses(
+        (status = 200, description = "OK", body = AdminResetPasswordResponse),
+        (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
+        (status = 404, description = "Not Found"),
+        (status = 500, description = "Internal Server Error", body = InternalServerError),
+    ),
+    tag = "Users",
+    security(
+        ("Token" = [])
+    )
+)]
+#[post("/{id}/reset-password/")]
+#[protect("CAN_UPDATE_USER")]
+pub async fn admin_reset_password(
+    id: web::Path<String>,
+    admin_reset_password: JsonBody<AdminResetPassword>,
+    pool: web::Data<Config>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    ClientIp(ip): ClientIp,
+) -> HttpResponse {
+    let id = id.into_inner();
+    let admin_reset_password = admin_reset_password.into_inner();
+
+    let user_id = current_user.id;
+
+    let user = match pool
+        .services
+        .user_service
+        .find_by_id(&id, &pool.database)
+        .await
+    {
+        Ok(Some(d)) => d,
+        Ok(None) => return HttpResponse::NotFound().finish(),
+        Err(e) => {
+            error!("Error finding User by ID {}: {}", id, e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new(&e.to_string()));
+        }
+    };
+
+    // There is no mail transport to deliver a reset link, so a generated password is returned to the administrator once
+    let (temporary_password, generated) = match admin_reset_password.password {
+        Some(p) => (Zeroizing::new(p), false),
+        None => (
+            pool.services.password_service.generate_temporary_password(),
+            true,
+        ),
+    };
+
+    if temporary_password.is_empty() {
+        return HttpResponse::BadRequest().json(BadRequest::new("Empty passwords are not allowed"));
+    }
+
+    if pool
+        .services
+        .password_service
+        .exceeds_max_new_length(&temporary_password)
+    {
+        return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "Passwords may not be longer than {} bytes",
+            pool.services.password_service.max_new_length
+        )));
+    }
+
+    if pool.services.password_service.is_reused(
+        &temporary_password,
+        &user.password,
+        &user.password_history,
+    ) {
+        return HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "Passwords may not match any of the last {} passwords",
+            pool.services.password_service.history_size
+        )));
+    }
+
+    let password_hash = match pool
+        .services
+        .password_service
+        .hash_password(&temporary_password)
+    {
+        Ok(e) => e.to_string(),
+        Err(e) => {
+            error!("Error hashing password: {}", e);
+            return HttpResponse::InternalServerError()
+                .json(InternalServerError::new("Failed to hash password"));
+        }
+    };
+
+    let password_history = pool
+        .services
+        .password_service
+        .push_history(user.password, user.password_history);
+
+    if let Err(e) = pool
+        .services
+        .user_service
+        .update_password(
+            &user.id.to_hex(),
+            &password_hash,
+            Some(password_history),
+            true,
+            Some(user_id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        error!("Error resetting password: {}", e);
+        return HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()));
+    }
+
+    pool.services.event_service.password_changed(
+        &user.id,
+        user.email,
+        &user_id,
+        PasswordChangeMethod::Reset,
+        ip,
+    );
+
+    let response = revoke_sessions(&user.id, &pool).await;
+    if !generated || !response.status().is_success() {
+        return response;
+    }
+
+    HttpResponse::Ok().json(AdminResetPasswordResponse {
+        temporary_password: temporary_password.to_string(),
+    })
+}
+
 #[utoipa::path(
     delete,
     path = "/api/v1/users/{id}",
@@ -859,6 +1664,8 @@ pub async fn admin_update_password(
     ),
     responses(
         (status = 200, description = "OK"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden"),
         (status = 404, description = "Not Found"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
@@ -872,16 +1679,9 @@ pub async fn admin_update_password(
 pub async fn delete(
     id: web::Path<String>,
     pool: web::Data<Config>,
-    req: HttpRequest,
+    AuthenticatedUser(current_user): AuthenticatedUser,
 ) -> HttpResponse {
-    let user_id = match user_id_extractor::get_user_id_from_token(&req, &pool).await {
-        Some(e) => e,
-        None => {
-            error!("Failed to get User ID from token");
-            return HttpResponse::InternalServerError()
-                .json(InternalServerError::new("Failed to get User ID from token"));
-        }
-    };
+    let user_id = current_user.id;
 
     match pool
         .services
@@ -914,6 +1714,7 @@ pub async fn delete(
     responses(
         (status = 200, description = "OK"),
         (status = 400, description = "Bad Request", body = BadRequest),
+        (status = 401, description = "Unauthorized"),
         (status = 403, description = "Forbidden"),
         (status = 500, description = "Internal Server Error", body = InternalServerError),
     ),
@@ -924,50 +1725,28 @@ pub async fn delete(
 )]
 #[delete("/{id}/self/")]
 #[protect("CAN_DELETE_SELF")]
-pub async fn delete_self(req: HttpRequest, pool: web::Data<Config>) -> HttpResponse {
-    if let Some(auth_header) = req.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                let user_oid = match ObjectId::parse_str(token) {
-                    Ok(oid) => oid,
-                    Err(e) => {
-                        error!("Error parsing user ID {}: {}", token, e);
-                        return HttpResponse::InternalServerError()
-                            .json(InternalServerError::new(&e.to_string()));
-                    }
-                };
-
-                let username = match pool.services.jwt_service.verify_jwt_token(token) {
-                    Ok(user) => user,
-                    Err(e) => {
-                        error!("Failed to verify JWT token: {}", e);
-                        return HttpResponse::Forbidden().finish();
-                    }
-                };
-
-                return match pool
-                    .services
-                    .user_service
-                    .delete(
-                        &username,
-                        Some(user_oid),
-                        &pool.database,
-                        &pool.services.audit_service,
-                    )
-                    .await
-                {
-                    Ok(_) => HttpResponse::Ok().finish(),
-                    Err(e) => match e {
-                        Error::UserNotFound(_) => HttpResponse::Ok().finish(),
-                        _ => {
-                            error!("Error deleting User: {}", e);
-                            HttpResponse::InternalServerError()
-                                .json(InternalServerError::new(&e.to_string()))
-                        }
-                    },
-                };
+pub async fn delete_self(
+    AuthenticatedUser(user): AuthenticatedUser,
+    pool: web::Data<Config>,
+) -> HttpResponse {
+    match pool
+        .services
+        .user_service
+        .delete(
+            &user.id.to_hex(),
+            Some(user.id),
+            &pool.database,
+            &pool.services.audit_service,
+        )
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().finish(),
+        Err(e) => match e {
+            Error::UserNotFound(_) => HttpResponse::Ok().finish(),
+            _ => {
+                error!("Error deleting User: {}", e);
+                HttpResponse::InternalServerError().json(InternalServerError::new(&e.to_string()))
             }
-        }
+        },
     }
-    HttpResponse::BadRequest().finish()
 }
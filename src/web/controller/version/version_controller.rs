@@ -0,0 +1,62 @@
+use actix_web::{get, HttpResponse};
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// The version of the crate.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// The abbreviated hash of the commit that was built, as determined by the build script.
+pub const GIT_COMMIT: &str = env!("GIT_COMMIT");
+/// The build timestamp in seconds since the Unix epoch, as determined by the build script.
+pub const BUILD_TIMESTAMP: &str = env!("BUILD_TIMESTAMP");
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct VersionResponse {
+    pub version: String,
+    #[serde(rename = "gitCommit")]
+    pub git_commit: String,
+    #[serde(rename = "buildTimestamp")]
+    pub build_timestamp: Option<String>,
+}
+
+impl VersionResponse {
+    /// # Summary
+    ///
+    /// Create a new VersionResponse that describes the running build.
+    ///
+    /// # Returns
+    ///
+    /// * `VersionResponse` - The new VersionResponse.
+    pub fn new() -> Self {
+        let build_timestamp = BUILD_TIMESTAMP
+            .parse::<i64>()
+            .ok()
+            .and_then(|t| Utc.timestamp_opt(t, 0).single())
+            .map(|t| t.to_rfc3339());
+
+        Self {
+            version: VERSION.to_string(),
+            git_commit: GIT_COMMIT.to_string(),
+            build_timestamp,
+        }
+    }
+}
+
+impl Default for VersionResponse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/version",
+    responses(
+        (status = 200, description = "OK", body = VersionResponse),
+    ),
+    tag = "Health",
+)]
+#[get("/version")]
+pub async fn version() -> HttpResponse {
+    HttpResponse::Ok().json(VersionResponse::new())
+}
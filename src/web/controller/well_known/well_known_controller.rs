@@ -0,0 +1,15 @@
+use crate::configuration::config::Config;
+use actix_web::{get, web, HttpResponse};
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    responses(
+        (status = 200, description = "OK", body = crate::services::jwt::jwk::JwkSet),
+    ),
+    tag = "Authentication",
+)]
+#[get("/jwks.json")]
+pub async fn jwks(pool: web::Data<Config>) -> HttpResponse {
+    HttpResponse::Ok().json(pool.services.jwt_service.jwk_set())
+}
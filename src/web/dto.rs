@@ -1,6 +1,12 @@
 pub mod audit;
 pub mod authentication;
+pub mod authz;
+pub mod common;
+pub mod debug;
+pub mod invite;
 pub mod permission;
 pub mod role;
 pub mod search;
+pub mod secret_string;
+pub mod session;
 pub mod user;
@@ -1 +1,5 @@
+pub mod audit_actor_dto;
 pub mod audit_dto;
+pub mod audit_stats_dto;
+pub mod audit_stats_request;
+pub mod delete_audits;
@@ -0,0 +1,64 @@
+use crate::repository::user::user_model::User;
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AuditActorDto {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub deleted: bool,
+}
+
+impl AuditActorDto {
+    /// # Summary
+    ///
+    /// Create a new AuditActorDto for an actor that no longer exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the deleted User.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let actor = AuditActorDto::deleted(&ObjectId::new());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `AuditActorDto` - The new AuditActorDto.
+    pub fn deleted(id: &ObjectId) -> AuditActorDto {
+        AuditActorDto {
+            id: id.to_hex(),
+            username: None,
+            email: None,
+            deleted: true,
+        }
+    }
+}
+
+impl From<&User> for AuditActorDto {
+    /// # Summary
+    ///
+    /// Convert a User to an AuditActorDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A User.
+    ///
+    /// # Returns
+    ///
+    /// An AuditActorDto.
+    fn from(value: &User) -> Self {
+        AuditActorDto {
+            id: value.id.to_hex(),
+            username: Some(value.username.clone()),
+            email: value.email.clone(),
+            deleted: false,
+        }
+    }
+}
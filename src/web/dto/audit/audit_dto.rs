@@ -1,5 +1,9 @@
-use crate::repository::audit::audit_model::{Action, Audit, ResourceIdType, ResourceType};
+use crate::repository::audit::audit_model::{
+    Action, Audit, LoginOutcome, ResourceIdType, ResourceType,
+};
+use crate::web::dto::audit::audit_actor_dto::AuditActorDto;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use utoipa::ToSchema;
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -10,6 +14,30 @@ pub enum ActionDto {
     Update,
     #[serde(rename = "delete")]
     Delete,
+    #[serde(rename = "search")]
+    Search,
+    #[serde(rename = "login")]
+    Login,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub enum LoginOutcomeDto {
+    #[serde(rename = "OK")]
+    Ok,
+    #[serde(rename = "BAD_PASSWORD")]
+    BadPassword,
+    #[serde(rename = "USER_NOT_FOUND")]
+    UserNotFound,
+    #[serde(rename = "DISABLED")]
+    Disabled,
+    #[serde(rename = "LOCKED")]
+    Locked,
+    #[serde(rename = "MFA_REQUIRED")]
+    MfaRequired,
+    #[serde(rename = "PASSWORD_EXPIRED")]
+    PasswordExpired,
+    #[serde(rename = "PASSWORD_CHANGE_REQUIRED")]
+    PasswordChangeRequired,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -42,6 +70,8 @@ pub enum ResourceIdTypeDto {
 
 #[derive(Serialize, Deserialize, ToSchema)]
 pub enum ResourceTypeDto {
+    #[serde(rename = "audit")]
+    Audit,
     #[serde(rename = "permission")]
     Permission,
     #[serde(rename = "role")]
@@ -62,8 +92,17 @@ pub struct AuditDto {
     pub resource_id_type: ResourceIdTypeDto,
     #[serde(rename = "resourceType")]
     pub resource_type: ResourceTypeDto,
+    #[serde(rename = "requestId")]
+    pub request_id: Option<String>,
+    pub system: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<LoginOutcomeDto>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<BTreeMap<String, String>>,
     #[serde(rename = "createdAt")]
     pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<AuditActorDto>,
 }
 
 impl From<Audit> for AuditDto {
@@ -90,7 +129,12 @@ impl From<Audit> for AuditDto {
             resource_id: value.resource_id.to_hex(),
             resource_id_type,
             resource_type,
+            request_id: value.request_id,
+            system: value.system,
+            reason: value.reason.map(LoginOutcomeDto::from),
+            details: value.details,
             created_at: value.created_at.to_rfc3339(),
+            actor: None,
         }
     }
 }
@@ -109,6 +153,7 @@ impl From<ResourceType> for ResourceTypeDto {
     /// A ResourceTypeDto.
     fn from(value: ResourceType) -> Self {
         match value {
+            ResourceType::Audit => ResourceTypeDto::Audit,
             ResourceType::Permission => ResourceTypeDto::Permission,
             ResourceType::Role => ResourceTypeDto::Role,
             ResourceType::User => ResourceTypeDto::User,
@@ -163,6 +208,34 @@ impl From<Action> for ActionDto {
             Action::Create => ActionDto::Create,
             Action::Update => ActionDto::Update,
             Action::Delete => ActionDto::Delete,
+            Action::Search => ActionDto::Search,
+            Action::Login => ActionDto::Login,
+        }
+    }
+}
+
+impl From<LoginOutcome> for LoginOutcomeDto {
+    /// # Summary
+    ///
+    /// Convert a LoginOutcome to a LoginOutcomeDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - A LoginOutcome.
+    ///
+    /// # Returns
+    ///
+    /// A LoginOutcomeDto.
+    fn from(value: LoginOutcome) -> Self {
+        match value {
+            LoginOutcome::Ok => LoginOutcomeDto::Ok,
+            LoginOutcome::BadPassword => LoginOutcomeDto::BadPassword,
+            LoginOutcome::UserNotFound => LoginOutcomeDto::UserNotFound,
+            LoginOutcome::Disabled => LoginOutcomeDto::Disabled,
+            LoginOutcome::Locked => LoginOutcomeDto::Locked,
+            LoginOutcome::MfaRequired => LoginOutcomeDto::MfaRequired,
+            LoginOutcome::PasswordExpired => LoginOutcomeDto::PasswordExpired,
+            LoginOutcome::PasswordChangeRequired => LoginOutcomeDto::PasswordChangeRequired,
         }
     }
 }
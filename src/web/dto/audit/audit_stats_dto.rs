@@ -0,0 +1,46 @@
+use crate::repository::audit::audit_model::AuditStats;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct AuditStatsDto {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub total: u64,
+    #[serde(rename = "byAction")]
+    pub by_action: BTreeMap<String, u64>,
+    #[serde(rename = "byResourceType")]
+    pub by_resource_type: BTreeMap<String, u64>,
+}
+
+impl AuditStatsDto {
+    /// # Summary
+    ///
+    /// Create a new AuditStatsDto from the AuditStats of a date range.
+    ///
+    /// # Arguments
+    ///
+    /// * `stats` - The AuditStats.
+    /// * `from` - The inclusive start of the date range, if any.
+    /// * `to` - The exclusive end of the date range, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let audit_stats_dto = AuditStatsDto::new(stats, None, None);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `AuditStatsDto` - The new AuditStatsDto.
+    pub fn new(stats: AuditStats, from: Option<String>, to: Option<String>) -> AuditStatsDto {
+        AuditStatsDto {
+            from,
+            to,
+            total: stats.total,
+            by_action: stats.by_action,
+            by_resource_type: stats.by_resource_type,
+        }
+    }
+}
@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct AuditStatsRequest {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
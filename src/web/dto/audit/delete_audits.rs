@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize)]
+pub struct DeleteAuditsRequest {
+    pub before: Option<String>,
+    pub confirm: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct DeleteAuditsResponse {
+    #[serde(rename = "deletedCount")]
+    pub deleted_count: u64,
+    pub before: String,
+}
+
+impl DeleteAuditsResponse {
+    /// # Summary
+    ///
+    /// Create a new DeleteAuditsResponse.
+    ///
+    /// # Arguments
+    ///
+    /// * `deleted_count` - The number of deleted Audits.
+    /// * `before` - The date before which the Audits were deleted, in RFC 3339 format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let delete_audits_response = DeleteAuditsResponse::new(42, Utc::now().to_rfc3339());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `DeleteAuditsResponse` - The new DeleteAuditsResponse.
+    pub fn new(deleted_count: u64, before: String) -> DeleteAuditsResponse {
+        DeleteAuditsResponse {
+            deleted_count,
+            before,
+        }
+    }
+}
@@ -1,3 +1,10 @@
+pub mod account_locked_response;
+pub mod change_password_request;
+pub mod expand_request;
+pub mod include_request;
+pub mod introspect_response;
 pub mod login_request;
 pub mod login_response;
+pub mod password_change_required_response;
+pub mod refresh_request;
 pub mod register_request;
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct AccountLockedResponse {
+    pub message: String,
+    pub reason: String,
+    #[serde(rename = "lockedUntil")]
+    pub locked_until: String,
+    pub timestamp: String,
+    #[serde(rename = "errorCode")]
+    pub error_code: u16,
+}
+
+impl AccountLockedResponse {
+    /// # Summary
+    ///
+    /// Create a new AccountLockedResponse.
+    ///
+    /// # Arguments
+    ///
+    /// * `locked_until` - The time at which the lock of the account ends.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let response = AccountLockedResponse::new(Utc::now());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `AccountLockedResponse` - The new AccountLockedResponse.
+    pub fn new(locked_until: DateTime<Utc>) -> AccountLockedResponse {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        AccountLockedResponse {
+            message: String::from(
+                "The account is locked because of too many failed login attempts",
+            ),
+            reason: String::from("account_locked"),
+            locked_until: locked_until.to_rfc3339(),
+            timestamp: now,
+            error_code: 403,
+        }
+    }
+}
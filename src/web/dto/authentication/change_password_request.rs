@@ -0,0 +1,17 @@
+use crate::web::dto::secret_string::SecretString;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct ChangePasswordRequest {
+    #[serde(rename = "tenantId")]
+    pub tenant_id: Option<String>,
+    #[serde(default)]
+    pub username: String,
+    #[serde(rename = "oldPassword")]
+    #[schema(value_type = String)]
+    pub old_password: SecretString,
+    #[serde(rename = "newPassword")]
+    #[schema(value_type = String)]
+    pub new_password: SecretString,
+}
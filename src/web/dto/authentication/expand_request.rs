@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct ExpandRequest {
+    pub expand: Option<String>,
+}
+
+impl ExpandRequest {
+    /// # Summary
+    ///
+    /// Check whether a relation should be expanded.
+    ///
+    /// # Arguments
+    ///
+    /// * `relation` - The name of the relation, for example `roles` or `permissions`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let expand_request = ExpandRequest { expand: Some(String::from("roles,permissions")) };
+    /// let expand_roles = expand_request.expands("roles");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the relation is present in the comma-separated `expand` parameter.
+    pub fn expands(&self, relation: &str) -> bool {
+        match &self.expand {
+            Some(e) => e
+                .split(',')
+                .any(|r| r.trim().eq_ignore_ascii_case(relation)),
+            None => false,
+        }
+    }
+}
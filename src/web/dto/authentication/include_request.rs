@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct IncludeRequest {
+    pub include: Option<String>,
+}
+
+impl IncludeRequest {
+    /// # Summary
+    ///
+    /// Check whether a resource should be included in the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - The name of the resource, for example `user`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let include_request = IncludeRequest { include: Some(String::from("user")) };
+    /// let include_user = include_request.includes("user");
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the resource is present in the comma-separated `include` parameter.
+    pub fn includes(&self, resource: &str) -> bool {
+        match &self.include {
+            Some(i) => i
+                .split(',')
+                .any(|r| r.trim().eq_ignore_ascii_case(resource)),
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::services::token::token_service::Claims;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct IntrospectResponse {
+    pub sub: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+    pub iat: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<usize>,
+    pub exp: usize,
+    #[serde(rename = "expiresIn")]
+    pub expires_in: i64,
+}
+
+impl From<Claims> for IntrospectResponse {
+    /// # Summary
+    ///
+    /// Convert the Claims of a verified token into an IntrospectResponse.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The Claims to convert.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let introspect_response = IntrospectResponse::from(claims);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `IntrospectResponse` - The new IntrospectResponse.
+    fn from(claims: Claims) -> Self {
+        let expires_in = (claims.exp as i64 - Utc::now().timestamp()).max(0);
+
+        IntrospectResponse {
+            sub: claims.sub,
+            jti: claims.jti,
+            tenant: claims.tenant,
+            iat: claims.iat,
+            nbf: claims.nbf,
+            exp: claims.exp,
+            expires_in,
+        }
+    }
+}
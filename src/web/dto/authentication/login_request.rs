@@ -1,8 +1,12 @@
+use crate::web::dto::secret_string::SecretString;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 #[derive(Deserialize, Serialize, ToSchema)]
 pub struct LoginRequest {
+    #[serde(rename = "tenantId")]
+    pub tenant_id: Option<String>,
     pub username: String,
-    pub password: String,
+    #[schema(value_type = String)]
+    pub password: SecretString,
 }
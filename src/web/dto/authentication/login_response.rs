@@ -1,9 +1,14 @@
+use crate::web::dto::user::user_dto::SimpleUserDto;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 #[derive(Deserialize, Serialize, ToSchema)]
 pub struct LoginResponse {
     pub token: String,
+    #[serde(rename = "refreshToken", skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<SimpleUserDto>,
 }
 
 impl LoginResponse {
@@ -14,17 +19,27 @@ impl LoginResponse {
     /// # Arguments
     ///
     /// * `token` - The token of the LoginResponse.
+    /// * `refresh_token` - The optional refresh token of the LoginResponse.
+    /// * `user` - The optional User that logged in.
     ///
     /// # Example
     ///
     /// ```
-    /// let login_response = LoginResponse::new(String::from("token"));
+    /// let login_response = LoginResponse::new(String::from("token"), None, None);
     /// ```
     ///
     /// # Returns
     ///
     /// * `LoginResponse` - The new LoginResponse.
-    pub fn new(token: String) -> LoginResponse {
-        LoginResponse { token }
+    pub fn new(
+        token: String,
+        refresh_token: Option<String>,
+        user: Option<SimpleUserDto>,
+    ) -> LoginResponse {
+        LoginResponse {
+            token,
+            refresh_token,
+            user,
+        }
     }
 }
@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct PasswordChangeRequiredResponse {
+    pub message: String,
+    pub reason: String,
+    pub token: String,
+    pub timestamp: String,
+    #[serde(rename = "errorCode")]
+    pub error_code: u16,
+}
+
+impl PasswordChangeRequiredResponse {
+    /// # Summary
+    ///
+    /// Create a new PasswordChangeRequiredResponse.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token that can only be used to change the password.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let response = PasswordChangeRequiredResponse::new(String::from("token"));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `PasswordChangeRequiredResponse` - The new PasswordChangeRequiredResponse.
+    pub fn new(token: String) -> PasswordChangeRequiredResponse {
+        let now: DateTime<Utc> = SystemTime::now().into();
+        let now: String = now.to_rfc3339();
+
+        PasswordChangeRequiredResponse {
+            message: String::from("The password was reset and must be changed"),
+            reason: String::from("password_change_required"),
+            token,
+            timestamp: now,
+            error_code: 403,
+        }
+    }
+}
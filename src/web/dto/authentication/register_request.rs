@@ -1,13 +1,20 @@
+use crate::web::dto::secret_string::SecretString;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct RegisterRequest {
+    #[serde(rename = "tenantId")]
+    pub tenant_id: Option<String>,
     pub username: String,
     pub email: Option<String>,
     #[serde(rename = "firstName")]
     pub first_name: Option<String>,
     #[serde(rename = "lastName")]
     pub last_name: Option<String>,
-    pub password: String,
+    #[schema(value_type = String)]
+    pub password: SecretString,
+    #[serde(rename = "inviteToken")]
+    #[schema(value_type = Option<String>)]
+    pub invite_token: Option<SecretString>,
 }
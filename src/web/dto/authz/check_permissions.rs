@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct CheckPermissionsRequest {
+    pub permissions: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct CheckPermissionsResponse {
+    pub permissions: BTreeMap<String, bool>,
+}
+
+impl CheckPermissionsResponse {
+    /// # Summary
+    ///
+    /// Create a new CheckPermissionsResponse.
+    ///
+    /// # Arguments
+    ///
+    /// * `permissions` - The checked Permission names and whether they are granted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let check_permissions_response = CheckPermissionsResponse::new(BTreeMap::new());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `CheckPermissionsResponse` - The new CheckPermissionsResponse.
+    pub fn new(permissions: BTreeMap<String, bool>) -> CheckPermissionsResponse {
+        CheckPermissionsResponse { permissions }
+    }
+}
@@ -0,0 +1,157 @@
+use crate::errors::bad_request::BadRequest;
+use crate::web::dto::audit::audit_dto::AuditDto;
+use crate::web::dto::invite::invite_dto::InviteDto;
+use crate::web::dto::permission::permission_dto::PermissionDto;
+use crate::web::dto::role::role_dto::RoleDto;
+use crate::web::dto::session::session_dto::SessionDto;
+use crate::web::dto::user::user_dto::UserDto;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[aliases(
+    AuditPage = Page<AuditDto>,
+    InvitePage = Page<InviteDto>,
+    PermissionPage = Page<PermissionDto>,
+    RolePage = Page<RoleDto>,
+    SessionPage = Page<SessionDto>,
+    UserPage = Page<UserDto>,
+)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+    pub limit: i64,
+    pub offset: Option<u64>,
+    #[serde(rename = "hasNext")]
+    pub has_next: bool,
+    #[serde(rename = "nextCursor", skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// # Summary
+    ///
+    /// Create a new Page for offset-based pagination.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The items of the page.
+    /// * `total` - The total number of items that match the request, across all pages.
+    /// * `limit` - The maximum number of items per page.
+    /// * `page` - The requested page, starting at one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let page = Page::new(vec![permission_dto], 1, 100, Some(1));
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Page<T>` - The new Page.
+    pub fn new(items: Vec<T>, total: u64, limit: i64, page: Option<i64>) -> Page<T> {
+        let offset = match page {
+            Some(p) if p > 1 => u64::try_from((p - 1) * limit).unwrap_or(0),
+            _ => 0,
+        };
+
+        Page {
+            has_next: has_next(offset, items.len(), total),
+            items,
+            total,
+            limit,
+            offset: Some(offset),
+            next_cursor: None,
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Create a new Page for cursor-based pagination. The offset of such a page is unknown.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The items of the page.
+    /// * `total` - The total number of items that match the request, across all pages.
+    /// * `limit` - The maximum number of items per page.
+    /// * `next_cursor` - The cursor of the next page, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let page = Page::with_cursor(vec![audit_dto], 1, 100, None);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Page<T>` - The new Page.
+    pub fn with_cursor(
+        items: Vec<T>,
+        total: u64,
+        limit: i64,
+        next_cursor: Option<String>,
+    ) -> Page<T> {
+        Page {
+            has_next: next_cursor.is_some(),
+            items,
+            total,
+            limit,
+            offset: None,
+            next_cursor,
+        }
+    }
+}
+
+/// # Summary
+///
+/// Check whether there are more items after a page.
+///
+/// # Arguments
+///
+/// * `offset` - The number of items before the page.
+/// * `count` - The number of items in the page.
+/// * `total` - The total number of items across all pages.
+///
+/// # Example
+///
+/// ```
+/// assert!(has_next(0, 10, 11));
+/// ```
+///
+/// # Returns
+///
+/// * `bool` - True if there are more items after the page.
+pub fn has_next(offset: u64, count: usize, total: u64) -> bool {
+    offset.saturating_add(count as u64) < total
+}
+
+/// # Summary
+///
+/// Resolve the effective limit of a page request. A missing limit defaults to the maximum page size and a larger limit
+/// is capped to it, so that a single request cannot load an unbounded amount of entities.
+///
+/// # Arguments
+///
+/// * `limit` - The requested limit, if any.
+/// * `max_page_size` - The maximum amount of items per page.
+///
+/// # Example
+///
+/// ```
+/// let limit = resolve_limit(Some(1000), 100).unwrap();
+/// assert_eq!(limit, 100);
+/// ```
+///
+/// # Returns
+///
+/// * `Result<i64, BadRequest>` - The effective limit, or a BadRequest if the requested limit is not positive.
+pub fn resolve_limit(limit: Option<i64>, max_page_size: i64) -> Result<i64, BadRequest> {
+    match limit {
+        Some(l) if l < 1 => Err(BadRequest::for_field(
+            "The limit must be greater than zero",
+            Some("limit"),
+        )),
+        Some(l) => Ok(l.min(max_page_size)),
+        None => Ok(max_page_size),
+    }
+}
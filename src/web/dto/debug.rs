@@ -0,0 +1 @@
+pub mod whoami_response;
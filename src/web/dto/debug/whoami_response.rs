@@ -0,0 +1,47 @@
+use crate::services::token::token_service::Claims;
+use crate::web::dto::user::user_dto::SimpleUserDto;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct WhoamiResponse {
+    pub claims: Claims,
+    pub user: SimpleUserDto,
+    pub roles: Vec<String>,
+    pub permissions: Vec<String>,
+}
+
+impl WhoamiResponse {
+    /// # Summary
+    ///
+    /// Create a new WhoamiResponse.
+    ///
+    /// # Arguments
+    ///
+    /// * `claims` - The decoded Claims of the token of the request.
+    /// * `user` - The SimpleUserDto of the User that the token belongs to.
+    /// * `roles` - The names of the Roles of the User.
+    /// * `permissions` - The names of the Permissions of the Roles of the User, without expanding wildcards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let whoami_response = WhoamiResponse::new(claims, SimpleUserDto::from(&user), vec![], vec![]);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `WhoamiResponse` - The new WhoamiResponse.
+    pub fn new(
+        claims: Claims,
+        user: SimpleUserDto,
+        roles: Vec<String>,
+        permissions: Vec<String>,
+    ) -> WhoamiResponse {
+        WhoamiResponse {
+            claims,
+            user,
+            roles,
+            permissions,
+        }
+    }
+}
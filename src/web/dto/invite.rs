@@ -0,0 +1,2 @@
+pub mod create_invite;
+pub mod invite_dto;
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct CreateInvite {
+    pub email: Option<String>,
+    pub roles: Option<Vec<String>>,
+}
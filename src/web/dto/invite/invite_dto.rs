@@ -0,0 +1,84 @@
+use crate::repository::invite::invite_model::Invite;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct InviteDto {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    pub email: Option<String>,
+    pub roles: Option<Vec<String>>,
+    #[serde(rename = "createdBy")]
+    pub created_by: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: String,
+    #[serde(rename = "usedAt")]
+    pub used_at: Option<String>,
+    #[serde(rename = "usedBy")]
+    pub used_by: Option<String>,
+}
+
+impl InviteDto {
+    /// # Summary
+    ///
+    /// Create a new InviteDto that contains the invite token, which is only available when the Invite is created.
+    ///
+    /// # Arguments
+    ///
+    /// * `invite` - The Invite entity.
+    /// * `token` - The invite token.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (token, invite) = invite_service.create(None, None, user.id.to_hex(), &db).await?;
+    /// let invite_dto = InviteDto::with_token(invite, token);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `InviteDto` - The new InviteDto.
+    pub fn with_token(invite: Invite, token: String) -> InviteDto {
+        let mut invite_dto = InviteDto::from(invite);
+        invite_dto.token = Some(token);
+        invite_dto
+    }
+}
+
+impl From<Invite> for InviteDto {
+    /// # Summary
+    ///
+    /// Convert an Invite entity into an InviteDto, without the invite token.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The Invite entity to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let invite_dto = InviteDto::from(invite);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `InviteDto` - The new InviteDto.
+    fn from(value: Invite) -> Self {
+        InviteDto {
+            id: value.id.to_hex(),
+            token: None,
+            email: value.email,
+            roles: value
+                .roles
+                .map(|r| r.iter().map(|oid| oid.to_hex()).collect()),
+            created_by: value.created_by,
+            created_at: value.created_at.to_rfc3339(),
+            expires_at: value.expires_at.to_rfc3339(),
+            used_at: value.used_at.map(|d| d.to_chrono().to_rfc3339()),
+            used_by: value.used_by,
+        }
+    }
+}
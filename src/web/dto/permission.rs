@@ -1,3 +1,4 @@
 pub mod create_permission;
+pub mod delete_permission_response;
 pub mod permission_dto;
 pub mod update_permission;
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct DeletePermissionResponse {
+    #[serde(rename = "affectedRoles")]
+    pub affected_roles: u64,
+}
+
+impl DeletePermissionResponse {
+    /// # Summary
+    ///
+    /// Create a new DeletePermissionResponse.
+    ///
+    /// # Arguments
+    ///
+    /// * `affected_roles` - The number of Roles the deleted Permission was removed from.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let delete_permission_response = DeletePermissionResponse::new(3);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `DeletePermissionResponse` - The new DeletePermissionResponse.
+    pub fn new(affected_roles: u64) -> DeletePermissionResponse {
+        DeletePermissionResponse { affected_roles }
+    }
+}
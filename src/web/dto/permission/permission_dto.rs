@@ -11,6 +11,8 @@ pub struct PermissionDto {
     pub created_at: String,
     #[serde(rename = "updatedAt")]
     pub updated_at: String,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
 }
 
 impl From<Permission> for PermissionDto {
@@ -44,6 +46,7 @@ impl From<Permission> for PermissionDto {
             description: permission.description,
             created_at: permission.created_at.to_rfc3339(),
             updated_at: permission.updated_at.to_rfc3339(),
+            updated_by: permission.updated_by,
         }
     }
 }
@@ -79,6 +82,7 @@ impl From<&Permission> for PermissionDto {
             description: value.description.clone(),
             created_at: value.created_at.to_rfc3339(),
             updated_at: value.updated_at.to_rfc3339(),
+            updated_by: value.updated_by.clone(),
         }
     }
 }
@@ -13,6 +13,9 @@ pub struct RoleDto {
     pub created_at: String,
     #[serde(rename = "updatedAt")]
     pub updated_at: String,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
+    pub version: u64,
 }
 
 impl From<Role> for RoleDto {
@@ -47,6 +50,8 @@ impl From<Role> for RoleDto {
             permissions: None,
             created_at: value.created_at.to_rfc3339(),
             updated_at: value.updated_at.to_rfc3339(),
+            updated_by: value.updated_by,
+            version: value.version,
         }
     }
 }
@@ -83,6 +88,8 @@ impl From<&Role> for RoleDto {
             permissions: None,
             created_at: value.created_at.to_rfc3339(),
             updated_at: value.updated_at.to_rfc3339(),
+            updated_by: value.updated_by.clone(),
+            version: value.version,
         }
     }
 }
@@ -162,3 +169,46 @@ impl From<&Role> for SimpleRoleDto {
         }
     }
 }
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DetailedRoleDto {
+    #[serde(flatten)]
+    pub role: SimpleRoleDto,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
+    pub version: u64,
+}
+
+impl DetailedRoleDto {
+    /// # Summary
+    ///
+    /// Create a new DetailedRoleDto that adds the metadata of a Role entity to its SimpleRoleDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `role_dto` - The SimpleRoleDto of the Role entity, which can contain its expanded Permission entities.
+    /// * `role` - The Role entity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let detailed_role_dto = DetailedRoleDto::new(SimpleRoleDto::from(&role), &role);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `DetailedRoleDto` - The new DetailedRoleDto.
+    pub fn new(role_dto: SimpleRoleDto, role: &Role) -> DetailedRoleDto {
+        DetailedRoleDto {
+            role: role_dto,
+            created_at: role.created_at.to_rfc3339(),
+            updated_at: role.updated_at.to_rfc3339(),
+            updated_by: role.updated_by.clone(),
+            version: role.version,
+        }
+    }
+}
@@ -6,4 +6,5 @@ pub struct UpdateRole {
     pub name: String,
     pub description: Option<String>,
     pub permissions: Option<Vec<String>>,
+    pub version: Option<u64>,
 }
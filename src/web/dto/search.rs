@@ -1 +1,4 @@
+pub mod audit_search_request;
+pub mod query_search_request;
 pub mod search_request;
+pub mod user_search_request;
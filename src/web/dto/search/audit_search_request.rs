@@ -0,0 +1,19 @@
+use crate::repository::audit::audit_model::ResourceIdType;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct AuditSearchRequest {
+    pub text: Option<String>,
+    pub system: Option<bool>,
+    pub limit: Option<i64>,
+    pub page: Option<i64>,
+    pub after: Option<String>,
+    #[serde(rename = "resourceId")]
+    pub resource_id: Option<String>,
+    #[serde(rename = "resourceIdType")]
+    pub resource_id_type: Option<ResourceIdType>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    #[serde(rename = "expandActor")]
+    pub expand_actor: Option<bool>,
+}
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct QuerySearchRequest {
+    pub q: Option<String>,
+    pub limit: Option<i64>,
+    pub page: Option<i64>,
+}
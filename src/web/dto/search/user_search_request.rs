@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct UserSearchRequest {
+    pub text: Option<String>,
+    pub role: Option<String>,
+    pub limit: Option<i64>,
+    pub page: Option<i64>,
+    pub after: Option<String>,
+    #[serde(rename = "createdAfter")]
+    pub created_after: Option<String>,
+    #[serde(rename = "createdBefore")]
+    pub created_before: Option<String>,
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Debug, Display, Formatter};
+use zeroize::Zeroize;
+
+const REDACTED: &str = "********";
+
+pub struct SecretString(String);
+
+impl SecretString {
+    /// # Summary
+    ///
+    /// Expose the secret value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let password = login_request.password.expose_secret();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `&str` - The secret value.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    /// # Summary
+    ///
+    /// Check whether the secret value is empty.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the secret value is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Drop for SecretString {
+    /// # Summary
+    ///
+    /// Overwrite the secret value with zeroes before the memory is released.
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Debug for SecretString {
+    /// # Summary
+    ///
+    /// Format the SecretString without revealing the secret value.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the operation.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", REDACTED)
+    }
+}
+
+impl Display for SecretString {
+    /// # Summary
+    ///
+    /// Display the SecretString without revealing the secret value.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The Formatter.
+    ///
+    /// # Returns
+    ///
+    /// * `std::fmt::Result` - The result of the operation.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", REDACTED)
+    }
+}
+
+impl Serialize for SecretString {
+    /// # Summary
+    ///
+    /// Serialize the SecretString without revealing the secret value.
+    ///
+    /// # Arguments
+    ///
+    /// * `serializer` - The Serializer.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<S::Ok, S::Error>` - The result of the operation.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(REDACTED)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    /// # Summary
+    ///
+    /// Deserialize a SecretString from a string.
+    ///
+    /// # Arguments
+    ///
+    /// * `deserializer` - The Deserializer.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SecretString, D::Error>` - The result of the operation.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<SecretString, D::Error> {
+        String::deserialize(deserializer).map(SecretString)
+    }
+}
@@ -0,0 +1,43 @@
+use crate::repository::session::session_model::Session;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SessionDto {
+    pub id: String,
+    #[serde(rename = "userAgent")]
+    pub user_agent: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: String,
+}
+
+impl From<Session> for SessionDto {
+    /// # Summary
+    ///
+    /// Convert a Session entity into a SessionDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The Session entity to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let session = Session::new(user.id, String::from("jti"), None, expires_at);
+    /// let session_dto = SessionDto::from(session);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `SessionDto` - The new SessionDto.
+    fn from(session: Session) -> Self {
+        SessionDto {
+            id: session.id.to_hex(),
+            user_agent: session.user_agent,
+            created_at: session.created_at.to_rfc3339(),
+            expires_at: session.expires_at.to_rfc3339(),
+        }
+    }
+}
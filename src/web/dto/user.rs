@@ -2,3 +2,6 @@ pub mod create_user;
 pub mod update_password;
 pub mod update_user;
 pub mod user_dto;
+pub mod user_export;
+pub mod user_import_response;
+pub mod view_request;
@@ -3,6 +3,8 @@ use utoipa::ToSchema;
 
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct CreateUser {
+    #[serde(rename = "tenantId")]
+    pub tenant_id: Option<String>,
     pub username: String,
     pub email: Option<String>,
     #[serde(rename = "firstName")]
@@ -13,3 +13,14 @@ pub struct UpdatePassword {
 pub struct AdminUpdatePassword {
     pub password: String,
 }
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct AdminResetPassword {
+    pub password: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct AdminResetPasswordResponse {
+    #[serde(rename = "temporaryPassword")]
+    pub temporary_password: String,
+}
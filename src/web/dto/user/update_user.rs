@@ -11,6 +11,7 @@ pub struct UpdateUser {
     pub last_name: Option<String>,
     pub roles: Option<Vec<String>>,
     pub enabled: bool,
+    pub version: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -22,3 +23,18 @@ pub struct UpdateOwnUser {
     #[serde(rename = "lastName")]
     pub last_name: Option<String>,
 }
+
+#[derive(Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PatchOwnUser {
+    pub email: Option<String>,
+    #[serde(rename = "firstName")]
+    pub first_name: Option<String>,
+    #[serde(rename = "lastName")]
+    pub last_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct ConfirmEmailChange {
+    pub token: String,
+}
@@ -6,6 +6,8 @@ use utoipa::ToSchema;
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct UserDto {
     pub id: String,
+    #[serde(rename = "tenantId", skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
     pub username: String,
     pub email: Option<String>,
     #[serde(rename = "firstName")]
@@ -17,6 +19,9 @@ pub struct UserDto {
     pub created_at: String,
     #[serde(rename = "updatedAt")]
     pub updated_at: String,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
+    pub version: u64,
     pub enabled: bool,
 }
 
@@ -51,6 +56,7 @@ impl From<User> for UserDto {
     fn from(value: User) -> Self {
         UserDto {
             id: value.id.to_hex(),
+            tenant_id: value.tenant_id,
             username: value.username,
             email: value.email,
             first_name: value.first_name,
@@ -58,6 +64,8 @@ impl From<User> for UserDto {
             roles: None,
             created_at: value.created_at.to_rfc3339(),
             updated_at: value.updated_at.to_rfc3339(),
+            updated_by: value.updated_by,
+            version: value.version,
             enabled: value.enabled,
         }
     }
@@ -94,6 +102,7 @@ impl From<&User> for UserDto {
     fn from(value: &User) -> Self {
         UserDto {
             id: value.id.to_hex(),
+            tenant_id: value.tenant_id.clone(),
             username: value.username.clone(),
             email: value.email.clone(),
             first_name: value.first_name.clone(),
@@ -101,6 +110,8 @@ impl From<&User> for UserDto {
             roles: None,
             created_at: value.created_at.to_rfc3339(),
             updated_at: value.updated_at.to_rfc3339(),
+            updated_by: value.updated_by.clone(),
+            version: value.version,
             enabled: value.enabled,
         }
     }
@@ -109,12 +120,18 @@ impl From<&User> for UserDto {
 #[derive(Serialize, Deserialize, ToSchema)]
 pub struct SimpleUserDto {
     pub id: String,
+    #[serde(rename = "tenantId", skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
     pub username: String,
     pub email: Option<String>,
+    #[serde(rename = "pendingEmail", skip_serializing_if = "Option::is_none")]
+    pub pending_email: Option<String>,
     #[serde(rename = "firstName")]
     pub first_name: Option<String>,
     #[serde(rename = "lastName")]
     pub last_name: Option<String>,
+    #[serde(rename = "roleIds")]
+    pub role_ids: Option<Vec<String>>,
     pub roles: Option<Vec<SimpleRoleDto>>,
 }
 
@@ -149,10 +166,13 @@ impl From<User> for SimpleUserDto {
     fn from(value: User) -> Self {
         SimpleUserDto {
             id: value.id.to_hex(),
+            tenant_id: value.tenant_id,
             username: value.username,
             email: value.email,
+            pending_email: value.pending_email,
             first_name: value.first_name,
             last_name: value.last_name,
+            role_ids: value.roles.map(|r| r.iter().map(|r| r.to_hex()).collect()),
             roles: None,
         }
     }
@@ -189,11 +209,77 @@ impl From<&User> for SimpleUserDto {
     fn from(value: &User) -> Self {
         SimpleUserDto {
             id: value.id.to_hex(),
+            tenant_id: value.tenant_id.clone(),
             username: value.username.clone(),
             email: value.email.clone(),
+            pending_email: value.pending_email.clone(),
             first_name: value.first_name.clone(),
             last_name: value.last_name.clone(),
+            role_ids: value
+                .roles
+                .as_ref()
+                .map(|r| r.iter().map(|r| r.to_hex()).collect()),
             roles: None,
         }
     }
 }
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DetailedUserDto {
+    #[serde(flatten)]
+    pub user: SimpleUserDto,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
+    pub version: u64,
+    pub enabled: bool,
+    #[serde(rename = "mustChangePassword")]
+    pub must_change_password: bool,
+    #[serde(rename = "passwordChangedAt")]
+    pub password_changed_at: String,
+    #[serde(rename = "failedLoginAttempts")]
+    pub failed_login_attempts: u32,
+    #[serde(rename = "lockedUntil")]
+    pub locked_until: Option<String>,
+    #[serde(rename = "lastLoginAt")]
+    pub last_login_at: Option<String>,
+}
+
+impl DetailedUserDto {
+    /// # Summary
+    ///
+    /// Create a new DetailedUserDto that adds the account state of a User entity to its SimpleUserDto.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_dto` - The SimpleUserDto of the User entity, which can contain its expanded Role entities.
+    /// * `user` - The User entity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let detailed_user_dto = DetailedUserDto::new(SimpleUserDto::from(&user), &user);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `DetailedUserDto` - The new DetailedUserDto.
+    pub fn new(user_dto: SimpleUserDto, user: &User) -> DetailedUserDto {
+        DetailedUserDto {
+            user: user_dto,
+            created_at: user.created_at.to_rfc3339(),
+            updated_at: user.updated_at.to_rfc3339(),
+            updated_by: user.updated_by.clone(),
+            version: user.version,
+            enabled: user.enabled,
+            must_change_password: user.must_change_password,
+            password_changed_at: user.password_changed_at().to_rfc3339(),
+            failed_login_attempts: user.failed_login_attempts,
+            locked_until: user.locked_until.map(|d| d.to_chrono().to_rfc3339()),
+            last_login_at: user.last_login_at.map(|d| d.to_chrono().to_rfc3339()),
+        }
+    }
+}
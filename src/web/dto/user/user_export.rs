@@ -0,0 +1,80 @@
+use crate::repository::user::user_model::User;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UserExport {
+    pub id: String,
+    #[serde(rename = "tenantId", default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    pub username: String,
+    pub email: Option<String>,
+    #[serde(rename = "firstName")]
+    pub first_name: Option<String>,
+    #[serde(rename = "lastName")]
+    pub last_name: Option<String>,
+    /// The PHC string of the password hash, never the password itself
+    #[serde(rename = "passwordHash")]
+    pub password_hash: String,
+    /// The PHC strings of the previous password hashes
+    #[serde(rename = "passwordHistoryHashes", default)]
+    pub password_history_hashes: Vec<String>,
+    #[serde(rename = "passwordChangedAt")]
+    pub password_changed_at: Option<String>,
+    #[serde(rename = "roleIds", default)]
+    pub role_ids: Vec<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: String,
+    #[serde(rename = "updatedBy")]
+    pub updated_by: Option<String>,
+    pub enabled: bool,
+}
+
+impl From<User> for UserExport {
+    /// # Summary
+    ///
+    /// Convert a User entity into a UserExport, including its password hashes.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The User entity to be converted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let user_export = UserExport::from(user);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `UserExport` - The new UserExport.
+    fn from(value: User) -> Self {
+        let password_changed_at = value
+            .password_changed_at
+            .map(|d| d.to_chrono().to_rfc3339());
+
+        UserExport {
+            id: value.id.to_hex(),
+            tenant_id: value.tenant_id,
+            username: value.username,
+            email: value.email,
+            first_name: value.first_name,
+            last_name: value.last_name,
+            password_hash: value.password,
+            password_history_hashes: value.password_history,
+            password_changed_at,
+            role_ids: value
+                .roles
+                .unwrap_or_default()
+                .iter()
+                .map(|r| r.to_hex())
+                .collect(),
+            created_at: value.created_at.to_rfc3339(),
+            updated_at: value.updated_at.to_rfc3339(),
+            updated_by: value.updated_by,
+            enabled: value.enabled,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, Deserialize, ToSchema, Default)]
+pub struct UserImportResponse {
+    pub created: u64,
+    pub overwritten: u64,
+    pub skipped: u64,
+    pub errors: Vec<String>,
+}
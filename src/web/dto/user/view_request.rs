@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct ViewRequest {
+    pub view: Option<String>,
+}
+
+impl ViewRequest {
+    /// # Summary
+    ///
+    /// Check whether the detailed representation of a User was requested instead of the default one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let view_request = ViewRequest { view: Some(String::from("detailed")) };
+    /// let detailed = view_request.is_detailed();
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the `view` parameter is `detailed`.
+    pub fn is_detailed(&self) -> bool {
+        self.view
+            .as_deref()
+            .is_some_and(|v| v.trim().eq_ignore_ascii_case("detailed"))
+    }
+}
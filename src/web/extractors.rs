@@ -1,2 +1,8 @@
+pub mod authenticated_user;
+pub mod bearer_token;
+pub mod client_ip;
+pub mod json_body;
+pub mod json_config;
 pub mod jwt_extractor;
-pub mod user_id_extractor;
+pub mod locale;
+pub mod query_config;
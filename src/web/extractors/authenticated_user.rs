@@ -0,0 +1,108 @@
+use crate::configuration::config::Config;
+use crate::errors::not_found::NotFound;
+use crate::repository::user::user_model::User;
+use crate::web::extractors::bearer_token::BearerToken;
+use actix_web::dev::Payload;
+use actix_web::error::{
+    ErrorForbidden, ErrorInternalServerError, ErrorUnauthorized, InternalError,
+};
+use actix_web::{web, Error, FromRequest, HttpRequest, HttpResponse};
+use futures::future::LocalBoxFuture;
+use log::error;
+
+#[derive(Clone)]
+pub struct AuthenticatedUser(pub User);
+
+impl FromRequest for AuthenticatedUser {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    /// # Summary
+    ///
+    /// Verify the bearer token of the request and load the User that it was issued to.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The HttpRequest.
+    /// * `_payload` - The request Payload.
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Future` - The future that resolves to the AuthenticatedUser, a 401 Unauthorized error if the token is
+    ///   missing, invalid or its session was revoked, a 404 Not Found error if the User no longer exists or a 403 Forbidden error if the User
+    ///   is disabled.
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let config = req.app_data::<web::Data<Config>>().cloned();
+        let token = match &config {
+            Some(c) => {
+                BearerToken::from_headers_or_cookie(req.method(), req.headers(), &c.cookie_config)
+            }
+            None => BearerToken::from_headers(req.headers()),
+        };
+
+        Box::pin(async move {
+            let config = match config {
+                Some(c) => c,
+                None => {
+                    error!("Failed to get Config from request");
+                    return Err(ErrorInternalServerError(
+                        "Failed to get Config from request",
+                    ));
+                }
+            };
+
+            let token = match token {
+                Some(BearerToken(t)) => t,
+                None => return Err(ErrorUnauthorized("Missing or malformed bearer token")),
+            };
+
+            let claims = match config.token_service.verify(&token).await {
+                Ok(c) => c,
+                Err(e) => {
+                    error!("Failed to verify token: {}", e);
+                    return Err(ErrorUnauthorized("Invalid bearer token"));
+                }
+            };
+
+            match config
+                .services
+                .session_service
+                .is_active(claims.jti.as_deref(), &config.database)
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => return Err(ErrorUnauthorized("Session has been revoked")),
+                Err(e) => {
+                    error!("Failed to find Session: {}", e);
+                    return Err(ErrorInternalServerError("Failed to find Session"));
+                }
+            }
+
+            let user = match config
+                .services
+                .user_service
+                .find_by_id(&claims.sub, &config.database)
+                .await
+            {
+                Ok(Some(u)) => u,
+                Ok(None) => {
+                    return Err(InternalError::from_response(
+                        "User not found",
+                        HttpResponse::NotFound().json(NotFound::new("User", &claims.sub)),
+                    )
+                    .into())
+                }
+                Err(e) => {
+                    error!("Failed to find user by ID: {}", e);
+                    return Err(ErrorInternalServerError("Failed to find User"));
+                }
+            };
+
+            if !user.enabled {
+                return Err(ErrorForbidden("User is disabled"));
+            }
+
+            Ok(AuthenticatedUser(user))
+        })
+    }
+}
@@ -0,0 +1,242 @@
+use crate::configuration::config::Config;
+use crate::configuration::cookie_config::CookieConfig;
+use actix_web::cookie::Cookie;
+use actix_web::dev::Payload;
+use actix_web::error::ErrorUnauthorized;
+use actix_web::http::header::{HeaderMap, AUTHORIZATION, COOKIE};
+use actix_web::http::Method;
+use actix_web::{web, Error, FromRequest, HttpRequest};
+use futures::future::{ready, Ready};
+use log::warn;
+
+/// The header that requests which are authenticated with the session cookie must send if they are not safe. Browsers only
+/// send custom headers cross-origin after a preflight request, which the Cors middleware refuses for unknown origins.
+pub const CSRF_HEADER: &str = "X-Requested-With";
+
+#[derive(Clone)]
+pub struct BearerToken(pub String);
+
+impl BearerToken {
+    /// # Summary
+    ///
+    /// Parse the bearer token from the Authorization header.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of the request.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let token = BearerToken::from_headers(req.headers());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<BearerToken>` - The BearerToken, or None if the header is missing or malformed.
+    pub fn from_headers(headers: &HeaderMap) -> Option<BearerToken> {
+        let auth_header = headers.get(AUTHORIZATION)?;
+
+        let auth_str = match auth_header.to_str() {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("Malformed Authorization header: {}", e);
+                return None;
+            }
+        };
+
+        let token = match auth_str.split_once(' ') {
+            Some((scheme, token)) if scheme.eq_ignore_ascii_case("Bearer") => token.trim(),
+            _ => {
+                warn!("Authorization header does not use the Bearer scheme");
+                return None;
+            }
+        };
+
+        if token.is_empty() {
+            warn!("Authorization header contains an empty bearer token");
+            return None;
+        }
+
+        Some(BearerToken(String::from(token)))
+    }
+
+    /// # Summary
+    ///
+    /// Parse the bearer token from the Authorization header, or from the session cookie if session cookies are
+    /// enabled and the request has no Authorization header. The session cookie of a request that is not safe is only
+    /// used if the request has the CSRF_HEADER, so that other websites cannot make changes on behalf of the User.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The method of the request.
+    /// * `headers` - The headers of the request.
+    /// * `cookie_config` - The CookieConfig.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let token = BearerToken::from_headers_or_cookie(req.method(), req.headers(), &config.cookie_config);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<BearerToken>` - The BearerToken, or None if neither the header nor the cookie holds a usable token.
+    pub fn from_headers_or_cookie(
+        method: &Method,
+        headers: &HeaderMap,
+        cookie_config: &CookieConfig,
+    ) -> Option<BearerToken> {
+        if headers.contains_key(AUTHORIZATION) || !cookie_config.enabled {
+            return BearerToken::from_headers(headers);
+        }
+
+        let is_safe = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+        if !is_safe && !headers.contains_key(CSRF_HEADER) {
+            warn!(
+                "Session cookie of a {} request without the {} header is ignored",
+                method, CSRF_HEADER
+            );
+            return None;
+        }
+
+        headers
+            .get_all(COOKIE)
+            .filter_map(|h| h.to_str().ok())
+            .flat_map(|h| h.split(';'))
+            .filter_map(|c| Cookie::parse(c.trim()).ok())
+            .find(|c| c.name() == cookie_config.name && !c.value().is_empty())
+            .map(|c| BearerToken(String::from(c.value())))
+    }
+}
+
+impl FromRequest for BearerToken {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    /// # Summary
+    ///
+    /// Extract the BearerToken from the Authorization header, or from the session cookie if session cookies are enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The HttpRequest.
+    /// * `_payload` - The request Payload.
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Future` - The future that resolves to the BearerToken, or a 401 Unauthorized error.
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let token = match req.app_data::<web::Data<Config>>() {
+            Some(c) => {
+                BearerToken::from_headers_or_cookie(req.method(), req.headers(), &c.cookie_config)
+            }
+            None => BearerToken::from_headers(req.headers()),
+        };
+
+        ready(match token {
+            Some(t) => Ok(t),
+            None => Err(ErrorUnauthorized("Missing or malformed bearer token")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::cookie::SameSite;
+    use actix_web::http::header::{HeaderName, HeaderValue};
+
+    fn headers(authorization: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static(authorization));
+        headers
+    }
+
+    #[test]
+    fn from_headers_returns_the_token() {
+        let token = BearerToken::from_headers(&headers("Bearer abc.def.ghi"));
+        assert_eq!(token.map(|t| t.0), Some(String::from("abc.def.ghi")));
+    }
+
+    #[test]
+    fn from_headers_accepts_the_scheme_in_any_case() {
+        let token = BearerToken::from_headers(&headers("bearer abc"));
+        assert_eq!(token.map(|t| t.0), Some(String::from("abc")));
+    }
+
+    #[test]
+    fn from_headers_rejects_a_missing_header() {
+        assert!(BearerToken::from_headers(&HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn from_headers_rejects_other_schemes() {
+        assert!(BearerToken::from_headers(&headers("Basic dXNlcjpwYXNz")).is_none());
+        assert!(BearerToken::from_headers(&headers("abc.def.ghi")).is_none());
+    }
+
+    #[test]
+    fn from_headers_rejects_an_empty_token() {
+        assert!(BearerToken::from_headers(&headers("Bearer ")).is_none());
+        assert!(BearerToken::from_headers(&headers("Bearer    ")).is_none());
+    }
+
+    fn cookie_headers(csrf: bool) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(COOKIE, HeaderValue::from_static("other=1; session=abc"));
+        if csrf {
+            headers.insert(
+                HeaderName::from_static("x-requested-with"),
+                HeaderValue::from_static("XMLHttpRequest"),
+            );
+        }
+        headers
+    }
+
+    fn cookie_config() -> CookieConfig {
+        CookieConfig::new(
+            true,
+            String::from("session"),
+            true,
+            true,
+            SameSite::Strict,
+            false,
+        )
+    }
+
+    #[test]
+    fn from_headers_or_cookie_reads_the_cookie_of_a_safe_request() {
+        let token = BearerToken::from_headers_or_cookie(
+            &Method::GET,
+            &cookie_headers(false),
+            &cookie_config(),
+        );
+        assert_eq!(token.map(|t| t.0), Some(String::from("abc")));
+    }
+
+    #[test]
+    fn from_headers_or_cookie_requires_the_csrf_header_for_other_requests() {
+        let config = cookie_config();
+        assert!(BearerToken::from_headers_or_cookie(
+            &Method::POST,
+            &cookie_headers(false),
+            &config
+        )
+        .is_none());
+
+        let token =
+            BearerToken::from_headers_or_cookie(&Method::POST, &cookie_headers(true), &config);
+        assert_eq!(token.map(|t| t.0), Some(String::from("abc")));
+    }
+
+    #[test]
+    fn from_headers_or_cookie_ignores_the_cookie_if_cookies_are_disabled() {
+        let mut config = cookie_config();
+        config.enabled = false;
+        assert!(
+            BearerToken::from_headers_or_cookie(&Method::GET, &cookie_headers(true), &config)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use crate::configuration::config::Config;
+use actix_web::dev::Payload;
+use actix_web::http::header::HeaderMap;
+use actix_web::{web, Error, FromRequest, HttpRequest};
+use futures::future::{ready, Ready};
+use std::net::{IpAddr, SocketAddr};
+
+#[derive(Clone)]
+pub struct ClientIp(pub Option<String>);
+
+impl ClientIp {
+    /// # Summary
+    ///
+    /// Resolve the IP address of the client. The `Forwarded` and `X-Forwarded-For` headers are only taken into account
+    /// if the peer is a trusted proxy, because any client can send them. The addresses in the header are walked from
+    /// the nearest to the farthest hop and the first address that is not a trusted proxy is the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `peer` - The IP address of the peer that opened the connection, if known.
+    /// * `headers` - The headers of the request.
+    /// * `trusted_proxies` - The IP addresses of the proxies whose forwarding headers are trusted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let ip = ClientIp::resolve(req.peer_addr().map(|a| a.ip()), req.headers(), &trusted_proxies);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `Option<IpAddr>` - The IP address of the client, or None if the peer address is unknown.
+    pub fn resolve(
+        peer: Option<IpAddr>,
+        headers: &HeaderMap,
+        trusted_proxies: &[IpAddr],
+    ) -> Option<IpAddr> {
+        let mut client = peer?;
+        if !trusted_proxies.contains(&client) {
+            return Some(client);
+        }
+
+        let hops = match Self::forwarded_hops(headers) {
+            Some(h) => h,
+            None => return Some(client),
+        };
+
+        for hop in hops.iter().rev() {
+            match hop {
+                Some(ip) => {
+                    client = *ip;
+                    if !trusted_proxies.contains(ip) {
+                        break;
+                    }
+                }
+                // An obfuscated or malformed address cannot be trusted, so the hop that reported it is the client
+                None => break,
+            }
+        }
+
+        Some(client)
+    }
+
+    /// # Summary
+    ///
+    /// Get the forwarded addresses of the request, in the order in which they were added. The `Forwarded` header takes
+    /// precedence over the `X-Forwarded-For` header.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of the request.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Vec<Option<IpAddr>>>` - The forwarded addresses, which are None if they could not be parsed, or None if
+    ///   the request has no forwarding headers.
+    fn forwarded_hops(headers: &HeaderMap) -> Option<Vec<Option<IpAddr>>> {
+        let forwarded: Vec<&str> = headers
+            .get_all("forwarded")
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .collect();
+
+        if !forwarded.is_empty() {
+            return Some(
+                forwarded
+                    .iter()
+                    .map(|element| {
+                        element
+                            .split(';')
+                            .filter_map(|pair| pair.split_once('='))
+                            .find(|(key, _)| key.trim().eq_ignore_ascii_case("for"))
+                            .and_then(|(_, value)| Self::parse_node(value))
+                    })
+                    .collect(),
+            );
+        }
+
+        let hops: Vec<Option<IpAddr>> = headers
+            .get_all("x-forwarded-for")
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(Self::parse_node)
+            .collect();
+
+        if hops.is_empty() {
+            None
+        } else {
+            Some(hops)
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Parse a forwarded node, which is an IP address that can be quoted, have a port or, for IPv6, be enclosed in
+    /// brackets.
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The forwarded node.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<IpAddr>` - The IP address of the node, or None if it is obfuscated or malformed.
+    fn parse_node(node: &str) -> Option<IpAddr> {
+        let node = node.trim().trim_matches('"');
+
+        if let Ok(ip) = node.parse::<IpAddr>() {
+            return Some(ip);
+        }
+
+        if let Ok(addr) = node.parse::<SocketAddr>() {
+            return Some(addr.ip());
+        }
+
+        node.strip_prefix('[')
+            .and_then(|n| n.strip_suffix(']'))
+            .and_then(|n| n.parse::<IpAddr>().ok())
+    }
+}
+
+impl FromRequest for ClientIp {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    /// # Summary
+    ///
+    /// Extract the IP address of the client that sent the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The HttpRequest.
+    /// * `_payload` - The request Payload.
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Future` - The future that resolves to the ClientIp, which is None if the peer address is unknown.
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let trusted_proxies = match req.app_data::<web::Data<Config>>() {
+            Some(c) => c.server_config.trusted_proxies.as_slice(),
+            None => &[],
+        };
+
+        let ip = ClientIp::resolve(
+            req.peer_addr().map(|a| a.ip()),
+            req.headers(),
+            trusted_proxies,
+        );
+
+        ready(Ok(ClientIp(ip.map(|i| i.to_string()))))
+    }
+}
@@ -0,0 +1,73 @@
+use crate::errors::bad_request::BadRequest;
+use actix_web::dev::Payload;
+use actix_web::error::InternalError;
+use actix_web::{web, Error, FromRequest, HttpRequest, HttpResponse};
+use futures::future::LocalBoxFuture;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::ops::{Deref, DerefMut};
+
+/// A JSON request body. The body is parsed by actix, which applies the JsonConfig, and is then deserialized into the
+/// type of the request, so that a 400 Bad Request can name the field that could not be deserialized.
+pub struct JsonBody<T>(pub T);
+
+impl<T> JsonBody<T> {
+    /// # Summary
+    ///
+    /// Unwrap the deserialized request body.
+    ///
+    /// # Returns
+    ///
+    /// * `T` - The deserialized request body.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for JsonBody<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for JsonBody<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: DeserializeOwned + 'static> FromRequest for JsonBody<T> {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    /// # Summary
+    ///
+    /// Extract the JsonBody of the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The HttpRequest.
+    /// * `payload` - The request Payload.
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Future` - The future that resolves to the JsonBody, or to a 400 Bad Request that names the field that
+    ///   could not be deserialized.
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let body = web::Json::<Value>::from_request(req, payload);
+
+        Box::pin(async move {
+            let value = body.await?.into_inner();
+
+            serde_path_to_error::deserialize(value)
+                .map(JsonBody)
+                .map_err(|e| {
+                    let res =
+                        HttpResponse::BadRequest().json(BadRequest::from_deserialize_error(&e));
+                    InternalError::from_response(e, res).into()
+                })
+        })
+    }
+}
@@ -0,0 +1,61 @@
+use crate::errors::bad_request::BadRequest;
+use crate::errors::unsupported_media_type::UnsupportedMediaType;
+use actix_web::error::{InternalError, JsonPayloadError};
+use actix_web::{web, Error, HttpRequest, HttpResponse};
+
+/// # Summary
+///
+/// Create the JsonConfig of the JSON request bodies, which turns malformed bodies into a 400 Bad Request with the same
+/// envelope as every other error, instead of the plain text error of actix.
+///
+/// # Example
+///
+/// ```
+/// let app = App::new().app_data(json_config::json_config());
+/// ```
+///
+/// # Returns
+///
+/// * `web::JsonConfig` - The JsonConfig.
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(handle_error)
+}
+
+/// # Summary
+///
+/// Convert an error that occurred while extracting a JSON request body into the response of the request.
+///
+/// # Arguments
+///
+/// * `err` - The JsonPayloadError.
+/// * `_req` - The HttpRequest.
+///
+/// # Returns
+///
+/// * `Error` - The Error that holds the response.
+fn handle_error(err: JsonPayloadError, _req: &HttpRequest) -> Error {
+    let res = match &err {
+        JsonPayloadError::Deserialize(e) => {
+            HttpResponse::BadRequest().json(BadRequest::from_json_error(e))
+        }
+        JsonPayloadError::OverflowKnownLength { limit, .. }
+        | JsonPayloadError::Overflow { limit } => {
+            HttpResponse::BadRequest().json(BadRequest::new(&format!(
+                "The request body is larger than the limit of {} bytes",
+                limit
+            )))
+        }
+        JsonPayloadError::Payload(e) => HttpResponse::BadRequest().json(BadRequest::new(&format!(
+            "The request body could not be read: {}",
+            e
+        ))),
+        JsonPayloadError::ContentType => {
+            HttpResponse::UnsupportedMediaType().json(UnsupportedMediaType::new(
+                "The request body must be sent with the application/json content type",
+            ))
+        }
+        _ => return err.into(),
+    };
+
+    InternalError::from_response(err, res).into()
+}
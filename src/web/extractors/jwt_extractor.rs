@@ -1,4 +1,6 @@
 use crate::configuration::config::Config;
+use crate::repository::permission::permission_model::Permission;
+use crate::web::extractors::bearer_token::BearerToken;
 use actix_web::dev::ServiceRequest;
 use actix_web::error::ErrorInternalServerError;
 use actix_web::Error;
@@ -34,92 +36,118 @@ pub async fn extract(req: &ServiceRequest) -> Result<HashSet<String>, Error> {
     };
 
     let mut permission_list: HashSet<String> = HashSet::<String>::new();
-    if let Some(auth_header) = req.headers().get("Authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                match res.services.jwt_service.verify_jwt_token(token) {
-                    Ok(subject) => {
-                        let user = match res
-                            .services
-                            .user_service
-                            .find_by_id(&subject, &res.database)
-                            .await
-                        {
-                            Ok(e) => match e {
-                                Some(e) => e,
-                                None => {
-                                    return Ok(HashSet::<String>::new());
-                                }
-                            },
-                            Err(e) => {
-                                error!("Failed to find user by ID: {}", e);
-                                return Ok(HashSet::<String>::new());
-                            }
-                        };
+    let mut wildcards: Vec<Permission> = vec![];
+    if let Some(BearerToken(token)) =
+        BearerToken::from_headers_or_cookie(req.method(), req.headers(), &res.cookie_config)
+    {
+        match res.token_service.verify(&token).await {
+            Ok(claims) => {
+                match res
+                    .services
+                    .session_service
+                    .is_active(claims.jti.as_deref(), &res.database)
+                    .await
+                {
+                    Ok(true) => {}
+                    Ok(false) => return Ok(HashSet::<String>::new()),
+                    Err(e) => {
+                        error!("Failed to find Session: {}", e);
+                        return Ok(HashSet::<String>::new());
+                    }
+                }
 
-                        if !user.enabled {
+                let user = match res
+                    .services
+                    .user_service
+                    .find_by_id(&claims.sub, &res.database)
+                    .await
+                {
+                    Ok(e) => match e {
+                        Some(e) => e,
+                        None => {
                             return Ok(HashSet::<String>::new());
                         }
+                    },
+                    Err(e) => {
+                        error!("Failed to find user by ID: {}", e);
+                        return Ok(HashSet::<String>::new());
+                    }
+                };
 
-                        if user.roles.is_some() {
-                            let mut role_vec: Vec<String> = vec![];
-                            for r in user.roles.unwrap() {
-                                role_vec.push(r.to_hex());
-                            }
+                if !user.enabled {
+                    return Ok(HashSet::<String>::new());
+                }
+
+                if user.roles.is_some() {
+                    let mut role_vec: Vec<String> = vec![];
+                    for r in user.roles.unwrap() {
+                        role_vec.push(r.to_hex());
+                    }
 
-                            let roles = match res
-                                .services
-                                .role_service
-                                .find_by_id_vec(role_vec, &res.database)
-                                .await
-                            {
-                                Ok(e) => e,
-                                Err(e) => {
-                                    error!("Failed to find roles by id vec: {}", e);
-                                    return Ok(HashSet::<String>::new());
+                    let roles = match res
+                        .services
+                        .role_service
+                        .find_by_id_vec(role_vec, false, &res.database)
+                        .await
+                    {
+                        Ok(e) => e,
+                        Err(e) => {
+                            error!("Failed to find roles by id vec: {}", e);
+                            return Ok(HashSet::<String>::new());
+                        }
+                    };
+
+                    if !roles.is_empty() {
+                        for r in roles {
+                            if r.permissions.is_some() {
+                                let mut oid_vec: Vec<String> = vec![];
+                                for r in r.permissions.unwrap() {
+                                    oid_vec.push(r.to_hex());
                                 }
-                            };
+                                let permissions = match res
+                                    .services
+                                    .permission_service
+                                    .find_by_id_vec(oid_vec, false, &res.database)
+                                    .await
+                                {
+                                    Ok(d) => d,
+                                    Err(e) => {
+                                        error!("Failed to find permissions by id vec: {}", e);
+                                        continue;
+                                    }
+                                };
 
-                            if !roles.is_empty() {
-                                for r in roles {
-                                    if r.permissions.is_some() {
-                                        let mut oid_vec: Vec<String> = vec![];
-                                        for r in r.permissions.unwrap() {
-                                            oid_vec.push(r.to_hex());
+                                if !permissions.is_empty() {
+                                    for p in permissions {
+                                        if p.is_wildcard() {
+                                            wildcards.push(p.clone());
                                         }
-                                        let permissions = match res
-                                            .services
-                                            .permission_service
-                                            .find_by_id_vec(oid_vec, &res.database)
-                                            .await
-                                        {
-                                            Ok(d) => d,
-                                            Err(e) => {
-                                                error!(
-                                                    "Failed to find permissions by id vec: {}",
-                                                    e
-                                                );
-                                                continue;
-                                            }
-                                        };
-
-                                        if !permissions.is_empty() {
-                                            for p in permissions {
-                                                if !permission_list.contains(&p.name) {
-                                                    permission_list.insert(p.name);
-                                                }
-                                            }
+                                        if !permission_list.contains(&p.name) {
+                                            permission_list.insert(p.name);
                                         }
                                     }
                                 }
                             }
                         }
                     }
-                    Err(e) => {
-                        error!("Failed to verify JWT token: {}", e);
-                    }
                 }
             }
+            Err(e) => {
+                error!("Failed to verify token: {}", e);
+            }
+        }
+    }
+
+    // Authorities are compared by name, so wildcards are expanded into every Permission name that they match
+    if !wildcards.is_empty() {
+        match res
+            .services
+            .permission_service
+            .find_by_wildcards(&wildcards, &res.database)
+            .await
+        {
+            Ok(matched) => permission_list.extend(matched.into_iter().map(|p| p.name)),
+            Err(e) => error!("Failed to expand wildcard permissions: {}", e),
         }
     }
 
@@ -0,0 +1,42 @@
+use crate::components::localizer::DEFAULT_LOCALE;
+use crate::configuration::config::Config;
+use actix_web::dev::Payload;
+use actix_web::http::header::ACCEPT_LANGUAGE;
+use actix_web::{web, Error, FromRequest, HttpRequest};
+use futures::future::{ready, Ready};
+
+/// The supported locale that the client prefers, which is used to localize the user-facing messages of a response.
+#[derive(Clone, Copy)]
+pub struct Locale(pub &'static str);
+
+impl FromRequest for Locale {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    /// # Summary
+    ///
+    /// Extract the Locale of the request from its Accept-Language header.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The HttpRequest.
+    /// * `_payload` - The request Payload.
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Future` - The future that resolves to the Locale, which is the DEFAULT_LOCALE if no supported locale is
+    ///   accepted.
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let accept_language = req
+            .headers()
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|h| h.to_str().ok());
+
+        let locale = match req.app_data::<web::Data<Config>>() {
+            Some(c) => c.localizer.negotiate(accept_language),
+            None => DEFAULT_LOCALE,
+        };
+
+        ready(Ok(Locale(locale)))
+    }
+}
@@ -0,0 +1,44 @@
+use crate::errors::bad_request::BadRequest;
+use actix_web::error::{InternalError, QueryPayloadError};
+use actix_web::{web, Error, HttpRequest, HttpResponse};
+
+/// # Summary
+///
+/// Create the QueryConfig of the query strings, which turns invalid query strings into a 400 Bad Request with the same
+/// envelope as every other error, instead of the plain text error of actix.
+///
+/// # Example
+///
+/// ```
+/// let app = App::new().app_data(query_config::query_config());
+/// ```
+///
+/// # Returns
+///
+/// * `web::QueryConfig` - The QueryConfig.
+pub fn query_config() -> web::QueryConfig {
+    web::QueryConfig::default().error_handler(handle_error)
+}
+
+/// # Summary
+///
+/// Convert an error that occurred while extracting a query string into the response of the request.
+///
+/// # Arguments
+///
+/// * `err` - The QueryPayloadError.
+/// * `req` - The HttpRequest.
+///
+/// # Returns
+///
+/// * `Error` - The Error that holds the response.
+fn handle_error(err: QueryPayloadError, req: &HttpRequest) -> Error {
+    let res = match &err {
+        QueryPayloadError::Deserialize(e) => {
+            HttpResponse::BadRequest().json(BadRequest::from_query_error(e, req.query_string()))
+        }
+        _ => return err.into(),
+    };
+
+    InternalError::from_response(err, res).into()
+}
@@ -0,0 +1,5 @@
+pub mod content_type;
+pub mod cors;
+pub mod etag;
+pub mod rate_limit;
+pub mod request_id;
@@ -0,0 +1,134 @@
+use crate::configuration::config::Config;
+use crate::errors::unsupported_media_type::UnsupportedMediaType;
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderMap, CONTENT_LENGTH, CONTENT_TYPE, TRANSFER_ENCODING};
+use actix_web::http::Method;
+use actix_web::{web, Error, HttpResponse};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use std::rc::Rc;
+
+/// The media types that are accepted besides JSON. The user import reads one JSON document per line.
+const ACCEPTED_MEDIA_TYPES: [&str; 1] = ["application/x-ndjson"];
+
+/// # Summary
+///
+/// Check whether a request carries a body, which is the case if it has a non-zero Content-Length or is chunked.
+///
+/// # Arguments
+///
+/// * `headers` - The headers of the request.
+///
+/// # Returns
+///
+/// * `bool` - True if the request has a body, otherwise false.
+fn has_body(headers: &HeaderMap) -> bool {
+    let content_length = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.trim().parse::<u64>().ok())
+        .unwrap_or(0);
+
+    content_length > 0 || headers.contains_key(TRANSFER_ENCODING)
+}
+
+/// # Summary
+///
+/// Check whether the Content-Type of a request is JSON, either `application/json` or a type with the `+json` suffix, or
+/// one of the other accepted media types. Parameters such as the charset are ignored.
+///
+/// # Arguments
+///
+/// * `content_type` - The value of the Content-Type header.
+///
+/// # Returns
+///
+/// * `bool` - True if the media type is accepted, otherwise false.
+fn is_accepted(content_type: &str) -> bool {
+    let media_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    media_type == "application/json"
+        || (media_type.starts_with("application/") && media_type.ends_with("+json"))
+        || ACCEPTED_MEDIA_TYPES.contains(&media_type.as_str())
+}
+
+pub struct ContentTypeMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for ContentTypeMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = ContentTypeService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ContentTypeService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct ContentTypeService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for ContentTypeService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    /// # Summary
+    ///
+    /// Refuse a POST, PUT or PATCH request with 415 Unsupported Media Type if it carries a body that is not JSON, so
+    /// that clients get a clear error instead of a deserialization failure.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The ServiceRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Future` - The future that resolves to the ServiceResponse.
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let enforced = match req.app_data::<web::Data<Config>>() {
+            Some(c) => c.server_config.enforce_json_content_type,
+            None => false,
+        };
+
+        let carries_body = matches!(*req.method(), Method::POST | Method::PUT | Method::PATCH)
+            && has_body(req.headers());
+
+        if enforced && carries_body {
+            let accepted = req
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|h| h.to_str().ok())
+                .is_some_and(is_accepted);
+
+            if !accepted {
+                let res = HttpResponse::UnsupportedMediaType().json(UnsupportedMediaType::new(
+                    "The request body must be sent with the application/json content type",
+                ));
+                return Box::pin(ready(Ok(req.into_response(res).map_into_right_body())));
+            }
+        }
+
+        let service = self.service.clone();
+        Box::pin(async move { Ok(service.call(req).await?.map_into_left_body()) })
+    }
+}
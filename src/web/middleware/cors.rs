@@ -0,0 +1,46 @@
+use crate::configuration::server_config::ServerConfig;
+use actix_cors::Cors;
+
+/// The amount of seconds a browser may cache the result of a preflight request.
+const PREFLIGHT_MAX_AGE: usize = 3600;
+
+/// # Summary
+///
+/// Create the Cors middleware. Without allowed origins, any origin may send requests, but responses are sent with a
+/// wildcard origin and without credentials, so that browsers never attach cookies to them. Credentials are only
+/// supported for the allowed origins.
+///
+/// # Arguments
+///
+/// * `server_config` - The ServerConfig that holds the allowed origins.
+///
+/// # Example
+///
+/// ```
+/// let app = App::new().wrap(cors::cors(&config.server_config));
+/// ```
+///
+/// # Returns
+///
+/// * `Cors` - The Cors middleware.
+pub fn cors(server_config: &ServerConfig) -> Cors {
+    if server_config.cors_allowed_origins.is_empty() {
+        return Cors::default()
+            .allow_any_origin()
+            .send_wildcard()
+            .allow_any_method()
+            .allow_any_header()
+            .expose_any_header()
+            .max_age(PREFLIGHT_MAX_AGE);
+    }
+
+    server_config
+        .cors_allowed_origins
+        .iter()
+        .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
+        .allow_any_method()
+        .allow_any_header()
+        .expose_any_header()
+        .supports_credentials()
+        .max_age(PREFLIGHT_MAX_AGE)
+}
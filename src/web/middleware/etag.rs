@@ -0,0 +1,165 @@
+use actix_web::body::{to_bytes, BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::ErrorInternalServerError;
+use actix_web::http::header::{HeaderMap, HeaderValue, CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use actix_web::http::{Method, StatusCode};
+use actix_web::{Error, HttpResponse};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+use std::rc::Rc;
+
+/// # Summary
+///
+/// Compute the entity tag of a response body.
+///
+/// # Arguments
+///
+/// * `body` - The serialized response body.
+///
+/// # Example
+///
+/// ```
+/// let etag = etag::compute(b"{}");
+/// ```
+///
+/// # Returns
+///
+/// * `String` - The quoted entity tag.
+pub fn compute(body: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(body);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+/// # Summary
+///
+/// Check whether the value of an If-None-Match header matches an entity tag, using the weak comparison.
+///
+/// # Arguments
+///
+/// * `if_none_match` - The value of the If-None-Match header.
+/// * `etag` - The quoted entity tag of the current representation.
+///
+/// # Returns
+///
+/// * `bool` - True if the client already has the current representation, otherwise false.
+fn matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag)
+}
+
+/// # Summary
+///
+/// Check whether a response carries a JSON body that is small enough to be buffered and hashed.
+///
+/// # Arguments
+///
+/// * `headers` - The headers of the response.
+///
+/// # Returns
+///
+/// * `bool` - True if the response is a JSON document, otherwise false.
+fn is_json(headers: &HeaderMap) -> bool {
+    // Streamed responses, such as the NDJSON export, are never buffered
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .map(|c| c.starts_with("application/json"))
+        .unwrap_or(false)
+}
+
+pub struct EtagMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for EtagMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Transform = EtagService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(EtagService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct EtagService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for EtagService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    /// # Summary
+    ///
+    /// Add an ETag header to successful JSON responses of GET requests, and answer with 304 Not Modified if the
+    /// If-None-Match header of the request matches it.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The ServiceRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Future` - The future that resolves to the ServiceResponse.
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let cacheable = req.method() == Method::GET;
+        let if_none_match = req
+            .headers()
+            .get(IF_NONE_MATCH)
+            .and_then(|h| h.to_str().ok())
+            .map(String::from);
+
+        let service = self.service.clone();
+        Box::pin(async move {
+            let res = service.call(req).await?;
+            if !cacheable
+                || res.status() != StatusCode::OK
+                || res.headers().contains_key(ETAG)
+                || !is_json(res.headers())
+            {
+                return Ok(res.map_into_boxed_body());
+            }
+
+            let (req, res) = res.into_parts();
+            let (res, body) = res.into_parts();
+            let body = match to_bytes(body).await {
+                Ok(b) => b,
+                Err(_) => return Err(ErrorInternalServerError("Unable to read the response body")),
+            };
+
+            let etag = compute(&body);
+            let etag_value =
+                HeaderValue::from_str(&etag).expect("ETag must be a valid header value");
+
+            if let Some(i) = if_none_match {
+                if matches(&i, &etag) {
+                    let not_modified = HttpResponse::NotModified()
+                        .insert_header((ETAG, etag_value))
+                        .finish();
+                    return Ok(ServiceResponse::new(req, not_modified));
+                }
+            }
+
+            let mut res = res.set_body(BoxBody::new(body));
+            res.headers_mut().insert(ETAG, etag_value);
+            Ok(ServiceResponse::new(req, res))
+        })
+    }
+}
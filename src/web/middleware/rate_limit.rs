@@ -0,0 +1,260 @@
+use crate::configuration::config::Config;
+use crate::errors::too_many_requests::TooManyRequests;
+use crate::web::extractors::client_ip::ClientIp;
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use actix_web::{web, Error, HttpResponse};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const RATE_LIMIT_LIMIT_HEADER: &str = "x-ratelimit-limit";
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "x-ratelimit-remaining";
+pub const RATE_LIMIT_RESET_HEADER: &str = "x-ratelimit-reset";
+
+struct RateLimitWindow {
+    start: Instant,
+    count: u32,
+}
+
+/// The state of the rate limit of a client after a request was counted.
+#[derive(Clone, Copy)]
+pub struct RateLimitState {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset: u64,
+}
+
+impl RateLimitState {
+    /// # Summary
+    ///
+    /// Write the X-RateLimit-Limit, X-RateLimit-Remaining and X-RateLimit-Reset headers, and the Retry-After header if
+    /// the request was refused.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of the response.
+    fn write_headers(&self, headers: &mut HeaderMap) {
+        headers.insert(
+            HeaderName::from_static(RATE_LIMIT_LIMIT_HEADER),
+            HeaderValue::from(self.limit),
+        );
+        headers.insert(
+            HeaderName::from_static(RATE_LIMIT_REMAINING_HEADER),
+            HeaderValue::from(self.remaining),
+        );
+        headers.insert(
+            HeaderName::from_static(RATE_LIMIT_RESET_HEADER),
+            HeaderValue::from(self.reset),
+        );
+
+        if !self.allowed {
+            headers.insert(RETRY_AFTER, HeaderValue::from(self.reset));
+        }
+    }
+}
+
+/// A fixed window rate limiter that counts the requests of every client IP address. The state is shared by all
+/// workers of the server, but not between instances.
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    state: Mutex<(HashMap<IpAddr, RateLimitWindow>, Instant)>,
+}
+
+impl RateLimiter {
+    /// # Summary
+    ///
+    /// Create a new RateLimiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum amount of requests of a client within a window, or 0 to disable rate limiting.
+    /// * `window` - The length of a window in seconds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let rate_limiter = RateLimiter::new(100, 60);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `RateLimiter` - The new RateLimiter.
+    pub fn new(limit: u32, window: u64) -> RateLimiter {
+        RateLimiter {
+            limit,
+            window: Duration::from_secs(window),
+            state: Mutex::new((HashMap::new(), Instant::now())),
+        }
+    }
+
+    /// # Summary
+    ///
+    /// Count a request of a client and check whether it is within the limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - The IP address of the client.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let state = rate_limiter.check(ip);
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `RateLimitState` - The state of the rate limit of the client, including whether the request is allowed.
+    pub fn check(&self, ip: IpAddr) -> RateLimitState {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (windows, last_prune) = &mut *state;
+
+        // Expired windows are removed once per window, so that the map does not grow with every client ever seen
+        if now.duration_since(*last_prune) >= self.window {
+            windows.retain(|_, w| now.duration_since(w.start) < self.window);
+            *last_prune = now;
+        }
+
+        let window = windows.entry(ip).or_insert(RateLimitWindow {
+            start: now,
+            count: 0,
+        });
+        if now.duration_since(window.start) >= self.window {
+            window.start = now;
+            window.count = 0;
+        }
+
+        let allowed = window.count < self.limit;
+        if allowed {
+            window.count += 1;
+        }
+
+        let elapsed = now.duration_since(window.start);
+        // The reset is rounded up, so that a client that waits for it is never refused again
+        let reset = (self.window - elapsed).as_secs_f64().ceil() as u64;
+
+        RateLimitState {
+            allowed,
+            limit: self.limit,
+            remaining: self.limit - window.count,
+            reset,
+        }
+    }
+}
+
+pub struct RateLimitMiddleware {
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl RateLimitMiddleware {
+    /// # Summary
+    ///
+    /// Create a new RateLimitMiddleware.
+    ///
+    /// # Arguments
+    ///
+    /// * `rate_limiter` - The RateLimiter that is shared by all workers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let rate_limit_middleware = RateLimitMiddleware::new(rate_limiter.clone());
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// * `RateLimitMiddleware` - The new RateLimitMiddleware.
+    pub fn new(rate_limiter: Arc<RateLimiter>) -> RateLimitMiddleware {
+        RateLimitMiddleware { rate_limiter }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimitMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RateLimitService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitService {
+            service: Rc::new(service),
+            rate_limiter: self.rate_limiter.clone(),
+        }))
+    }
+}
+
+pub struct RateLimitService<S> {
+    service: Rc<S>,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    /// # Summary
+    ///
+    /// Count the request against the rate limit of the client and refuse it with 429 Too Many Requests if the limit
+    /// was exceeded. The rate limit headers are added to every response, so that clients can back off in time.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The ServiceRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Future` - The future that resolves to the ServiceResponse.
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let trusted_proxies = match req.app_data::<web::Data<Config>>() {
+            Some(c) => c.server_config.trusted_proxies.as_slice(),
+            None => &[],
+        };
+        let ip = ClientIp::resolve(
+            req.peer_addr().map(|a| a.ip()),
+            req.headers(),
+            trusted_proxies,
+        );
+
+        // Requests of which the client cannot be identified are never limited
+        let state = match ip {
+            Some(ip) if self.rate_limiter.limit > 0 => Some(self.rate_limiter.check(ip)),
+            _ => None,
+        };
+
+        if let Some(s) = state.filter(|s| !s.allowed) {
+            let mut res = HttpResponse::TooManyRequests().json(TooManyRequests::new(
+                "The rate limit was exceeded, retry after the amount of seconds in the Retry-After header",
+            ));
+            s.write_headers(res.headers_mut());
+            return Box::pin(ready(Ok(req.into_response(res).map_into_right_body())));
+        }
+
+        let service = self.service.clone();
+        Box::pin(async move {
+            let mut res = service.call(req).await?;
+            if let Some(s) = state {
+                s.write_headers(res.headers_mut());
+            }
+            Ok(res.map_into_left_body())
+        })
+    }
+}
@@ -0,0 +1,153 @@
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::ErrorInternalServerError;
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::{Error, FromRequest, HttpMessage, HttpRequest};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use std::rc::Rc;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+#[derive(Clone)]
+pub struct RequestId(pub String);
+
+impl FromRequest for RequestId {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    /// # Summary
+    ///
+    /// Extract the RequestId that was stored by the RequestIdMiddleware.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The HttpRequest.
+    /// * `_payload` - The request Payload.
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Future` - The future that resolves to the RequestId.
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(match req.extensions().get::<RequestId>() {
+            Some(id) => Ok(RequestId(id.0.clone())),
+            None => Err(ErrorInternalServerError("Request ID is not available")),
+        })
+    }
+}
+
+/// # Summary
+///
+/// Get the ID of the request that is currently being handled.
+///
+/// # Example
+///
+/// ```
+/// let request_id = request_id::current();
+/// ```
+///
+/// # Returns
+///
+/// * `Option<String>` - The request ID, or None if called outside the scope of a request.
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// # Summary
+///
+/// Check whether an incoming request ID is safe to store and write to the logs.
+///
+/// # Arguments
+///
+/// * `id` - The request ID to check.
+///
+/// # Returns
+///
+/// * `bool` - True if the request ID can be used, otherwise false.
+fn is_valid(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LENGTH
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+pub struct RequestIdMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestIdMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestIdService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdService {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RequestIdService<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    /// # Summary
+    ///
+    /// Read the X-Request-Id header or generate a new ID, make it available to the rest of the
+    /// request and echo it back in the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `req` - The ServiceRequest.
+    ///
+    /// # Returns
+    ///
+    /// * `Self::Future` - The future that resolves to the ServiceResponse.
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let request_id = match req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|h| h.to_str().ok())
+        {
+            Some(id) if is_valid(id) => id.to_string(),
+            _ => Uuid::new_v4().to_string(),
+        };
+
+        let header_name = HeaderName::from_static(REQUEST_ID_HEADER);
+        let header_value =
+            HeaderValue::from_str(&request_id).expect("Request ID must be a valid header value");
+
+        // Overwrite the incoming header so that the access log always contains the effective ID
+        req.headers_mut()
+            .insert(header_name.clone(), header_value.clone());
+        req.extensions_mut().insert(RequestId(request_id.clone()));
+
+        let service = self.service.clone();
+        Box::pin(REQUEST_ID.scope(request_id, async move {
+            let mut res = service.call(req).await?;
+            res.headers_mut().insert(header_name, header_value);
+            Ok(res)
+        }))
+    }
+}
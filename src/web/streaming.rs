@@ -0,0 +1,52 @@
+use actix_web::web::Bytes;
+use actix_web::HttpResponse;
+use futures::StreamExt;
+use log::error;
+use mongodb::Cursor;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// # Summary
+///
+/// Stream the entities of a MongoDB Cursor as newline-delimited JSON, with one DTO per line. Every entity is written as
+/// soon as it is read from the cursor, so the collection is never buffered in memory.
+///
+/// # Arguments
+///
+/// * `cursor` - The Cursor over the entities.
+/// * `to_dto` - The function that converts an entity into the DTO that is written.
+///
+/// # Example
+///
+/// ```
+/// let cursor = pool.services.user_service.export(&pool.database).await?;
+/// let response = stream_cursor_as_ndjson(cursor, UserExport::from);
+/// ```
+///
+/// # Returns
+///
+/// * `HttpResponse` - The streaming HttpResponse.
+pub fn stream_cursor_as_ndjson<T, D, F>(cursor: Cursor<T>, to_dto: F) -> HttpResponse
+where
+    T: DeserializeOwned + Unpin + Send + Sync + 'static,
+    D: Serialize,
+    F: Fn(T) -> D + 'static,
+{
+    let lines = cursor.map(move |entity| -> Result<Bytes, Box<dyn std::error::Error>> {
+        // The status has already been sent once the first line is written, so an error can only end the stream
+        let entity = entity.map_err(|e| {
+            error!("Error while streaming entities: {}", e);
+            e
+        })?;
+
+        let mut line = serde_json::to_vec(&to_dto(entity))?;
+        line.push(b'\n');
+        Ok(Bytes::from(line))
+    });
+
+    HttpResponse::Ok()
+        .content_type(NDJSON_CONTENT_TYPE)
+        .streaming(lines)
+}